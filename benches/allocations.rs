@@ -0,0 +1,66 @@
+//! Heap allocations per operation, counted by a wrapping global allocator:
+//! `cargo bench --bench allocations`.
+//!
+//! Each figure is the number of `alloc` and `realloc` calls made while the
+//! operation runs once, after a warm-up call that builds the lazily
+//! initialized tables.
+
+use dlogproof::{PointJacobi, Prover, Secp256k1, Verifier};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn count<T>(mut operation: impl FnMut() -> T) -> usize {
+    black_box(operation());
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    black_box(operation());
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+fn main() {
+    let g: PointJacobi = PointJacobi::generator();
+    let x = Prover::generate_random_nonce();
+    let k = Prover::generate_random_nonce();
+    let y = PointJacobi::mul_generator(&x);
+    let proof = Prover::prove("sid", 1, &x, &y, &g).unwrap();
+    let rows = [
+        ("mul_unsafe", count(|| y.mul_unsafe(&k))),
+        ("mul_secret", count(|| y.mul_secret(&k))),
+        (
+            "mul_generator",
+            count(|| PointJacobi::<Secp256k1>::mul_generator(&k)),
+        ),
+        ("mul_double_base", count(|| g.mul_double_base(&k, &y, &x))),
+        ("prove", count(|| Prover::prove("sid", 1, &x, &y, &g))),
+        (
+            "verify",
+            count(|| Verifier::verify(&proof, "sid", 1, &y, &g)),
+        ),
+    ];
+    for (name, allocations) in rows {
+        println!("{:<16} {:>6} allocations", name, allocations);
+    }
+}
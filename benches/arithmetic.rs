@@ -0,0 +1,128 @@
+//! Field and point arithmetic benchmarks: `cargo bench --bench arithmetic`.
+//!
+//! The `field` group puts each fixed-limb field and scalar operation next to
+//! the `IBig` arithmetic it replaced in point arithmetic. The `mul` group
+//! times scalar multiplication on P-256, which runs on the lazily reduced
+//! `IBig` double and add formulas; compare it against an earlier build with
+//! criterion's `--save-baseline` and `--baseline`. The `verify` group puts
+//! [`VerifierContext::verify`] next to the stateless [`Verifier::verify`].
+//! The context is built once, so its cost per proof is the amortized cost of
+//! a long run against the same key.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dlogproof::curve;
+use dlogproof::field::{FieldElement, Scalar};
+use dlogproof::{PointJacobi, Prover, Secp256k1, Verifier, VerifierContext, P256};
+use ibig::IBig;
+
+fn field(c: &mut Criterion) {
+    let a = curve::rem(&Prover::generate_random_nonce());
+    let b = curve::rem(&Prover::generate_random_nonce());
+    let (fa, fb) = (FieldElement::from_ibig(&a), FieldElement::from_ibig(&b));
+    let mut group = c.benchmark_group("field");
+    let (sa, sb) = (Scalar::from_ibig(&a), Scalar::from_ibig(&b));
+    group.bench_function("add/limbs", |bench| {
+        bench.iter(|| black_box(&fa).add(black_box(&fb)))
+    });
+    group.bench_function("add/ibig", |bench| {
+        bench.iter(|| curve::rem(&(black_box(&a) + black_box(&b))))
+    });
+    group.bench_function("sub/limbs", |bench| {
+        bench.iter(|| black_box(&fa).sub(black_box(&fb)))
+    });
+    group.bench_function("sub/ibig", |bench| {
+        bench.iter(|| curve::rem(&(black_box(&a) - black_box(&b))))
+    });
+    group.bench_function("mul/fold", |bench| {
+        bench.iter(|| black_box(&fa).mul(black_box(&fb)))
+    });
+    group.bench_function("square/fold", |bench| {
+        bench.iter(|| black_box(&fa).square())
+    });
+    group.bench_function("mul/montgomery", |bench| {
+        bench.iter(|| black_box(&sa).mul(black_box(&sb)))
+    });
+    group.bench_function("mul/ibig", |bench| {
+        bench.iter(|| curve::rem(&(black_box(&a) * black_box(&b))))
+    });
+    let wide = &a * &b;
+    group.bench_function("rem_n/barrett", |bench| {
+        bench.iter(|| Scalar::reduce(black_box(&wide)))
+    });
+    group.bench_function("rem_n/ibig", |bench| {
+        bench.iter(|| curve::rem_n(black_box(&wide)))
+    });
+    group.bench_function("invert/fermat", |bench| {
+        bench.iter(|| black_box(&fa).invert())
+    });
+    group.bench_function("invert/binary", |bench| {
+        bench.iter(|| black_box(&fa).invert_vartime())
+    });
+    let values: Vec<FieldElement> = (0..64)
+        .map(|_| FieldElement::from_ibig(&Prover::generate_random_nonce()))
+        .collect();
+    group.bench_function("invert_batch/64", |bench| {
+        bench.iter(|| FieldElement::invert_batch(black_box(&values)))
+    });
+    group.finish();
+}
+
+fn point(c: &mut Criterion) {
+    let g = PointJacobi::generator();
+    let p = g.mul_unsafe(&Prover::generate_random_nonce());
+    let k: IBig = Prover::generate_random_nonce();
+    let mut group = c.benchmark_group("point");
+    group.bench_function("double", |bench| bench.iter(|| black_box(&p).double()));
+    group.bench_function("add", |bench| {
+        bench.iter(|| black_box(&p).add(black_box(&g)))
+    });
+    let g_affine = g.to_affine();
+    group.bench_function("add_affine", |bench| {
+        bench.iter(|| black_box(&p).add_affine(black_box(&g_affine)))
+    });
+    group.bench_function("mul_unsafe", |bench| {
+        bench.iter(|| black_box(&p).mul_unsafe(black_box(&k)))
+    });
+    group.bench_function("mul_secret", |bench| {
+        bench.iter(|| black_box(&p).mul_secret(black_box(&k)))
+    });
+    group.bench_function("mul_generator", |bench| {
+        bench.iter(|| PointJacobi::<Secp256k1>::mul_generator(black_box(&k)))
+    });
+    group.finish();
+}
+
+fn mul(c: &mut Criterion) {
+    let k: IBig = Prover::generate_random_nonce();
+    let p = PointJacobi::<P256>::generator().mul_unsafe(&k);
+    let mut group = c.benchmark_group("mul");
+    group.bench_function("ibig/mul_unsafe", |bench| {
+        bench.iter(|| black_box(&p).mul_unsafe(black_box(&k)))
+    });
+    group.bench_function("ibig/mul_secret", |bench| {
+        bench.iter(|| black_box(&p).mul_secret(black_box(&k)))
+    });
+    group.finish();
+}
+
+fn verify(c: &mut Criterion) {
+    let g = PointJacobi::<Secp256k1>::generator();
+    let x = Prover::generate_random_nonce();
+    let y = g.mul_unsafe(&x);
+    let proof = Prover::prove("bench", 1, &x, &y, &g).unwrap();
+    let context = VerifierContext::new(&y, &g);
+    let mut group = c.benchmark_group("verify");
+    group.bench_function("stateless", |bench| {
+        bench.iter(|| Verifier::verify(black_box(&proof), "bench", 1, &y, &g))
+    });
+    group.bench_function("context", |bench| {
+        bench.iter(|| context.verify(black_box(&proof), "bench", 1))
+    });
+    group.bench_function("context/new", |bench| {
+        bench.iter(|| VerifierContext::new(black_box(&y), &g))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, field, point, mul, verify);
+criterion_main!(benches);
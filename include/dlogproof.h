@@ -0,0 +1,132 @@
+#ifndef DLOGPROOF_H
+#define DLOGPROOF_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Length of a secret key, a big-endian scalar.
+#define DLOGPROOF_SECRET_BYTES 32
+
+// Length of a public key, a compressed point.
+#define DLOGPROOF_PUBLIC_KEY_BYTES 33
+
+// Length of a proof, `T || s`.
+#define DLOGPROOF_PROOF_BYTES 65
+
+// Success; for `dlogproof_verify`, the proof is valid.
+#define DLOGPROOF_OK 0
+
+// An output pointer, or an input pointer with a nonzero length, was null.
+#define DLOGPROOF_ERR_NULL_POINTER -1
+
+// The session id is not UTF-8.
+#define DLOGPROOF_ERR_INVALID_INPUT -2
+
+// An output buffer is shorter than the value written to it.
+#define DLOGPROOF_ERR_BUFFER_TOO_SMALL -3
+
+// The library panicked.
+#define DLOGPROOF_ERR_PANIC -4
+
+// [`ProofError::DeserializationError`].
+#define DLOGPROOF_ERR_DESERIALIZATION -10
+
+// [`ProofError::VerificationFailed`]: the proof is well formed but invalid.
+#define DLOGPROOF_ERR_VERIFICATION_FAILED -11
+
+// [`ProofError::Expired`].
+#define DLOGPROOF_ERR_EXPIRED -12
+
+// [`ProofError::NotYetValid`].
+#define DLOGPROOF_ERR_NOT_YET_VALID -13
+
+// [`ProofError::InvalidShare`].
+#define DLOGPROOF_ERR_INVALID_SHARE -14
+
+// [`ProofError::DuplicateShareIndex`].
+#define DLOGPROOF_ERR_DUPLICATE_SHARE_INDEX -15
+
+// [`ProofError::InsufficientShares`].
+#define DLOGPROOF_ERR_INSUFFICIENT_SHARES -16
+
+// [`ProofError::InvalidStatement`].
+#define DLOGPROOF_ERR_INVALID_STATEMENT -17
+
+// [`ProofError::DecryptionFailed`].
+#define DLOGPROOF_ERR_DECRYPTION_FAILED -18
+
+// [`ProofError::ScalarOutOfRange`].
+#define DLOGPROOF_ERR_SCALAR_OUT_OF_RANGE -19
+
+// [`ProofError::IdentityCommitment`].
+#define DLOGPROOF_ERR_IDENTITY_COMMITMENT -20
+
+// [`ProofError::PointNotOnCurve`].
+#define DLOGPROOF_ERR_POINT_NOT_ON_CURVE -21
+
+// [`ProofError::InvalidSecret`].
+#define DLOGPROOF_ERR_INVALID_SECRET -22
+
+// [`ProofError::InvalidPoint`].
+#define DLOGPROOF_ERR_INVALID_POINT -23
+
+// [`ProofError::InvalidScalar`].
+#define DLOGPROOF_ERR_INVALID_SCALAR -24
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Generates a random secret and its public key `secret·G`.
+//
+// # Safety
+//
+// `secret_out` must be valid for writes of `secret_out_len` bytes and
+// `public_key_out` for writes of `public_key_out_len` bytes.
+int32_t dlogproof_generate_keypair(uint8_t *secret_out,
+                                   size_t secret_out_len,
+                                   uint8_t *public_key_out,
+                                   size_t public_key_out_len);
+
+// Proves knowledge of `secret` for `public_key` in session `sid` as
+// participant `pid`, writing [`DLOGPROOF_PROOF_BYTES`] bytes to `proof_out`.
+//
+// # Safety
+//
+// Each input pointer must be valid for reads of its length, and `proof_out`
+// for writes of `proof_out_len` bytes.
+int32_t dlogproof_prove(const uint8_t *sid,
+                        size_t sid_len,
+                        int32_t pid,
+                        const uint8_t *secret,
+                        size_t secret_len,
+                        const uint8_t *public_key,
+                        size_t public_key_len,
+                        uint8_t *proof_out,
+                        size_t proof_out_len);
+
+// Checks `proof` for `public_key` in session `sid` as participant `pid`.
+// Returns [`DLOGPROOF_OK`] for a valid proof and
+// [`DLOGPROOF_ERR_VERIFICATION_FAILED`] for a well-formed invalid one.
+//
+// # Safety
+//
+// Each pointer must be valid for reads of its length.
+int32_t dlogproof_verify(const uint8_t *sid,
+                         size_t sid_len,
+                         int32_t pid,
+                         const uint8_t *public_key,
+                         size_t public_key_len,
+                         const uint8_t *proof,
+                         size_t proof_len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* DLOGPROOF_H */
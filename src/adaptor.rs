@@ -0,0 +1,200 @@
+//! Adaptor signatures over [`SchnorrSignature`]s, the building block of
+//! scriptless atomic swaps.
+//!
+//! A pre-signature is made for an adaptor point `T = t·G`. The signer commits
+//! `R = r·G` but derives the challenge from `R + T`:
+//!
+//! ```text
+//! c = H("dlogproof/schnorr", 0, G, Y, R + T, message),  s' = r + c·x
+//! ```
+//!
+//! Anyone can check `s'·G == R + c·Y`, but only someone who knows `t` can
+//! complete it into the ordinary signature `(c, s' + t)`. Once that signature
+//! is published, `t = s - s'` can be read off it.
+
+use crate::curve;
+use crate::error::ProofError;
+use crate::jacobi_point::PointJacobi;
+use crate::keys::{PublicKey, SecretKey};
+use crate::schnorr::{SchnorrSignature, MESSAGE_TAG};
+use ibig::IBig;
+use rand::{CryptoRng, RngCore};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdaptorSignature {
+    pub r: PointJacobi,
+    pub s: IBig,
+}
+
+impl AdaptorSignature {
+    /// `sign` makes a pre-signature on `message` encrypted to `adaptor_point`,
+    /// or fails as [`SchnorrSignature::sign`] does.
+    pub fn sign<R: RngCore + CryptoRng>(
+        key: &SecretKey,
+        message: &[u8],
+        adaptor_point: &PointJacobi,
+        rng: &mut R,
+    ) -> Result<Self, ProofError> {
+        let g = PointJacobi::generator();
+        let nonce = curve::random_scalar(rng);
+        let r = g.mul_secret(&nonce);
+        let c = Self::challenge(key.public_key().point(), &r, adaptor_point, message)?;
+        let s = curve::rem_n(&(nonce + c * key.scalar()));
+        Ok(Self { r, s })
+    }
+
+    /// `verify` checks that the pre-signature completes, with the discrete
+    /// log of `adaptor_point`, into a valid signature on `message`.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if `s'·G == R + c·Y`, `ProofError::VerificationFailed` otherwise.
+    pub fn verify(
+        &self,
+        public_key: &PublicKey,
+        message: &[u8],
+        adaptor_point: &PointJacobi,
+    ) -> Result<(), ProofError> {
+        if self.s >= *curve::N || self.r.is_zero() {
+            return Err(ProofError::VerificationFailed);
+        }
+        let y = public_key.point();
+        let c = Self::challenge(y, &self.r, adaptor_point, message)?;
+        if PointJacobi::generator().mul_unsafe(&self.s) == self.r.add(&y.mul_unsafe(&c)) {
+            Ok(())
+        } else {
+            Err(ProofError::VerificationFailed)
+        }
+    }
+
+    /// `adapt` completes the pre-signature with the adaptor secret `t`, or
+    /// fails as [`SchnorrSignature::sign`] does.
+    pub fn adapt(
+        &self,
+        public_key: &PublicKey,
+        message: &[u8],
+        adaptor_secret: &IBig,
+    ) -> Result<SchnorrSignature, ProofError> {
+        let adaptor_point = PointJacobi::mul_generator(adaptor_secret);
+        let c = Self::challenge(public_key.point(), &self.r, &adaptor_point, message)?;
+        let s = curve::rem_n(&(&self.s + adaptor_secret));
+        Ok(SchnorrSignature { c, s })
+    }
+
+    /// `extract` recovers the adaptor secret from the completed signature.
+    ///
+    /// Returns:
+    ///
+    /// `t = s - s'`, or `ProofError::VerificationFailed` if `signature` was
+    /// not completed from this pre-signature for `adaptor_point`.
+    pub fn extract(
+        &self,
+        signature: &SchnorrSignature,
+        adaptor_point: &PointJacobi,
+    ) -> Result<IBig, ProofError> {
+        let t = curve::rem_n(&(&signature.s - &self.s));
+        if PointJacobi::generator().mul_unsafe(&t) == *adaptor_point {
+            Ok(t)
+        } else {
+            Err(ProofError::VerificationFailed)
+        }
+    }
+
+    fn challenge(
+        y: &PointJacobi,
+        r: &PointJacobi,
+        adaptor_point: &PointJacobi,
+        message: &[u8],
+    ) -> Result<IBig, ProofError> {
+        SchnorrSignature::challenge(MESSAGE_TAG, y, &r.add(adaptor_point), message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    struct Swap {
+        key: SecretKey,
+        public_key: PublicKey,
+        t: IBig,
+        adaptor_point: PointJacobi,
+        rng: StdRng,
+    }
+
+    fn swap() -> Swap {
+        let mut rng = StdRng::seed_from_u64(1029);
+        let key = SecretKey::random(&mut rng);
+        let t = curve::random_scalar(&mut rng);
+        Swap {
+            public_key: key.public_key(),
+            key,
+            adaptor_point: PointJacobi::mul_generator(&t),
+            t,
+            rng,
+        }
+    }
+
+    #[test]
+    fn an_adapted_pre_signature_is_a_valid_signature() {
+        let mut s = swap();
+        let pre = AdaptorSignature::sign(&s.key, b"swap", &s.adaptor_point, &mut s.rng).unwrap();
+        assert_eq!(pre.verify(&s.public_key, b"swap", &s.adaptor_point), Ok(()));
+        let signature = pre.adapt(&s.public_key, b"swap", &s.t).unwrap();
+        assert_eq!(signature.verify(&s.public_key, b"swap"), Ok(()));
+        assert_eq!(pre.extract(&signature, &s.adaptor_point), Ok(s.t.clone()));
+    }
+
+    #[test]
+    fn a_pre_signature_is_bound_to_its_adaptor_point_and_message() {
+        let mut s = swap();
+        let pre = AdaptorSignature::sign(&s.key, b"swap", &s.adaptor_point, &mut s.rng).unwrap();
+        let other_point = s.adaptor_point.add(&PointJacobi::generator());
+        assert_eq!(
+            pre.verify(&s.public_key, b"swap", &other_point),
+            Err(ProofError::VerificationFailed)
+        );
+        assert_eq!(
+            pre.verify(&s.public_key, b"other", &s.adaptor_point),
+            Err(ProofError::VerificationFailed)
+        );
+        let other_key = SecretKey::random(&mut s.rng).public_key();
+        assert_eq!(
+            pre.verify(&other_key, b"swap", &s.adaptor_point),
+            Err(ProofError::VerificationFailed)
+        );
+
+        // Completing with the wrong secret does not give a valid signature.
+        let wrong = pre
+            .adapt(&s.public_key, b"swap", &curve::rem_n(&(&s.t + 1)))
+            .unwrap();
+        assert!(wrong.verify(&s.public_key, b"swap").is_err());
+
+        let mut unreduced = pre;
+        unreduced.s = &unreduced.s + &*curve::N;
+        assert_eq!(
+            unreduced.verify(&s.public_key, b"swap", &s.adaptor_point),
+            Err(ProofError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn an_unrelated_signature_reveals_nothing() {
+        let mut s = swap();
+        let pre = AdaptorSignature::sign(&s.key, b"swap", &s.adaptor_point, &mut s.rng).unwrap();
+        let unrelated = SchnorrSignature::sign(&s.key, b"swap", &mut s.rng).unwrap();
+        assert_eq!(unrelated.verify(&s.public_key, b"swap"), Ok(()));
+        assert_eq!(
+            pre.extract(&unrelated, &s.adaptor_point),
+            Err(ProofError::VerificationFailed)
+        );
+        let signature = pre.adapt(&s.public_key, b"swap", &s.t).unwrap();
+        let other_point = s.adaptor_point.add(&PointJacobi::generator());
+        assert_eq!(
+            pre.extract(&signature, &other_point),
+            Err(ProofError::VerificationFailed)
+        );
+    }
+}
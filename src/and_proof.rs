@@ -0,0 +1,275 @@
+//! Proof of knowledge of the discrete logs of several public keys at once.
+//!
+//! The prover commits `T_i = r_i·G` for every statement `Y_i = x_i·G`, derives
+//! a single challenge `c = H(sid, pid, G, Y_1, ..., Y_k, T_1, ..., T_k)`,
+//! tagged [`TAG`], and responds with `s_i = r_i + c·x_i`. The verifier checks
+//! `s_i·G == T_i + c·Y_i` for every `i`. Sharing the challenge ties the
+//! statements together: the proof cannot be split into, or assembled from,
+//! proofs of the individual keys.
+
+use crate::curve;
+use crate::error::ProofError;
+use crate::hash::hash_tagged;
+use crate::jacobi_point::PointJacobi;
+use crate::proof::DLogProof;
+use crate::prover::Prover;
+use crate::scalar::Scalar;
+use ibig::IBig;
+
+/// Tag of the challenge transcript; see [`crate::hash`].
+pub const TAG: &str = "dlogproof/and";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AndProof {
+    pub t: Vec<PointJacobi>,
+    pub s: Vec<IBig>,
+}
+
+impl AndProof {
+    /// `prove` proves knowledge of every `secrets[i]` with `ys[i] = secrets[i]·base_point`.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `secrets`: the secrets `x_i`
+    /// * `ys`: the public keys `Y_i`, one per secret
+    /// * `base_point`: the base point of the group
+    ///
+    /// Returns:
+    ///
+    /// The proof, `ProofError::InvalidSecret` if a secret is not in `[1, n)`,
+    /// or `ProofError::InvalidStatement` if `ys` is empty or its length
+    /// differs from that of `secrets`.
+    pub fn prove(
+        sid: &str,
+        pid: i32,
+        secrets: &[IBig],
+        ys: &[PointJacobi],
+        base_point: &PointJacobi,
+    ) -> Result<Self, ProofError> {
+        check_shape(secrets.len(), ys)?;
+        if secrets
+            .iter()
+            .any(|x| *x <= IBig::from(0) || *x >= *curve::N)
+        {
+            return Err(ProofError::InvalidSecret);
+        }
+        let nonces: Vec<IBig> = ys.iter().map(|_| Prover::generate_random_nonce()).collect();
+        let t: Vec<PointJacobi> = nonces.iter().map(|r| base_point.mul_secret(r)).collect();
+        let c = challenge(sid, pid, ys, &t, base_point)?;
+        let s = nonces
+            .into_iter()
+            .zip(secrets)
+            .map(|(r, x)| curve::rem_n(&(r + &c * x)))
+            .collect();
+        Ok(Self { t, s })
+    }
+
+    /// `verify` checks `s_i·G == T_i + c·Y_i` for every statement.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `ys`: the public keys `Y_i`
+    /// * `base_point`: the base point of the group
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid, `ProofError::InvalidStatement` if the
+    /// proof does not have one commitment and response per key,
+    /// `ProofError::ScalarOutOfRange` if a response is not reduced modulo n,
+    /// and `ProofError::VerificationFailed` otherwise.
+    pub fn verify(
+        &self,
+        sid: &str,
+        pid: i32,
+        ys: &[PointJacobi],
+        base_point: &PointJacobi,
+    ) -> Result<(), ProofError> {
+        check_shape(self.t.len(), ys)?;
+        check_shape(self.s.len(), ys)?;
+        if self.s.iter().any(|s| *s < IBig::from(0) || *s >= *curve::N) {
+            return Err(ProofError::ScalarOutOfRange);
+        }
+        let c = challenge(sid, pid, ys, &self.t, base_point)?;
+        let holds = self
+            .t
+            .iter()
+            .zip(&self.s)
+            .zip(ys)
+            .all(|((t, s), y)| base_point.mul_unsafe(s) == t.add(&y.mul_unsafe(&c)));
+        if holds {
+            Ok(())
+        } else {
+            Err(ProofError::VerificationFailed)
+        }
+    }
+
+    /// `to_bytes` encodes each `(t_i, s_i)` as a [`DLogProof`], back to back.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.t
+            .iter()
+            .zip(&self.s)
+            .flat_map(|(t, s)| DLogProof::new(t.clone(), Scalar::new(s)).to_bytes())
+            .collect()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.is_empty() || !bytes.len().is_multiple_of(DLogProof::BYTES) {
+            return Err(ProofError::DeserializationError(format!(
+                "invalid AND proof length {}",
+                bytes.len()
+            )));
+        }
+        let (t, s) = bytes
+            .chunks(DLogProof::BYTES)
+            .map(|chunk| DLogProof::from_bytes(chunk).map(|proof| (proof.t, IBig::from(proof.s))))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .unzip();
+        Ok(Self { t, s })
+    }
+}
+
+fn check_shape(items: usize, ys: &[PointJacobi]) -> Result<(), ProofError> {
+    if ys.is_empty() {
+        return Err(ProofError::InvalidStatement("no public keys".to_string()));
+    }
+    if items != ys.len() {
+        return Err(ProofError::InvalidStatement(format!(
+            "{} items for {} public keys",
+            items,
+            ys.len()
+        )));
+    }
+    Ok(())
+}
+
+fn challenge(
+    sid: &str,
+    pid: i32,
+    ys: &[PointJacobi],
+    ts: &[PointJacobi],
+    base_point: &PointJacobi,
+) -> Result<IBig, ProofError> {
+    let mut points = Vec::with_capacity(1 + 2 * ys.len());
+    points.push(base_point);
+    points.extend(ys);
+    points.extend(ts);
+    hash_tagged(TAG, sid, pid, &points, &[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn statements(count: usize) -> (Vec<IBig>, Vec<PointJacobi>) {
+        let mut rng = StdRng::seed_from_u64(1024);
+        let xs: Vec<IBig> = (0..count).map(|_| curve::random_scalar(&mut rng)).collect();
+        let ys = xs.iter().map(PointJacobi::mul_generator).collect();
+        (xs, ys)
+    }
+
+    #[test]
+    fn proofs_for_several_keys_verify() {
+        let g = PointJacobi::generator();
+        for count in [1, 2, 5] {
+            let (xs, ys) = statements(count);
+            let proof = AndProof::prove("and", 1, &xs, &ys, &g).unwrap();
+            assert_eq!(proof.t.len(), count);
+            assert_eq!(proof.verify("and", 1, &ys, &g), Ok(()));
+            assert_eq!(
+                proof.verify("and", 2, &ys, &g),
+                Err(ProofError::VerificationFailed)
+            );
+        }
+    }
+
+    #[test]
+    fn a_swapped_key_or_response_is_rejected() {
+        let g = PointJacobi::generator();
+        let (xs, ys) = statements(3);
+        let proof = AndProof::prove("and", 1, &xs, &ys, &g).unwrap();
+
+        let mut swapped_keys = ys.clone();
+        swapped_keys.swap(0, 2);
+        assert_eq!(
+            proof.verify("and", 1, &swapped_keys, &g),
+            Err(ProofError::VerificationFailed)
+        );
+        let mut replaced = ys.clone();
+        replaced[1] = PointJacobi::mul_generator(&IBig::from(1024));
+        assert_eq!(
+            proof.verify("and", 1, &replaced, &g),
+            Err(ProofError::VerificationFailed)
+        );
+
+        let mut swapped_responses = proof.clone();
+        swapped_responses.s.swap(0, 1);
+        assert_eq!(
+            swapped_responses.verify("and", 1, &ys, &g),
+            Err(ProofError::VerificationFailed)
+        );
+        let mut unreduced = proof;
+        unreduced.s[2] = &unreduced.s[2] + &*curve::N;
+        assert_eq!(
+            unreduced.verify("and", 1, &ys, &g),
+            Err(ProofError::ScalarOutOfRange)
+        );
+    }
+
+    #[test]
+    fn an_empty_or_mismatched_statement_is_invalid() {
+        let g = PointJacobi::generator();
+        let (xs, ys) = statements(3);
+        assert!(matches!(
+            AndProof::prove("and", 1, &[], &[], &g),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        assert!(matches!(
+            AndProof::prove("and", 1, &xs[..2], &ys, &g),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        assert_eq!(
+            AndProof::prove("and", 1, &[IBig::from(0)], &ys[..1], &g),
+            Err(ProofError::InvalidSecret)
+        );
+        let proof = AndProof::prove("and", 1, &xs, &ys, &g).unwrap();
+        assert!(matches!(
+            proof.verify("and", 1, &ys[..2], &g),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        assert!(matches!(
+            proof.verify("and", 1, &[], &g),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        let mut short = proof;
+        short.s.pop();
+        assert!(matches!(
+            short.verify("and", 1, &ys, &g),
+            Err(ProofError::InvalidStatement(_))
+        ));
+    }
+
+    #[test]
+    fn proofs_round_trip_through_bytes() {
+        let g = PointJacobi::generator();
+        let (xs, ys) = statements(3);
+        let proof = AndProof::prove("and", 1, &xs, &ys, &g).unwrap();
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes.len(), 3 * DLogProof::BYTES);
+        let decoded = AndProof::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, proof);
+        assert_eq!(decoded.verify("and", 1, &ys, &g), Ok(()));
+        for len in [0, bytes.len() - 1] {
+            assert!(matches!(
+                AndProof::from_bytes(&bytes[..len]),
+                Err(ProofError::DeserializationError(_))
+            ));
+        }
+    }
+}
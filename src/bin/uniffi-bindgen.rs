@@ -0,0 +1,6 @@
+//! Generates the Kotlin and Swift bindings of the `mobile` module; see its
+//! documentation for the invocation.
+
+fn main() {
+    uniffi::uniffi_bindgen_main()
+}
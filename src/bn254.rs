@@ -0,0 +1,65 @@
+//! The G1 group of BN254 (alt_bn128), `y² = x³ + 3`, so that proofs can bind
+//! to commitments made inside SNARK circuits over its scalar field, such as
+//! those verified by the Ethereum precompiles.
+//!
+//! As for [`crate::P256`], only the domain parameters are given here and the
+//! arithmetic is the provided `IBig` implementation of [`Curve`]. G1 has
+//! cofactor one, so every point on the curve is in the group. Points use the
+//! same 33-byte compressed encoding as on secp256k1.
+//!
+//! BLS12-381 G1 is not provided: its 381-bit coordinates do not fit that
+//! encoding, and its cofactor would make every decoded point need a
+//! subgroup check.
+
+use crate::curve::Curve;
+use crate::field::limbs_to_ibig;
+use ibig::{ibig, IBig};
+use std::sync::LazyLock;
+
+/// The G1 group of the BN254 pairing-friendly curve.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Bn254;
+
+/// The 254-bit field modulus `p`, little-endian limbs.
+const P_LIMBS: [u64; 4] = [
+    0x3C20_8C16_D87C_FD47,
+    0x9781_6A91_6871_CA8D,
+    0xB850_45B6_8181_585D,
+    0x3064_4E72_E131_A029,
+];
+/// The group order `n`, the scalar field of circuits over BN254.
+const N_LIMBS: [u64; 4] = [
+    0x43E1_F593_F000_0001,
+    0x2833_E848_79B9_7091,
+    0xB850_45B6_8181_585D,
+    0x3064_4E72_E131_A029,
+];
+
+static P: LazyLock<IBig> = LazyLock::new(|| limbs_to_ibig(&P_LIMBS));
+static N: LazyLock<IBig> = LazyLock::new(|| limbs_to_ibig(&N_LIMBS));
+static A: LazyLock<IBig> = LazyLock::new(|| ibig!(0));
+static B: LazyLock<IBig> = LazyLock::new(|| ibig!(3));
+static G: LazyLock<(IBig, IBig)> = LazyLock::new(|| (ibig!(1), ibig!(2)));
+
+impl Curve for Bn254 {
+    fn p() -> &'static IBig {
+        &P
+    }
+
+    fn n() -> &'static IBig {
+        &N
+    }
+
+    fn a() -> &'static IBig {
+        &A
+    }
+
+    fn b() -> &'static IBig {
+        &B
+    }
+
+    fn generator() -> (&'static IBig, &'static IBig) {
+        let (x, y) = &*G;
+        (x, y)
+    }
+}
@@ -0,0 +1,56 @@
+//! Borsh support behind the `borsh` feature.
+//!
+//! Values are written as their fixed-size canonical encodings with no length
+//! prefix: 33 bytes for a point, 65 for a [`DLogProof`]. Reading goes through
+//! `from_bytes`, so invalid points and unreduced scalars are rejected with
+//! `io::ErrorKind::InvalidData`.
+
+use crate::error::ProofError;
+use crate::jacobi_point::{Point, PointJacobi, POINT_BYTES};
+use crate::proof::DLogProof;
+use borsh::io::{Error, ErrorKind, Read, Write};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+fn read_encoded<R: Read, const LEN: usize, T>(
+    reader: &mut R,
+    parse: fn(&[u8]) -> Result<T, ProofError>,
+) -> Result<T, Error> {
+    let bytes = <[u8; LEN]>::deserialize_reader(reader)?;
+    parse(&bytes).map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))
+}
+
+impl BorshSerialize for Point {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        writer.write_all(&self.to_bytes())
+    }
+}
+
+impl BorshDeserialize for Point {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        read_encoded::<R, POINT_BYTES, _>(reader, Point::from_bytes)
+    }
+}
+
+impl BorshSerialize for PointJacobi {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        writer.write_all(&self.to_bytes())
+    }
+}
+
+impl BorshDeserialize for PointJacobi {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        read_encoded::<R, POINT_BYTES, _>(reader, PointJacobi::from_bytes)
+    }
+}
+
+impl BorshSerialize for DLogProof {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        writer.write_all(&self.to_bytes())
+    }
+}
+
+impl BorshDeserialize for DLogProof {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        read_encoded::<R, { DLogProof::BYTES }, _>(reader, DLogProof::from_bytes)
+    }
+}
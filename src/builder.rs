@@ -0,0 +1,100 @@
+//! A fluent alternative to [`Prover::prove`]'s five positional arguments.
+//!
+//! ```text
+//! let proof = DLogProof::builder()
+//!     .session("sid")
+//!     .participant(1)
+//!     .base(&g)
+//!     .public_key(&y)
+//!     .secret(&x)
+//!     .prove()?;
+//! ```
+//!
+//! Every field is named at the call site, so two of them cannot be swapped
+//! silently, and [`ProofBuilder::prove`] checks that the public key is the
+//! secret times the base, so a mismatched pair is reported rather than
+//! producing a proof that never verifies.
+
+use crate::curve::{Curve, Secp256k1};
+use crate::error::ProofError;
+use crate::jacobi_point::PointJacobi;
+use crate::proof::DLogProof;
+use crate::prover::Prover;
+use ibig::IBig;
+
+/// Collects the inputs of a [`DLogProof`] one named field at a time; see
+/// [`crate::builder`].
+#[derive(Debug, Clone)]
+pub struct ProofBuilder<'a, C: Curve = Secp256k1> {
+    sid: Option<&'a str>,
+    pid: Option<i32>,
+    base_point: Option<&'a PointJacobi<C>>,
+    public_key: Option<&'a PointJacobi<C>>,
+    secret: Option<&'a IBig>,
+}
+
+impl<C: Curve> Default for ProofBuilder<'_, C> {
+    fn default() -> Self {
+        Self {
+            sid: None,
+            pid: None,
+            base_point: None,
+            public_key: None,
+            secret: None,
+        }
+    }
+}
+
+impl<'a, C: Curve> ProofBuilder<'a, C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn session(mut self, sid: &'a str) -> Self {
+        self.sid = Some(sid);
+        self
+    }
+
+    pub fn participant(mut self, pid: i32) -> Self {
+        self.pid = Some(pid);
+        self
+    }
+
+    /// `base` sets the base point, the generator of `C` if never called.
+    pub fn base(mut self, base_point: &'a PointJacobi<C>) -> Self {
+        self.base_point = Some(base_point);
+        self
+    }
+
+    pub fn public_key(mut self, y: &'a PointJacobi<C>) -> Self {
+        self.public_key = Some(y);
+        self
+    }
+
+    pub fn secret(mut self, x: &'a IBig) -> Self {
+        self.secret = Some(x);
+        self
+    }
+
+    /// `prove` makes the proof with [`Prover::prove_checked`].
+    ///
+    /// Returns:
+    ///
+    /// The proof, `ProofError::InvalidStatement` naming the first of the
+    /// session, participant, public key and secret that was never set, or the
+    /// errors of [`Prover::prove_checked`].
+    pub fn prove(self) -> Result<DLogProof<C>, ProofError> {
+        let sid = self.sid.ok_or_else(|| missing("session id"))?;
+        let pid = self.pid.ok_or_else(|| missing("participant id"))?;
+        let y = self.public_key.ok_or_else(|| missing("public key"))?;
+        let x = self.secret.ok_or_else(|| missing("secret"))?;
+        match self.base_point {
+            Some(base_point) => Prover::prove_checked(sid, pid, x, y, base_point),
+            None => Prover::prove_checked(sid, pid, x, y, &PointJacobi::generator()),
+        }
+    }
+}
+
+fn missing(field: &str) -> ProofError {
+    ProofError::InvalidStatement(format!("{} not set", field))
+}
@@ -0,0 +1,265 @@
+use crate::error::ProofError;
+use crate::jacobi_point::{PointJacobi, POINT_BYTES};
+use crate::proof::DLogProof;
+use crate::verifier::Verifier;
+use std::collections::HashSet;
+
+/// A set of proofs sharing one session id and base point, each made by a
+/// different participant for its own public key.
+///
+/// The canonical encoding stores the shared fields once:
+///
+/// ```text
+/// sid_len: u32 BE | sid | base_point: 33 | count: u32 BE | entry * count
+/// entry = pid_len: u8 | pid (decimal ASCII) | public_key: 33 | proof: 65
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofBundle {
+    pub sid: String,
+    pub base_point: PointJacobi,
+    entries: Vec<(i32, PointJacobi, DLogProof)>,
+    /// The `(pid, compressed public key)` of every entry, for `push`.
+    seen: HashSet<(i32, [u8; POINT_BYTES])>,
+}
+
+impl ProofBundle {
+    pub fn new(sid: &str, base_point: PointJacobi) -> Self {
+        Self {
+            sid: sid.to_string(),
+            base_point,
+            entries: vec![],
+            seen: HashSet::new(),
+        }
+    }
+
+    pub fn entries(&self) -> &[(i32, PointJacobi, DLogProof)] {
+        &self.entries
+    }
+
+    /// `push` adds a participant's proof to the bundle.
+    ///
+    /// Arguments:
+    ///
+    /// * `pid`: the participant id
+    /// * `public_key`: the point the proof is about
+    /// * `proof`: the participant's proof
+    ///
+    /// Returns:
+    ///
+    /// A `DeserializationError` if the bundle already holds an entry for the
+    /// same `(pid, public_key)` pair, since such a bundle could not be decoded.
+    pub fn push(
+        &mut self,
+        pid: i32,
+        public_key: PointJacobi,
+        proof: DLogProof,
+    ) -> Result<(), ProofError> {
+        if !self.seen.insert((pid, public_key.to_bytes())) {
+            return Err(duplicate_entry(pid));
+        }
+        self.entries.push((pid, public_key, proof));
+        Ok(())
+    }
+
+    /// `verify_all` batch-verifies every entry against the shared sid and base point.
+    pub fn verify_all(&self) -> Result<(), ProofError> {
+        Verifier::verify_batch(&self.sid, &self.base_point, &self.entries)
+    }
+
+    /// `to_bytes` encodes the bundle in the canonical format.
+    ///
+    /// Returns:
+    ///
+    /// The encoding, or `ProofError::InvalidStatement` if `sid` is longer
+    /// than `u32::MAX` bytes or the bundle holds more than `u32::MAX` entries.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ProofError> {
+        let too_long = |what: &str| {
+            ProofError::InvalidStatement(format!("bundle {} does not fit a u32 length", what))
+        };
+        let sid_len = u32::try_from(self.sid.len()).map_err(|_| too_long("sid"))?;
+        let count = u32::try_from(self.entries.len()).map_err(|_| too_long("entry count"))?;
+        let mut out = vec![];
+        out.extend(sid_len.to_be_bytes());
+        out.extend(self.sid.as_bytes());
+        out.extend(self.base_point.to_bytes());
+        out.extend(count.to_be_bytes());
+        for (pid, public_key, proof) in &self.entries {
+            let pid = pid.to_string();
+            out.push(pid.len() as u8);
+            out.extend(pid.as_bytes());
+            out.extend(public_key.to_bytes());
+            out.extend(proof.to_bytes());
+        }
+        Ok(out)
+    }
+
+    /// `from_bytes` parses the canonical encoding produced by [`ProofBundle::to_bytes`].
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: the encoded bundle
+    ///
+    /// Returns:
+    ///
+    /// The decoded bundle, or a `DeserializationError` if the input is
+    /// truncated, holds fewer or more entries than its header declares, or
+    /// repeats a `(pid, public_key)` pair.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        let mut reader = Reader { bytes };
+        let sid_len = u32::from_be_bytes(reader.take(4)?.try_into().unwrap()) as usize;
+        let sid = std::str::from_utf8(reader.take(sid_len)?)
+            .map_err(|_| ProofError::DeserializationError("sid is not valid UTF-8".to_string()))?;
+        let base_point = PointJacobi::from_bytes(reader.take(POINT_BYTES)?)?;
+        let count = u32::from_be_bytes(reader.take(4)?.try_into().unwrap());
+
+        let mut bundle = Self::new(sid, base_point);
+        for index in 0..count {
+            if reader.bytes.is_empty() {
+                return Err(ProofError::DeserializationError(format!(
+                    "header declares {} entries but only {} are present",
+                    count, index
+                )));
+            }
+            let pid_len = reader.take(1)?[0] as usize;
+            let pid = parse_pid(reader.take(pid_len)?)?;
+            let public_key = PointJacobi::from_bytes(reader.take(POINT_BYTES)?)?;
+            let proof = DLogProof::from_bytes(reader.take(DLogProof::BYTES)?)?;
+            bundle.push(pid, public_key, proof)?;
+        }
+        if !reader.bytes.is_empty() {
+            return Err(ProofError::DeserializationError(format!(
+                "{} trailing bytes after {} entries",
+                reader.bytes.len(),
+                count
+            )));
+        }
+        Ok(bundle)
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ProofError> {
+        if self.bytes.len() < len {
+            return Err(ProofError::DeserializationError(format!(
+                "unexpected end of input: needed {} bytes, {} left",
+                len,
+                self.bytes.len()
+            )));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+}
+
+/// Parses a pid, accepting only the canonical decimal form written by `to_bytes`.
+fn parse_pid(bytes: &[u8]) -> Result<i32, ProofError> {
+    let invalid = || ProofError::DeserializationError("invalid pid encoding".to_string());
+    let text = std::str::from_utf8(bytes).map_err(|_| invalid())?;
+    let pid: i32 = text.parse().map_err(|_| invalid())?;
+    if pid.to_string() != text {
+        return Err(invalid());
+    }
+    Ok(pid)
+}
+
+fn duplicate_entry(pid: i32) -> ProofError {
+    ProofError::DeserializationError(format!("duplicate entry for pid {} and public key", pid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve;
+    use crate::prover::Prover;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const SID: &str = "epoch-42";
+
+    fn bundle(size: i32) -> ProofBundle {
+        let mut rng = StdRng::seed_from_u64(837);
+        let g = PointJacobi::generator();
+        let mut bundle = ProofBundle::new(SID, g.clone());
+        for pid in 0..size {
+            let x = curve::random_scalar(&mut rng);
+            let y = g.mul_secret(&x);
+            let proof = Prover::prove(SID, pid, &x, &y, &g).unwrap();
+            bundle.push(pid, y, proof).unwrap();
+        }
+        bundle
+    }
+
+    #[test]
+    fn fifty_entries_round_trip() {
+        let bundle = bundle(50);
+        let bytes = bundle.to_bytes().unwrap();
+        let entry_bytes = 1 + 2 + POINT_BYTES + DLogProof::BYTES;
+        assert_eq!(
+            bytes.len(),
+            4 + SID.len() + POINT_BYTES + 4 + 10 * (entry_bytes - 1) + 40 * entry_bytes
+        );
+        let decoded = ProofBundle::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, bundle);
+        assert_eq!(decoded.entries().len(), 50);
+        assert_eq!(decoded.verify_all(), Ok(()));
+    }
+
+    #[test]
+    fn one_bad_proof_fails_the_bundle() {
+        let good = bundle(5);
+        let mut bad = ProofBundle::new(SID, good.base_point.clone());
+        for (i, (pid, y, proof)) in good.entries().iter().enumerate() {
+            // Entry 3's proof is for another participant's key.
+            let y = if i == 3 { &good.entries()[0].1 } else { y };
+            bad.push(*pid, y.clone(), proof.clone()).unwrap();
+        }
+        assert_eq!(bad.verify_all(), Err(ProofError::VerificationFailed));
+        let decoded = ProofBundle::from_bytes(&bad.to_bytes().unwrap()).unwrap();
+        assert_eq!(decoded.verify_all(), Err(ProofError::VerificationFailed));
+    }
+
+    #[test]
+    fn duplicate_entries_are_rejected() {
+        let mut bundle = bundle(3);
+        let (pid, y, proof) = bundle.entries()[1].clone();
+        assert!(matches!(
+            bundle.push(pid, y.clone(), proof.clone()),
+            Err(ProofError::DeserializationError(_))
+        ));
+        // The same key under another pid is a distinct entry.
+        bundle.push(pid + 10, y, proof).unwrap();
+        assert_eq!(bundle.entries().len(), 4);
+    }
+
+    #[test]
+    fn truncated_bundles_are_rejected() {
+        let bundle = bundle(3);
+        let bytes = bundle.to_bytes().unwrap();
+        let count_at = 4 + SID.len() + POINT_BYTES;
+        // The header claims a fourth entry that is not there.
+        let mut overclaimed = bytes.clone();
+        overclaimed[count_at..count_at + 4].copy_from_slice(&4u32.to_be_bytes());
+        let err = ProofBundle::from_bytes(&overclaimed).unwrap_err();
+        assert_eq!(
+            err,
+            ProofError::DeserializationError(
+                "header declares 4 entries but only 3 are present".to_string()
+            )
+        );
+        // A header cut short, and every other truncation.
+        for len in 0..bytes.len() {
+            assert!(matches!(
+                ProofBundle::from_bytes(&bytes[..len]),
+                Err(ProofError::DeserializationError(_))
+            ));
+        }
+        let mut trailing = bytes;
+        trailing.push(0);
+        assert!(ProofBundle::from_bytes(&trailing).is_err());
+    }
+}
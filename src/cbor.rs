@@ -0,0 +1,91 @@
+//! CBOR encoding behind the `cbor` feature.
+//!
+//! Each value is a single tagged byte string holding its canonical binary
+//! encoding:
+//!
+//! ```text
+//! Point:     tag 25712 (bstr, 33 bytes)   compressed SEC1
+//! DLogProof: tag 25713 (bstr, 66 bytes)   DLogProof::to_versioned_bytes
+//! ```
+//!
+//! Decoding is strict: the tag must match, the content must be a byte string
+//! of the exact size, heads and lengths must use their shortest definite
+//! form, and nothing may follow the item.
+
+use crate::error::ProofError;
+use crate::jacobi_point::{Point, POINT_BYTES};
+use crate::proof::DLogProof;
+use ciborium::value::Value;
+
+/// CBOR tag of an encoded [`Point`].
+pub const CBOR_TAG_POINT: u64 = 25712;
+/// CBOR tag of an encoded [`DLogProof`].
+pub const CBOR_TAG_PROOF: u64 = 25713;
+
+fn encode(tag: u64, bytes: &[u8]) -> Vec<u8> {
+    let mut out = vec![];
+    ciborium::into_writer(
+        &Value::Tag(tag, Box::new(Value::Bytes(bytes.to_vec()))),
+        &mut out,
+    )
+    .expect("writing CBOR to a Vec cannot fail");
+    out
+}
+
+fn decode(tag: u64, len: usize, cbor: &[u8]) -> Result<Vec<u8>, ProofError> {
+    let invalid = |msg: String| ProofError::DeserializationError(msg);
+    let mut reader = cbor;
+    let value: Value =
+        ciborium::from_reader(&mut reader).map_err(|e| invalid(format!("invalid CBOR: {}", e)))?;
+    if !reader.is_empty() {
+        return Err(invalid(format!(
+            "{} trailing bytes after CBOR item",
+            reader.len()
+        )));
+    }
+    match value {
+        Value::Tag(actual, content) if actual == tag => match *content {
+            Value::Bytes(bytes) if bytes.len() == len => {
+                // Rules out indefinite lengths and non-minimal heads, which
+                // ciborium accepts, so that every value has one encoding.
+                if encode(tag, &bytes) != cbor {
+                    return Err(invalid("CBOR item is not canonically encoded".to_string()));
+                }
+                Ok(bytes)
+            }
+            Value::Bytes(bytes) => Err(invalid(format!(
+                "expected a {}-byte string, got {} bytes",
+                len,
+                bytes.len()
+            ))),
+            _ => Err(invalid("tagged content is not a byte string".to_string())),
+        },
+        Value::Tag(actual, _) => Err(invalid(format!(
+            "expected CBOR tag {}, found {}",
+            tag, actual
+        ))),
+        _ => Err(invalid(format!("expected CBOR tag {}", tag))),
+    }
+}
+
+impl Point {
+    pub fn to_cbor(&self) -> Vec<u8> {
+        encode(CBOR_TAG_POINT, &self.to_bytes())
+    }
+
+    /// `from_cbor` parses the encoding produced by [`Point::to_cbor`].
+    pub fn from_cbor(cbor: &[u8]) -> Result<Self, ProofError> {
+        Self::from_bytes(&decode(CBOR_TAG_POINT, POINT_BYTES, cbor)?)
+    }
+}
+
+impl DLogProof {
+    pub fn to_cbor(&self) -> Vec<u8> {
+        encode(CBOR_TAG_PROOF, &self.to_versioned_bytes())
+    }
+
+    /// `from_cbor` parses the encoding produced by [`DLogProof::to_cbor`].
+    pub fn from_cbor(cbor: &[u8]) -> Result<Self, ProofError> {
+        Self::from_versioned_bytes(&decode(CBOR_TAG_PROOF, Self::VERSIONED_BYTES, cbor)?)
+    }
+}
@@ -0,0 +1,235 @@
+//! Pedersen commitments `C = x·G + r·H` and a proof of knowledge of an opening.
+//!
+//! `H` is a nothing-up-my-sleeve generator: the first point whose `x`
+//! coordinate is `SHA-256("dlogproof/pedersen/H" || counter)` for a big-endian
+//! `u32` counter counting up from zero, taking the even `y`. Nobody knows the
+//! discrete log of `H` to base `G`, so a commitment hides `x` perfectly and
+//! binds it computationally.
+//!
+//! The proof of opening is a [`RepresentationProof`] over the bases `(G, H)`.
+
+use crate::curve;
+use crate::error::ProofError;
+use crate::jacobi_point::{Point, PointJacobi, POINT_BYTES};
+use crate::linear::RepresentationProof;
+use ibig::{ibig, IBig};
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use std::sync::LazyLock;
+
+static H: LazyLock<PointJacobi> = LazyLock::new(|| nums_point(b"dlogproof/pedersen/H"));
+
+/// `nums_point` hashes `domain` to a point of unknown discrete log: the first
+/// point whose `x` coordinate is `SHA-256(domain || counter)`, for a big-endian
+/// `u32` counter counting up from zero, taking the even `y`.
+pub(crate) fn nums_point(domain: &[u8]) -> PointJacobi {
+    (0u32..)
+        .find_map(|counter| {
+            let digest: [u8; 32] = Sha256::new()
+                .chain_update(domain)
+                .chain_update(counter.to_be_bytes())
+                .finalize()
+                .into();
+            let x = curve::field_from_bytes(&digest);
+            if x >= *curve::P {
+                return None;
+            }
+            let y = curve::sqrt(&(x.pow(3) + ibig!(7)))?;
+            let y = if (&y & 1_u8) == 0 { y } else { curve::rem(&-y) };
+            Some(PointJacobi::from_affine(Point::new(x, y)))
+        })
+        .unwrap()
+}
+
+/// `h` returns the second generator `H` of the commitment scheme.
+pub fn h() -> PointJacobi {
+    H.clone()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PedersenCommitment {
+    pub c: PointJacobi,
+}
+
+impl PedersenCommitment {
+    /// `commit` computes `x·G + r·H`.
+    ///
+    /// Arguments:
+    ///
+    /// * `x`: the committed value
+    /// * `r`: the blinding factor; it must be uniformly random and kept secret
+    ///   for the commitment to hide `x`
+    pub fn commit(x: &IBig, r: &IBig) -> Self {
+        let c = PointJacobi::mul_generator(x).add(&H.mul_secret(r));
+        Self { c }
+    }
+
+    /// `commit_random` commits to `x` with a blinding factor drawn from `rng`
+    /// and returns it alongside the commitment.
+    pub fn commit_random<R: RngCore + CryptoRng>(x: &IBig, rng: &mut R) -> (Self, IBig) {
+        let r = curve::random_scalar(rng);
+        (Self::commit(x, &r), r)
+    }
+
+    /// `open` checks that `(x, r)` is an opening of the commitment.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if `C == x·G + r·H`, `ProofError::VerificationFailed` otherwise.
+    pub fn open(&self, x: &IBig, r: &IBig) -> Result<(), ProofError> {
+        if Self::commit(x, r) == *self {
+            Ok(())
+        } else {
+            Err(ProofError::VerificationFailed)
+        }
+    }
+
+    /// `prove_opening` proves knowledge of `(x, r)` opening the commitment
+    /// without revealing either.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `x`: the committed value
+    /// * `r`: the blinding factor
+    ///
+    /// Returns:
+    ///
+    /// The proof, or `ProofError::InvalidStatement` if `sid` is longer than
+    /// `u32::MAX` bytes.
+    pub fn prove_opening(
+        &self,
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        r: &IBig,
+    ) -> Result<OpeningProof, ProofError> {
+        RepresentationProof::prove(sid, pid, &[x.clone(), r.clone()], &bases(), &self.c)
+    }
+
+    /// `verify_opening` checks a proof made by [`PedersenCommitment::prove_opening`].
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid, `ProofError::VerificationFailed` otherwise.
+    pub fn verify_opening(
+        &self,
+        proof: &OpeningProof,
+        sid: &str,
+        pid: i32,
+    ) -> Result<(), ProofError> {
+        proof.verify(sid, pid, &bases(), &self.c)
+    }
+
+    pub fn to_bytes(&self) -> [u8; POINT_BYTES] {
+        self.c.to_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        Ok(Self {
+            c: PointJacobi::from_bytes(bytes)?,
+        })
+    }
+}
+
+/// Proof of knowledge of `(x, r)` with `C = x·G + r·H`: a
+/// [`RepresentationProof`] over the bases `(G, H)`.
+pub type OpeningProof = RepresentationProof;
+
+fn bases() -> [PointJacobi; 2] {
+    [PointJacobi::generator(), h()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn committed() -> (PedersenCommitment, IBig, IBig) {
+        let mut rng = StdRng::seed_from_u64(1022);
+        let x = curve::random_scalar(&mut rng);
+        let (c, r) = PedersenCommitment::commit_random(&x, &mut rng);
+        (c, x, r)
+    }
+
+    #[test]
+    fn a_commitment_opens_only_to_its_values() {
+        let (c, x, r) = committed();
+        assert_eq!(c.open(&x, &r), Ok(()));
+        assert_eq!(c.open(&(&x + 1), &r), Err(ProofError::VerificationFailed));
+        assert_eq!(c.open(&x, &(&r + 1)), Err(ProofError::VerificationFailed));
+        assert_eq!(c.open(&r, &x), Err(ProofError::VerificationFailed));
+    }
+
+    #[test]
+    fn h_is_a_fixed_point_unrelated_to_g() {
+        let h = h();
+        assert!(h.is_on_curve());
+        assert_ne!(h, PointJacobi::generator());
+        assert_eq!(h, nums_point(b"dlogproof/pedersen/H"));
+        assert_ne!(h, nums_point(b"dlogproof/pedersen/H2"));
+        assert_eq!(&h.to_affine().y & 1_u8, 0);
+    }
+
+    #[test]
+    fn an_honest_opening_proof_verifies() {
+        let (c, x, r) = committed();
+        let proof = c.prove_opening("pedersen", 1, &x, &r).unwrap();
+        assert_eq!(c.verify_opening(&proof, "pedersen", 1), Ok(()));
+    }
+
+    #[test]
+    fn an_opening_proof_is_bound_to_its_statement() {
+        let (c, x, r) = committed();
+        let proof = c.prove_opening("pedersen", 1, &x, &r).unwrap();
+        let other = PedersenCommitment::commit(&x, &(&r + 1));
+        assert_eq!(
+            other.verify_opening(&proof, "pedersen", 1),
+            Err(ProofError::VerificationFailed)
+        );
+        let other_h = [
+            PointJacobi::generator(),
+            nums_point(b"dlogproof/pedersen/H2"),
+        ];
+        assert_eq!(
+            proof.verify("pedersen", 1, &other_h, &c.c),
+            Err(ProofError::VerificationFailed)
+        );
+        assert_eq!(
+            c.verify_opening(&proof, "other", 1),
+            Err(ProofError::VerificationFailed)
+        );
+        assert_eq!(
+            c.verify_opening(&proof, "pedersen", 2),
+            Err(ProofError::VerificationFailed)
+        );
+        let wrong = c.prove_opening("pedersen", 1, &(&x + 1), &r).unwrap();
+        assert_eq!(
+            c.verify_opening(&wrong, "pedersen", 1),
+            Err(ProofError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn commitments_and_proofs_round_trip_through_bytes() {
+        let (c, x, r) = committed();
+        let decoded = PedersenCommitment::from_bytes(&c.to_bytes()).unwrap();
+        assert_eq!(decoded, c);
+        assert_eq!(decoded.open(&x, &r), Ok(()));
+
+        let proof = c.prove_opening("pedersen", 1, &x, &r).unwrap();
+        let bytes = proof.to_bytes();
+        let proof = OpeningProof::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.verify_opening(&proof, "pedersen", 1), Ok(()));
+        assert!(matches!(
+            OpeningProof::from_bytes(&bytes[1..]),
+            Err(ProofError::DeserializationError(_))
+        ));
+        assert!(matches!(
+            PedersenCommitment::from_bytes(&c.to_bytes()[1..]),
+            Err(ProofError::DeserializationError(_))
+        ));
+    }
+}
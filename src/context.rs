@@ -0,0 +1,135 @@
+//! The data a proof is bound to, built once per session.
+//!
+//! A [`ProofContext`] gathers the session id, participant id and any
+//! application data that would otherwise be passed loose to every call, checks
+//! them once, and derives the challenge transcript from them the same way for
+//! every proof made or checked with it. Prove with
+//! [`Prover::prove_in_context`](crate::Prover::prove_in_context) and check with
+//! [`Verifier::verify_in_context`](crate::Verifier::verify_in_context).
+//!
+//! The transcript is tagged [`CONTEXT_TAG`] and carries `aux_data` as the
+//! message labelled `aux_data`, so a context proof is never a plain,
+//! time-bound or message-bound proof, even with empty `aux_data`. The
+//! canonical encoding is
+//!
+//! ```text
+//! sid_len: u32 BE | sid | pid: i32 BE | aux_len: u32 BE | aux_data
+//! ```
+
+use crate::curve::Curve;
+use crate::error::ProofError;
+use crate::hash::CONTEXT_TAG;
+use crate::transcript::Transcript;
+
+/// A session id, participant id and application data bound into proofs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProofContext {
+    sid: String,
+    pid: i32,
+    aux_data: Vec<u8>,
+}
+
+impl ProofContext {
+    /// `new` builds a context with no application data.
+    ///
+    /// Returns:
+    ///
+    /// The context, or `ProofError::InvalidStatement` if `sid` is empty, since
+    /// a proof bound to no session can be replayed into any.
+    pub fn new(sid: &str, pid: i32) -> Result<Self, ProofError> {
+        Self::with_aux_data(sid, pid, &[])
+    }
+
+    /// `with_aux_data` is [`ProofContext::new`] with application data, e.g. a
+    /// protocol round or a hash of the surrounding message.
+    ///
+    /// Returns:
+    ///
+    /// The context, or `ProofError::InvalidStatement` if `sid` is empty or
+    /// `sid` or `aux_data` is longer than `u32::MAX` bytes.
+    pub fn with_aux_data(sid: &str, pid: i32, aux_data: &[u8]) -> Result<Self, ProofError> {
+        if sid.is_empty() {
+            return Err(ProofError::InvalidStatement(
+                "session id is empty".to_string(),
+            ));
+        }
+        if u32::try_from(sid.len()).is_err() || u32::try_from(aux_data.len()).is_err() {
+            return Err(ProofError::InvalidStatement(
+                "context field longer than u32::MAX bytes".to_string(),
+            ));
+        }
+        Ok(Self {
+            sid: sid.to_string(),
+            pid,
+            aux_data: aux_data.to_vec(),
+        })
+    }
+
+    pub fn sid(&self) -> &str {
+        &self.sid
+    }
+
+    pub fn pid(&self) -> i32 {
+        self.pid
+    }
+
+    pub fn aux_data(&self) -> &[u8] {
+        &self.aux_data
+    }
+
+    /// `transcript` starts the challenge transcript of a proof in this
+    /// context, before the statement's points are appended.
+    pub fn transcript<C: Curve>(&self) -> Transcript<C> {
+        let mut transcript = Transcript::new(CONTEXT_TAG, &self.sid, self.pid);
+        transcript.append_message(b"aux_data", &self.aux_data);
+        transcript
+    }
+
+    /// `to_bytes` returns the canonical encoding described in
+    /// [`crate::context`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(12 + self.sid.len() + self.aux_data.len());
+        out.extend((self.sid.len() as u32).to_be_bytes());
+        out.extend(self.sid.as_bytes());
+        out.extend(self.pid.to_be_bytes());
+        out.extend((self.aux_data.len() as u32).to_be_bytes());
+        out.extend(&self.aux_data);
+        out
+    }
+
+    /// `from_bytes` parses the encoding produced by [`ProofContext::to_bytes`].
+    ///
+    /// Returns:
+    ///
+    /// The context, a `DeserializationError` if the input is truncated, has
+    /// trailing bytes or a `sid` that is not UTF-8, or the errors of
+    /// [`ProofContext::with_aux_data`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        let mut rest = bytes;
+        let mut take = |len: usize| -> Result<&[u8], ProofError> {
+            if rest.len() < len {
+                return Err(ProofError::DeserializationError(format!(
+                    "unexpected end of input: needed {} bytes, {} left",
+                    len,
+                    rest.len()
+                )));
+            }
+            let (head, tail) = rest.split_at(len);
+            rest = tail;
+            Ok(head)
+        };
+        let sid_len = u32::from_be_bytes(take(4)?.try_into().unwrap()) as usize;
+        let sid = std::str::from_utf8(take(sid_len)?)
+            .map_err(|_| ProofError::DeserializationError("sid is not valid UTF-8".to_string()))?;
+        let pid = i32::from_be_bytes(take(4)?.try_into().unwrap());
+        let aux_len = u32::from_be_bytes(take(4)?.try_into().unwrap()) as usize;
+        let aux_data = take(aux_len)?;
+        if !rest.is_empty() {
+            return Err(ProofError::DeserializationError(format!(
+                "{} trailing bytes after the context",
+                rest.len()
+            )));
+        }
+        Self::with_aux_data(sid, pid, aux_data)
+    }
+}
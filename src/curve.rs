@@ -0,0 +1,449 @@
+//! secp256k1 domain parameters and modular helpers shared by the point
+//! arithmetic and the proof system, and the [`Curve`] trait that points,
+//! proofs, the prover and the verifier are generic over.
+
+use crate::error::ProofError;
+use crate::field::{FieldElement, Scalar};
+use crate::jacobi_point::{self, PointJacobi};
+use ibig::{ibig, modular::ModuloRing, IBig, UBig};
+use rand::{CryptoRng, RngCore};
+use std::fmt::Debug;
+use std::sync::LazyLock;
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroizing;
+
+/// A short Weierstrass curve `y² = x³ + a·x + b` over a prime field, with a
+/// group of prime order `n` generated by `G`.
+///
+/// [`PointJacobi`], [`crate::Point`], [`crate::DLogProof`], [`crate::Prover`]
+/// and [`crate::Verifier`] take the curve as a type parameter defaulting to
+/// [`Secp256k1`]. Field elements and scalars must fit in [`SCALAR_BYTES`]
+/// bytes, so points keep the 33-byte compressed encoding, and `p` must be
+/// `3 mod 4`, so that decompression takes one exponentiation.
+///
+/// The provided methods implement the arithmetic on `IBig`s from the
+/// parameters alone. A curve with a faster backend overrides them, as
+/// [`Secp256k1`] does with fixed-limb field elements and its endomorphism.
+pub trait Curve: Copy + Debug + Default + PartialEq + Eq + Send + Sync + 'static {
+    /// The field modulus `p`.
+    fn p() -> &'static IBig;
+    /// The order `n` of the group generated by `G`.
+    fn n() -> &'static IBig;
+    /// The coefficient `a`.
+    fn a() -> &'static IBig;
+    /// The coefficient `b`.
+    fn b() -> &'static IBig;
+    /// The affine coordinates of the generator `G`.
+    fn generator() -> (&'static IBig, &'static IBig);
+
+    /// Reduces `a` into `[0, p)`.
+    fn rem(a: &IBig) -> IBig {
+        rem_mod(a, Self::p())
+    }
+
+    /// Reduces `a` into `[0, n)`.
+    fn rem_n(a: &IBig) -> IBig {
+        rem_mod(a, Self::n())
+    }
+
+    /// Returns `a² mod p`.
+    fn sqr(a: &IBig) -> IBig {
+        Self::rem(&(a * a))
+    }
+
+    /// Inverts `a` modulo `p`, in variable time. Zero maps to zero.
+    fn invert(a: &IBig) -> IBig {
+        let ring = ModuloRing::new(&UBig::try_from(Self::p()).unwrap());
+        ring.from(Self::rem(a))
+            .inverse()
+            .map_or(ibig!(0), |inv| IBig::from(inv.residue()))
+    }
+
+    /// Inverts `a` modulo `p` as `a^(p-2)`, for points whose `z` coordinate
+    /// depends on a secret. Zero maps to zero. This default runs on `IBig`
+    /// modular arithmetic, whose timing depends on `a`, so it is not
+    /// constant-time; secp256k1 overrides it with the fixed-limb
+    /// [`invert_ct`].
+    fn invert_ct(a: &IBig) -> IBig {
+        let p = Self::p();
+        let ring = ModuloRing::new(&UBig::try_from(p).unwrap());
+        let exp = UBig::try_from(p - ibig!(2)).unwrap();
+        IBig::from(ring.from(Self::rem(a)).pow(&exp).residue())
+    }
+
+    /// Computes a square root of `a` modulo `p`, if one exists, as
+    /// `a^((p + 1) / 4)`.
+    fn sqrt(a: &IBig) -> Option<IBig> {
+        sqrt_mod(&Self::rem(a), Self::p())
+    }
+
+    fn double(p: &PointJacobi<Self>) -> PointJacobi<Self> {
+        jacobi_point::double_ibig(p)
+    }
+
+    fn add(p: &PointJacobi<Self>, q: &PointJacobi<Self>) -> PointJacobi<Self> {
+        jacobi_point::add_ibig(p, q)
+    }
+
+    /// `k·P` for a public `k`; see [`PointJacobi::mul_unsafe`].
+    fn mul_unsafe(p: &PointJacobi<Self>, k: &IBig) -> PointJacobi<Self> {
+        jacobi_point::mul_ibig(p, k)
+    }
+
+    /// `a·P + b·Q` for public scalars; see [`PointJacobi::mul_double_base`].
+    fn mul_double_base(
+        p: &PointJacobi<Self>,
+        a: &IBig,
+        q: &PointJacobi<Self>,
+        b: &IBig,
+    ) -> PointJacobi<Self> {
+        Self::add(&Self::mul_unsafe(p, a), &Self::mul_unsafe(q, b))
+    }
+
+    /// `k·P` for a secret `k`; see [`PointJacobi::mul_secret`].
+    fn mul_secret(p: &PointJacobi<Self>, k: &IBig) -> PointJacobi<Self> {
+        jacobi_point::ladder_ibig(p, k)
+    }
+
+    /// `k·G` for a secret `k`.
+    fn mul_generator(k: &IBig) -> PointJacobi<Self> {
+        Self::mul_secret(&PointJacobi::generator(), k)
+    }
+
+    /// Rescales every point to `z = 1`, leaving points at infinity as they
+    /// are.
+    fn normalize_batch(points: &mut [PointJacobi<Self>]) {
+        points.iter_mut().for_each(PointJacobi::normalize);
+    }
+
+    /// Compares two points in constant time, whatever their `z` coordinates.
+    fn ct_eq(p: &PointJacobi<Self>, q: &PointJacobi<Self>) -> Choice {
+        jacobi_point::ct_eq_ibig(p, q)
+    }
+}
+
+/// The secp256k1 curve, `y² = x³ + 7`, whose parameters are the statics of
+/// this module. Its [`Curve`] implementation, in [`crate::jacobi_point`],
+/// runs on fixed-limb field elements and uses the GLV endomorphism.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Secp256k1;
+
+/// The field modulus `p = 2^256 - 2^32 - 977`.
+pub static P: LazyLock<IBig> = LazyLock::new(FieldElement::modulus);
+/// The order `n` of the group generated by `G`.
+pub static N: LazyLock<IBig> = LazyLock::new(Scalar::modulus);
+/// Cube root of unity in the base field used by the GLV endomorphism.
+pub static BETA: LazyLock<IBig> = LazyLock::new(|| BETA_FIELD.to_ibig());
+static P_RING: LazyLock<ModuloRing> =
+    LazyLock::new(|| ModuloRing::new(&UBig::try_from(&*P).unwrap()));
+pub(crate) static A: LazyLock<IBig> = LazyLock::new(|| ibig!(0));
+pub(crate) static B: LazyLock<IBig> = LazyLock::new(|| ibig!(7));
+pub(crate) static G: LazyLock<(IBig, IBig)> = LazyLock::new(|| (GX.to_ibig(), GY.to_ibig()));
+
+/// The affine `x` coordinate of the generator `G`.
+pub(crate) const GX: FieldElement = FieldElement::from_limbs([
+    0x59F2_815B_16F8_1798,
+    0x029B_FCDB_2DCE_28D9,
+    0x55A0_6295_CE87_0B07,
+    0x79BE_667E_F9DC_BBAC,
+]);
+/// The affine `y` coordinate of the generator `G`.
+pub(crate) const GY: FieldElement = FieldElement::from_limbs([
+    0x9C47_D08F_FB10_D4B8,
+    0xFD17_B448_A685_5419,
+    0x5DA4_FBFC_0E11_08A8,
+    0x483A_DA77_26A3_C465,
+]);
+/// [`BETA`] as a field element.
+pub(crate) const BETA_FIELD: FieldElement = FieldElement::from_limbs([
+    0xC139_6C28_7195_01EE,
+    0x9CF0_4975_12F5_8995,
+    0x6E64_479E_AC34_34E9,
+    0x7AE9_6A2B_657C_0710,
+]);
+
+/// Length in bytes of a canonically encoded scalar.
+pub const SCALAR_BYTES: usize = 32;
+
+/// Reduces `a` into `[0, p)`.
+///
+/// Point arithmetic does not come through here: it runs on [`FieldElement`],
+/// whose products are reduced by folding with `2^256 ≡ 2^32 + 977`. For a
+/// lone `IBig`, converting to limbs and back costs more than the division it
+/// would save.
+pub fn rem(a: &IBig) -> IBig {
+    rem_mod(a, &P)
+}
+
+/// Returns `a² mod p`, with the dedicated squaring of [`FieldElement`].
+pub fn sqr(a: &IBig) -> IBig {
+    FieldElement::from_ibig(a).square().to_ibig()
+}
+
+/// Reduces `a` into `[0, n)`.
+///
+/// [`Scalar::reduce`] reduces values below `2^512`, such as `r + c·x`,
+/// by Barrett reduction on fixed limbs instead. As with [`rem`], converting
+/// an `IBig` to limbs and back costs more than the division saves, so
+/// `IBig` callers stay here.
+pub fn rem_n(a: &IBig) -> IBig {
+    rem_mod(a, &N)
+}
+
+/// Inverts `number` modulo `p` with the binary extended Euclidean algorithm
+/// on fixed-limb field elements. Its running time depends on `number`.
+/// Zero maps to zero.
+pub fn invert(number: &IBig) -> IBig {
+    FieldElement::from_ibig(number).invert_vartime().to_ibig()
+}
+
+/// Inverts `number` modulo `p` as `number^(p-2)` (Fermat's little theorem)
+/// on fixed-limb field elements; see [`FieldElement::invert`]. The exponent
+/// is fixed, so the sequence of field operations does not depend on
+/// `number`, unlike [`invert`]; only the conversions from and back to
+/// `IBig` at either end do. It is several times slower; use it where
+/// `number` is derived from a secret. Zero maps to zero.
+pub fn invert_ct(number: &IBig) -> IBig {
+    FieldElement::from_ibig(number).invert().to_ibig()
+}
+
+/// Inverts every number modulo `p` with a single inversion; see
+/// [`FieldElement::invert_batch`]. Zeros map to zero.
+pub fn invert_batch(numbers: &[IBig]) -> Vec<IBig> {
+    let values: Vec<FieldElement> = numbers.iter().map(FieldElement::from_ibig).collect();
+    FieldElement::invert_batch(&values)
+        .iter()
+        .map(FieldElement::to_ibig)
+        .collect()
+}
+
+/// Inverts `number` modulo the group order `n`, in variable time. Zero maps
+/// to zero.
+pub fn invert_n(number: &IBig) -> IBig {
+    Scalar::from_ibig(number).invert_vartime().to_ibig()
+}
+
+/// Computes a square root of `a` modulo `p`, if one exists.
+///
+/// Since `p ≡ 3 (mod 4)` the candidate root is `a^((p + 1) / 4)`.
+pub fn sqrt(a: &IBig) -> Option<IBig> {
+    let a = rem(a);
+    let exp = UBig::try_from((&*P + ibig!(1)) / ibig!(4)).unwrap();
+    let root = IBig::from(P_RING.from(&a).pow(&exp).residue());
+    if rem(&(&root * &root)) == a {
+        Some(root)
+    } else {
+        None
+    }
+}
+
+/// `a mod m` in `[0, m)`.
+pub(crate) fn rem_mod(a: &IBig, m: &IBig) -> IBig {
+    let r = a % m;
+    if r < IBig::from(0) {
+        m + r
+    } else {
+        r
+    }
+}
+
+/// A square root of `a`, reduced modulo the prime `p ≡ 3 (mod 4)`, if one
+/// exists.
+fn sqrt_mod(a: &IBig, p: &IBig) -> Option<IBig> {
+    let ring = ModuloRing::new(&UBig::try_from(p).unwrap());
+    let exp = UBig::try_from((p + ibig!(1)) / ibig!(4)).unwrap();
+    let root = IBig::from(ring.from(a).pow(&exp).residue());
+    (rem_mod(&(&root * &root), p) == *a).then_some(root)
+}
+
+/// Encodes a scalar as 32 big-endian bytes after reducing it modulo `n`.
+pub fn scalar_to_bytes(scalar: &IBig) -> [u8; SCALAR_BYTES] {
+    to_be_bytes(&rem_n(scalar))
+}
+
+/// Compares two scalars through their encodings in constant time. A value
+/// outside `[0, n)` is unequal to everything, as it is under `==`.
+pub fn scalar_ct_eq(a: &IBig, b: &IBig) -> Choice {
+    let reduced = |v: &IBig| *v >= IBig::from(0) && *v < *N;
+    if !reduced(a) || !reduced(b) {
+        return Choice::from(0);
+    }
+    scalar_to_bytes(a).ct_eq(&scalar_to_bytes(b))
+}
+
+/// Decodes 32 big-endian bytes into a scalar without reducing it.
+pub fn scalar_from_bytes(bytes: &[u8; SCALAR_BYTES]) -> IBig {
+    IBig::from(UBig::from_be_bytes(bytes))
+}
+
+/// Decodes the canonical 32-byte big-endian encoding of a scalar, rejecting
+/// any other length and values not below `n` so that every scalar has exactly
+/// one encoding.
+pub fn scalar_from_canonical_bytes(bytes: &[u8]) -> Result<IBig, ProofError> {
+    canonical_scalar::<Secp256k1>(bytes)
+}
+
+/// [`scalar_from_canonical_bytes`] for the order of any [`Curve`].
+pub(crate) fn canonical_scalar<C: Curve>(bytes: &[u8]) -> Result<IBig, ProofError> {
+    let bytes: &[u8; SCALAR_BYTES] = bytes.try_into().map_err(|_| {
+        ProofError::DeserializationError(format!(
+            "expected {} scalar bytes, got {}",
+            SCALAR_BYTES,
+            bytes.len()
+        ))
+    })?;
+    let scalar = scalar_from_bytes(bytes);
+    if scalar >= *C::n() {
+        return Err(ProofError::DeserializationError(
+            "scalar is not reduced modulo the curve order".to_string(),
+        ));
+    }
+    Ok(scalar)
+}
+
+/// Encodes a field element as 32 big-endian bytes after reducing it modulo `p`.
+pub fn field_to_bytes(a: &IBig) -> [u8; SCALAR_BYTES] {
+    to_be_bytes(&rem(a))
+}
+
+/// Decodes 32 big-endian bytes into a field element without reducing it.
+pub fn field_from_bytes(bytes: &[u8; SCALAR_BYTES]) -> IBig {
+    IBig::from(UBig::from_be_bytes(bytes))
+}
+
+/// Encodes a value in `[0, 2^256)` as 32 big-endian bytes.
+pub(crate) fn to_be_bytes(reduced: &IBig) -> [u8; SCALAR_BYTES] {
+    let be = UBig::try_from(reduced).unwrap().to_be_bytes();
+    let mut out = [0u8; SCALAR_BYTES];
+    out[SCALAR_BYTES - be.len()..].copy_from_slice(&be);
+    out
+}
+
+/// Samples a uniformly random scalar in `[1, n)` by rejection sampling.
+pub fn random_scalar<R: RngCore + CryptoRng>(rng: &mut R) -> IBig {
+    random_below(rng, &N)
+}
+
+/// Samples a uniformly random value in `[1, n)` for an `n` of at most 256
+/// bits. Candidates are drawn with as many bits as `n`, so that at least
+/// half of them are accepted.
+pub(crate) fn random_below<R: RngCore + CryptoRng>(rng: &mut R, n: &IBig) -> IBig {
+    let excess = 8 * SCALAR_BYTES - UBig::try_from(n).unwrap().bit_len();
+    loop {
+        let mut bytes = Zeroizing::new([0u8; SCALAR_BYTES]);
+        rng.fill_bytes(bytes.as_mut());
+        bytes[..excess / 8].fill(0);
+        bytes[excess / 8] &= 0xff >> (excess % 8);
+        let k = scalar_from_bytes(&bytes);
+        if k != IBig::from(0) && k < *n {
+            return k;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn constant_time_inversion_matches_the_variable_time_one() {
+        let mut rng = StdRng::seed_from_u64(1019);
+        let mut values = vec![ibig!(0), ibig!(1), ibig!(2), &*P - 1, &*P + 3, ibig!(-5)];
+        values.extend((0..64).map(|_| random_below(&mut rng, &P)));
+        for a in &values {
+            let inverse = invert_ct(a);
+            assert_eq!(inverse, invert(a));
+            assert_eq!(Secp256k1::invert_ct(a), inverse);
+            if rem(a) != ibig!(0) {
+                assert_eq!(rem(&(inverse * a)), ibig!(1));
+            }
+        }
+        for a in values
+            .iter()
+            .map(|a| random_below(&mut rng, crate::P256::p()) + a)
+        {
+            assert_eq!(crate::P256::invert_ct(&a), crate::P256::invert(&a));
+        }
+    }
+
+    /// Hands out the given 32-byte candidates in order.
+    struct Scripted(Vec<[u8; SCALAR_BYTES]>);
+
+    impl RngCore for Scripted {
+        fn next_u32(&mut self) -> u32 {
+            unimplemented!()
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            unimplemented!()
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            dest.copy_from_slice(&self.0.remove(0));
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for Scripted {}
+
+    #[test]
+    fn out_of_range_candidates_are_rejected() {
+        let n_minus_1 = scalar_to_bytes(&(&*N - 1));
+        let mut rng = Scripted(vec![
+            [0; SCALAR_BYTES],
+            [0xff; SCALAR_BYTES],
+            scalar_to_bytes(&N),
+            n_minus_1,
+            [0x11; SCALAR_BYTES],
+        ]);
+        assert_eq!(random_scalar(&mut rng), &*N - 1);
+        assert_eq!(
+            random_scalar(&mut rng),
+            scalar_from_bytes(&[0x11; SCALAR_BYTES])
+        );
+    }
+
+    #[test]
+    fn nonces_span_the_full_range() {
+        let nonces: Vec<IBig> = (0..256)
+            .map(|_| crate::Prover::generate_random_nonce())
+            .collect();
+        assert!(nonces.iter().all(|k| *k >= ibig!(1) && *k < *N));
+        // About half have bit 255 set, and none is small: the old sampler
+        // drew from [1, 10^9).
+        let top = nonces.iter().filter(|k| *k >= &(ibig!(1) << 255)).count();
+        assert!(
+            (64..=192).contains(&top),
+            "{top} of 256 nonces have bit 255 set"
+        );
+        assert!(nonces.iter().all(|k| *k > ibig!(1) << 192));
+        // Every byte of the encoding varies.
+        let encodings: Vec<_> = nonces.iter().map(scalar_to_bytes).collect();
+        for i in 0..SCALAR_BYTES {
+            assert!(
+                encodings.iter().any(|e| e[i] != encodings[0][i]),
+                "byte {i} is fixed"
+            );
+        }
+    }
+
+    #[test]
+    fn small_bounds_are_sampled_uniformly() {
+        let mut rng = StdRng::seed_from_u64(1011);
+        let mut counts = [0usize; 7];
+        for _ in 0..7000 {
+            let k = random_below(&mut rng, &ibig!(7));
+            counts[usize::try_from(&k).unwrap()] += 1;
+        }
+        assert_eq!(counts[0], 0);
+        for count in &counts[1..] {
+            assert!((1000..1350).contains(count), "{counts:?}");
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! Short Weierstrass curves given by their parameters, for instantiating the
+//! protocol over a curve this crate does not ship.
+//!
+//! A curve is still a type: implement [`CustomCurve`] for a marker type
+//! returning its [`CurveParams`], and every [`Curve`] method follows from
+//! them, including a GLV split of public scalars when the parameters carry
+//! an [`Endomorphism`]. Check the parameters once with
+//! [`CustomCurve::validate`] before use:
+//!
+//! ```text
+//! #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+//! struct MyCurve;
+//!
+//! static PARAMS: LazyLock<CurveParams> = LazyLock::new(|| CurveParams { ... });
+//!
+//! impl CustomCurve for MyCurve {
+//!     fn params() -> &'static CurveParams {
+//!         &PARAMS
+//!     }
+//! }
+//!
+//! MyCurve::validate()?;
+//! let g = PointJacobi::<MyCurve>::generator();
+//! ```
+
+use crate::curve::{Curve, SCALAR_BYTES};
+use crate::error::ProofError;
+use crate::jacobi_point::{self, PointJacobi};
+use ibig::{ibig, IBig, UBig};
+use std::fmt::Debug;
+
+/// The parameters of `y² = x³ + a·x + b` over the prime field of order `p`,
+/// with a generator `(gx, gy)` of prime order `n`.
+///
+/// `p` must be `3 mod 4` and both `p` and `n` must fit in 256 bits; see
+/// [`Curve`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurveParams {
+    pub p: IBig,
+    pub n: IBig,
+    pub a: IBig,
+    pub b: IBig,
+    pub gx: IBig,
+    pub gy: IBig,
+    pub endomorphism: Option<Endomorphism>,
+}
+
+/// An endomorphism `φ(x, y) = (β·x, y)` that acts on the group as
+/// multiplication by `λ`, as on curves with `a = 0` and `p ≡ 1 mod 3`, and a
+/// reduced basis `(a1, b1)`, `(a2, b2)` of the lattice of `(x, y)` with
+/// `x + y·λ ≡ 0 (mod n)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endomorphism {
+    pub beta: IBig,
+    pub lambda: IBig,
+    pub a1: IBig,
+    pub b1: IBig,
+    pub a2: IBig,
+    pub b2: IBig,
+}
+
+/// A curve defined by a [`CurveParams`].
+pub trait CustomCurve: Copy + Debug + Default + PartialEq + Eq + Send + Sync + 'static {
+    fn params() -> &'static CurveParams;
+
+    /// `validate` checks what the arithmetic relies on. The primality of `p`
+    /// and `n` is not checked.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())`, or `ProofError::InvalidStatement` naming the first check
+    /// that fails: the sizes of `p` and `n`, `p ≡ 3 mod 4`, a non-zero
+    /// discriminant, `G` on the curve with order `n`, and for an
+    /// endomorphism `β³ = 1`, `λ·G = φ(G)` and the lattice basis.
+    fn validate() -> Result<(), ProofError> {
+        let params = Self::params();
+        let (p, n) = (&params.p, &params.n);
+        let invalid = |msg: &str| Err(ProofError::InvalidStatement(msg.to_string()));
+        let fits =
+            |v: &IBig| *v > ibig!(3) && UBig::try_from(v).unwrap().bit_len() <= 8 * SCALAR_BYTES;
+        if !fits(p) || !fits(n) {
+            return invalid("p and n must be above 3 and fit in 256 bits");
+        }
+        if p % ibig!(4) != ibig!(3) {
+            return invalid("p is not 3 mod 4");
+        }
+        let discriminant = ibig!(4) * params.a.pow(3) + ibig!(27) * params.b.pow(2);
+        if Self::rem(&discriminant) == ibig!(0) {
+            return invalid("the curve is singular");
+        }
+        let g = PointJacobi::<Self>::generator();
+        let in_field = |v: &IBig| *v >= ibig!(0) && v < p;
+        if !in_field(&params.gx) || !in_field(&params.gy) || !g.is_on_curve() {
+            return invalid("the generator is not on the curve");
+        }
+        if !jacobi_point::mul_ibig(&g, &(n - ibig!(1)))
+            .add(&g)
+            .is_zero()
+        {
+            return invalid("the generator does not have order n");
+        }
+        if let Some(endo) = &params.endomorphism {
+            let beta = Self::rem(&endo.beta);
+            if beta == ibig!(1) || Self::rem(&beta.pow(3)) != ibig!(1) {
+                return invalid("beta is not a primitive cube root of unity");
+            }
+            let phi = PointJacobi::<Self>::new(
+                Self::rem(&(&beta * &params.gx)),
+                params.gy.clone(),
+                ibig!(1),
+            );
+            if jacobi_point::mul_ibig(&g, &endo.lambda) != phi {
+                return invalid("lambda·G is not (beta·x, y)");
+            }
+            let on_lattice = |a: &IBig, b: &IBig| Self::rem_n(&(a + b * &endo.lambda)) == ibig!(0);
+            if !on_lattice(&endo.a1, &endo.b1) || !on_lattice(&endo.a2, &endo.b2) {
+                return invalid("the basis is not on the lattice of lambda");
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<C: CustomCurve> Curve for C {
+    fn p() -> &'static IBig {
+        &C::params().p
+    }
+
+    fn n() -> &'static IBig {
+        &C::params().n
+    }
+
+    fn a() -> &'static IBig {
+        &C::params().a
+    }
+
+    fn b() -> &'static IBig {
+        &C::params().b
+    }
+
+    fn generator() -> (&'static IBig, &'static IBig) {
+        let params = C::params();
+        (&params.gx, &params.gy)
+    }
+
+    fn mul_unsafe(p: &PointJacobi<Self>, k: &IBig) -> PointJacobi<Self> {
+        match &C::params().endomorphism {
+            Some(endo) => jacobi_point::mul_endo_ibig(
+                p,
+                k,
+                &endo.beta,
+                [&endo.a1, &endo.b1, &endo.a2, &endo.b2],
+            ),
+            None => jacobi_point::mul_ibig(p, k),
+        }
+    }
+}
@@ -0,0 +1,600 @@
+//! Distributed key generation: `n` participants jointly create a key `Y = x·G`
+//! whose secret `x` is Shamir-shared among them with threshold `t + 1` and
+//! never held by anyone, in the style of Pedersen's DKG with the proofs of
+//! knowledge of Gennaro, Jarecki, Krawczyk and Rabin.
+//!
+//! Every participant `i` acts as a [`crate::vss`] dealer of a random secret
+//! `a_i0`:
+//!
+//! 1. It broadcasts a [`Round1Broadcast`] with its commitments `A_ij` and a
+//!    proof of knowledge of `a_i0` under the session id and its own index,
+//!    which keeps anyone from choosing `A_i0` as a function of the others'.
+//! 2. It sends each other participant `j` the [`Round2Share`] `f_i(j)`
+//!    privately. A recipient that finds the share inconsistent with `A_i`
+//!    broadcasts a [`Complaint`]; the accused answers with a
+//!    [`ShareReveal`] of that share, and is disqualified if it does not or
+//!    the revealed share is also wrong.
+//!
+//! The qualified participants' secrets add up to `x`: each participant's
+//! share of `x` is `Σ f_i(j)` and the group commitments are `Σ A_ik`, so
+//! [`crate::vss::share_public_key`] gives every share's public key and
+//! [`crate::threshold`] can prove knowledge of `x` without reconstructing it.
+
+use crate::curve;
+use crate::error::ProofError;
+use crate::jacobi_point::PointJacobi;
+use crate::proof::DLogProof;
+use crate::vss::{check_indices, share_public_key, verify_dealer_proof, verify_share, Polynomial};
+use ibig::IBig;
+use rand::{CryptoRng, RngCore};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Round-one message, broadcast: the sender's commitments and proof of
+/// knowledge of its secret.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round1Broadcast {
+    pub sender: u32,
+    pub commitments: Vec<PointJacobi>,
+    pub proof: DLogProof,
+}
+
+/// Round-two message, sent privately: the sender's share for `recipient`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round2Share {
+    pub sender: u32,
+    pub recipient: u32,
+    pub share: IBig,
+}
+
+/// Broadcast by `accuser` when the share `accused` sent it is wrong or missing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Complaint {
+    pub accuser: u32,
+    pub accused: u32,
+}
+
+/// Broadcast by the accused in answer to a [`Complaint`]: the disputed share.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShareReveal {
+    pub sender: u32,
+    pub recipient: u32,
+    pub share: IBig,
+}
+
+/// The result of a successful run.
+#[derive(Debug, Clone)]
+pub struct DkgOutput {
+    pub index: u32,
+    /// This participant's share of the joint secret.
+    pub share: IBig,
+    /// The joint public key `Y`.
+    pub public_key: PointJacobi,
+    /// The group commitments `Σ A_ik`; the first is `public_key`.
+    pub commitments: Vec<PointJacobi>,
+    /// The participants whose contributions make up the key.
+    pub qualified: Vec<u32>,
+}
+
+/// One participant's state across the rounds.
+pub struct DkgParticipant {
+    sid: String,
+    index: u32,
+    participants: Vec<u32>,
+    polynomial: Polynomial,
+    broadcasts: BTreeMap<u32, Round1Broadcast>,
+    shares: BTreeMap<u32, IBig>,
+    disqualified: BTreeSet<u32>,
+}
+
+impl DkgParticipant {
+    /// `new` starts a run and returns this participant's round-one broadcast.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id, the same for every participant
+    /// * `index`: this participant's index
+    /// * `degree`: the degree `t` of the sharing; any `t + 1` shares
+    ///   determine the secret
+    /// * `participants`: every participant's index, including this one
+    /// * `rng`: source of the secret and polynomial coefficients
+    ///
+    /// Returns:
+    ///
+    /// The state and the broadcast, `ProofError::InvalidStatement` if `index`
+    /// is not a participant or there are not more than `degree` of them, or
+    /// an error naming an index that is zero or repeated.
+    pub fn new<R: RngCore + CryptoRng>(
+        sid: &str,
+        index: u32,
+        degree: usize,
+        participants: &[u32],
+        rng: &mut R,
+    ) -> Result<(Self, Round1Broadcast), ProofError> {
+        check_indices(participants)?;
+        if !participants.contains(&index) {
+            return Err(ProofError::InvalidStatement(format!(
+                "{} is not a participant",
+                index
+            )));
+        }
+        if participants.len() <= degree {
+            return Err(ProofError::InvalidStatement(format!(
+                "{} participants for degree {}",
+                participants.len(),
+                degree
+            )));
+        }
+        let secret = curve::random_scalar(rng);
+        let polynomial = Polynomial::random(degree, &secret, rng);
+        let broadcast = Round1Broadcast {
+            sender: index,
+            commitments: polynomial.commitments(),
+            proof: polynomial.prove_secret(sid, index as i32)?,
+        };
+        let mut participant = Self {
+            sid: sid.to_string(),
+            index,
+            participants: participants.to_vec(),
+            polynomial,
+            broadcasts: BTreeMap::new(),
+            shares: BTreeMap::new(),
+            disqualified: BTreeSet::new(),
+        };
+        let own_share = participant.polynomial.share(index)?;
+        participant.shares.insert(index, own_share);
+        participant.broadcasts.insert(index, broadcast.clone());
+        Ok((participant, broadcast))
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// `disqualified` returns the participants excluded so far.
+    pub fn disqualified(&self) -> Vec<u32> {
+        self.disqualified.iter().copied().collect()
+    }
+
+    /// `receive_broadcast` records another participant's round-one broadcast,
+    /// disqualifying the sender if it is malformed.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())`, `ProofError::InvalidStatement` if the sender is unknown or
+    /// has already broadcast, or the error that disqualified the sender:
+    /// `ProofError::InvalidStatement` for the wrong number of commitments
+    /// or the error of an invalid proof of knowledge.
+    pub fn receive_broadcast(&mut self, broadcast: Round1Broadcast) -> Result<(), ProofError> {
+        let sender = broadcast.sender;
+        self.check_sender(sender)?;
+        if self.broadcasts.contains_key(&sender) {
+            return Err(ProofError::InvalidStatement(format!(
+                "second broadcast from {}",
+                sender
+            )));
+        }
+        let result = if broadcast.commitments.len() != self.polynomial.degree() + 1 {
+            Err(ProofError::InvalidStatement(format!(
+                "{} commitments from {}",
+                broadcast.commitments.len(),
+                sender
+            )))
+        } else {
+            verify_dealer_proof(
+                &broadcast.proof,
+                &self.sid,
+                sender as i32,
+                &broadcast.commitments,
+            )
+        };
+        match result {
+            Ok(()) => {
+                self.broadcasts.insert(sender, broadcast);
+                Ok(())
+            }
+            Err(err) => {
+                self.disqualified.insert(sender);
+                Err(err)
+            }
+        }
+    }
+
+    /// `shares` returns the round-two share for every other participant, to
+    /// be sent to each over a private channel.
+    pub fn shares(&self) -> Vec<Round2Share> {
+        self.participants
+            .iter()
+            .filter(|j| **j != self.index)
+            .map(|j| Round2Share {
+                sender: self.index,
+                recipient: *j,
+                share: self
+                    .polynomial
+                    .share(*j)
+                    .expect("participant indices are checked to be non-zero in `new`"),
+            })
+            .collect()
+    }
+
+    /// `receive_share` checks a share against its sender's round-one
+    /// commitments, which must already have been received.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(None)` if the share is accepted, `Ok(Some(complaint))` to be
+    /// broadcast if it is not, or `ProofError::InvalidStatement` if the share
+    /// is not addressed to this participant or its sender is unknown, has no
+    /// broadcast on record or has already sent one.
+    pub fn receive_share(&mut self, share: Round2Share) -> Result<Option<Complaint>, ProofError> {
+        let sender = share.sender;
+        if share.recipient != self.index {
+            return Err(ProofError::InvalidStatement(format!(
+                "share for {} delivered to {}",
+                share.recipient, self.index
+            )));
+        }
+        let commitments = &self.broadcast_from(sender)?.commitments;
+        if self.shares.contains_key(&sender) {
+            return Err(ProofError::InvalidStatement(format!(
+                "second share from {}",
+                sender
+            )));
+        }
+        if verify_share(self.index, &share.share, commitments).is_err() {
+            return Ok(Some(Complaint {
+                accuser: self.index,
+                accused: sender,
+            }));
+        }
+        self.shares.insert(sender, share.share);
+        Ok(None)
+    }
+
+    /// `complain_missing` returns a complaint against every participant not
+    /// yet disqualified whose share never arrived, including those that never
+    /// broadcast.
+    pub fn complain_missing(&self) -> Vec<Complaint> {
+        self.participants
+            .iter()
+            .filter(|j| !self.shares.contains_key(j) && !self.disqualified.contains(j))
+            .map(|j| Complaint {
+                accuser: self.index,
+                accused: *j,
+            })
+            .collect()
+    }
+
+    /// `answer_complaint` reveals the disputed share if this participant is
+    /// the accused and the accuser's index is not zero.
+    pub fn answer_complaint(&self, complaint: &Complaint) -> Option<ShareReveal> {
+        if complaint.accused != self.index || complaint.accuser == self.index {
+            return None;
+        }
+        Some(ShareReveal {
+            sender: self.index,
+            recipient: complaint.accuser,
+            share: self.polynomial.share(complaint.accuser).ok()?,
+        })
+    }
+
+    /// `resolve_complaint` settles a complaint given the accused's answer,
+    /// `None` if it never came. A correct revealed share clears the accused
+    /// and, if this participant made the complaint, replaces the bad share;
+    /// otherwise the accused is disqualified.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` once the complaint is settled, or
+    /// `ProofError::InvalidStatement` if the complaint names an unknown
+    /// participant or the answer does not match it.
+    pub fn resolve_complaint(
+        &mut self,
+        complaint: &Complaint,
+        reveal: Option<&ShareReveal>,
+    ) -> Result<(), ProofError> {
+        self.check_sender(complaint.accuser)?;
+        self.check_sender(complaint.accused)?;
+        if let Some(reveal) = reveal {
+            if reveal.sender != complaint.accused || reveal.recipient != complaint.accuser {
+                return Err(ProofError::InvalidStatement(
+                    "answer does not match the complaint".to_string(),
+                ));
+            }
+        }
+        let valid = match (reveal, self.broadcasts.get(&complaint.accused)) {
+            (Some(reveal), Some(broadcast)) => {
+                verify_share(reveal.recipient, &reveal.share, &broadcast.commitments).is_ok()
+            }
+            _ => false,
+        };
+        match reveal {
+            Some(reveal) if valid => {
+                if complaint.accuser == self.index {
+                    self.shares.insert(reveal.sender, reveal.share.clone());
+                }
+            }
+            _ => {
+                self.disqualified.insert(complaint.accused);
+            }
+        }
+        Ok(())
+    }
+
+    /// `finish` combines the qualified participants' contributions.
+    ///
+    /// Every participant that is not disqualified must have both a broadcast
+    /// and a share on record; a participant heard from in neither round
+    /// should be resolved through a complaint first.
+    ///
+    /// Returns:
+    ///
+    /// The output, `ProofError::InvalidStatement` naming a participant whose
+    /// broadcast or share is missing, or `ProofError::InsufficientShares` if
+    /// `t` or fewer participants remain qualified.
+    pub fn finish(self) -> Result<DkgOutput, ProofError> {
+        let qualified: Vec<u32> = self
+            .participants
+            .iter()
+            .copied()
+            .filter(|j| !self.disqualified.contains(j))
+            .collect();
+        if qualified.len() <= self.polynomial.degree() || !qualified.contains(&self.index) {
+            return Err(ProofError::InsufficientShares);
+        }
+        let mut share = IBig::from(0);
+        let mut commitments = vec![PointJacobi::zero(); self.polynomial.degree() + 1];
+        for j in &qualified {
+            let broadcast = self.broadcast_from(*j)?;
+            let f_j = self
+                .shares
+                .get(j)
+                .ok_or_else(|| ProofError::InvalidStatement(format!("no share from {}", j)))?;
+            share = curve::rem_n(&(share + f_j));
+            for (sum, a) in commitments.iter_mut().zip(&broadcast.commitments) {
+                *sum = sum.add(a);
+            }
+        }
+        debug_assert!(
+            PointJacobi::mul_generator(&share) == share_public_key(self.index, &commitments)
+        );
+        Ok(DkgOutput {
+            index: self.index,
+            share,
+            public_key: commitments[0].clone(),
+            commitments,
+            qualified,
+        })
+    }
+
+    fn check_sender(&self, sender: u32) -> Result<(), ProofError> {
+        if self.participants.contains(&sender) {
+            Ok(())
+        } else {
+            Err(ProofError::InvalidStatement(format!(
+                "{} is not a participant",
+                sender
+            )))
+        }
+    }
+
+    fn broadcast_from(&self, sender: u32) -> Result<&Round1Broadcast, ProofError> {
+        self.check_sender(sender)?;
+        self.broadcasts
+            .get(&sender)
+            .ok_or_else(|| ProofError::InvalidStatement(format!("no broadcast from {}", sender)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vss::reconstruct;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const INDICES: [u32; 3] = [1, 2, 3];
+
+    /// Starts a 2-of-3 run and delivers every round-one broadcast.
+    fn start() -> (Vec<DkgParticipant>, Vec<Round1Broadcast>) {
+        let mut rng = StdRng::seed_from_u64(1037);
+        let (mut participants, broadcasts): (Vec<_>, Vec<_>) = INDICES
+            .iter()
+            .map(|i| DkgParticipant::new("dkg", *i, 1, &INDICES, &mut rng).unwrap())
+            .unzip();
+        for participant in &mut participants {
+            for broadcast in &broadcasts {
+                if broadcast.sender != participant.index() {
+                    participant.receive_broadcast(broadcast.clone()).unwrap();
+                }
+            }
+        }
+        (participants, broadcasts)
+    }
+
+    fn all_shares(participants: &[DkgParticipant]) -> Vec<Round2Share> {
+        participants.iter().flat_map(|p| p.shares()).collect()
+    }
+
+    /// Delivers each share to its recipient and collects the complaints.
+    fn deliver(participants: &mut [DkgParticipant], shares: Vec<Round2Share>) -> Vec<Complaint> {
+        shares
+            .into_iter()
+            .filter_map(|share| {
+                let recipient = share.recipient as usize - 1;
+                participants[recipient].receive_share(share).unwrap()
+            })
+            .collect()
+    }
+
+    fn resolve(
+        participants: &mut [DkgParticipant],
+        complaint: &Complaint,
+        reveal: Option<&ShareReveal>,
+    ) {
+        for participant in participants.iter_mut() {
+            participant.resolve_complaint(complaint, reveal).unwrap();
+        }
+    }
+
+    fn assert_consistent(outputs: &[DkgOutput], qualified: &[u32]) {
+        let public_key = &outputs[0].public_key;
+        for output in outputs {
+            assert_eq!(&output.public_key, public_key);
+            assert_eq!(output.qualified, qualified);
+            assert_eq!(
+                PointJacobi::mul_generator(&output.share),
+                share_public_key(output.index, &output.commitments)
+            );
+        }
+        for pair in outputs.windows(2) {
+            let secret = reconstruct(&[
+                (pair[0].index, pair[0].share.clone()),
+                (pair[1].index, pair[1].share.clone()),
+            ])
+            .unwrap();
+            assert_eq!(&PointJacobi::mul_generator(&secret), public_key);
+        }
+    }
+
+    #[test]
+    fn an_honest_run_agrees_on_a_key_the_shares_reconstruct() {
+        let (mut participants, broadcasts) = start();
+        let shares = all_shares(&participants);
+        assert!(deliver(&mut participants, shares).is_empty());
+        assert!(participants.iter().all(|p| p.complain_missing().is_empty()));
+        let outputs: Vec<DkgOutput> = participants
+            .into_iter()
+            .map(|p| p.finish().unwrap())
+            .collect();
+        assert_consistent(&outputs, &INDICES);
+        let sum = broadcasts
+            .iter()
+            .fold(PointJacobi::zero(), |acc, b| acc.add(&b.commitments[0]));
+        assert_eq!(outputs[0].public_key, sum);
+    }
+
+    #[test]
+    fn a_correct_reveal_keeps_the_accused_qualified() {
+        let (mut participants, _) = start();
+        let mut shares = all_shares(&participants);
+        let bad = shares
+            .iter_mut()
+            .find(|s| s.sender == 1 && s.recipient == 2)
+            .unwrap();
+        bad.share = curve::rem_n(&(&bad.share + 1));
+        let complaints = deliver(&mut participants, shares);
+        assert_eq!(
+            complaints,
+            vec![Complaint {
+                accuser: 2,
+                accused: 1
+            }]
+        );
+        assert_eq!(
+            participants[1].complain_missing(),
+            vec![Complaint {
+                accuser: 2,
+                accused: 1
+            }]
+        );
+        let reveal = participants[0].answer_complaint(&complaints[0]).unwrap();
+        assert!(participants[2].answer_complaint(&complaints[0]).is_none());
+        resolve(&mut participants, &complaints[0], Some(&reveal));
+        assert!(participants.iter().all(|p| p.disqualified().is_empty()));
+        let outputs: Vec<DkgOutput> = participants
+            .into_iter()
+            .map(|p| p.finish().unwrap())
+            .collect();
+        assert_consistent(&outputs, &INDICES);
+    }
+
+    #[test]
+    fn a_wrong_or_missing_reveal_disqualifies_the_accused() {
+        let complaint = Complaint {
+            accuser: 2,
+            accused: 1,
+        };
+        for wrong in [true, false] {
+            let (mut participants, broadcasts) = start();
+            let shares: Vec<Round2Share> = all_shares(&participants)
+                .into_iter()
+                .filter(|s| !(s.sender == 1 && s.recipient == 2))
+                .collect();
+            assert!(deliver(&mut participants, shares).is_empty());
+            assert_eq!(participants[1].complain_missing(), vec![complaint.clone()]);
+
+            let mut reveal = participants[0].answer_complaint(&complaint).unwrap();
+            reveal.share = curve::rem_n(&(&reveal.share + 1));
+            resolve(&mut participants, &complaint, wrong.then_some(&reveal));
+            assert!(participants.iter().all(|p| p.disqualified() == vec![1]));
+
+            let mut participants = participants.into_iter();
+            assert_eq!(
+                participants.next().unwrap().finish().err(),
+                Some(ProofError::InsufficientShares)
+            );
+            let outputs: Vec<DkgOutput> = participants.map(|p| p.finish().unwrap()).collect();
+            assert_consistent(&outputs, &[2, 3]);
+            let sum = broadcasts[1].commitments[0].add(&broadcasts[2].commitments[0]);
+            assert_eq!(outputs[0].public_key, sum);
+        }
+    }
+
+    #[test]
+    fn a_bad_proof_of_knowledge_disqualifies_the_sender() {
+        let mut rng = StdRng::seed_from_u64(1037);
+        let (mut participant, _) = DkgParticipant::new("dkg", 1, 1, &INDICES, &mut rng).unwrap();
+        let (_, mut forged) = DkgParticipant::new("dkg", 2, 1, &INDICES, &mut rng).unwrap();
+        let (_, other) = DkgParticipant::new("other", 3, 1, &INDICES, &mut rng).unwrap();
+        forged.proof = other.proof;
+        assert_eq!(
+            participant.receive_broadcast(forged),
+            Err(ProofError::VerificationFailed)
+        );
+        assert_eq!(participant.disqualified(), vec![2]);
+
+        let (_, mut short) = DkgParticipant::new("dkg", 3, 1, &INDICES, &mut rng).unwrap();
+        short.commitments.pop();
+        assert!(matches!(
+            participant.receive_broadcast(short),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        assert_eq!(participant.disqualified(), vec![2, 3]);
+    }
+
+    #[test]
+    fn duplicate_broadcasts_and_shares_are_refused() {
+        let (mut participants, broadcasts) = start();
+        assert!(matches!(
+            participants[0].receive_broadcast(broadcasts[1].clone()),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        let share = participants[1].shares()[0].clone();
+        assert_eq!(share.recipient, 1);
+        assert_eq!(participants[0].receive_share(share.clone()), Ok(None));
+        assert!(matches!(
+            participants[0].receive_share(share),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        assert!(participants[0].disqualified().is_empty());
+    }
+
+    #[test]
+    fn finishing_with_too_few_qualified_participants_fails() {
+        let (mut participants, _) = start();
+        let shares = all_shares(&participants);
+        assert!(deliver(&mut participants, shares).is_empty());
+        for accused in [2, 3] {
+            let complaint = Complaint {
+                accuser: 1,
+                accused,
+            };
+            participants[0].resolve_complaint(&complaint, None).unwrap();
+        }
+        assert_eq!(participants[0].disqualified(), vec![2, 3]);
+        assert_eq!(
+            participants.remove(0).finish().err(),
+            Some(ProofError::InsufficientShares)
+        );
+    }
+}
@@ -0,0 +1,311 @@
+//! Proof that two public keys `Y_1 = x·G_1` and `Y_2 = x·G_2`, under different
+//! generators chosen by the caller, share the same secret `x` (Chaum and
+//! Pedersen, CRYPTO '92).
+//!
+//! The prover commits `T_1 = r·G_1` and `T_2 = r·G_2`, derives
+//! `c = H(sid, pid, G_1, Y_1, G_2, Y_2, T_1, T_2)`, tagged [`TAG`], and
+//! responds with
+//! `s = r + c·x`. The verifier checks `s·G_1 == T_1 + c·Y_1` and
+//! `s·G_2 == T_2 + c·Y_2`. Both generators are in the transcript, so a proof
+//! made for one pair of generators does not verify for another.
+//!
+//! The statement travels as a [`DleqStatement`], whose encoding is the four
+//! compressed points in transcript order.
+
+use crate::curve::{self, SCALAR_BYTES};
+use crate::error::ProofError;
+use crate::hash::hash_tagged;
+use crate::jacobi_point::{PointJacobi, POINT_BYTES};
+use crate::prover::Prover;
+use ibig::IBig;
+
+/// Tag of the challenge transcript; see [`crate::hash`].
+pub const TAG: &str = "dlogproof/dleq";
+
+/// The claim `log_{G_1} Y_1 == log_{G_2} Y_2`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DleqStatement {
+    pub g1: PointJacobi,
+    pub y1: PointJacobi,
+    pub g2: PointJacobi,
+    pub y2: PointJacobi,
+}
+
+impl DleqStatement {
+    /// Length in bytes of an encoded statement: `g1`, `y1`, `g2` and `y2`,
+    /// compressed.
+    pub const BYTES: usize = 4 * POINT_BYTES;
+
+    /// `new` builds the statement for the secret `x`, computing
+    /// `Y_1 = x·G_1` and `Y_2 = x·G_2`.
+    pub fn new(x: &IBig, g1: &PointJacobi, g2: &PointJacobi) -> Self {
+        Self {
+            g1: g1.clone(),
+            y1: g1.mul_secret(x),
+            g2: g2.clone(),
+            y2: g2.mul_secret(x),
+        }
+    }
+
+    /// `validate` rejects statements that are trivially true or meaningless:
+    /// a point at infinity, or a public key equal to its generator.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())`, or `ProofError::InvalidPoint`.
+    pub fn validate(&self) -> Result<(), ProofError> {
+        let points = [&self.g1, &self.y1, &self.g2, &self.y2];
+        if points.iter().any(|p| p.is_zero()) || self.y1 == self.g1 || self.y2 == self.g2 {
+            return Err(ProofError::InvalidPoint);
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut out = [0u8; Self::BYTES];
+        for (i, point) in self.points().iter().enumerate() {
+            out[i * POINT_BYTES..(i + 1) * POINT_BYTES].copy_from_slice(&point.to_bytes());
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != Self::BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} DLEQ statement bytes, got {}",
+                Self::BYTES,
+                bytes.len()
+            )));
+        }
+        let point =
+            |i: usize| PointJacobi::from_bytes(&bytes[i * POINT_BYTES..(i + 1) * POINT_BYTES]);
+        Ok(Self {
+            g1: point(0)?,
+            y1: point(1)?,
+            g2: point(2)?,
+            y2: point(3)?,
+        })
+    }
+
+    fn points(&self) -> [&PointJacobi; 4] {
+        [&self.g1, &self.y1, &self.g2, &self.y2]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DleqProof {
+    pub t1: PointJacobi,
+    pub t2: PointJacobi,
+    pub s: IBig,
+}
+
+impl DleqProof {
+    /// Length in bytes of an encoded proof: compressed `t1` and `t2`, then
+    /// the 32-byte `s`.
+    pub const BYTES: usize = 2 * POINT_BYTES + SCALAR_BYTES;
+
+    /// `prove` proves that both public keys of `statement` have the discrete
+    /// log `x`.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `x`: the shared secret
+    /// * `statement`: the generators and public keys
+    ///
+    /// Returns:
+    ///
+    /// The proof, `ProofError::InvalidSecret` if `x` is not in `[1, n)`,
+    /// `ProofError::InvalidPoint` if the statement fails
+    /// [`DleqStatement::validate`], or `ProofError::InvalidStatement` if either
+    /// public key is not `x` times its generator.
+    pub fn prove(
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        statement: &DleqStatement,
+    ) -> Result<Self, ProofError> {
+        if *x <= IBig::from(0) || *x >= *curve::N {
+            return Err(ProofError::InvalidSecret);
+        }
+        statement.validate()?;
+        if statement.g1.mul_secret(x) != statement.y1 || statement.g2.mul_secret(x) != statement.y2
+        {
+            return Err(ProofError::InvalidStatement(
+                "public keys do not match the secret".to_string(),
+            ));
+        }
+        let r = Prover::generate_random_nonce();
+        let t1 = statement.g1.mul_secret(&r);
+        let t2 = statement.g2.mul_secret(&r);
+        let c = challenge(sid, pid, statement, &t1, &t2)?;
+        let s = curve::rem_n(&(r + c * x));
+        Ok(Self { t1, t2, s })
+    }
+
+    /// `verify` checks `s·G_1 == T_1 + c·Y_1` and `s·G_2 == T_2 + c·Y_2`.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `statement`: the generators and public keys
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid, `ProofError::InvalidPoint` if the
+    /// statement fails [`DleqStatement::validate`],
+    /// `ProofError::ScalarOutOfRange` if `s` is not reduced modulo n, and
+    /// `ProofError::VerificationFailed` otherwise.
+    pub fn verify(&self, sid: &str, pid: i32, statement: &DleqStatement) -> Result<(), ProofError> {
+        if self.s < IBig::from(0) || self.s >= *curve::N {
+            return Err(ProofError::ScalarOutOfRange);
+        }
+        statement.validate()?;
+        let c = challenge(sid, pid, statement, &self.t1, &self.t2)?;
+        let holds = |g: &PointJacobi, y: &PointJacobi, t: &PointJacobi| {
+            g.mul_unsafe(&self.s) == t.add(&y.mul_unsafe(&c))
+        };
+        if holds(&statement.g1, &statement.y1, &self.t1)
+            && holds(&statement.g2, &statement.y2, &self.t2)
+        {
+            Ok(())
+        } else {
+            Err(ProofError::VerificationFailed)
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut out = [0u8; Self::BYTES];
+        out[..POINT_BYTES].copy_from_slice(&self.t1.to_bytes());
+        out[POINT_BYTES..2 * POINT_BYTES].copy_from_slice(&self.t2.to_bytes());
+        out[2 * POINT_BYTES..].copy_from_slice(&curve::scalar_to_bytes(&self.s));
+        out
+    }
+
+    /// `from_bytes` parses the encoding produced by [`DleqProof::to_bytes`],
+    /// rejecting an `s` that is not reduced modulo n.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != Self::BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} DLEQ proof bytes, got {}",
+                Self::BYTES,
+                bytes.len()
+            )));
+        }
+        Ok(Self {
+            t1: PointJacobi::from_bytes(&bytes[..POINT_BYTES])?,
+            t2: PointJacobi::from_bytes(&bytes[POINT_BYTES..2 * POINT_BYTES])?,
+            s: curve::scalar_from_canonical_bytes(&bytes[2 * POINT_BYTES..])?,
+        })
+    }
+}
+
+fn challenge(
+    sid: &str,
+    pid: i32,
+    statement: &DleqStatement,
+    t1: &PointJacobi,
+    t2: &PointJacobi,
+) -> Result<IBig, ProofError> {
+    let [g1, y1, g2, y2] = statement.points();
+    hash_tagged(TAG, sid, pid, &[g1, y1, g2, y2, t1, t2], &[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commitment;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn statement() -> (IBig, DleqStatement) {
+        let x = curve::random_scalar(&mut StdRng::seed_from_u64(1032));
+        let statement = DleqStatement::new(&x, &PointJacobi::generator(), &commitment::h());
+        (x, statement)
+    }
+
+    #[test]
+    fn an_honest_proof_verifies() {
+        let (x, statement) = statement();
+        let proof = DleqProof::prove("dleq", 1, &x, &statement).unwrap();
+        assert_eq!(proof.verify("dleq", 1, &statement), Ok(()));
+    }
+
+    #[test]
+    fn a_proof_is_bound_to_both_generators_and_keys() {
+        let (x, statement) = statement();
+        let proof = DleqProof::prove("dleq", 1, &x, &statement).unwrap();
+
+        // The same x under another second generator is still a true
+        // statement, but not the one proven.
+        let other_g2 = commitment::nums_point(b"dlogproof/test/dleq");
+        let moved = DleqStatement::new(&x, &statement.g1, &other_g2);
+        assert_eq!(
+            proof.verify("dleq", 1, &moved),
+            Err(ProofError::VerificationFailed)
+        );
+        let mut other_y2 = statement.clone();
+        other_y2.y2 = other_y2.y2.add(&PointJacobi::generator());
+        assert_eq!(
+            proof.verify("dleq", 1, &other_y2),
+            Err(ProofError::VerificationFailed)
+        );
+        let mut swapped = statement.clone();
+        std::mem::swap(&mut swapped.g1, &mut swapped.g2);
+        std::mem::swap(&mut swapped.y1, &mut swapped.y2);
+        assert_eq!(
+            proof.verify("dleq", 1, &swapped),
+            Err(ProofError::VerificationFailed)
+        );
+
+        let x2 = curve::rem_n(&(&x + 1));
+        assert!(matches!(
+            DleqProof::prove("dleq", 1, &x2, &statement),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        let mut degenerate = statement.clone();
+        degenerate.y2 = PointJacobi::zero();
+        assert_eq!(
+            proof.verify("dleq", 1, &degenerate),
+            Err(ProofError::InvalidPoint)
+        );
+    }
+
+    #[test]
+    fn a_proof_is_bound_to_the_session() {
+        let (x, statement) = statement();
+        let proof = DleqProof::prove("dleq", 1, &x, &statement).unwrap();
+        assert_eq!(
+            proof.verify("other", 1, &statement),
+            Err(ProofError::VerificationFailed)
+        );
+        assert_eq!(
+            proof.verify("dleq", 2, &statement),
+            Err(ProofError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn statements_and_proofs_round_trip_through_bytes() {
+        let (x, statement) = statement();
+        let proof = DleqProof::prove("dleq", 1, &x, &statement).unwrap();
+        let decoded_statement = DleqStatement::from_bytes(&statement.to_bytes()).unwrap();
+        let decoded_proof = DleqProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(decoded_statement, statement);
+        assert_eq!(decoded_proof, proof);
+        assert_eq!(decoded_proof.verify("dleq", 1, &decoded_statement), Ok(()));
+        assert!(matches!(
+            DleqStatement::from_bytes(&statement.to_bytes()[1..]),
+            Err(ProofError::DeserializationError(_))
+        ));
+        assert!(matches!(
+            DleqProof::from_bytes(&proof.to_bytes()[1..]),
+            Err(ProofError::DeserializationError(_))
+        ));
+        let mut unreduced = proof.to_bytes();
+        unreduced[2 * POINT_BYTES..].fill(0xff);
+        assert!(DleqProof::from_bytes(&unreduced).is_err());
+    }
+}
@@ -0,0 +1,234 @@
+//! Elliptic-curve Diffie-Hellman with a proof that the shared point was
+//! computed honestly.
+//!
+//! The shared point of a key `x` and a peer key `P` is `S = x·P`, and the
+//! shared secret is its 32-byte x coordinate, as in SEC 1. A
+//! [`SharedPointProof`] publishes `S` with a [`DleqProof`] that
+//! `log_G Y == log_P S` for the prover's registered key `Y = x·G`, so a
+//! relying party can audit the exchange without learning `x`. Publishing `S`
+//! reveals the shared secret, so the proof is for audits, not for sessions
+//! whose key must stay private.
+
+use crate::curve;
+use crate::dleq::{DleqProof, DleqStatement};
+use crate::error::ProofError;
+use crate::jacobi_point::{PointJacobi, POINT_BYTES};
+use crate::keys::{PublicKey, SecretKey};
+
+/// `shared_point` computes `S = x·P`.
+///
+/// Returns:
+///
+/// The shared point, `ProofError::PointNotOnCurve` if `peer` is not on the
+/// curve, or `ProofError::InvalidPoint` if it is the point at infinity.
+pub fn shared_point(key: &SecretKey, peer: &PublicKey) -> Result<PointJacobi, ProofError> {
+    let p = peer.point();
+    if !p.is_on_curve() {
+        return Err(ProofError::PointNotOnCurve);
+    }
+    if p.is_zero() {
+        return Err(ProofError::InvalidPoint);
+    }
+    Ok(p.mul_secret(&key.scalar()))
+}
+
+/// `shared_secret` returns the x coordinate of [`shared_point`], big-endian.
+pub fn shared_secret(key: &SecretKey, peer: &PublicKey) -> Result<[u8; 32], ProofError> {
+    let s = shared_point(key, peer)?;
+    Ok(curve::field_to_bytes(&s.to_affine().x))
+}
+
+/// A published shared point and the proof it was derived from the prover's
+/// registered key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedPointProof {
+    pub shared: PointJacobi,
+    pub proof: DleqProof,
+}
+
+impl SharedPointProof {
+    /// Length in bytes of an encoded proof: the compressed shared point, then
+    /// the [`DleqProof`].
+    pub const BYTES: usize = POINT_BYTES + DleqProof::BYTES;
+
+    /// `prove` computes the shared point with `peer` and proves it correct.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `key`: the prover's secret key
+    /// * `peer`: the other party's public key
+    ///
+    /// Returns:
+    ///
+    /// The proof, the errors of [`shared_point`], or those of
+    /// [`DleqProof::prove`].
+    pub fn prove(
+        sid: &str,
+        pid: i32,
+        key: &SecretKey,
+        peer: &PublicKey,
+    ) -> Result<Self, ProofError> {
+        let shared = shared_point(key, peer)?;
+        let statement = statement(&key.public_key(), peer, &shared);
+        let proof = DleqProof::prove(sid, pid, &key.scalar(), &statement)?;
+        Ok(Self { shared, proof })
+    }
+
+    /// `verify` checks that `shared` is `x·peer` for the `x` behind
+    /// `public_key`.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid, `ProofError::PointNotOnCurve` if
+    /// `peer` is not on the curve, or the errors of [`DleqProof::verify`].
+    pub fn verify(
+        &self,
+        sid: &str,
+        pid: i32,
+        public_key: &PublicKey,
+        peer: &PublicKey,
+    ) -> Result<(), ProofError> {
+        if !public_key.point().is_on_curve() || !peer.point().is_on_curve() {
+            return Err(ProofError::PointNotOnCurve);
+        }
+        let statement = statement(public_key, peer, &self.shared);
+        self.proof.verify(sid, pid, &statement)
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut out = [0u8; Self::BYTES];
+        out[..POINT_BYTES].copy_from_slice(&self.shared.to_bytes());
+        out[POINT_BYTES..].copy_from_slice(&self.proof.to_bytes());
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != Self::BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} shared point proof bytes, got {}",
+                Self::BYTES,
+                bytes.len()
+            )));
+        }
+        Ok(Self {
+            shared: PointJacobi::from_bytes(&bytes[..POINT_BYTES])?,
+            proof: DleqProof::from_bytes(&bytes[POINT_BYTES..])?,
+        })
+    }
+}
+
+fn statement(public_key: &PublicKey, peer: &PublicKey, shared: &PointJacobi) -> DleqStatement {
+    DleqStatement {
+        g1: PointJacobi::generator(),
+        y1: public_key.point().clone(),
+        g2: peer.point().clone(),
+        y2: shared.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn parties() -> (SecretKey, SecretKey) {
+        let mut rng = StdRng::seed_from_u64(1040);
+        (SecretKey::random(&mut rng), SecretKey::random(&mut rng))
+    }
+
+    #[test]
+    fn the_shared_secret_is_symmetric() {
+        let (alice, bob) = parties();
+        let secret = shared_secret(&alice, &bob.public_key()).unwrap();
+        assert_eq!(shared_secret(&bob, &alice.public_key()), Ok(secret));
+        assert_eq!(
+            shared_point(&alice, &bob.public_key()),
+            shared_point(&bob, &alice.public_key())
+        );
+        let carol = SecretKey::random(&mut StdRng::seed_from_u64(1041));
+        assert_ne!(shared_secret(&alice, &carol.public_key()), Ok(secret));
+    }
+
+    #[test]
+    fn an_honest_proof_verifies() {
+        let (alice, bob) = parties();
+        let proof = SharedPointProof::prove("ecdh", 1, &alice, &bob.public_key()).unwrap();
+        assert_eq!(
+            proof.verify("ecdh", 1, &alice.public_key(), &bob.public_key()),
+            Ok(())
+        );
+        assert_eq!(
+            curve::field_to_bytes(&proof.shared.to_affine().x),
+            shared_secret(&bob, &alice.public_key()).unwrap()
+        );
+    }
+
+    #[test]
+    fn a_proof_is_bound_to_the_shared_point_and_keys() {
+        let (alice, bob) = parties();
+        let carol = SecretKey::random(&mut StdRng::seed_from_u64(1041)).public_key();
+        let proof = SharedPointProof::prove("ecdh", 1, &alice, &bob.public_key()).unwrap();
+        let mut moved = proof.clone();
+        moved.shared = moved.shared.add(&PointJacobi::generator());
+        assert_eq!(
+            moved.verify("ecdh", 1, &alice.public_key(), &bob.public_key()),
+            Err(ProofError::VerificationFailed)
+        );
+        assert_eq!(
+            proof.verify("ecdh", 1, &alice.public_key(), &carol),
+            Err(ProofError::VerificationFailed)
+        );
+        assert_eq!(
+            proof.verify("ecdh", 1, &bob.public_key(), &alice.public_key()),
+            Err(ProofError::VerificationFailed)
+        );
+        let p = bob.public_key().point().clone();
+        let off_curve = PublicKey::new(PointJacobi::new(p.x.clone(), &p.y + 1, p.z.clone()));
+        assert_eq!(
+            proof.verify("ecdh", 1, &alice.public_key(), &off_curve),
+            Err(ProofError::PointNotOnCurve)
+        );
+        assert_eq!(
+            shared_point(&alice, &off_curve),
+            Err(ProofError::PointNotOnCurve)
+        );
+        assert_eq!(
+            shared_point(&alice, &PublicKey::new(PointJacobi::zero())),
+            Err(ProofError::InvalidPoint)
+        );
+    }
+
+    #[test]
+    fn a_proof_is_bound_to_the_session() {
+        let (alice, bob) = parties();
+        let proof = SharedPointProof::prove("ecdh", 1, &alice, &bob.public_key()).unwrap();
+        assert_eq!(
+            proof.verify("other", 1, &alice.public_key(), &bob.public_key()),
+            Err(ProofError::VerificationFailed)
+        );
+        assert_eq!(
+            proof.verify("ecdh", 2, &alice.public_key(), &bob.public_key()),
+            Err(ProofError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn proofs_round_trip_through_bytes() {
+        let (alice, bob) = parties();
+        let proof = SharedPointProof::prove("ecdh", 1, &alice, &bob.public_key()).unwrap();
+        let bytes = proof.to_bytes();
+        let decoded = SharedPointProof::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, proof);
+        assert_eq!(
+            decoded.verify("ecdh", 1, &alice.public_key(), &bob.public_key()),
+            Ok(())
+        );
+        assert!(matches!(
+            SharedPointProof::from_bytes(&bytes[1..]),
+            Err(ProofError::DeserializationError(_))
+        ));
+    }
+}
@@ -0,0 +1,1005 @@
+//! Verifiable ElGamal encryption of a discrete log.
+//!
+//! The secret `x` is encrypted "in the exponent" under a recipient key `E`:
+//! `c1 = k·G`, `c2 = x·G + k·E`. Decrypting yields the point `x·G`, which the
+//! recipient compares against the prover's public key `Y`. The accompanying
+//! proof shows, without revealing `x` or `k`, that the same `x` satisfies
+//! `Y = x·G` and is the one encrypted in `(c1, c2)`.
+//!
+//! This is the escrow protocol: [`VerifiableEncryptionProof::encrypt_and_prove`]
+//! produces the ciphertext and proof, and [`VerifiableEncryptionProof::verify`]
+//! lets anyone check them against `Y` and `E`. Because `x` is encrypted in the
+//! exponent, the escrow agent recovers `x·G` rather than `x` itself, which
+//! attests to the encrypted key but cannot replace a lost one.
+//!
+//! To escrow a key that can be restored, [`RecoverableEncryptionProof`]
+//! encrypts `x` one byte at a time in a [`ChunkedCiphertext`]. Each chunk
+//! decrypts to `x_j·G` with `x_j < 256`, small enough for [`decode_small`], so
+//! [`decrypt_chunked`] returns `x = Σ 256^j·x_j` itself. The proof carries a
+//! [`RangeProof`] per chunk and shows that the chunks recombine to the
+//! discrete log of `Y`.
+//!
+//! The same scheme serves as additively homomorphic "exponential" ElGamal for
+//! small values, as in voting: ciphertexts add with [`ElGamalCiphertext::add`],
+//! [`decode_small`] recovers a small `m` from `m·G`, and a
+//! [`DecryptionProof`] shows that a published decryption is correct without
+//! revealing the recipient's secret.
+
+use crate::commitment::{self, PedersenCommitment};
+use crate::curve::{self, SCALAR_BYTES};
+use crate::dleq::{DleqProof, DleqStatement};
+use crate::error::ProofError;
+use crate::hash::hash_tagged;
+use crate::jacobi_point::{PointJacobi, POINT_BYTES};
+use crate::range_proof::RangeProof;
+use ibig::IBig;
+use rand::{CryptoRng, RngCore};
+
+/// Tag of the challenge transcript; see [`crate::hash`].
+pub const TAG: &str = "dlogproof/encryption";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElGamalCiphertext {
+    pub c1: PointJacobi,
+    pub c2: PointJacobi,
+}
+
+impl ElGamalCiphertext {
+    /// Length in bytes of an encoded ciphertext: compressed `c1` then `c2`.
+    pub const BYTES: usize = 2 * POINT_BYTES;
+
+    /// `add` returns the componentwise sum, an encryption of the sum of the
+    /// two plaintexts under the same key.
+    pub fn add(&self, other: &Self) -> Self {
+        Self {
+            c1: self.c1.add(&other.c1),
+            c2: self.c2.add(&other.c2),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut out = [0u8; Self::BYTES];
+        out[..POINT_BYTES].copy_from_slice(&self.c1.to_bytes());
+        out[POINT_BYTES..].copy_from_slice(&self.c2.to_bytes());
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != Self::BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} ciphertext bytes, got {}",
+                Self::BYTES,
+                bytes.len()
+            )));
+        }
+        Ok(Self {
+            c1: PointJacobi::from_bytes(&bytes[..POINT_BYTES])?,
+            c2: PointJacobi::from_bytes(&bytes[POINT_BYTES..])?,
+        })
+    }
+}
+
+/// `encrypt` encrypts the scalar `x` as the point `x·G` under `recipient_key`.
+///
+/// Arguments:
+///
+/// * `x`: the scalar to encrypt
+/// * `recipient_key`: the recipient's public key `E`
+/// * `rng`: source of the encryption randomness `k`
+///
+/// Returns:
+///
+/// The ciphertext `(k·G, x·G + k·E)`.
+pub fn encrypt<R: RngCore + CryptoRng>(
+    x: &IBig,
+    recipient_key: &PointJacobi,
+    rng: &mut R,
+) -> ElGamalCiphertext {
+    let k = curve::random_scalar(rng);
+    encrypt_with_randomness(x, &k, recipient_key)
+}
+
+/// `decrypt` recovers the encrypted point `x·G = c2 - secret·c1`.
+///
+/// Arguments:
+///
+/// * `secret`: the recipient's secret key `e`, with `E = e·G`
+/// * `ciphertext`: the ciphertext to decrypt
+///
+/// Returns:
+///
+/// The point `x·G`; the caller compares it against the expected public key.
+pub fn decrypt(secret: &IBig, ciphertext: &ElGamalCiphertext) -> PointJacobi {
+    ciphertext
+        .c2
+        .add(&ciphertext.c1.mul_secret(secret).negate())
+}
+
+/// `decode_small` finds `m` in `[0, max]` with `point = m·G`, trying each in
+/// turn; it is meant for tallies and other values small enough to search.
+pub fn decode_small(point: &PointJacobi, max: u64) -> Option<u64> {
+    let g = PointJacobi::generator();
+    let mut candidate = PointJacobi::zero();
+    for m in 0..=max {
+        if candidate == *point {
+            return Some(m);
+        }
+        candidate = candidate.add(&g);
+    }
+    None
+}
+
+/// Proof that `d = e·c1` for the recipient's secret `e`, so that
+/// `c2 - d` is the correct decryption of a ciphertext under `E = e·G`: a
+/// [`DleqProof`] for `log_G E == log_{c1} d`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecryptionProof {
+    /// The decryption share `d = e·c1`.
+    pub d: PointJacobi,
+    pub proof: DleqProof,
+}
+
+impl DecryptionProof {
+    /// Length in bytes of an encoded proof: compressed `d`, then the
+    /// [`DleqProof`].
+    pub const BYTES: usize = POINT_BYTES + DleqProof::BYTES;
+
+    /// `prove` decrypts `ciphertext` and proves the decryption correct.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `secret`: the recipient's secret key `e`
+    /// * `recipient_key`: the recipient's public key `E = e·G`
+    /// * `ciphertext`: the ciphertext to decrypt
+    ///
+    /// Returns:
+    ///
+    /// The plaintext point `c2 - e·c1` and the proof, or the errors of
+    /// [`DleqProof::prove`].
+    pub fn prove(
+        sid: &str,
+        pid: i32,
+        secret: &IBig,
+        recipient_key: &PointJacobi,
+        ciphertext: &ElGamalCiphertext,
+    ) -> Result<(PointJacobi, Self), ProofError> {
+        let d = ciphertext.c1.mul_secret(secret);
+        let statement = Self::statement(recipient_key, ciphertext, &d);
+        let proof = DleqProof::prove(sid, pid, secret, &statement)?;
+        let plaintext = ciphertext.c2.add(&d.clone().negate());
+        Ok((plaintext, Self { d, proof }))
+    }
+
+    /// `verify` checks the proof and returns the plaintext point it attests.
+    ///
+    /// Returns:
+    ///
+    /// The plaintext point `c2 - d` if the proof is valid, or the errors of
+    /// [`DleqProof::verify`].
+    pub fn verify(
+        &self,
+        sid: &str,
+        pid: i32,
+        recipient_key: &PointJacobi,
+        ciphertext: &ElGamalCiphertext,
+    ) -> Result<PointJacobi, ProofError> {
+        let statement = Self::statement(recipient_key, ciphertext, &self.d);
+        self.proof.verify(sid, pid, &statement)?;
+        Ok(ciphertext.c2.add(&self.d.clone().negate()))
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut out = [0u8; Self::BYTES];
+        out[..POINT_BYTES].copy_from_slice(&self.d.to_bytes());
+        out[POINT_BYTES..].copy_from_slice(&self.proof.to_bytes());
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != Self::BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} decryption proof bytes, got {}",
+                Self::BYTES,
+                bytes.len()
+            )));
+        }
+        Ok(Self {
+            d: PointJacobi::from_bytes(&bytes[..POINT_BYTES])?,
+            proof: DleqProof::from_bytes(&bytes[POINT_BYTES..])?,
+        })
+    }
+
+    fn statement(
+        recipient_key: &PointJacobi,
+        ciphertext: &ElGamalCiphertext,
+        d: &PointJacobi,
+    ) -> DleqStatement {
+        DleqStatement {
+            g1: PointJacobi::generator(),
+            y1: recipient_key.clone(),
+            g2: ciphertext.c1.clone(),
+            y2: d.clone(),
+        }
+    }
+}
+
+fn encrypt_with_randomness(x: &IBig, k: &IBig, recipient_key: &PointJacobi) -> ElGamalCiphertext {
+    let g = PointJacobi::generator();
+    ElGamalCiphertext {
+        c1: g.mul_secret(k),
+        c2: g.mul_secret(x).add(&recipient_key.mul_secret(k)),
+    }
+}
+
+/// Proof that a ciphertext encrypts the discrete log of a public key: a
+/// conjunction of Schnorr statements over the witnesses `(x, k)` sharing one
+/// Fiat-Shamir challenge,
+///
+/// ```text
+/// c1 = k·G,  c2 = x·G + k·E,  Y = x·G
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiableEncryptionProof {
+    /// Commitment `a1 = r_k·G`.
+    pub a1: PointJacobi,
+    /// Commitment `a2 = r_x·G + r_k·E`.
+    pub a2: PointJacobi,
+    /// Commitment `a3 = r_x·G`.
+    pub a3: PointJacobi,
+    /// Response `z_x = r_x + c·x`.
+    pub z_x: IBig,
+    /// Response `z_k = r_k + c·k`.
+    pub z_k: IBig,
+}
+
+impl VerifiableEncryptionProof {
+    /// Length in bytes of an encoded proof: three compressed points then two scalars.
+    pub const BYTES: usize = 3 * POINT_BYTES + 2 * SCALAR_BYTES;
+
+    /// `encrypt_and_prove` encrypts `x` to `recipient_key` and proves the
+    /// ciphertext encrypts the discrete log of `y`.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `x`: the secret, with `y = x·G`
+    /// * `y`: the prover's public key
+    /// * `recipient_key`: the escrow agent's public key `E`
+    /// * `rng`: source of the encryption randomness and proof nonces
+    ///
+    /// Returns:
+    ///
+    /// The ciphertext and the proof of its consistency with `y`, or
+    /// `ProofError::InvalidStatement` if `sid` is longer than `u32::MAX` bytes.
+    pub fn encrypt_and_prove<R: RngCore + CryptoRng>(
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        y: &PointJacobi,
+        recipient_key: &PointJacobi,
+        rng: &mut R,
+    ) -> Result<(ElGamalCiphertext, Self), ProofError> {
+        let g = PointJacobi::generator();
+        let k = curve::random_scalar(rng);
+        let ciphertext = encrypt_with_randomness(x, &k, recipient_key);
+
+        let r_x = curve::random_scalar(rng);
+        let r_k = curve::random_scalar(rng);
+        let a1 = g.mul_secret(&r_k);
+        let a3 = g.mul_secret(&r_x);
+        let a2 = a3.add(&recipient_key.mul_secret(&r_k));
+        let c = Self::challenge(sid, pid, y, recipient_key, &ciphertext, &a1, &a2, &a3)?;
+        let z_x = curve::rem_n(&(r_x + &c * x));
+        let z_k = curve::rem_n(&(r_k + &c * k));
+        Ok((
+            ciphertext,
+            Self {
+                a1,
+                a2,
+                a3,
+                z_x,
+                z_k,
+            },
+        ))
+    }
+
+    /// `verify` checks that `ciphertext` encrypts the discrete log of `y` under
+    /// `recipient_key`.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `y`: the prover's public key
+    /// * `recipient_key`: the escrow agent's public key `E`
+    /// * `ciphertext`: the ciphertext the proof is about
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if all three equations hold, `ProofError::VerificationFailed` otherwise.
+    pub fn verify(
+        &self,
+        sid: &str,
+        pid: i32,
+        y: &PointJacobi,
+        recipient_key: &PointJacobi,
+        ciphertext: &ElGamalCiphertext,
+    ) -> Result<(), ProofError> {
+        let g = PointJacobi::generator();
+        let c = Self::challenge(
+            sid,
+            pid,
+            y,
+            recipient_key,
+            ciphertext,
+            &self.a1,
+            &self.a2,
+            &self.a3,
+        )?;
+        let z_x_g = g.mul_unsafe(&self.z_x);
+        let c1_ok = g.mul_unsafe(&self.z_k) == self.a1.add(&ciphertext.c1.mul_unsafe(&c));
+        let c2_ok = z_x_g.add(&recipient_key.mul_unsafe(&self.z_k))
+            == self.a2.add(&ciphertext.c2.mul_unsafe(&c));
+        let y_ok = z_x_g == self.a3.add(&y.mul_unsafe(&c));
+        if c1_ok && c2_ok && y_ok {
+            Ok(())
+        } else {
+            Err(ProofError::VerificationFailed)
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut out = [0u8; Self::BYTES];
+        out[..POINT_BYTES].copy_from_slice(&self.a1.to_bytes());
+        out[POINT_BYTES..2 * POINT_BYTES].copy_from_slice(&self.a2.to_bytes());
+        out[2 * POINT_BYTES..3 * POINT_BYTES].copy_from_slice(&self.a3.to_bytes());
+        let scalars = &mut out[3 * POINT_BYTES..];
+        scalars[..SCALAR_BYTES].copy_from_slice(&curve::scalar_to_bytes(&self.z_x));
+        scalars[SCALAR_BYTES..].copy_from_slice(&curve::scalar_to_bytes(&self.z_k));
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != Self::BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} proof bytes, got {}",
+                Self::BYTES,
+                bytes.len()
+            )));
+        }
+        let point =
+            |i: usize| PointJacobi::from_bytes(&bytes[i * POINT_BYTES..(i + 1) * POINT_BYTES]);
+        let scalar = |i: usize| {
+            let start = 3 * POINT_BYTES + i * SCALAR_BYTES;
+            curve::scalar_from_canonical_bytes(&bytes[start..start + SCALAR_BYTES])
+        };
+        Ok(Self {
+            a1: point(0)?,
+            a2: point(1)?,
+            a3: point(2)?,
+            z_x: scalar(0)?,
+            z_k: scalar(1)?,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn challenge(
+        sid: &str,
+        pid: i32,
+        y: &PointJacobi,
+        recipient_key: &PointJacobi,
+        ciphertext: &ElGamalCiphertext,
+        a1: &PointJacobi,
+        a2: &PointJacobi,
+        a3: &PointJacobi,
+    ) -> Result<IBig, ProofError> {
+        hash_tagged(
+            TAG,
+            sid,
+            pid,
+            &[
+                &PointJacobi::generator(),
+                y,
+                recipient_key,
+                &ciphertext.c1,
+                &ciphertext.c2,
+                a1,
+                a2,
+                a3,
+            ],
+            &[],
+        )
+    }
+}
+
+/// Number of one-byte chunks in a [`ChunkedCiphertext`], enough for any
+/// scalar.
+pub const CHUNKS: usize = SCALAR_BYTES;
+
+/// Bit length of the range each chunk is proven to lie in.
+const CHUNK_BITS: usize = 8;
+
+/// Encoded length of an 8-bit [`RangeProof`]: four points, five scalars and
+/// three inner-product rounds.
+const RANGE_PROOF_BYTES: usize = 4 * POINT_BYTES + 5 * SCALAR_BYTES + 6 * POINT_BYTES;
+
+/// Exponent ElGamal encryptions of the bytes of a scalar, least significant
+/// first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkedCiphertext {
+    pub chunks: Vec<ElGamalCiphertext>,
+}
+
+impl ChunkedCiphertext {
+    /// Length in bytes of an encoded ciphertext: the [`CHUNKS`] chunk
+    /// ciphertexts in order.
+    pub const BYTES: usize = CHUNKS * ElGamalCiphertext::BYTES;
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.chunks.iter().flat_map(|ct| ct.to_bytes()).collect()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != Self::BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} chunked ciphertext bytes, got {}",
+                Self::BYTES,
+                bytes.len()
+            )));
+        }
+        Ok(Self {
+            chunks: bytes
+                .chunks(ElGamalCiphertext::BYTES)
+                .map(ElGamalCiphertext::from_bytes)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// `decrypt_chunked` recovers the scalar encrypted in a [`ChunkedCiphertext`].
+///
+/// Arguments:
+///
+/// * `secret`: the recipient's secret key `e`, with `E = e·G`
+/// * `ciphertext`: the ciphertext to decrypt
+///
+/// Returns:
+///
+/// `x = Σ 256^j·x_j mod n`, or `ProofError::DecryptionFailed` if the
+/// ciphertext does not have [`CHUNKS`] chunks or a chunk does not decrypt to
+/// a byte. Neither happens for a ciphertext whose
+/// [`RecoverableEncryptionProof`] verifies.
+pub fn decrypt_chunked(secret: &IBig, ciphertext: &ChunkedCiphertext) -> Result<IBig, ProofError> {
+    if ciphertext.chunks.len() != CHUNKS {
+        return Err(ProofError::DecryptionFailed);
+    }
+    let mut x = IBig::from(0);
+    for chunk in ciphertext.chunks.iter().rev() {
+        let byte =
+            decode_small(&decrypt(secret, chunk), 255).ok_or(ProofError::DecryptionFailed)?;
+        x = x * 256 + byte;
+    }
+    Ok(curve::rem_n(&x))
+}
+
+/// Proof that a [`ChunkedCiphertext`] encrypts the discrete log of a public
+/// key one byte per chunk. Each chunk value `x_j` is committed to as
+/// `V_j = x_j·G + γ_j·H` and range-proven below `2^8`, and a conjunction of
+/// Schnorr statements sharing one Fiat-Shamir challenge shows, for every `j`,
+///
+/// ```text
+/// c1_j = k_j·G,  c2_j = x_j·G + k_j·E,  V_j = x_j·G + γ_j·H
+/// ```
+///
+/// and `Y = Σ 256^j·x_j·G`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoverableEncryptionProof {
+    /// The commitments `V_j` to the chunk values.
+    pub commitments: Vec<PedersenCommitment>,
+    /// A proof that each `V_j` hides a value in `[0, 256)`.
+    pub ranges: Vec<RangeProof>,
+    /// Per chunk, the commitments `(r_k·G, r_x·G + r_k·E, r_x·G + r_γ·H)`.
+    pub announcements: Vec<(PointJacobi, PointJacobi, PointJacobi)>,
+    /// Commitment `Σ 256^j·r_x_j·G`.
+    pub a_y: PointJacobi,
+    /// Per chunk, the responses `(z_x, z_k, z_γ) = (r_x + c·x_j, r_k + c·k_j, r_γ + c·γ_j)`.
+    pub responses: Vec<(IBig, IBig, IBig)>,
+}
+
+impl RecoverableEncryptionProof {
+    /// Length in bytes of the encoding of one chunk: `V_j`, its range proof,
+    /// the three announcements and the three responses.
+    const CHUNK_BYTES: usize = 4 * POINT_BYTES + RANGE_PROOF_BYTES + 3 * SCALAR_BYTES;
+
+    /// Length in bytes of an encoded proof: compressed `a_y`, then each chunk
+    /// in order.
+    pub const BYTES: usize = POINT_BYTES + CHUNKS * Self::CHUNK_BYTES;
+
+    /// `encrypt_and_prove` encrypts `x` to `recipient_key` so that the
+    /// recipient can recover it, and proves the ciphertext encrypts the
+    /// discrete log of `y`.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `x`: the secret, with `y = x·G`
+    /// * `y`: the prover's public key
+    /// * `recipient_key`: the escrow agent's public key `E`
+    /// * `rng`: source of the encryption randomness, blinding factors and
+    ///   proof nonces
+    ///
+    /// Returns:
+    ///
+    /// The ciphertext and the proof, or `ProofError::InvalidStatement` if
+    /// `sid` is longer than `u32::MAX` bytes.
+    pub fn encrypt_and_prove<R: RngCore + CryptoRng>(
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        y: &PointJacobi,
+        recipient_key: &PointJacobi,
+        rng: &mut R,
+    ) -> Result<(ChunkedCiphertext, Self), ProofError> {
+        let g = PointJacobi::generator();
+        let h = commitment::h();
+        let bytes = curve::scalar_to_bytes(&curve::rem_n(x));
+        let mut chunks = Vec::with_capacity(CHUNKS);
+        let mut commitments = Vec::with_capacity(CHUNKS);
+        let mut ranges = Vec::with_capacity(CHUNKS);
+        let mut announcements = Vec::with_capacity(CHUNKS);
+        let mut witnesses = Vec::with_capacity(CHUNKS);
+        let mut nonces = Vec::with_capacity(CHUNKS);
+        for byte in bytes.iter().rev() {
+            let x_j = IBig::from(*byte);
+            let k_j = curve::random_scalar(rng);
+            let gamma_j = curve::random_scalar(rng);
+            chunks.push(encrypt_with_randomness(&x_j, &k_j, recipient_key));
+            let (range, v_j) = RangeProof::prove(sid, pid, *byte as u64, &gamma_j, CHUNK_BITS)?;
+            commitments.push(v_j);
+            ranges.push(range);
+
+            let r_x = curve::random_scalar(rng);
+            let r_k = curve::random_scalar(rng);
+            let r_gamma = curve::random_scalar(rng);
+            let r_x_g = g.mul_secret(&r_x);
+            announcements.push((
+                g.mul_secret(&r_k),
+                r_x_g.add(&recipient_key.mul_secret(&r_k)),
+                r_x_g.add(&h.mul_secret(&r_gamma)),
+            ));
+            witnesses.push((x_j, k_j, gamma_j));
+            nonces.push((r_x, r_k, r_gamma));
+        }
+        let a_y = g.mul_secret(&recombine(nonces.iter().map(|(r_x, _, _)| r_x)));
+        let ciphertext = ChunkedCiphertext { chunks };
+        let c = Self::challenge(
+            sid,
+            pid,
+            y,
+            recipient_key,
+            &ciphertext,
+            &commitments,
+            &announcements,
+            &a_y,
+        )?;
+        let responses = witnesses
+            .iter()
+            .zip(&nonces)
+            .map(|((x_j, k_j, gamma_j), (r_x, r_k, r_gamma))| {
+                (
+                    curve::rem_n(&(r_x + &c * x_j)),
+                    curve::rem_n(&(r_k + &c * k_j)),
+                    curve::rem_n(&(r_gamma + &c * gamma_j)),
+                )
+            })
+            .collect();
+        Ok((
+            ciphertext,
+            Self {
+                commitments,
+                ranges,
+                announcements,
+                a_y,
+                responses,
+            },
+        ))
+    }
+
+    /// `verify` checks that `ciphertext` encrypts the discrete log of `y`
+    /// under `recipient_key`, one byte per chunk.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `y`: the prover's public key
+    /// * `recipient_key`: the escrow agent's public key `E`
+    /// * `ciphertext`: the ciphertext the proof is about
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if every range proof and equation holds,
+    /// `ProofError::InvalidStatement` if the ciphertext or proof does not
+    /// have [`CHUNKS`] chunks, the errors of [`RangeProof::verify`], and
+    /// `ProofError::VerificationFailed` otherwise.
+    pub fn verify(
+        &self,
+        sid: &str,
+        pid: i32,
+        y: &PointJacobi,
+        recipient_key: &PointJacobi,
+        ciphertext: &ChunkedCiphertext,
+    ) -> Result<(), ProofError> {
+        let lengths = [
+            ciphertext.chunks.len(),
+            self.commitments.len(),
+            self.ranges.len(),
+            self.announcements.len(),
+            self.responses.len(),
+        ];
+        if lengths.iter().any(|len| *len != CHUNKS) {
+            return Err(ProofError::InvalidStatement(format!(
+                "expected {} chunks, got {:?}",
+                CHUNKS, lengths
+            )));
+        }
+        let g = PointJacobi::generator();
+        let h = commitment::h();
+        let c = Self::challenge(
+            sid,
+            pid,
+            y,
+            recipient_key,
+            ciphertext,
+            &self.commitments,
+            &self.announcements,
+            &self.a_y,
+        )?;
+        for (((chunk, v_j), (a1, a2, a3)), (z_x, z_k, z_gamma)) in ciphertext
+            .chunks
+            .iter()
+            .zip(&self.commitments)
+            .zip(&self.announcements)
+            .zip(&self.responses)
+        {
+            let z_x_g = g.mul_unsafe(z_x);
+            let c1_ok = g.mul_unsafe(z_k) == a1.add(&chunk.c1.mul_unsafe(&c));
+            let c2_ok =
+                z_x_g.add(&recipient_key.mul_unsafe(z_k)) == a2.add(&chunk.c2.mul_unsafe(&c));
+            let v_ok = z_x_g.add(&h.mul_unsafe(z_gamma)) == a3.add(&v_j.c.mul_unsafe(&c));
+            if !(c1_ok && c2_ok && v_ok) {
+                return Err(ProofError::VerificationFailed);
+            }
+        }
+        let z_y = recombine(self.responses.iter().map(|(z_x, _, _)| z_x));
+        if g.mul_unsafe(&z_y) != self.a_y.add(&y.mul_unsafe(&c)) {
+            return Err(ProofError::VerificationFailed);
+        }
+        // The range proofs cost far more than the equations above, so they
+        // run last.
+        for (range, v_j) in self.ranges.iter().zip(&self.commitments) {
+            range.verify(sid, pid, v_j, CHUNK_BITS)?;
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(Self::BYTES);
+        out.extend(self.a_y.to_bytes());
+        for (((v_j, range), (a1, a2, a3)), (z_x, z_k, z_gamma)) in self
+            .commitments
+            .iter()
+            .zip(&self.ranges)
+            .zip(&self.announcements)
+            .zip(&self.responses)
+        {
+            out.extend(v_j.to_bytes());
+            out.extend(range.to_bytes());
+            for point in [a1, a2, a3] {
+                out.extend(point.to_bytes());
+            }
+            for scalar in [z_x, z_k, z_gamma] {
+                out.extend(curve::scalar_to_bytes(scalar));
+            }
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != Self::BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} recoverable encryption proof bytes, got {}",
+                Self::BYTES,
+                bytes.len()
+            )));
+        }
+        let mut proof = Self {
+            commitments: Vec::with_capacity(CHUNKS),
+            ranges: Vec::with_capacity(CHUNKS),
+            announcements: Vec::with_capacity(CHUNKS),
+            a_y: PointJacobi::from_bytes(&bytes[..POINT_BYTES])?,
+            responses: Vec::with_capacity(CHUNKS),
+        };
+        for chunk in bytes[POINT_BYTES..].chunks(Self::CHUNK_BYTES) {
+            let point = |i: usize| {
+                let start = POINT_BYTES + RANGE_PROOF_BYTES + i * POINT_BYTES;
+                PointJacobi::from_bytes(&chunk[start..start + POINT_BYTES])
+            };
+            let scalar = |i: usize| {
+                let start = 4 * POINT_BYTES + RANGE_PROOF_BYTES + i * SCALAR_BYTES;
+                curve::scalar_from_canonical_bytes(&chunk[start..start + SCALAR_BYTES])
+            };
+            proof
+                .commitments
+                .push(PedersenCommitment::from_bytes(&chunk[..POINT_BYTES])?);
+            proof.ranges.push(RangeProof::from_bytes(
+                &chunk[POINT_BYTES..POINT_BYTES + RANGE_PROOF_BYTES],
+            )?);
+            proof.announcements.push((point(0)?, point(1)?, point(2)?));
+            proof.responses.push((scalar(0)?, scalar(1)?, scalar(2)?));
+        }
+        Ok(proof)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn challenge(
+        sid: &str,
+        pid: i32,
+        y: &PointJacobi,
+        recipient_key: &PointJacobi,
+        ciphertext: &ChunkedCiphertext,
+        commitments: &[PedersenCommitment],
+        announcements: &[(PointJacobi, PointJacobi, PointJacobi)],
+        a_y: &PointJacobi,
+    ) -> Result<IBig, ProofError> {
+        let g = PointJacobi::generator();
+        let h = commitment::h();
+        let mut points = vec![&g, &h, y, recipient_key];
+        for ((chunk, v_j), (a1, a2, a3)) in
+            ciphertext.chunks.iter().zip(commitments).zip(announcements)
+        {
+            points.extend([&chunk.c1, &chunk.c2, &v_j.c, a1, a2, a3]);
+        }
+        points.push(a_y);
+        hash_tagged(TAG, sid, pid, &points, b"chunked")
+    }
+}
+
+/// `Σ 256^j·v_j mod n` over per-chunk values, least significant first.
+fn recombine<'a>(values: impl DoubleEndedIterator<Item = &'a IBig>) -> IBig {
+    values
+        .rev()
+        .fold(IBig::from(0), |acc, v| curve::rem_n(&(acc * 256 + v)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::sync::OnceLock;
+
+    struct Escrow {
+        x: IBig,
+        y: PointJacobi,
+        e: IBig,
+        recipient_key: PointJacobi,
+        rng: StdRng,
+    }
+
+    fn escrow() -> Escrow {
+        let mut rng = StdRng::seed_from_u64(838);
+        let g = PointJacobi::generator();
+        let x = curve::random_scalar(&mut rng);
+        let e = curve::random_scalar(&mut rng);
+        Escrow {
+            y: g.mul_unsafe(&x),
+            recipient_key: g.mul_unsafe(&e),
+            x,
+            e,
+            rng,
+        }
+    }
+
+    #[test]
+    fn honest_escrow_verifies_and_decrypts_to_the_public_key() {
+        let mut s = escrow();
+        let (ciphertext, proof) = VerifiableEncryptionProof::encrypt_and_prove(
+            "escrow",
+            1,
+            &s.x,
+            &s.y,
+            &s.recipient_key,
+            &mut s.rng,
+        )
+        .unwrap();
+        assert_eq!(
+            proof.verify("escrow", 1, &s.y, &s.recipient_key, &ciphertext),
+            Ok(())
+        );
+        assert_eq!(decrypt(&s.e, &ciphertext), s.y);
+
+        let ciphertext = ElGamalCiphertext::from_bytes(&ciphertext.to_bytes()).unwrap();
+        let proof = VerifiableEncryptionProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(
+            proof.verify("escrow", 1, &s.y, &s.recipient_key, &ciphertext),
+            Ok(())
+        );
+        assert_eq!(
+            proof.verify("escrow", 2, &s.y, &s.recipient_key, &ciphertext),
+            Err(ProofError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn a_ciphertext_of_another_value_is_rejected() {
+        let mut s = escrow();
+        let (_, proof) = VerifiableEncryptionProof::encrypt_and_prove(
+            "escrow",
+            1,
+            &s.x,
+            &s.y,
+            &s.recipient_key,
+            &mut s.rng,
+        )
+        .unwrap();
+        let other = curve::rem_n(&(&s.x + 1));
+        let swapped = encrypt(&other, &s.recipient_key, &mut s.rng);
+        assert_eq!(
+            proof.verify("escrow", 1, &s.y, &s.recipient_key, &swapped),
+            Err(ProofError::VerificationFailed)
+        );
+
+        // A prover encrypting a value other than log_G Y cannot make a valid
+        // proof for Y.
+        let (ciphertext, proof) = VerifiableEncryptionProof::encrypt_and_prove(
+            "escrow",
+            1,
+            &other,
+            &s.y,
+            &s.recipient_key,
+            &mut s.rng,
+        )
+        .unwrap();
+        assert_eq!(
+            proof.verify("escrow", 1, &s.y, &s.recipient_key, &ciphertext),
+            Err(ProofError::VerificationFailed)
+        );
+        assert_ne!(decrypt(&s.e, &ciphertext), s.y);
+    }
+
+    #[test]
+    fn sums_of_small_values_decrypt_with_a_proof() {
+        let mut s = escrow();
+        let votes = [1u64, 0, 1, 1, 0];
+        let tally = votes
+            .iter()
+            .map(|m| encrypt(&IBig::from(*m), &s.recipient_key, &mut s.rng))
+            .reduce(|acc, ct| acc.add(&ct))
+            .unwrap();
+        let (plaintext, proof) =
+            DecryptionProof::prove("tally", 1, &s.e, &s.recipient_key, &tally).unwrap();
+        assert_eq!(decode_small(&plaintext, 5), Some(3));
+        assert_eq!(decode_small(&plaintext, 2), None);
+        let proof = DecryptionProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(
+            proof.verify("tally", 1, &s.recipient_key, &tally),
+            Ok(plaintext)
+        );
+        let forged = DecryptionProof {
+            d: proof.d.add(&PointJacobi::generator()),
+            proof: proof.proof.clone(),
+        };
+        assert!(forged.verify("tally", 1, &s.recipient_key, &tally).is_err());
+    }
+
+    #[test]
+    fn malformed_encodings_are_rejected() {
+        let mut s = escrow();
+        let ciphertext = encrypt(&s.x, &s.recipient_key, &mut s.rng);
+        let bytes = ciphertext.to_bytes();
+        assert!(matches!(
+            ElGamalCiphertext::from_bytes(&bytes[1..]),
+            Err(ProofError::DeserializationError(_))
+        ));
+        assert!(matches!(
+            VerifiableEncryptionProof::from_bytes(&[0; VerifiableEncryptionProof::BYTES - 1]),
+            Err(ProofError::DeserializationError(_))
+        ));
+    }
+
+    /// One recoverable escrow shared by the tests below: its 32 range proofs
+    /// take tens of seconds to make in a debug build.
+    fn recoverable_escrow() -> &'static (ChunkedCiphertext, RecoverableEncryptionProof) {
+        static ESCROW: OnceLock<(ChunkedCiphertext, RecoverableEncryptionProof)> = OnceLock::new();
+        ESCROW.get_or_init(|| {
+            let mut s = escrow();
+            RecoverableEncryptionProof::encrypt_and_prove(
+                "escrow",
+                1,
+                &s.x,
+                &s.y,
+                &s.recipient_key,
+                &mut s.rng,
+            )
+            .unwrap()
+        })
+    }
+
+    #[test]
+    fn a_recoverable_escrow_decrypts_back_to_the_secret() {
+        let s = escrow();
+        let (ciphertext, proof) = recoverable_escrow();
+        assert_eq!(
+            proof.verify("escrow", 1, &s.y, &s.recipient_key, ciphertext),
+            Ok(())
+        );
+        assert_eq!(decrypt_chunked(&s.e, ciphertext), Ok(s.x.clone()));
+
+        assert_eq!(
+            ChunkedCiphertext::from_bytes(&ciphertext.to_bytes()).as_ref(),
+            Ok(ciphertext)
+        );
+        assert_eq!(
+            RecoverableEncryptionProof::from_bytes(&proof.to_bytes()).as_ref(),
+            Ok(proof)
+        );
+        assert_eq!(
+            proof.verify("escrow", 2, &s.y, &s.recipient_key, ciphertext),
+            Err(ProofError::VerificationFailed)
+        );
+        let other_key = s.y.add(&PointJacobi::generator());
+        assert_eq!(
+            proof.verify("escrow", 1, &other_key, &s.recipient_key, ciphertext),
+            Err(ProofError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn tampered_recoverable_escrows_are_rejected() {
+        let mut s = escrow();
+        let (ciphertext, proof) = recoverable_escrow();
+
+        // Shifting one chunk by G changes the plaintext; the proof no longer
+        // matches it.
+        let mut shifted = ciphertext.clone();
+        shifted.chunks[3].c2 = shifted.chunks[3].c2.add(&PointJacobi::generator());
+        assert_eq!(
+            proof.verify("escrow", 1, &s.y, &s.recipient_key, &shifted),
+            Err(ProofError::VerificationFailed)
+        );
+
+        // A chunk encrypting a value above a byte cannot be decoded.
+        let mut wide = ciphertext.clone();
+        wide.chunks[0] = encrypt(&IBig::from(256), &s.recipient_key, &mut s.rng);
+        assert_eq!(
+            decrypt_chunked(&s.e, &wide),
+            Err(ProofError::DecryptionFailed)
+        );
+        assert_eq!(
+            proof.verify("escrow", 1, &s.y, &s.recipient_key, &wide),
+            Err(ProofError::VerificationFailed)
+        );
+
+        let mut short = proof.clone();
+        short.ranges.pop();
+        assert!(matches!(
+            short.verify("escrow", 1, &s.y, &s.recipient_key, ciphertext),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        let mut swapped = proof.clone();
+        swapped.ranges.swap(0, 1);
+        assert_eq!(
+            swapped.verify("escrow", 1, &s.y, &s.recipient_key, ciphertext),
+            Err(ProofError::VerificationFailed)
+        );
+        assert!(matches!(
+            RecoverableEncryptionProof::from_bytes(&proof.to_bytes()[1..]),
+            Err(ProofError::DeserializationError(_))
+        ));
+    }
+}
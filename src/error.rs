@@ -0,0 +1,69 @@
+use std::fmt;
+
+/// Errors produced while decoding or verifying proofs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofError {
+    /// The input bytes do not describe a well-formed value.
+    DeserializationError(String),
+    /// The verification equation `s·G == T + c·Y` does not hold.
+    VerificationFailed,
+    /// A time-bound proof was checked after its validity window closed.
+    Expired,
+    /// A time-bound proof was checked before its `issued_at` time.
+    NotYetValid,
+    /// A secret share is inconsistent with the dealer's commitments or has an
+    /// invalid index.
+    InvalidShare(u32),
+    /// The same share index was supplied more than once.
+    DuplicateShareIndex(u32),
+    /// Too few shares were supplied to reconstruct the secret.
+    InsufficientShares,
+    /// The statement is malformed, e.g. an empty basis or a number of
+    /// witnesses that does not match the number of bases.
+    InvalidStatement(String),
+    /// An encrypted secret key could not be decrypted: the passphrase is
+    /// wrong or the ciphertext was modified.
+    DecryptionFailed,
+    /// A scalar in a proof is not reduced modulo the curve order.
+    ScalarOutOfRange,
+    /// The proof's commitment `T` is the point at infinity.
+    IdentityCommitment,
+    /// A point in the statement is not on the curve.
+    PointNotOnCurve,
+    /// A secret scalar is zero or not below the curve order.
+    InvalidSecret,
+    /// A public key or base point that makes the statement meaningless: the
+    /// point at infinity, or a public key equal to the base point.
+    InvalidPoint,
+    /// A challenge that is zero modulo n, which makes the proof independent
+    /// of the public key.
+    InvalidScalar,
+}
+
+impl fmt::Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofError::DeserializationError(msg) => write!(f, "deserialization error: {}", msg),
+            ProofError::VerificationFailed => write!(f, "proof verification failed"),
+            ProofError::Expired => write!(f, "proof has expired"),
+            ProofError::NotYetValid => write!(f, "proof is not yet valid"),
+            ProofError::InvalidShare(index) => write!(f, "invalid share for index {}", index),
+            ProofError::DuplicateShareIndex(index) => {
+                write!(f, "duplicate share index {}", index)
+            }
+            ProofError::InsufficientShares => write!(f, "not enough shares"),
+            ProofError::InvalidStatement(msg) => write!(f, "invalid statement: {}", msg),
+            ProofError::DecryptionFailed => write!(f, "decryption failed"),
+            ProofError::ScalarOutOfRange => {
+                write!(f, "scalar is not reduced modulo the curve order")
+            }
+            ProofError::IdentityCommitment => write!(f, "commitment is the point at infinity"),
+            ProofError::PointNotOnCurve => write!(f, "point is not on the curve"),
+            ProofError::InvalidSecret => write!(f, "secret must be in [1, n)"),
+            ProofError::InvalidPoint => write!(f, "weak public key or base point"),
+            ProofError::InvalidScalar => write!(f, "challenge is zero"),
+        }
+    }
+}
+
+impl std::error::Error for ProofError {}
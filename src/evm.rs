@@ -0,0 +1,240 @@
+//! An EVM profile of the secp256k1 proof, so that proofs can be checked by a
+//! contract.
+//!
+//! The transcript is the version 2 transcript of [`crate::hash`] over
+//! `G, Y, T`, which is exactly what Solidity's `abi.encodePacked` produces
+//! from the same fields, but the challenge is its Keccak-256 digest instead
+//! of SHA-256. The base point is always `G`, since the contract checks the
+//! equation with the `ecrecover` precompile, which only multiplies the
+//! generator and a point recovered from an x coordinate:
+//! `ecrecover(e, v, r, s')` is the address of `r⁻¹·(s'·R - e·G)`, so with
+//! `R = Y`, `r = Y.x`, `s' = -c·r` and `e = -s·r` it is the address of
+//! `s·G - c·Y`, which must be that of `T`. This needs `Y.x < n`, which fails
+//! for a negligible fraction of keys.
+//!
+//! [`DLogProof::to_calldata`](crate::DLogProof::to_calldata) encodes a proof as
+//! `abi.encode(uint256 tx, uint256 ty, uint256 s)`, 96 bytes, and
+//! [`point_to_calldata`] a public key as its two affine coordinates. Prove
+//! with [`Prover::prove_evm`](crate::Prover::prove_evm) and check off-chain
+//! with [`Verifier::verify_evm`](crate::Verifier::verify_evm). A reference
+//! verifier:
+//!
+//! ```solidity
+//! // SPDX-License-Identifier: MIT
+//! pragma solidity ^0.8.20;
+//!
+//! library DLogProofVerifier {
+//!     uint256 constant N = 0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141;
+//!     uint256 constant GX = 0x79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798;
+//!
+//!     function challenge(
+//!         string memory sid, int32 pid, uint256 yx, uint256 yy, uint256 tx, uint256 ty
+//!     ) internal pure returns (uint256) {
+//!         return uint256(keccak256(abi.encodePacked(
+//!             uint8(2), uint32(bytes(sid).length), sid, pid, uint32(3),
+//!             uint8(2), GX,
+//!             uint8(2 + (yy & 1)), yx,
+//!             uint8(2 + (ty & 1)), tx,
+//!             uint32(0)
+//!         ))) % N;
+//!     }
+//!
+//!     function verify(
+//!         string memory sid, int32 pid, uint256 yx, uint256 yy, bytes calldata proof
+//!     ) internal pure returns (bool) {
+//!         (uint256 tx, uint256 ty, uint256 s) = abi.decode(proof, (uint256, uint256, uint256));
+//!         if (s == 0 || s >= N || yx == 0 || yx >= N) return false;
+//!         uint256 c = challenge(sid, pid, yx, yy, tx, ty);
+//!         if (c == 0) return false;
+//!         address recovered = ecrecover(
+//!             bytes32(N - mulmod(s, yx, N)),
+//!             uint8(27 + (yy & 1)),
+//!             bytes32(yx),
+//!             bytes32(N - mulmod(c, yx, N))
+//!         );
+//!         return recovered != address(0)
+//!             && recovered == address(uint160(uint256(keccak256(abi.encodePacked(tx, ty)))));
+//!     }
+//! }
+//! ```
+
+use crate::curve::{self, SCALAR_BYTES};
+use crate::error::ProofError;
+use crate::hash::{challenge_transcript, ChallengeHash, Keccak256};
+use crate::jacobi_point::PointJacobi;
+use ibig::IBig;
+
+/// Length in bytes of [`DLogProof::to_calldata`](crate::DLogProof::to_calldata): three 32-byte words.
+pub const CALLDATA_BYTES: usize = 3 * SCALAR_BYTES;
+
+/// `challenge` computes `c = keccak256(transcript(sid, pid, G, y, t)) mod n`,
+/// the digest read as a big-endian integer and reduced as the contract does,
+/// or `ProofError::InvalidStatement` if `sid` is longer than `u32::MAX` bytes.
+pub fn challenge(
+    sid: &str,
+    pid: i32,
+    y: &PointJacobi,
+    t: &PointJacobi,
+) -> Result<IBig, ProofError> {
+    let transcript =
+        challenge_transcript(sid, pid, &[PointJacobi::generator(), y.clone(), t.clone()])?;
+    Ok(curve::rem_n(&Keccak256::challenge(&transcript)))
+}
+
+/// `validate_public_key` rejects keys the `ecrecover` check cannot handle:
+/// `ProofError::InvalidPoint` if `y` is the point at infinity or its `x`
+/// coordinate is not below `n`.
+pub(crate) fn validate_public_key(y: &PointJacobi) -> Result<(), ProofError> {
+    if y.is_zero() || y.to_affine().x >= *curve::N {
+        return Err(ProofError::InvalidPoint);
+    }
+    Ok(())
+}
+
+/// `point_to_calldata` encodes a point as `abi.encode(uint256 x, uint256 y)`.
+///
+/// Returns:
+///
+/// The 64 bytes, or `ProofError::InvalidPoint` for the point at infinity.
+pub fn point_to_calldata(p: &PointJacobi) -> Result<[u8; 2 * SCALAR_BYTES], ProofError> {
+    let bytes = p
+        .to_affine()
+        .to_uncompressed_bytes()
+        .ok_or(ProofError::InvalidPoint)?;
+    Ok(bytes[1..].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+    use crate::proof::DLogProof;
+    use crate::prover::Prover;
+    use crate::scalar::Scalar;
+    use crate::verifier::Verifier;
+    use sha3::Digest;
+
+    /// `x = 1069`, `y = x·G`, `T = 2·G` and `s = 3`.
+    fn fixed() -> (IBig, PointJacobi, PointJacobi, IBig) {
+        let x = IBig::from(1069);
+        let y = PointJacobi::mul_generator(&x);
+        let t = PointJacobi::mul_generator(&IBig::from(2));
+        (x, y, t, IBig::from(3))
+    }
+
+    /// The reference contract's `abi.encodePacked` transcript, built field by
+    /// field.
+    fn packed(sid: &str, pid: i32, y: &PointJacobi, t: &PointJacobi) -> Vec<u8> {
+        let mut out = vec![2u8];
+        out.extend((sid.len() as u32).to_be_bytes());
+        out.extend(sid.as_bytes());
+        out.extend(pid.to_be_bytes());
+        out.extend(3u32.to_be_bytes());
+        for point in [&PointJacobi::generator(), y, t] {
+            out.extend(point.to_bytes());
+        }
+        out.extend(0u32.to_be_bytes());
+        out
+    }
+
+    #[test]
+    fn the_challenge_and_calldata_are_pinned() {
+        let (_, y, t, s) = fixed();
+        let c = challenge("evm", 5, &y, &t).unwrap();
+        let digest = sha3::Keccak256::digest(packed("evm", 5, &y, &t));
+        assert_eq!(
+            c,
+            curve::rem_n(&IBig::from(ibig::UBig::from_be_bytes(&digest)))
+        );
+        assert_eq!(
+            hex::encode(&curve::scalar_to_bytes(&c)),
+            "9b77655288a19c12f6affa5f72b7a90c5dd6b62d97846ad1497311bfa439a900"
+        );
+
+        let proof = DLogProof::new(t.clone(), Scalar::new(&s));
+        let calldata = proof.to_calldata().unwrap();
+        // 2·G's affine coordinates, then s.
+        assert_eq!(
+            hex::encode(&calldata),
+            concat!(
+                "c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+                "1ae168fea63dc339a3c58419466ceaeef7f632653266d0e1236431a950cfe52a",
+                "0000000000000000000000000000000000000000000000000000000000000003",
+            )
+        );
+        assert_eq!(
+            &calldata[..2 * SCALAR_BYTES],
+            &point_to_calldata(&t).unwrap()[..]
+        );
+        assert_eq!(DLogProof::from_calldata(&calldata), Ok(proof));
+        assert_eq!(
+            point_to_calldata(&PointJacobi::zero()),
+            Err(ProofError::InvalidPoint)
+        );
+    }
+
+    #[test]
+    fn evm_proofs_round_trip_and_are_bound_to_the_session() {
+        let (x, y, _, _) = fixed();
+        let proof = Prover::prove_evm("evm", 5, &x, &y).unwrap();
+        assert_eq!(Verifier::verify_evm(&proof, "evm", 5, &y), Ok(()));
+        let decoded = DLogProof::from_calldata(&proof.to_calldata().unwrap()).unwrap();
+        assert_eq!(Verifier::verify_evm(&decoded, "evm", 5, &y), Ok(()));
+        assert_eq!(
+            Verifier::verify_evm(&proof, "other", 5, &y),
+            Err(ProofError::VerificationFailed)
+        );
+        assert_eq!(
+            Verifier::verify_evm(&proof, "evm", 6, &y),
+            Err(ProofError::VerificationFailed)
+        );
+        // The SHA-256 profile does not accept it.
+        assert!(Verifier::verify(&proof, "evm", 5, &y, &PointJacobi::generator()).is_err());
+    }
+
+    #[test]
+    fn keys_with_x_not_below_n_are_rejected() {
+        let big = (0u32..)
+            .find_map(|k| {
+                let x = ibig::UBig::try_from(&*curve::N + k).unwrap();
+                let mut bytes = vec![2u8];
+                bytes.extend(x.to_be_bytes());
+                PointJacobi::from_bytes(&bytes).ok()
+            })
+            .unwrap();
+        assert!(big.to_affine().x >= *curve::N);
+        assert_eq!(validate_public_key(&big), Err(ProofError::InvalidPoint));
+        assert_eq!(
+            validate_public_key(&PointJacobi::zero()),
+            Err(ProofError::InvalidPoint)
+        );
+        let (x, y, _, _) = fixed();
+        assert_eq!(validate_public_key(&y), Ok(()));
+        assert_eq!(
+            Prover::prove_evm("evm", 5, &x, &big),
+            Err(ProofError::InvalidPoint)
+        );
+        let proof = Prover::prove_evm("evm", 5, &x, &y).unwrap();
+        assert_eq!(
+            Verifier::verify_evm(&proof, "evm", 5, &big),
+            Err(ProofError::InvalidPoint)
+        );
+    }
+
+    #[test]
+    fn the_ecrecover_identity_recovers_t() {
+        // With r = Y.x, s' = -c·r and e = -s·r, ecrecover(e, v, r, s') is the
+        // address of r⁻¹·(s'·Y - e·G), which must be T.
+        let (x, y, _, _) = fixed();
+        let proof = Prover::prove_evm("evm", 5, &x, &y).unwrap();
+        let c = challenge("evm", 5, &y, &proof.t).unwrap();
+        let r = y.to_affine().x;
+        let s_prime = curve::rem_n(&-(&c * &r));
+        let e = curve::rem_n(&-(proof.s.value() * &r));
+        let recovered = y
+            .mul_unsafe(&s_prime)
+            .add(&PointJacobi::mul_generator(&e).negate())
+            .mul_unsafe(&curve::invert_n(&r));
+        assert_eq!(recovered, proof.t);
+    }
+}
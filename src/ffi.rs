@@ -0,0 +1,216 @@
+//! C ABI for C, C++ and Go (cgo) callers, behind the `ffi` feature.
+//!
+//! As in [`crate::mobile`], every value crosses the boundary in its canonical
+//! byte encoding: secrets as 32-byte big-endian scalars, public keys as
+//! 33-byte compressed points and proofs as [`DLogProof::to_bytes`], and
+//! proofs are over the generator `G`. Each buffer is passed as a pointer and
+//! a length; a null pointer is accepted only with a zero length. The session
+//! id must be UTF-8, the bytes the other bindings hash for the same string.
+//!
+//! Every function returns [`DLOGPROOF_OK`] or one of the negative
+//! `DLOGPROOF_ERR_*` codes, one per [`ProofError`] variant plus the failures
+//! that can only happen at the boundary. Output buffers are written only on
+//! success. Panics are caught and reported as [`DLOGPROOF_ERR_PANIC`] rather
+//! than unwinding into the caller.
+//!
+//! The header `include/dlogproof.h` is generated from this module with
+//! `cbindgen --config cbindgen.toml --output include/dlogproof.h`.
+
+use crate::curve::SCALAR_BYTES;
+use crate::error::ProofError;
+use crate::jacobi_point::POINT_BYTES;
+use crate::keys::{PublicKey, SecretKey};
+use crate::proof::DLogProof;
+use crate::prover::Prover;
+use crate::verifier::Verifier;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Length of a secret key, a big-endian scalar.
+pub const DLOGPROOF_SECRET_BYTES: usize = SCALAR_BYTES;
+/// Length of a public key, a compressed point.
+pub const DLOGPROOF_PUBLIC_KEY_BYTES: usize = POINT_BYTES;
+/// Length of a proof, `T || s`.
+pub const DLOGPROOF_PROOF_BYTES: usize = DLogProof::BYTES;
+
+/// Success; for `dlogproof_verify`, the proof is valid.
+pub const DLOGPROOF_OK: i32 = 0;
+/// An output pointer, or an input pointer with a nonzero length, was null.
+pub const DLOGPROOF_ERR_NULL_POINTER: i32 = -1;
+/// The session id is not UTF-8.
+pub const DLOGPROOF_ERR_INVALID_INPUT: i32 = -2;
+/// An output buffer is shorter than the value written to it.
+pub const DLOGPROOF_ERR_BUFFER_TOO_SMALL: i32 = -3;
+/// The library panicked.
+pub const DLOGPROOF_ERR_PANIC: i32 = -4;
+/// [`ProofError::DeserializationError`].
+pub const DLOGPROOF_ERR_DESERIALIZATION: i32 = -10;
+/// [`ProofError::VerificationFailed`]: the proof is well formed but invalid.
+pub const DLOGPROOF_ERR_VERIFICATION_FAILED: i32 = -11;
+/// [`ProofError::Expired`].
+pub const DLOGPROOF_ERR_EXPIRED: i32 = -12;
+/// [`ProofError::NotYetValid`].
+pub const DLOGPROOF_ERR_NOT_YET_VALID: i32 = -13;
+/// [`ProofError::InvalidShare`].
+pub const DLOGPROOF_ERR_INVALID_SHARE: i32 = -14;
+/// [`ProofError::DuplicateShareIndex`].
+pub const DLOGPROOF_ERR_DUPLICATE_SHARE_INDEX: i32 = -15;
+/// [`ProofError::InsufficientShares`].
+pub const DLOGPROOF_ERR_INSUFFICIENT_SHARES: i32 = -16;
+/// [`ProofError::InvalidStatement`].
+pub const DLOGPROOF_ERR_INVALID_STATEMENT: i32 = -17;
+/// [`ProofError::DecryptionFailed`].
+pub const DLOGPROOF_ERR_DECRYPTION_FAILED: i32 = -18;
+/// [`ProofError::ScalarOutOfRange`].
+pub const DLOGPROOF_ERR_SCALAR_OUT_OF_RANGE: i32 = -19;
+/// [`ProofError::IdentityCommitment`].
+pub const DLOGPROOF_ERR_IDENTITY_COMMITMENT: i32 = -20;
+/// [`ProofError::PointNotOnCurve`].
+pub const DLOGPROOF_ERR_POINT_NOT_ON_CURVE: i32 = -21;
+/// [`ProofError::InvalidSecret`].
+pub const DLOGPROOF_ERR_INVALID_SECRET: i32 = -22;
+/// [`ProofError::InvalidPoint`].
+pub const DLOGPROOF_ERR_INVALID_POINT: i32 = -23;
+/// [`ProofError::InvalidScalar`].
+pub const DLOGPROOF_ERR_INVALID_SCALAR: i32 = -24;
+
+/// Generates a random secret and its public key `secret·G`.
+///
+/// # Safety
+///
+/// `secret_out` must be valid for writes of `secret_out_len` bytes and
+/// `public_key_out` for writes of `public_key_out_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn dlogproof_generate_keypair(
+    secret_out: *mut u8,
+    secret_out_len: usize,
+    public_key_out: *mut u8,
+    public_key_out_len: usize,
+) -> i32 {
+    guard(|| {
+        let secret_out = output(secret_out, secret_out_len, DLOGPROOF_SECRET_BYTES)?;
+        let public_key_out = output(
+            public_key_out,
+            public_key_out_len,
+            DLOGPROOF_PUBLIC_KEY_BYTES,
+        )?;
+        let key = SecretKey::random(&mut rand::rngs::OsRng);
+        secret_out.copy_from_slice(&key.to_bytes());
+        public_key_out.copy_from_slice(&key.public_key().to_bytes());
+        Ok(())
+    })
+}
+
+/// Proves knowledge of `secret` for `public_key` in session `sid` as
+/// participant `pid`, writing [`DLOGPROOF_PROOF_BYTES`] bytes to `proof_out`.
+///
+/// # Safety
+///
+/// Each input pointer must be valid for reads of its length, and `proof_out`
+/// for writes of `proof_out_len` bytes.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn dlogproof_prove(
+    sid: *const u8,
+    sid_len: usize,
+    pid: i32,
+    secret: *const u8,
+    secret_len: usize,
+    public_key: *const u8,
+    public_key_len: usize,
+    proof_out: *mut u8,
+    proof_out_len: usize,
+) -> i32 {
+    guard(|| {
+        let sid = session_id(sid, sid_len)?;
+        let key = SecretKey::from_bytes(input(secret, secret_len)?)?;
+        let public_key = PublicKey::from_bytes(input(public_key, public_key_len)?)?;
+        let proof_out = output(proof_out, proof_out_len, DLOGPROOF_PROOF_BYTES)?;
+        let proof = Prover::prove_with_key(sid, pid, &key, &public_key)?;
+        proof_out.copy_from_slice(&proof.to_bytes());
+        Ok(())
+    })
+}
+
+/// Checks `proof` for `public_key` in session `sid` as participant `pid`.
+/// Returns [`DLOGPROOF_OK`] for a valid proof and
+/// [`DLOGPROOF_ERR_VERIFICATION_FAILED`] for a well-formed invalid one.
+///
+/// # Safety
+///
+/// Each pointer must be valid for reads of its length.
+#[no_mangle]
+pub unsafe extern "C" fn dlogproof_verify(
+    sid: *const u8,
+    sid_len: usize,
+    pid: i32,
+    public_key: *const u8,
+    public_key_len: usize,
+    proof: *const u8,
+    proof_len: usize,
+) -> i32 {
+    guard(|| {
+        let sid = session_id(sid, sid_len)?;
+        let public_key = PublicKey::from_bytes(input(public_key, public_key_len)?)?;
+        let proof = DLogProof::from_bytes(input(proof, proof_len)?)?;
+        Verifier::verify_with_key(&proof, sid, pid, &public_key)?;
+        Ok(())
+    })
+}
+
+/// An error code, converted from a [`ProofError`] by `?`.
+struct Code(i32);
+
+impl From<ProofError> for Code {
+    fn from(err: ProofError) -> Self {
+        Code(match err {
+            ProofError::DeserializationError(_) => DLOGPROOF_ERR_DESERIALIZATION,
+            ProofError::VerificationFailed => DLOGPROOF_ERR_VERIFICATION_FAILED,
+            ProofError::Expired => DLOGPROOF_ERR_EXPIRED,
+            ProofError::NotYetValid => DLOGPROOF_ERR_NOT_YET_VALID,
+            ProofError::InvalidShare(_) => DLOGPROOF_ERR_INVALID_SHARE,
+            ProofError::DuplicateShareIndex(_) => DLOGPROOF_ERR_DUPLICATE_SHARE_INDEX,
+            ProofError::InsufficientShares => DLOGPROOF_ERR_INSUFFICIENT_SHARES,
+            ProofError::InvalidStatement(_) => DLOGPROOF_ERR_INVALID_STATEMENT,
+            ProofError::DecryptionFailed => DLOGPROOF_ERR_DECRYPTION_FAILED,
+            ProofError::ScalarOutOfRange => DLOGPROOF_ERR_SCALAR_OUT_OF_RANGE,
+            ProofError::IdentityCommitment => DLOGPROOF_ERR_IDENTITY_COMMITMENT,
+            ProofError::PointNotOnCurve => DLOGPROOF_ERR_POINT_NOT_ON_CURVE,
+            ProofError::InvalidSecret => DLOGPROOF_ERR_INVALID_SECRET,
+            ProofError::InvalidPoint => DLOGPROOF_ERR_INVALID_POINT,
+            ProofError::InvalidScalar => DLOGPROOF_ERR_INVALID_SCALAR,
+        })
+    }
+}
+
+fn guard(f: impl FnOnce() -> Result<(), Code>) -> i32 {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => DLOGPROOF_OK,
+        Ok(Err(Code(code))) => code,
+        Err(_) => DLOGPROOF_ERR_PANIC,
+    }
+}
+
+unsafe fn input<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], Code> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    if ptr.is_null() {
+        return Err(Code(DLOGPROOF_ERR_NULL_POINTER));
+    }
+    Ok(std::slice::from_raw_parts(ptr, len))
+}
+
+/// The first `needed` bytes of the caller's output buffer.
+unsafe fn output<'a>(ptr: *mut u8, len: usize, needed: usize) -> Result<&'a mut [u8], Code> {
+    if ptr.is_null() {
+        return Err(Code(DLOGPROOF_ERR_NULL_POINTER));
+    }
+    if len < needed {
+        return Err(Code(DLOGPROOF_ERR_BUFFER_TOO_SMALL));
+    }
+    Ok(std::slice::from_raw_parts_mut(ptr, needed))
+}
+
+unsafe fn session_id<'a>(ptr: *const u8, len: usize) -> Result<&'a str, Code> {
+    std::str::from_utf8(input(ptr, len)?).map_err(|_| Code(DLOGPROOF_ERR_INVALID_INPUT))
+}
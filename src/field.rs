@@ -0,0 +1,687 @@
+//! Fixed-size arithmetic modulo the field prime `p` and the group order `n`.
+//!
+//! [`FieldElement`] and [`Scalar`] hold a residue as four little-endian
+//! 64-bit limbs, always fully reduced, and never allocate. Point arithmetic
+//! runs on them internally; `IBig` remains the type of the public API, and
+//! `from_ibig`/`to_ibig` convert at that boundary.
+//!
+//! The two moduli are reduced differently. `p = 2^256 - c` with the 33-bit
+//! `c = 2^32 + 977`, so a 512-bit product `hi·2^256 + lo` is congruent to
+//! `hi·c + lo`: folding the high half down twice with one-limb
+//! multiplications, then subtracting `p` at most once, reduces it fully.
+//! `n` has no such shape, so scalars are kept in Montgomery form, `a·R mod n`
+//! for `R = 2^256`, and a product is reduced by Montgomery reduction (REDC):
+//! adding the multiple of `n` that clears the low limbs one at a time and
+//! dropping them, which needs only the precomputed `-n^-1 mod 2^64`. Neither
+//! divides, and the sequence of operations does not depend on the values.
+//! Wider `IBig`s converted into scalars, up to 512 bits, are reduced by
+//! Barrett reduction against a precomputed `floor(2^512 / n)`.
+
+use ibig::{ops::UnsignedAbs, IBig, UBig};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+/// `a + b + carry`, returning the low word and the carry.
+#[inline(always)]
+fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+/// `a - b - borrow`, returning the low word and the borrow (0 or 1).
+#[inline(always)]
+fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let t = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (t as u64, ((t >> 64) as u64) & 1)
+}
+
+/// `acc + a·b + carry`, returning the low word and the carry.
+#[inline(always)]
+fn mac(acc: u64, a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = acc as u128 + (a as u128) * (b as u128) + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+fn add4(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], u64) {
+    let mut out = [0u64; 4];
+    let mut carry = 0;
+    for i in 0..4 {
+        (out[i], carry) = adc(a[i], b[i], carry);
+    }
+    (out, carry)
+}
+
+fn sub4(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], u64) {
+    let mut out = [0u64; 4];
+    let mut borrow = 0;
+    for i in 0..4 {
+        (out[i], borrow) = sbb(a[i], b[i], borrow);
+    }
+    (out, borrow)
+}
+
+/// `mask` is all ones to pick `a`, all zeros to pick `b`.
+fn select4(mask: u64, a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    std::array::from_fn(|i| (a[i] & mask) | (b[i] & !mask))
+}
+
+fn mul_wide(a: &[u64; 4], b: &[u64; 4]) -> [u64; 8] {
+    let mut out = [0u64; 8];
+    for i in 0..4 {
+        let mut carry = 0;
+        for j in 0..4 {
+            (out[i + j], carry) = mac(out[i + j], a[i], b[j], carry);
+        }
+        out[i + 4] = carry;
+    }
+    out
+}
+
+/// `a²`, computing each cross product `a_i·a_j` once and doubling the sum:
+/// 10 limb multiplications where `mul_wide` needs 16.
+fn square_wide(a: &[u64; 4]) -> [u64; 8] {
+    let mut out = [0u64; 8];
+    for i in 0..4 {
+        let mut carry = 0;
+        for j in i + 1..4 {
+            (out[i + j], carry) = mac(out[i + j], a[i], a[j], carry);
+        }
+        out[i + 4] = carry;
+    }
+    for i in (1..8).rev() {
+        out[i] = (out[i] << 1) | (out[i - 1] >> 63);
+    }
+    out[0] <<= 1;
+    let mut carry = 0;
+    for i in 0..4 {
+        let t = a[i] as u128 * a[i] as u128;
+        (out[2 * i], carry) = adc(out[2 * i], t as u64, carry);
+        (out[2 * i + 1], carry) = adc(out[2 * i + 1], (t >> 64) as u64, carry);
+    }
+    out
+}
+
+/// `c = 2^256 - p`.
+const P_COMPLEMENT: u64 = 0x1_0000_03D1;
+
+/// `wide mod p`, folding the high half into the low one with `2^256 ≡ c`.
+fn reduce_p(wide: &[u64; 8]) -> [u64; 4] {
+    // hi·c + lo fits in 256 + 34 bits: four limbs and a small top word.
+    let mut r = [0u64; 4];
+    let mut top = 0;
+    for i in 0..4 {
+        (r[i], top) = mac(wide[i], wide[i + 4], P_COMPLEMENT, top);
+    }
+    // Fold the top word: top·c < 2^67.
+    let t = top as u128 * P_COMPLEMENT as u128;
+    let mut carry;
+    (r[0], carry) = adc(r[0], t as u64, 0);
+    (r[1], carry) = adc(r[1], (t >> 64) as u64, carry);
+    (r[2], carry) = adc(r[2], 0, carry);
+    (r[3], carry) = adc(r[3], 0, carry);
+    // A final carry leaves r below 2^67, so adding c once more cannot carry.
+    (r[0], carry) = adc(r[0], P_COMPLEMENT & carry.wrapping_neg(), 0);
+    (r[1], carry) = adc(r[1], 0, carry);
+    (r[2], carry) = adc(r[2], 0, carry);
+    r[3] = r[3].wrapping_add(carry);
+    FieldElement::reduce_once(r)
+}
+
+/// `wide·R^-1 mod m` for `wide < m·R`, by Montgomery reduction with
+/// `m_inv = -m^-1 mod 2^64`.
+fn montgomery_reduce(mut wide: [u64; 8], modulus: &[u64; 4], m_inv: u64) -> [u64; 4] {
+    let mut top = 0;
+    for i in 0..4 {
+        // u·m cancels limb i, so the sum stays divisible by 2^(64·(i + 1)).
+        let u = wide[i].wrapping_mul(m_inv);
+        let mut carry = 0;
+        for j in 0..4 {
+            (wide[i + j], carry) = mac(wide[i + j], u, modulus[j], carry);
+        }
+        (wide[i + 4], top) = adc(wide[i + 4], carry, top);
+    }
+    // The result is below 2m: subtract m when it overflowed or is at least m.
+    let hi = [wide[4], wide[5], wide[6], wide[7]];
+    let (reduced, borrow) = sub4(&hi, modulus);
+    select4((top | (borrow ^ 1)).wrapping_neg(), &reduced, &hi)
+}
+
+/// `wide mod m` by Barrett reduction with `mu = floor(2^512 / m)`. The
+/// quotient estimate `q = floor(floor(wide / 2^192)·mu / 2^320)` falls short
+/// of the true quotient by at most two, so `wide - q·m`, which fits in five
+/// limbs, needs at most two subtractions of `m`.
+fn barrett_reduce(wide: &[u64; 8], modulus: &[u64; 4], mu: &[u64; 5]) -> [u64; 4] {
+    let mut qmu = [0u64; 10];
+    for i in 0..5 {
+        let mut carry = 0;
+        for j in 0..5 {
+            (qmu[i + j], carry) = mac(qmu[i + j], wide[i + 3], mu[j], carry);
+        }
+        qmu[i + 5] = carry;
+    }
+    let q = &qmu[5..];
+    // q·m and the remainder are only needed modulo 2^320.
+    let mut qm = [0u64; 5];
+    for i in 0..5 {
+        let mut carry = 0;
+        for j in 0..4.min(5 - i) {
+            (qm[i + j], carry) = mac(qm[i + j], q[i], modulus[j], carry);
+        }
+        if i == 0 {
+            qm[4] = carry;
+        }
+    }
+    let mut r = [0u64; 5];
+    let mut borrow = 0;
+    for i in 0..5 {
+        (r[i], borrow) = sbb(wide[i], qm[i], borrow);
+    }
+    for _ in 0..2 {
+        let mut reduced = [0u64; 5];
+        let mut borrow = 0;
+        for i in 0..5 {
+            let m = if i < 4 { modulus[i] } else { 0 };
+            (reduced[i], borrow) = sbb(r[i], m, borrow);
+        }
+        let mask = borrow.wrapping_sub(1);
+        for i in 0..5 {
+            r[i] = (reduced[i] & mask) | (r[i] & !mask);
+        }
+    }
+    [r[0], r[1], r[2], r[3]]
+}
+
+/// `a^-1 mod m` for `a` in `[1, m)` and odd `m`, by the binary extended
+/// Euclidean algorithm. It keeps `x1·a ≡ u` and `x2·a ≡ v (mod m)` while
+/// halving and subtracting `u` and `v` down to one.
+fn binary_inverse(a: [u64; 4], modulus: &[u64; 4]) -> [u64; 4] {
+    const ONE: [u64; 4] = [1, 0, 0, 0];
+    // x/2 mod m: x when even, (x + m)/2 otherwise, keeping the carry.
+    let halve = |x: &mut [u64; 4]| {
+        let (sum, carry) = add4(x, modulus);
+        let (y, top) = if x[0] & 1 == 0 { (*x, 0) } else { (sum, carry) };
+        for i in 0..4 {
+            let next = if i < 3 { y[i + 1] } else { top };
+            x[i] = (y[i] >> 1) | (next << 63);
+        }
+    };
+    let shr1 = |x: &mut [u64; 4]| {
+        for i in 0..4 {
+            let next = if i < 3 { x[i + 1] } else { 0 };
+            x[i] = (x[i] >> 1) | (next << 63);
+        }
+    };
+    let sub_mod = |x: &[u64; 4], y: &[u64; 4]| {
+        let (diff, borrow) = sub4(x, y);
+        if borrow == 1 {
+            add4(&diff, modulus).0
+        } else {
+            diff
+        }
+    };
+    let (mut u, mut v) = (a, *modulus);
+    let (mut x1, mut x2) = (ONE, [0u64; 4]);
+    while u != ONE && v != ONE {
+        while u[0] & 1 == 0 {
+            shr1(&mut u);
+            halve(&mut x1);
+        }
+        while v[0] & 1 == 0 {
+            shr1(&mut v);
+            halve(&mut x2);
+        }
+        let (diff, borrow) = sub4(&u, &v);
+        if borrow == 0 {
+            u = diff;
+            x1 = sub_mod(&x1, &x2);
+        } else {
+            v = sub4(&v, &u).0;
+            x2 = sub_mod(&x2, &x1);
+        }
+    }
+    if u == ONE {
+        x1
+    } else {
+        x2
+    }
+}
+
+fn limbs_from_le_bytes<const L: usize>(bytes: &[u8]) -> [u64; L] {
+    std::array::from_fn(|i| {
+        let mut limb = [0u8; 8];
+        let chunk = bytes.get(8 * i..).unwrap_or_default();
+        let len = chunk.len().min(8);
+        limb[..len].copy_from_slice(&chunk[..len]);
+        u64::from_le_bytes(limb)
+    })
+}
+
+pub(crate) fn limbs_to_ibig(limbs: &[u64; 4]) -> IBig {
+    let mut bytes = [0u8; 32];
+    for (chunk, limb) in bytes.chunks_exact_mut(8).zip(limbs) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    IBig::from(UBig::from_le_bytes(&bytes))
+}
+
+/// Arithmetic shared by both residue types. Each type supplies `ONE`, `mul`,
+/// `square`, `reduce_wide` for 512-bit values and the conversions `from_canonical`/`canonical` between a value in
+/// `[0, m)` and its internal form.
+macro_rules! residue {
+    ($(#[$doc:meta])* $name:ident, $modulus:expr) => {
+        $(#[$doc])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub struct $name([u64; 4]);
+
+        impl $name {
+            pub const ZERO: Self = Self([0; 4]);
+            const MODULUS: [u64; 4] = $modulus;
+
+            /// `from_ibig` reduces `value` into the residue type.
+            pub fn from_ibig(value: &IBig) -> Self {
+                Self::from_canonical(Self::reduce_limbs(value))
+            }
+
+            /// `reduce` returns `value` modulo the modulus, without
+            /// dividing when `|value| < 2^512`.
+            pub fn reduce(value: &IBig) -> IBig {
+                limbs_to_ibig(&Self::reduce_limbs(value))
+            }
+
+            fn reduce_limbs(value: &IBig) -> [u64; 4] {
+                let magnitude = value.unsigned_abs();
+                let r = match magnitude.bit_len() {
+                    0..=256 => Self::reduce_once(limbs_from_le_bytes(&magnitude.to_le_bytes())),
+                    257..=512 => Self::reduce_wide(&limbs_from_le_bytes(&magnitude.to_le_bytes())),
+                    _ => {
+                        let r = magnitude % UBig::try_from(Self::modulus()).unwrap();
+                        limbs_from_le_bytes(&r.to_le_bytes())
+                    }
+                };
+                if *value < IBig::from(0) && r != [0; 4] {
+                    sub4(&Self::MODULUS, &r).0
+                } else {
+                    r
+                }
+            }
+
+            pub fn to_ibig(&self) -> IBig {
+                limbs_to_ibig(&self.canonical())
+            }
+
+            /// `modulus` returns the modulus as an `IBig`.
+            pub fn modulus() -> IBig {
+                limbs_to_ibig(&Self::MODULUS)
+            }
+
+            /// `from_bytes` parses 32 big-endian bytes, returning `None` if
+            /// they encode a value not below the modulus.
+            pub fn from_bytes(bytes: &[u8; 32]) -> Option<Self> {
+                let mut le = *bytes;
+                le.reverse();
+                let limbs = limbs_from_le_bytes(&le);
+                let (_, borrow) = sub4(&limbs, &Self::MODULUS);
+                (borrow == 1).then(|| Self::from_canonical(limbs))
+            }
+
+            /// `to_bytes` returns the 32-byte big-endian encoding.
+            pub fn to_bytes(&self) -> [u8; 32] {
+                let mut out = [0u8; 32];
+                for (i, limb) in self.canonical().iter().enumerate() {
+                    out[24 - 8 * i..32 - 8 * i].copy_from_slice(&limb.to_be_bytes());
+                }
+                out
+            }
+
+            pub fn is_zero(&self) -> bool {
+                self.0.iter().fold(0, |acc, l| acc | l) == 0
+            }
+
+            pub fn add(&self, other: &Self) -> Self {
+                let (sum, carry) = add4(&self.0, &other.0);
+                let (reduced, borrow) = sub4(&sum, &Self::MODULUS);
+                // Subtract when the sum overflowed or is at least the modulus.
+                let mask = (carry | (borrow ^ 1)).wrapping_neg();
+                Self(select4(mask, &reduced, &sum))
+            }
+
+            pub fn sub(&self, other: &Self) -> Self {
+                let (diff, borrow) = sub4(&self.0, &other.0);
+                let (wrapped, _) = add4(&diff, &Self::MODULUS);
+                Self(select4(borrow.wrapping_neg(), &wrapped, &diff))
+            }
+
+            pub fn neg(&self) -> Self {
+                Self::ZERO.sub(self)
+            }
+
+            /// `double` returns `2·self`.
+            pub fn double(&self) -> Self {
+                self.add(self)
+            }
+
+            /// `mul_small` multiplies by a small public constant with
+            /// doublings and additions.
+            pub fn mul_small(&self, k: u64) -> Self {
+                (0..u64::BITS - k.leading_zeros()).rev().fold(Self::ZERO, |acc, bit| {
+                    let acc = acc.double();
+                    if (k >> bit) & 1 == 1 {
+                        acc.add(self)
+                    } else {
+                        acc
+                    }
+                })
+            }
+
+            /// `pow` raises to a public exponent given as little-endian limbs.
+            pub fn pow(&self, exponent: &[u64; 4]) -> Self {
+                let mut result = Self::ONE;
+                for limb in exponent.iter().rev() {
+                    for bit in (0..64).rev() {
+                        result = result.square();
+                        if (limb >> bit) & 1 == 1 {
+                            result = result.mul(self);
+                        }
+                    }
+                }
+                result
+            }
+
+            /// `invert` returns `self^(m - 2)`, the inverse for non-zero
+            /// values and zero for zero. The exponent is fixed, so the
+            /// sequence of operations does not depend on `self`.
+            pub fn invert(&self) -> Self {
+                let (exponent, _) = sub4(&Self::MODULUS, &[2, 0, 0, 0]);
+                self.pow(&exponent)
+            }
+
+            /// `invert_vartime` returns the same inverse as
+            /// [`invert`](Self::invert) with the binary extended Euclidean
+            /// algorithm, several times faster. Its running time depends on
+            /// `self`, so it is only for public values.
+            pub fn invert_vartime(&self) -> Self {
+                if self.is_zero() {
+                    return Self::ZERO;
+                }
+                Self::from_canonical(binary_inverse(self.canonical(), &Self::MODULUS))
+            }
+
+            /// `invert_batch` inverts every value with one
+            /// [`invert_vartime`](Self::invert_vartime) and three
+            /// multiplications per value (Montgomery's trick). Zeros map to
+            /// zero and do not affect the other values.
+            pub fn invert_batch(values: &[Self]) -> Vec<Self> {
+                // prefix[i] is the product of the non-zero values before i.
+                let mut prefix = Vec::with_capacity(values.len());
+                let mut acc = Self::ONE;
+                for value in values {
+                    prefix.push(acc);
+                    if !value.is_zero() {
+                        acc = acc.mul(value);
+                    }
+                }
+                let mut inv = acc.invert_vartime();
+                let mut out = vec![Self::ZERO; values.len()];
+                for (i, value) in values.iter().enumerate().rev() {
+                    if !value.is_zero() {
+                        out[i] = inv.mul(&prefix[i]);
+                        inv = inv.mul(value);
+                    }
+                }
+                out
+            }
+
+            /// `reduce_once` brings a value below `2^256` into `[0, m)`.
+            fn reduce_once(limbs: [u64; 4]) -> [u64; 4] {
+                let (reduced, borrow) = sub4(&limbs, &Self::MODULUS);
+                select4(borrow.wrapping_sub(1), &reduced, &limbs)
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::ZERO
+            }
+        }
+
+        impl ConditionallySelectable for $name {
+            fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+                Self(select4(u64::from(choice.unwrap_u8()).wrapping_neg(), &b.0, &a.0))
+            }
+        }
+
+        impl ConstantTimeEq for $name {
+            fn ct_eq(&self, other: &Self) -> Choice {
+                self.0.ct_eq(&other.0)
+            }
+        }
+    };
+}
+
+residue!(
+    /// An element of the secp256k1 base field, modulo
+    /// `p = 2^256 - 2^32 - 977`.
+    FieldElement,
+    [
+        0xFFFF_FFFE_FFFF_FC2F,
+        0xFFFF_FFFF_FFFF_FFFF,
+        0xFFFF_FFFF_FFFF_FFFF,
+        0xFFFF_FFFF_FFFF_FFFF
+    ]
+);
+
+impl FieldElement {
+    pub const ONE: Self = Self([1, 0, 0, 0]);
+
+    /// `from_limbs` builds an element from little-endian limbs that are
+    /// already below `p`, for constants.
+    pub(crate) const fn from_limbs(limbs: [u64; 4]) -> Self {
+        Self(limbs)
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        Self(reduce_p(&mul_wide(&self.0, &other.0)))
+    }
+
+    pub fn square(&self) -> Self {
+        Self(reduce_p(&square_wide(&self.0)))
+    }
+
+    fn reduce_wide(wide: &[u64; 8]) -> [u64; 4] {
+        reduce_p(wide)
+    }
+
+    fn from_canonical(limbs: [u64; 4]) -> Self {
+        Self(limbs)
+    }
+
+    fn canonical(&self) -> [u64; 4] {
+        self.0
+    }
+}
+
+residue!(
+    /// A scalar modulo the secp256k1 group order `n`, in Montgomery form.
+    Scalar,
+    [
+        0xBFD2_5E8C_D036_4141,
+        0xBAAE_DCE6_AF48_A03B,
+        0xFFFF_FFFF_FFFF_FFFE,
+        0xFFFF_FFFF_FFFF_FFFF
+    ]
+);
+
+impl Scalar {
+    /// One in Montgomery form, `R mod n = 2^256 - n`.
+    pub const ONE: Self = Self([0x402D_A173_2FC9_BEBF, 0x4551_2319_50B7_5FC4, 0x1, 0]);
+    /// `R² mod n`, which converts into Montgomery form.
+    const R2: [u64; 4] = [
+        0x896C_F214_67D7_D140,
+        0x7414_96C2_0E7C_F878,
+        0xE697_F5E4_5BCD_07C6,
+        0x9D67_1CD5_81C6_9BC5,
+    ];
+    /// `-n^-1 mod 2^64`.
+    const M_INV: u64 = 0x4B0D_FF66_5588_B13F;
+    /// `floor(2^512 / n)`, for Barrett reduction.
+    const MU: [u64; 5] = [0x402D_A173_2FC9_BEC0, 0x4551_2319_50B7_5FC4, 0x1, 0, 0x1];
+
+    pub fn mul(&self, other: &Self) -> Self {
+        Self(montgomery_reduce(
+            mul_wide(&self.0, &other.0),
+            &Self::MODULUS,
+            Self::M_INV,
+        ))
+    }
+
+    pub fn square(&self) -> Self {
+        Self(montgomery_reduce(
+            square_wide(&self.0),
+            &Self::MODULUS,
+            Self::M_INV,
+        ))
+    }
+
+    /// `from_montgomery` builds a scalar from little-endian limbs already in
+    /// Montgomery form and below `n`, for constants.
+    #[cfg(feature = "group")]
+    pub(crate) const fn from_montgomery(limbs: [u64; 4]) -> Self {
+        Self(limbs)
+    }
+
+    fn reduce_wide(wide: &[u64; 8]) -> [u64; 4] {
+        barrett_reduce(wide, &Self::MODULUS, &Self::MU)
+    }
+
+    /// `from_canonical` converts a value in `[0, n)` into Montgomery form.
+    fn from_canonical(limbs: [u64; 4]) -> Self {
+        Self(limbs).mul(&Self(Self::R2))
+    }
+
+    /// `canonical` converts out of Montgomery form.
+    fn canonical(&self) -> [u64; 4] {
+        let mut wide = [0u64; 8];
+        wide[..4].copy_from_slice(&self.0);
+        montgomery_reduce(wide, &Self::MODULUS, Self::M_INV)
+    }
+
+    /// `ladder_limbs` returns the scalar offset by `n`, or by `2·n` when
+    /// that stays below `2^256`, as five little-endian limbs: the integer in
+    /// `[2^256, 2^257)` that [`crate::PointJacobi::mul_secret`] runs its
+    /// ladder over. The offset is chosen by mask rather than by branch.
+    pub(crate) fn ladder_limbs(&self) -> [u64; 5] {
+        let (once, carry) = add4(&self.canonical(), &Self::MODULUS);
+        let (twice, carry2) = add4(&once, &Self::MODULUS);
+        // k + n < 2^257 always, so `carry` is bit 256 of k + n; without it,
+        // k + 2·n lies in [2^256, 2^257) and its bit 256 is `carry2`.
+        let mask = carry.wrapping_neg();
+        let low = select4(mask, &once, &twice);
+        [low[0], low[1], low[2], low[3], carry | carry2]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve;
+    use ibig::modular::ModuloRing;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// `x mod m` in `[0, m)`; `IBig`'s `%` keeps the sign of `x`.
+    fn rem(x: &IBig, m: &IBig) -> IBig {
+        ((x % m) + m) % m
+    }
+
+    fn inverse(x: &IBig, m: &IBig) -> IBig {
+        let ring = ModuloRing::new(&UBig::try_from(m).unwrap());
+        ring.from(UBig::try_from(x).unwrap())
+            .inverse()
+            .map_or(IBig::from(0), |inv| IBig::from(inv.residue()))
+    }
+
+    /// The residues the limb code special-cases, then random ones.
+    fn residues(m: &IBig, rng: &mut StdRng) -> Vec<IBig> {
+        let one = IBig::from(1);
+        let mut values = vec![
+            IBig::from(0),
+            one.clone(),
+            IBig::from(2),
+            m - 1,
+            m - 2,
+            m >> 1,
+            (m >> 1) + 1,
+            (&one << 64) - 1,
+            &one << 128,
+            &one << 255,
+            (&one << 256) - m,
+        ];
+        values.extend((0..48).map(|_| curve::random_below(rng, m)));
+        values
+    }
+
+    /// Checks every operation of a residue type against `IBig` arithmetic
+    /// modulo the same modulus.
+    macro_rules! differential {
+        ($name:ident, $seed:expr) => {{
+            let m = $name::modulus();
+            let mut rng = StdRng::seed_from_u64($seed);
+            let values = residues(&m, &mut rng);
+            let elements: Vec<$name> = values.iter().map($name::from_ibig).collect();
+            for (a, fa) in values.iter().zip(&elements) {
+                assert_eq!(fa.to_ibig(), *a);
+                assert_eq!($name::from_bytes(&fa.to_bytes()), Some(*fa));
+                assert_eq!(fa.is_zero(), *a == IBig::from(0));
+                assert_eq!(fa.neg().to_ibig(), rem(&-a, &m));
+                assert_eq!(fa.double().to_ibig(), rem(&(a * 2), &m));
+                assert_eq!(fa.mul_small(977).to_ibig(), rem(&(a * 977), &m));
+                assert_eq!(fa.square().to_ibig(), rem(&(a * a), &m));
+                let inv = inverse(a, &m);
+                assert_eq!(fa.invert().to_ibig(), inv);
+                assert_eq!(fa.invert_vartime().to_ibig(), inv);
+                for (b, fb) in values.iter().zip(&elements).step_by(7) {
+                    assert_eq!(fa.add(fb).to_ibig(), rem(&(a + b), &m));
+                    assert_eq!(fa.sub(fb).to_ibig(), rem(&(a - b), &m));
+                    assert_eq!(fa.mul(fb).to_ibig(), rem(&(a * b), &m));
+                }
+            }
+            let inverses: Vec<IBig> = $name::invert_batch(&elements)
+                .iter()
+                .map($name::to_ibig)
+                .collect();
+            let expected: Vec<IBig> = values.iter().map(|a| inverse(a, &m)).collect();
+            assert_eq!(inverses, expected);
+
+            // Reduction of values outside [0, m): above it, negative, wider
+            // than 256 and than 512 bits.
+            let one = IBig::from(1);
+            for x in [
+                m.clone(),
+                &m + 1,
+                (&one << 256) - 1,
+                -IBig::from(1),
+                -&m,
+                -(&one << 300),
+                (&m - 1) * (&m - 1),
+                (&one << 512) - 1,
+                (&one << 700) + 12345,
+            ] {
+                assert_eq!($name::reduce(&x), rem(&x, &m), "reduce {x}");
+                assert_eq!($name::from_ibig(&x).to_ibig(), rem(&x, &m));
+            }
+            let mut above = [0xff; 32];
+            assert_eq!($name::from_bytes(&above), None);
+            above = $name::from_ibig(&(&m - 1)).to_bytes();
+            assert!($name::from_bytes(&above).is_some());
+        }};
+    }
+
+    #[test]
+    fn field_elements_match_ibig_arithmetic() {
+        differential!(FieldElement, 849);
+    }
+
+    #[test]
+    fn scalars_match_ibig_arithmetic() {
+        differential!(Scalar, 849);
+    }
+}
@@ -0,0 +1,377 @@
+//! RustCrypto `group` and `ff` support behind the `group` feature.
+//!
+//! [`field::Scalar`](crate::field::Scalar) implements `ff::PrimeField`, and
+//! [`ProjectivePoint`], a `Copy` secp256k1 point over fixed-limb coordinates,
+//! implements `group::Group` and `group::GroupEncoding` with that scalar, so
+//! protocols written generically against those traits run on this crate's
+//! arithmetic. Convert with `From` at the boundary to [`PointJacobi`].
+//!
+//! Scalar multiplication is [`PointJacobi::mul_secret`], constant-time in
+//! the scalar. Points encode as the 33-byte compressed SEC1 form of
+//! [`PointJacobi::to_bytes`], all zeros for the identity; scalars as 32
+//! big-endian bytes.
+
+use crate::field::Scalar;
+use crate::jacobi_point::{Jacobian, PointJacobi, POINT_BYTES};
+use ff::helpers::sqrt_tonelli_shanks;
+use ff::{Field, PrimeField};
+use group::{Group, GroupEncoding};
+use rand::RngCore;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::sync::LazyLock;
+use subtle::{Choice, ConstantTimeEq, CtOption};
+
+/// `(t - 1) / 2` for `n - 1 = 2^6·t`, the exponent of Tonelli-Shanks.
+const TM1D2: [u64; 4] = [
+    0x777F_A4BD_19A0_6C82,
+    0xFD75_5DB9_CD5E_9140,
+    0xFFFF_FFFF_FFFF_FFFF,
+    0x01FF_FFFF_FFFF_FFFF,
+];
+
+/// Implements the owned and borrowed forms of a binary operator and its
+/// assigning form from a method `$method(&self, &Rhs) -> Self`.
+macro_rules! binary_ops {
+    ($lhs:ty, $rhs:ty, $op:ident, $op_fn:ident, $assign:ident, $assign_fn:ident, $method:expr) => {
+        impl $op<&$rhs> for $lhs {
+            type Output = $lhs;
+
+            fn $op_fn(self, rhs: &$rhs) -> $lhs {
+                $method(&self, rhs)
+            }
+        }
+
+        impl $op<$rhs> for $lhs {
+            type Output = $lhs;
+
+            fn $op_fn(self, rhs: $rhs) -> $lhs {
+                $method(&self, &rhs)
+            }
+        }
+
+        impl $op<&$rhs> for &$lhs {
+            type Output = $lhs;
+
+            fn $op_fn(self, rhs: &$rhs) -> $lhs {
+                $method(self, rhs)
+            }
+        }
+
+        impl $assign<&$rhs> for $lhs {
+            fn $assign_fn(&mut self, rhs: &$rhs) {
+                *self = $method(self, rhs);
+            }
+        }
+
+        impl $assign<$rhs> for $lhs {
+            fn $assign_fn(&mut self, rhs: $rhs) {
+                *self = $method(self, &rhs);
+            }
+        }
+    };
+}
+
+binary_ops!(Scalar, Scalar, Add, add, AddAssign, add_assign, Scalar::add);
+binary_ops!(Scalar, Scalar, Sub, sub, SubAssign, sub_assign, Scalar::sub);
+binary_ops!(Scalar, Scalar, Mul, mul, MulAssign, mul_assign, Scalar::mul);
+
+impl Neg for Scalar {
+    type Output = Scalar;
+
+    fn neg(self) -> Scalar {
+        Scalar::neg(&self)
+    }
+}
+
+impl Sum for Scalar {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Scalar::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl<'a> Sum<&'a Scalar> for Scalar {
+    fn sum<I: Iterator<Item = &'a Scalar>>(iter: I) -> Self {
+        iter.fold(Scalar::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl Product for Scalar {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Scalar::ONE, |acc, x| acc * x)
+    }
+}
+
+impl<'a> Product<&'a Scalar> for Scalar {
+    fn product<I: Iterator<Item = &'a Scalar>>(iter: I) -> Self {
+        iter.fold(Scalar::ONE, |acc, x| acc * x)
+    }
+}
+
+impl From<u64> for Scalar {
+    fn from(value: u64) -> Self {
+        Scalar::from_ibig(&value.into())
+    }
+}
+
+impl Field for Scalar {
+    const ZERO: Self = Scalar::ZERO;
+    const ONE: Self = Scalar::ONE;
+
+    /// Rejection-samples 32 random bytes until they encode a value below `n`.
+    fn random(mut rng: impl RngCore) -> Self {
+        let mut bytes = [0u8; 32];
+        loop {
+            rng.fill_bytes(&mut bytes);
+            if let Some(scalar) = Scalar::from_bytes(&bytes) {
+                return scalar;
+            }
+        }
+    }
+
+    fn square(&self) -> Self {
+        Scalar::square(self)
+    }
+
+    fn double(&self) -> Self {
+        Scalar::double(self)
+    }
+
+    fn invert(&self) -> CtOption<Self> {
+        CtOption::new(Scalar::invert(self), !self.ct_eq(&Scalar::ZERO))
+    }
+
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+        ff::helpers::sqrt_ratio_generic(num, div)
+    }
+
+    fn sqrt(&self) -> CtOption<Self> {
+        sqrt_tonelli_shanks(self, TM1D2)
+    }
+}
+
+impl PrimeField for Scalar {
+    type Repr = [u8; 32];
+
+    const MODULUS: &'static str =
+        "0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+    const NUM_BITS: u32 = 256;
+    const CAPACITY: u32 = 255;
+    const TWO_INV: Self = Scalar::from_montgomery([0, 0, 0, 0x8000_0000_0000_0000]);
+    const MULTIPLICATIVE_GENERATOR: Self = Scalar::from_montgomery([
+        0xC13F_6A26_4E84_3739,
+        0xE537_F5B1_3503_9E5D,
+        0x0000_0000_0000_0008,
+        0x0000_0000_0000_0000,
+    ]);
+    const S: u32 = 6;
+    const ROOT_OF_UNITY: Self = Scalar::from_montgomery([
+        0x944C_F2A2_2091_0E04,
+        0x815C_829C_7805_89F4,
+        0x5598_0B07_BC22_2113,
+        0xC702_B0D2_4882_5B36,
+    ]);
+    const ROOT_OF_UNITY_INV: Self = Scalar::from_montgomery([
+        0xB2DC_D52A_AF4D_D71F,
+        0x428E_55DC_1672_BE1D,
+        0xE44B_48D2_D795_A1B6,
+        0xC14E_C331_4E10_97C2,
+    ]);
+    const DELTA: Self = Scalar::from_montgomery([
+        0xD91B_33D2_4319_D9E8,
+        0xB81C_6596_FF5D_6740,
+        0xA463_969C_A14C_51C1,
+        0x1900_960D_E4B7_929C,
+    ]);
+
+    fn from_repr(repr: Self::Repr) -> CtOption<Self> {
+        let scalar = Scalar::from_bytes(&repr);
+        CtOption::new(
+            scalar.unwrap_or(Scalar::ZERO),
+            Choice::from(scalar.is_some() as u8),
+        )
+    }
+
+    fn to_repr(&self) -> Self::Repr {
+        self.to_bytes()
+    }
+
+    fn is_odd(&self) -> Choice {
+        Choice::from(self.to_bytes()[31] & 1)
+    }
+}
+
+static GENERATOR: LazyLock<Jacobian> =
+    LazyLock::new(|| Jacobian::from(&PointJacobi::<crate::Secp256k1>::generator()));
+
+/// A secp256k1 point in Jacobian coordinates over fixed-limb field elements.
+/// Unlike [`PointJacobi`] it is `Copy`, as `group::Group` requires.
+#[derive(Debug, Clone, Copy)]
+pub struct ProjectivePoint(Jacobian);
+
+impl ProjectivePoint {
+    fn add(&self, other: &Self) -> Self {
+        Self(self.0.add(&other.0))
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        Self(self.0.add(&other.0.negate()))
+    }
+
+    fn mul(&self, k: &Scalar) -> Self {
+        (&PointJacobi::from(self.0).mul_secret(&k.to_ibig())).into()
+    }
+}
+
+impl From<&PointJacobi> for ProjectivePoint {
+    fn from(p: &PointJacobi) -> Self {
+        Self(Jacobian::from(p))
+    }
+}
+
+impl From<ProjectivePoint> for PointJacobi {
+    fn from(p: ProjectivePoint) -> Self {
+        p.0.into()
+    }
+}
+
+impl ConstantTimeEq for ProjectivePoint {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl PartialEq for ProjectivePoint {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for ProjectivePoint {}
+
+impl Default for ProjectivePoint {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+binary_ops!(
+    ProjectivePoint,
+    ProjectivePoint,
+    Add,
+    add,
+    AddAssign,
+    add_assign,
+    ProjectivePoint::add
+);
+binary_ops!(
+    ProjectivePoint,
+    ProjectivePoint,
+    Sub,
+    sub,
+    SubAssign,
+    sub_assign,
+    ProjectivePoint::sub
+);
+binary_ops!(
+    ProjectivePoint,
+    Scalar,
+    Mul,
+    mul,
+    MulAssign,
+    mul_assign,
+    ProjectivePoint::mul
+);
+
+impl Neg for ProjectivePoint {
+    type Output = ProjectivePoint;
+
+    fn neg(self) -> ProjectivePoint {
+        Self(self.0.negate())
+    }
+}
+
+impl Sum for ProjectivePoint {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::identity(), |acc, p| acc + p)
+    }
+}
+
+impl<'a> Sum<&'a ProjectivePoint> for ProjectivePoint {
+    fn sum<I: Iterator<Item = &'a ProjectivePoint>>(iter: I) -> Self {
+        iter.fold(Self::identity(), |acc, p| acc + p)
+    }
+}
+
+impl Group for ProjectivePoint {
+    type Scalar = Scalar;
+
+    fn random(mut rng: impl RngCore) -> Self {
+        loop {
+            let k = Scalar::random(&mut rng);
+            if !k.is_zero() {
+                return Self::generator() * k;
+            }
+        }
+    }
+
+    fn identity() -> Self {
+        Self(Jacobian::ZERO)
+    }
+
+    fn generator() -> Self {
+        Self(*GENERATOR)
+    }
+
+    fn is_identity(&self) -> Choice {
+        Choice::from(self.0.is_zero() as u8)
+    }
+
+    fn double(&self) -> Self {
+        Self(self.0.double())
+    }
+}
+
+/// The compressed SEC1 encoding of a [`ProjectivePoint`], a
+/// `GroupEncoding::Repr`, which must implement `Default`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointBytes(pub [u8; POINT_BYTES]);
+
+impl Default for PointBytes {
+    fn default() -> Self {
+        Self([0; POINT_BYTES])
+    }
+}
+
+impl AsRef<[u8]> for PointBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsMut<[u8]> for PointBytes {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl GroupEncoding for ProjectivePoint {
+    type Repr = PointBytes;
+
+    fn from_bytes(bytes: &Self::Repr) -> CtOption<Self> {
+        match PointJacobi::from_bytes(&bytes.0) {
+            Ok(p) => CtOption::new(Self::from(&p), Choice::from(1)),
+            Err(_) => CtOption::new(Self::identity(), Choice::from(0)),
+        }
+    }
+
+    /// Every decoded point is on the curve and secp256k1 has cofactor one, so
+    /// this is [`from_bytes`](GroupEncoding::from_bytes).
+    fn from_bytes_unchecked(bytes: &Self::Repr) -> CtOption<Self> {
+        Self::from_bytes(bytes)
+    }
+
+    fn to_bytes(&self) -> Self::Repr {
+        PointBytes(PointJacobi::from(*self).to_bytes())
+    }
+}
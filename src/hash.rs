@@ -0,0 +1,645 @@
+//! Fiat-Shamir challenge derivation.
+//!
+//! The transcript hashed for a challenge is
+//!
+//! ```text
+//! version: u8 = 4 | tag_len: u32 BE | tag (UTF-8 bytes) | sid_len: u32 BE
+//!   | sid | pid: i32 BE | count: u32 BE | point_1 | ... | point_count
+//!   | aux_len: u32 BE | aux
+//! ```
+//!
+//! where every point is the 33-byte compressed SEC1 encoding of its affine
+//! form, tagged `0x02`/`0x03`, or 33 zero bytes for the point at infinity.
+//! Points are normalized before encoding, so a point hashes the same whatever
+//! the `z` of its Jacobian representation. Every field is
+//! either fixed-width or length-prefixed, so distinct inputs never produce the
+//! same transcript, and `tag` names the protocol and the challenge within
+//! it, e.g. [`DLOG_TAG`] for a proof, so that a challenge of one protocol is
+//! never a challenge of another over the same points. `aux` carries extra
+//! statement data such as the validity window of a time-bound proof and is
+//! empty otherwise. [`crate::transcript::Transcript`] builds the same bytes
+//! incrementally. The challenge is the SHA-256 digest of the transcript
+//! read as a big-endian integer and reduced modulo the curve order `n`, so
+//! that every challenge is a canonical scalar: a digest `d ≥ n` and `d - n`
+//! would otherwise be distinct challenges with the same effect, and an
+//! implementation comparing raw digests would disagree with one comparing
+//! scalars. For a proof the points are `G, Y, T` in that order.
+//!
+//! As a vector for other implementations, the proof statement `Y = 2·G`,
+//! `T = 3·G` on secp256k1 under [`DLOG_TAG`], with `sid = "sid"` and
+//! `pid = 1`, has the transcript
+//!
+//! ```text
+//! 04 0000000e 646c6f6770726f6f662f646c6f67 00000003 736964 00000001 00000003
+//!   0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798
+//!   02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5
+//!   02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9
+//!   00000000
+//! ```
+//!
+//! and the challenge
+//! `0x7aba070edad7898dd97cd3ea4a920f943906624180fccfbd949b2b79c388bc4f`.
+//!
+//! Other digests implement [`ChallengeHash`]: [`Sha512`], `Blake3` behind
+//! the `blake3` feature and `Keccak256` behind the `keccak` feature. Prove
+//! and verify with one through [`crate::Prover::prove_with_hash`] and
+//! [`crate::Verifier::verify_with_hash`]; both sides must agree on it, as the
+//! transcript does not name the hash.
+//!
+//! Curves without a [`crate::Curve`] implementation, such as ristretto255,
+//! hash a version 3 transcript that also names the curve, so that a
+//! challenge on one curve is never a challenge on another:
+//!
+//! ```text
+//! version: u8 = 3 | curve_len: u32 BE | curve (UTF-8 bytes) | sid_len: u32 BE
+//!   | sid | pid: i32 BE | count: u32 BE | point_len: u32 BE | point_1
+//!   | ... | point_len: u32 BE | point_count | aux_len: u32 BE | aux
+//! ```
+//!
+//! Sessions and participants identified by arbitrary bytes, such as a
+//! 32-byte hash and a UUID, use version 5, which is version 4 with a byte
+//! `sid` and a length-prefixed `pid`:
+//!
+//! ```text
+//! version: u8 = 5 | tag_len: u32 BE | tag | sid_len: u32 BE | sid
+//!   | pid_len: u32 BE | pid | count: u32 BE | point_1 | ... | point_count
+//!   | aux_len: u32 BE | aux
+//! ```
+//!
+//! Only [`crate::transcript::Transcript::with_ids`] builds it; see
+//! [`crate::Prover::prove_with_ids`].
+//!
+//! Older releases hashed untagged transcripts, selected for verification
+//! with a [`TranscriptEncoding`]. Version 2 is version 4 without the tag,
+//! `version: u8 = 2 | sid_len | sid | pid | count | points | aux_len | aux`,
+//! built by [`challenge_transcript`] and still used by the EVM profile.
+//! Version 1 transcripts, `sid | pid (decimal ASCII) | point_1 | ... | aux`,
+//! were ambiguous: `("s1", 23)` and `("s12", 3)` both began `s123`.
+//! They remain available through [`legacy_challenge_transcript`] so proofs
+//! made by older releases can still be checked with
+//! [`crate::Verifier::verify_legacy`].
+
+use crate::curve::Curve;
+use crate::error::ProofError;
+use crate::jacobi_point::PointJacobi;
+use ibig::{IBig, UBig};
+use sha2::Digest;
+
+/// Version byte at the start of every untagged transcript made by
+/// [`challenge_transcript`].
+pub const TRANSCRIPT_VERSION: u8 = 2;
+
+/// Version byte at the start of every transcript made by
+/// [`tagged_transcript`].
+pub const TAGGED_TRANSCRIPT_VERSION: u8 = 4;
+
+/// Version byte at the start of every transcript with byte identifiers, made
+/// by [`crate::transcript::Transcript::with_ids`].
+pub const ID_TRANSCRIPT_VERSION: u8 = 5;
+
+/// Tag of the challenge of a [`crate::DLogProof`], in every form: plain,
+/// short, time-bound and MuSig-aggregated.
+pub const DLOG_TAG: &str = "dlogproof/dlog";
+
+/// Tag of the challenge of a [`crate::DLogProof`] bound to a message, made by
+/// [`crate::Prover::prove_with_message`].
+pub const DLOG_MESSAGE_TAG: &str = "dlogproof/dlog/message";
+
+/// Tag of the challenge of a [`crate::DLogProof`] made in a
+/// [`crate::context::ProofContext`].
+pub const CONTEXT_TAG: &str = "dlogproof/dlog/context";
+
+/// Version byte at the start of every transcript made by
+/// [`curve_transcript`].
+pub const CURVE_TRANSCRIPT_VERSION: u8 = 3;
+
+/// The transcript encoding a proof's challenge was computed over.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TranscriptEncoding {
+    /// Version 4, with a domain tag. Every proof made by this release.
+    #[default]
+    Tagged,
+    /// Version 2, framed but untagged, made by the releases before tags.
+    Framed,
+    /// Version 1, unframed, made by the releases before framing.
+    Legacy,
+}
+
+/// `challenge_transcript` returns the untagged version 2 transcript, the
+/// bytes hashed to derive a challenge before tags were introduced.
+///
+/// Arguments:
+///
+/// * `sid`: the session id
+/// * `pid`: the id of the participant
+/// * `points`: The points that are being hashed, each in its compressed encoding.
+///
+/// Returns:
+///
+/// The transcript bytes, with an empty `aux`, or
+/// `ProofError::InvalidStatement` if `sid` is longer than `u32::MAX` bytes.
+pub fn challenge_transcript<C: Curve>(
+    sid: &str,
+    pid: i32,
+    points: &[PointJacobi<C>],
+) -> Result<Vec<u8>, ProofError> {
+    challenge_transcript_with_aux(sid, pid, points, &[])
+}
+
+/// `challenge_transcript_with_aux` is [`challenge_transcript`] with extra
+/// statement data in the `aux` field.
+pub fn challenge_transcript_with_aux<C: Curve>(
+    sid: &str,
+    pid: i32,
+    points: &[PointJacobi<C>],
+    aux: &[u8],
+) -> Result<Vec<u8>, ProofError> {
+    framed_transcript(None, sid, pid, &points.iter().collect::<Vec<_>>(), aux)
+}
+
+/// `tagged_transcript` returns the version 4 transcript, the exact bytes
+/// hashed to derive a challenge.
+///
+/// Arguments:
+///
+/// * `tag`: the protocol and challenge, e.g. [`DLOG_TAG`]
+/// * `sid`: the session id
+/// * `pid`: the id of the participant
+/// * `points`: the points being hashed, each in its compressed encoding
+/// * `aux`: extra statement data, empty if there is none
+///
+/// Returns:
+///
+/// The transcript bytes, or `ProofError::InvalidStatement` if a field is
+/// longer than `u32::MAX` bytes or there are more than `u32::MAX` points.
+pub fn tagged_transcript<C: Curve>(
+    tag: &str,
+    sid: &str,
+    pid: i32,
+    points: &[&PointJacobi<C>],
+    aux: &[u8],
+) -> Result<Vec<u8>, ProofError> {
+    framed_transcript(Some(tag), sid, pid, points, aux)
+}
+
+fn framed_transcript<C: Curve>(
+    tag: Option<&str>,
+    sid: &str,
+    pid: i32,
+    points: &[&PointJacobi<C>],
+    aux: &[u8],
+) -> Result<Vec<u8>, ProofError> {
+    let mut transcript = match tag {
+        Some(tag) => {
+            let mut transcript = vec![TAGGED_TRANSCRIPT_VERSION];
+            transcript.extend(length_prefix(tag.len())?);
+            transcript.extend(tag.as_bytes());
+            transcript
+        }
+        None => vec![TRANSCRIPT_VERSION],
+    };
+    transcript.extend(length_prefix(sid.len())?);
+    transcript.extend(sid.as_bytes());
+    transcript.extend(pid.to_be_bytes());
+    transcript.extend(length_prefix(points.len())?);
+    for point in points {
+        transcript.extend(point.to_bytes());
+    }
+    transcript.extend(length_prefix(aux.len())?);
+    transcript.extend(aux);
+    Ok(transcript)
+}
+
+/// `curve_transcript` returns the version 3 transcript, which binds the
+/// challenge to the curve named by `curve`.
+///
+/// Arguments:
+///
+/// * `curve`: the curve identifier, e.g. `"ristretto255"`
+/// * `sid`: the session id
+/// * `pid`: the id of the participant
+/// * `points`: the encoded points, each length-prefixed in the transcript
+/// * `aux`: extra statement data, empty if there is none
+///
+/// Returns:
+///
+/// The transcript bytes, or `ProofError::InvalidStatement` if a field is
+/// longer than `u32::MAX` bytes.
+pub fn curve_transcript(
+    curve: &str,
+    sid: &str,
+    pid: i32,
+    points: &[&[u8]],
+    aux: &[u8],
+) -> Result<Vec<u8>, ProofError> {
+    let mut transcript = vec![CURVE_TRANSCRIPT_VERSION];
+    transcript.extend(length_prefix(curve.len())?);
+    transcript.extend(curve.as_bytes());
+    transcript.extend(length_prefix(sid.len())?);
+    transcript.extend(sid.as_bytes());
+    transcript.extend(pid.to_be_bytes());
+    transcript.extend(length_prefix(points.len())?);
+    for point in points {
+        transcript.extend(length_prefix(point.len())?);
+        transcript.extend(*point);
+    }
+    transcript.extend(length_prefix(aux.len())?);
+    transcript.extend(aux);
+    Ok(transcript)
+}
+
+/// `legacy_challenge_transcript` returns the unframed version 1 transcript,
+/// for verifying proofs made before framing was introduced. It must not be
+/// used to make new proofs.
+pub fn legacy_challenge_transcript<C: Curve>(
+    sid: &str,
+    pid: i32,
+    points: &[PointJacobi<C>],
+    aux: &[u8],
+) -> Vec<u8> {
+    let mut transcript = vec![];
+    transcript.extend(sid.as_bytes());
+    transcript.extend(pid.to_string().as_bytes());
+    for point in points {
+        transcript.extend(point.to_bytes());
+    }
+    transcript.extend(aux);
+    transcript
+}
+
+/// The `u32` big-endian length prefix of a transcript field, or
+/// `ProofError::InvalidStatement` for a field that does not fit.
+pub(crate) fn length_prefix(len: usize) -> Result<[u8; 4], ProofError> {
+    u32::try_from(len).map(u32::to_be_bytes).map_err(|_| {
+        ProofError::InvalidStatement("transcript field longer than u32::MAX bytes".to_string())
+    })
+}
+
+/// A hash function turning transcript bytes into a challenge.
+pub trait ChallengeHash {
+    /// `challenge` returns the digest of `transcript` read as a big-endian
+    /// integer, not reduced modulo n.
+    fn challenge(transcript: &[u8]) -> IBig;
+}
+
+fn from_digest(digest: &[u8]) -> IBig {
+    UBig::from_be_bytes(digest).into()
+}
+
+/// SHA-256, the default challenge hash.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sha256;
+
+impl ChallengeHash for Sha256 {
+    fn challenge(transcript: &[u8]) -> IBig {
+        from_digest(&sha2::Sha256::digest(transcript))
+    }
+}
+
+/// SHA-512. Its 512-bit challenges are reduced modulo n, which leaves them
+/// statistically uniform.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sha512;
+
+impl ChallengeHash for Sha512 {
+    fn challenge(transcript: &[u8]) -> IBig {
+        from_digest(&sha2::Sha512::digest(transcript))
+    }
+}
+
+/// BLAKE3 with its default 32-byte output.
+#[cfg(feature = "blake3")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Blake3;
+
+#[cfg(feature = "blake3")]
+impl ChallengeHash for Blake3 {
+    fn challenge(transcript: &[u8]) -> IBig {
+        from_digest(blake3::hash(transcript).as_bytes())
+    }
+}
+
+/// Keccak-256 as in Ethereum, not the standardized SHA3-256.
+#[cfg(feature = "keccak")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Keccak256;
+
+#[cfg(feature = "keccak")]
+impl ChallengeHash for Keccak256 {
+    fn challenge(transcript: &[u8]) -> IBig {
+        from_digest(&sha3::Keccak256::digest(transcript))
+    }
+}
+
+/// `challenge_from_transcript` hashes transcript bytes into a challenge with
+/// [`Sha256`].
+pub fn challenge_from_transcript(transcript: &[u8]) -> IBig {
+    Sha256::challenge(transcript)
+}
+
+/// `compute_challenge` derives the untagged version 2 challenge for `sid`,
+/// `pid` and `points`.
+///
+/// For `sid = "sid"`, `pid = 1` and the single point `G` the transcript is
+///
+/// ```text
+/// 02 00000003 736964 00000001 00000001
+///   0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798 00000000
+/// ```
+///
+/// and the challenge, already below `n`, is
+/// `0xb6f4ac0957d054781cfd5604d783e82d0dd8e596b6f19c3892f466c1b62f5041`.
+///
+/// Returns:
+///
+/// The challenge, or the errors of [`challenge_transcript`].
+pub fn compute_challenge<C: Curve>(
+    sid: &str,
+    pid: i32,
+    points: &[PointJacobi<C>],
+) -> Result<IBig, ProofError> {
+    Ok(C::rem_n(&challenge_from_transcript(&challenge_transcript(
+        sid, pid, points,
+    )?)))
+}
+
+/// `hash_points` takes a string, an integer, and a slice of points, and
+/// returns the untagged version 2 challenge.
+///
+/// Arguments:
+///
+/// * `sid`: the session id
+/// * `pid`: the id of the participant
+/// * `points`: The points that are being hashed, borrowed so that callers
+///   need not clone them.
+///
+/// Returns:
+///
+/// The challenge computed by [`compute_challenge`], reduced modulo `n`, or
+/// its errors.
+pub fn hash_points<C: Curve>(
+    sid: &str,
+    pid: i32,
+    points: &[&PointJacobi<C>],
+) -> Result<IBig, ProofError> {
+    hash_points_with_aux(sid, pid, points, &[])
+}
+
+/// `hash_points_with_aux` is [`hash_points`] with extra statement data in the
+/// transcript's `aux` field, e.g. the validity window of a
+/// time-bound proof.
+pub fn hash_points_with_aux<C: Curve>(
+    sid: &str,
+    pid: i32,
+    points: &[&PointJacobi<C>],
+    aux: &[u8],
+) -> Result<IBig, ProofError> {
+    hash_points_with::<Sha256, C>(sid, pid, points, aux)
+}
+
+/// `hash_points_with` is [`hash_points_with_aux`] with the challenge hash `H`
+/// in place of SHA-256. The digest is reduced modulo the order of `C`.
+pub fn hash_points_with<H: ChallengeHash, C: Curve>(
+    sid: &str,
+    pid: i32,
+    points: &[&PointJacobi<C>],
+    aux: &[u8],
+) -> Result<IBig, ProofError> {
+    Ok(C::rem_n(&H::challenge(&framed_transcript(
+        None, sid, pid, points, aux,
+    )?)))
+}
+
+/// `hash_tagged` derives the challenge of the protocol named by `tag` from
+/// its [`tagged_transcript`].
+///
+/// For [`DLOG_TAG`], `sid = "sid"`, `pid = 1` and the single point `G` the
+/// transcript is
+///
+/// ```text
+/// 04 0000000e 646c6f6770726f6f662f646c6f67 00000003 736964 00000001 00000001
+///   0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798 00000000
+/// ```
+///
+/// and the challenge, already below `n`, is
+/// `0xb6ecd65fb7c16130328c4a6d548e737ee42b133a61aaf0c8fec957ed613cb99f`.
+///
+/// Returns:
+///
+/// The challenge, or the errors of [`tagged_transcript`].
+pub fn hash_tagged<C: Curve>(
+    tag: &str,
+    sid: &str,
+    pid: i32,
+    points: &[&PointJacobi<C>],
+    aux: &[u8],
+) -> Result<IBig, ProofError> {
+    hash_tagged_with::<Sha256, C>(tag, sid, pid, points, aux)
+}
+
+/// `hash_tagged_with` is [`hash_tagged`] with the challenge hash `H` in place
+/// of SHA-256. The digest is reduced modulo the order of `C`, which for a
+/// 512-bit `H` is what makes the challenge statistically uniform.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "dlogproof.hash_points",
+        skip_all,
+        fields(tag = tag, sid_len = sid.len(), pid = pid, points = points.len(), aux_len = aux.len())
+    )
+)]
+pub fn hash_tagged_with<H: ChallengeHash, C: Curve>(
+    tag: &str,
+    sid: &str,
+    pid: i32,
+    points: &[&PointJacobi<C>],
+    aux: &[u8],
+) -> Result<IBig, ProofError> {
+    Ok(C::rem_n(&H::challenge(&framed_transcript(
+        Some(tag),
+        sid,
+        pid,
+        points,
+        aux,
+    )?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::Secp256k1;
+    use crate::hex;
+
+    /// Bytes of a documented transcript, ignoring the spacing.
+    fn documented(text: &str) -> Vec<u8> {
+        let digits: String = text.split_whitespace().collect();
+        hex::decode(&digits).expect("hex")
+    }
+
+    fn challenge(hex: &str) -> IBig {
+        IBig::from_str_radix(hex, 16).expect("hex")
+    }
+
+    fn multiple(k: u32) -> PointJacobi<Secp256k1> {
+        PointJacobi::mul_generator(&IBig::from(k))
+    }
+
+    #[test]
+    fn module_vector() {
+        let (g, y, t) = (multiple(1), multiple(2), multiple(3));
+        let transcript = tagged_transcript(DLOG_TAG, "sid", 1, &[&g, &y, &t], &[]).unwrap();
+        assert_eq!(
+            transcript,
+            documented(
+                "04 0000000e 646c6f6770726f6f662f646c6f67 00000003 736964 00000001 00000003
+                   0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798
+                   02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5
+                   02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9
+                   00000000"
+            )
+        );
+        let expected =
+            challenge("7aba070edad7898dd97cd3ea4a920f943906624180fccfbd949b2b79c388bc4f");
+        assert_eq!(
+            hash_tagged(DLOG_TAG, "sid", 1, &[&g, &y, &t], &[]),
+            Ok(expected.clone())
+        );
+        let mut incremental = crate::transcript::Transcript::<Secp256k1>::new(DLOG_TAG, "sid", 1);
+        incremental
+            .append_point(&g)
+            .append_point(&y)
+            .append_point(&t);
+        assert_eq!(incremental.to_bytes(), Ok(transcript));
+        assert_eq!(incremental.challenge_scalar(), Ok(expected));
+    }
+
+    #[test]
+    fn compute_challenge_vector() {
+        let g = multiple(1);
+        let transcript = challenge_transcript("sid", 1, std::slice::from_ref(&g)).unwrap();
+        assert_eq!(
+            transcript,
+            documented(
+                "02 00000003 736964 00000001 00000001
+                   0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798 00000000"
+            )
+        );
+        let expected =
+            challenge("b6f4ac0957d054781cfd5604d783e82d0dd8e596b6f19c3892f466c1b62f5041");
+        assert_eq!(
+            compute_challenge("sid", 1, std::slice::from_ref(&g)),
+            Ok(expected.clone())
+        );
+        assert_eq!(hash_points("sid", 1, &[&g]), Ok(expected));
+    }
+
+    #[test]
+    fn hash_tagged_vector() {
+        let g = multiple(1);
+        assert_eq!(
+            tagged_transcript(DLOG_TAG, "sid", 1, &[&g], &[]).unwrap(),
+            documented(
+                "04 0000000e 646c6f6770726f6f662f646c6f67 00000003 736964 00000001 00000001
+                   0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798 00000000"
+            )
+        );
+        assert_eq!(
+            hash_tagged(DLOG_TAG, "sid", 1, &[&g], &[]),
+            Ok(challenge(
+                "b6ecd65fb7c16130328c4a6d548e737ee42b133a61aaf0c8fec957ed613cb99f"
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn overlong_fields_are_an_error() {
+        assert_eq!(length_prefix(u32::MAX as usize), Ok([0xff; 4]));
+        assert!(matches!(
+            length_prefix(u32::MAX as usize + 1),
+            Err(ProofError::InvalidStatement(_))
+        ));
+    }
+
+    /// Asserts that two statements the version 1 transcript cannot tell apart
+    /// get distinct version 2 transcripts and challenges.
+    fn assert_framing_separates(
+        (sid_a, pid_a, points_a, aux_a): (&str, i32, &[PointJacobi<Secp256k1>], &[u8]),
+        (sid_b, pid_b, points_b, aux_b): (&str, i32, &[PointJacobi<Secp256k1>], &[u8]),
+    ) {
+        assert_eq!(
+            legacy_challenge_transcript(sid_a, pid_a, points_a, aux_a),
+            legacy_challenge_transcript(sid_b, pid_b, points_b, aux_b)
+        );
+        let framed_a = challenge_transcript_with_aux(sid_a, pid_a, points_a, aux_a).unwrap();
+        let framed_b = challenge_transcript_with_aux(sid_b, pid_b, points_b, aux_b).unwrap();
+        assert_ne!(framed_a, framed_b);
+        let [refs_a, refs_b] = [points_a, points_b].map(|p| p.iter().collect::<Vec<_>>());
+        assert_ne!(
+            hash_points_with_aux(sid_a, pid_a, &refs_a, aux_a).unwrap(),
+            hash_points_with_aux(sid_b, pid_b, &refs_b, aux_b).unwrap()
+        );
+    }
+
+    #[test]
+    fn framing_separates_sid_from_pid() {
+        let points = [multiple(1), multiple(2), multiple(3)];
+        assert_framing_separates(("s1", 23, &points, &[]), ("s12", 3, &points, &[]));
+    }
+
+    #[test]
+    fn framing_separates_an_empty_sid() {
+        let points = [multiple(1)];
+        assert_framing_separates(("", 123, &points, &[]), ("12", 3, &points, &[]));
+    }
+
+    #[test]
+    fn framing_separates_points_from_aux() {
+        let (g, y) = (multiple(1), multiple(2));
+        assert_framing_separates(
+            ("sid", 1, &[g.clone(), y.clone()], &[]),
+            ("sid", 1, std::slice::from_ref(&g), &y.to_bytes()),
+        );
+    }
+
+    #[test]
+    fn byte_ids_separate_an_empty_pid() {
+        use crate::transcript::Transcript;
+        let a = Transcript::<Secp256k1>::with_ids(DLOG_TAG, b"ab", b"");
+        let b = Transcript::<Secp256k1>::with_ids(DLOG_TAG, b"a", b"b");
+        assert_ne!(a.to_bytes().unwrap(), b.to_bytes().unwrap());
+        assert_ne!(a.challenge_scalar().unwrap(), b.challenge_scalar().unwrap());
+    }
+
+    #[test]
+    fn hash_points_pins_the_reduced_challenge() {
+        let points = [multiple(1), multiple(2), multiple(3)];
+        let refs: Vec<_> = points.iter().collect();
+        assert_eq!(
+            hash_points("sid", 1, &refs),
+            Ok(challenge(
+                "6d5f6b64f9a197a3c8d29ec542de2fff1fb1461f53b99c32e552bffc7adde3a9"
+            ))
+        );
+        assert_eq!(
+            hash_points::<crate::P256>("sid", 1, &[&PointJacobi::generator()]),
+            Ok(challenge(
+                "1a9e641a3e17da8c4604f77ecb202346ac536d13b5e6159c171143c145087637"
+            ))
+        );
+    }
+
+    #[test]
+    fn wide_digests_are_reduced_modulo_n() {
+        let points = [multiple(1), multiple(2), multiple(3)];
+        let refs: Vec<_> = points.iter().collect();
+        let digest = Sha512::challenge(&challenge_transcript("sid", 1, &points).unwrap());
+        assert!(digest >= *crate::curve::N);
+        let c = hash_points_with::<Sha512, Secp256k1>("sid", 1, &refs, &[]).unwrap();
+        assert_eq!(
+            c,
+            challenge("f53792abbc9f760e7e2f944e40e08f40f20d4bb11a82ee1d8acb31b920fa2a09")
+        );
+        assert_eq!(c, Secp256k1::rem_n(&digest));
+    }
+}
@@ -0,0 +1,319 @@
+//! Hashing to secp256k1 as specified by RFC 9380, suite
+//! `secp256k1_XMD:SHA-256_SSWU_RO_`.
+//!
+//! A message is expanded with `expand_message_xmd` over SHA-256 into two
+//! field elements `u0`, `u1`. Each is mapped with the simplified SWU map to
+//! the curve `E': y² = x³ + A'·x + B'`, since secp256k1 has `a = 0` and the
+//! map needs `a·b ≠ 0`, and carried to secp256k1 by the 3-isogeny `E' → E`.
+//! The result is the sum of the two images. The discrete log of the output to
+//! any base is unknown, so it serves as a nothing-up-my-sleeve generator or as
+//! a base point derived from a session id, and anyone can recompute it from
+//! the domain and message alone.
+//!
+//! Unlike the try-and-increment derivation of [`crate::commitment::h`], the
+//! output is indistinguishable from a uniformly random point. The arithmetic
+//! is not constant-time; do not hash secret messages.
+
+use crate::curve;
+use crate::jacobi_point::{Point, PointJacobi};
+use ibig::{ibig, IBig, UBig};
+use sha2::{Digest, Sha256};
+use std::sync::LazyLock;
+
+/// The suite identifier of RFC 9380, to be included in domain separation tags.
+pub const SUITE_ID: &str = "secp256k1_XMD:SHA-256_SSWU_RO_";
+
+/// Bytes of uniform output per field element: `ceil((ceil(log2(p)) + k) / 8)`
+/// for the security level `k = 128`.
+const FIELD_ELEMENT_BYTES: usize = 48;
+
+/// The largest domain separation tag used as is; longer ones are hashed.
+const MAX_DST_BYTES: usize = 255;
+
+/// `Z`, `A'` and `B'` of the simplified SWU map.
+static SSWU: LazyLock<[IBig; 3]> = LazyLock::new(|| {
+    [
+        curve::rem(&ibig!(-11)),
+        hex("3f8731abdd661adca08a5558f0f5d272e953d363cb6f0e5d405447c01a444533"),
+        ibig!(1771),
+    ]
+});
+
+/// The coefficients of the 3-isogeny map, lowest degree first:
+/// `x = x_num / x_den` and `y = y' · y_num / y_den`, with the leading
+/// coefficient of both denominators one.
+static ISOGENY: LazyLock<[Vec<IBig>; 4]> = LazyLock::new(|| {
+    let coefficients = |hexes: &[&str]| hexes.iter().map(|h| hex(h)).collect();
+    [
+        coefficients(&[
+            "8e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38daaaaa8c7",
+            "07d3d4c80bc321d5b9f315cea7fd44c5d595d2fc0bf63b92dfff1044f17c6581",
+            "534c328d23f234e6e2a413deca25caece4506144037c40314ecbd0b53d9dd262",
+            "8e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38daaaaa88c",
+        ]),
+        coefficients(&[
+            "d35771193d94918a9ca34ccbb7b640dd86cd409542f8487d9fe6b745781eb49b",
+            "edadc6f64383dc1df7c4b2d51b54225406d36b641f5e41bbc52a56612a8c6d14",
+            "01",
+        ]),
+        coefficients(&[
+            "4bda12f684bda12f684bda12f684bda12f684bda12f684bda12f684b8e38e23c",
+            "c75e0c32d5cb7c0fa9d0a54b12a0a6d5647ab046d686da6fdffc90fc201d71a3",
+            "29a6194691f91a73715209ef6512e576722830a201be2018a765e85a9ecee931",
+            "2f684bda12f684bda12f684bda12f684bda12f684bda12f684bda12f38e38d84",
+        ]),
+        coefficients(&[
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffff93b",
+            "7a06534bb8bdb49fd5e9e6632722c2989467c1bfc8e8d978dfb425d2685c2573",
+            "6484aa716545ca2cf3a70c3fa8fe337e0a3d21162f0d6299a7bf8192bfd2a76f",
+            "01",
+        ]),
+    ]
+});
+
+fn hex(digits: &str) -> IBig {
+    UBig::from_str_radix(digits, 16).unwrap().into()
+}
+
+/// `hash_to_curve` hashes `msg` to a point under the domain separation tag
+/// `dst`, as `hash_to_curve` of RFC 9380 with [`SUITE_ID`].
+///
+/// For `dst = "QUUX-V01-CS02-with-secp256k1_XMD:SHA-256_SSWU_RO_"` and an
+/// empty message the point is
+///
+/// ```text
+/// x = c1cae290e291aee617ebaef1be6d73861479c48b841eaba9b7b5852ddfeb1346
+/// y = 64fa678e07ae116126f08b022a94af6de15985c996c3a91b64c406a960e51067
+/// ```
+pub fn hash_to_curve(dst: &[u8], msg: &[u8]) -> PointJacobi {
+    let [u0, u1] = hash_to_field(dst, msg);
+    map_to_curve(&u0).add(&map_to_curve(&u1))
+}
+
+/// `hash_to_field` returns the two field elements `u0`, `u1` that
+/// [`hash_to_curve`] maps to the curve.
+pub fn hash_to_field(dst: &[u8], msg: &[u8]) -> [IBig; 2] {
+    let uniform = expand_message_xmd(dst, msg, 2 * FIELD_ELEMENT_BYTES);
+    let element = |bytes: &[u8]| curve::rem(&UBig::from_be_bytes(bytes).into());
+    let (u0, u1) = uniform.split_at(FIELD_ELEMENT_BYTES);
+    [element(u0), element(u1)]
+}
+
+/// `expand_message_xmd` expands `msg` into `len` uniform bytes with SHA-256,
+/// as in section 5.3.1 of RFC 9380. A `dst` longer than 255 bytes is replaced
+/// by `SHA-256("H2C-OVERSIZE-DST-" || dst)`.
+///
+/// Panics if `len` is above `255·32` bytes.
+pub fn expand_message_xmd(dst: &[u8], msg: &[u8], len: usize) -> Vec<u8> {
+    const HASH_BYTES: usize = 32;
+    const BLOCK_BYTES: usize = 64;
+    let ell = len.div_ceil(HASH_BYTES);
+    assert!(ell <= 255, "expand_message_xmd output too long");
+    let dst = if dst.len() > MAX_DST_BYTES {
+        Sha256::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(dst)
+            .finalize()
+            .to_vec()
+    } else {
+        dst.to_vec()
+    };
+    let mut dst_prime = dst;
+    dst_prime.push(dst_prime.len() as u8);
+
+    let b0: [u8; HASH_BYTES] = Sha256::new()
+        .chain_update([0u8; BLOCK_BYTES])
+        .chain_update(msg)
+        .chain_update((len as u16).to_be_bytes())
+        .chain_update([0])
+        .chain_update(&dst_prime)
+        .finalize()
+        .into();
+    let mut uniform = Vec::with_capacity(ell * HASH_BYTES);
+    let mut b: [u8; HASH_BYTES] = [0; HASH_BYTES];
+    for i in 1..=ell {
+        let mut input = b0;
+        input.iter_mut().zip(&b).for_each(|(x, y)| *x ^= y);
+        b = Sha256::new()
+            .chain_update(input)
+            .chain_update([i as u8])
+            .chain_update(&dst_prime)
+            .finalize()
+            .into();
+        uniform.extend(b);
+    }
+    uniform.truncate(len);
+    uniform
+}
+
+/// `map_to_curve` maps a field element to a point with the simplified SWU map
+/// to `E'` followed by the 3-isogeny to secp256k1.
+pub fn map_to_curve(u: &IBig) -> PointJacobi {
+    let (x, y) = map_to_isogenous_curve(u);
+    isogeny_map(&x, &y)
+}
+
+/// The simplified SWU map of section 6.6.2 of RFC 9380, onto `E'`.
+fn map_to_isogenous_curve(u: &IBig) -> (IBig, IBig) {
+    let [z, a, b] = &*SSWU;
+    let g = |x: &IBig| curve::rem(&(x.pow(3) + a * x + b));
+    let zu2 = curve::rem(&(z * u * u));
+    let tv1 = curve::invert(&curve::rem(&(&zu2 * &zu2 + &zu2)));
+    let x1 = if tv1 == ibig!(0) {
+        curve::rem(&(b * curve::invert(&(z * a))))
+    } else {
+        curve::rem(&(-b * curve::invert(a) * (ibig!(1) + tv1)))
+    };
+    let (x, y) = match curve::sqrt(&g(&x1)) {
+        Some(y) => (x1, y),
+        None => {
+            let x2 = curve::rem(&(zu2 * x1));
+            let y = curve::sqrt(&g(&x2)).expect("g(x1)·g(x2) is a square");
+            (x2, y)
+        }
+    };
+    let sgn0 = |v: &IBig| (v & 1_u8) == 1;
+    let y = if sgn0(u) == sgn0(&y) {
+        y
+    } else {
+        curve::rem(&-y)
+    };
+    (x, y)
+}
+
+/// The 3-isogeny `E' → E` of appendix E.1 of RFC 9380. Its kernel maps to
+/// the point at infinity.
+fn isogeny_map(x: &IBig, y: &IBig) -> PointJacobi {
+    let polynomial = |coefficients: &[IBig]| {
+        coefficients
+            .iter()
+            .rev()
+            .fold(ibig!(0), |acc, k| curve::rem(&(acc * x + k)))
+    };
+    let [x_num, x_den, y_num, y_den] = &*ISOGENY;
+    let (x_den, y_den) = (polynomial(x_den), polynomial(y_den));
+    if x_den == ibig!(0) || y_den == ibig!(0) {
+        return PointJacobi::zero();
+    }
+    let [x_den_inv, y_den_inv] =
+        <[IBig; 2]>::try_from(curve::invert_batch(&[x_den, y_den])).expect("two inverses");
+    PointJacobi::from_affine(Point::new(
+        curve::rem(&(polynomial(x_num) * x_den_inv)),
+        curve::rem(&(y * polynomial(y_num) * y_den_inv)),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    const DST: &[u8] = b"QUUX-V01-CS02-with-secp256k1_XMD:SHA-256_SSWU_RO_";
+    const EXPANDER_DST: &[u8] = b"QUUX-V01-CS02-with-expander-SHA256-128";
+
+    fn field_hex(u: &IBig) -> String {
+        hex::encode(&UBig::try_from(u).unwrap().to_be_bytes())
+    }
+
+    /// The `secp256k1_XMD:SHA-256_SSWU_RO_` vectors of RFC 9380, appendix
+    /// J.8.1: message, `u0`, `u1`, `P.x`, `P.y`.
+    const VECTORS: [[&str; 5]; 2] = [
+        [
+            "",
+            "6b0f9910dd2ba71c78f2ee9f04d73b5f4c5f7fc773a701abea1e573cab002fb3",
+            "1ae6c212e08fe1a5937f6202f929a2cc8ef4ee5b9782db68b0d5799fd8f09e16",
+            "c1cae290e291aee617ebaef1be6d73861479c48b841eaba9b7b5852ddfeb1346",
+            "64fa678e07ae116126f08b022a94af6de15985c996c3a91b64c406a960e51067",
+        ],
+        [
+            "abc",
+            "128aab5d3679a1f7601e3bdf94ced1f43e491f544767e18a4873f397b08a2b61",
+            "5897b65da3b595a813d0fdcc75c895dc531be76a03518b044daaa0f2e4689e00",
+            "3377e01eab42db296b512293120c6cee72b6ecf9f9205760bd9ff11fb3cb2c4b",
+            "7f95890f33efebd1044d382a01b1bee0900fb6116f94688d487c6c7b9c8371f6",
+        ],
+    ];
+
+    #[test]
+    fn hash_to_curve_matches_the_rfc_vectors() {
+        for [msg, u0, u1, x, y] in VECTORS {
+            let [got_u0, got_u1] = hash_to_field(DST, msg.as_bytes());
+            assert_eq!(field_hex(&got_u0), u0, "u0 of {:?}", msg);
+            assert_eq!(field_hex(&got_u1), u1, "u1 of {:?}", msg);
+            let point = hash_to_curve(DST, msg.as_bytes()).to_affine();
+            assert_eq!(field_hex(&point.x), x, "x of {:?}", msg);
+            assert_eq!(field_hex(&point.y), y, "y of {:?}", msg);
+        }
+    }
+
+    #[test]
+    fn expand_message_xmd_matches_the_rfc_vectors() {
+        // RFC 9380, appendix K.1.
+        for (msg, len, expected) in [
+            (
+                "",
+                0x20,
+                "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235",
+            ),
+            (
+                "abc",
+                0x20,
+                "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615",
+            ),
+            (
+                "abcdef0123456789",
+                0x20,
+                "eff31487c770a893cfb36f912fbfcbff40d5661771ca4b2cb4eafe524333f5c1",
+            ),
+        ] {
+            assert_eq!(
+                hex::encode(&expand_message_xmd(EXPANDER_DST, msg.as_bytes(), len)),
+                expected,
+                "{:?}",
+                msg
+            );
+        }
+    }
+
+    #[test]
+    fn an_oversize_dst_is_hashed_first() {
+        let long = [b'1'; MAX_DST_BYTES + 1];
+        let hashed = Sha256::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(long)
+            .finalize();
+        assert_eq!(
+            expand_message_xmd(&long, b"abc", 0x20),
+            expand_message_xmd(&hashed, b"abc", 0x20)
+        );
+        assert_eq!(hash_to_curve(&long, b"abc"), hash_to_curve(&hashed, b"abc"));
+        // RFC 9380, appendix K.2: a 256-byte tag.
+        let mut rfc_dst = b"QUUX-V01-CS02-with-expander-SHA256-128-long-DST-".to_vec();
+        rfc_dst.resize(256, b'1');
+        assert_eq!(
+            hex::encode(&expand_message_xmd(&rfc_dst, b"", 0x20)),
+            "e8dc0c8b686b7ef2074086fbdd2f30e3f8bfbd3bdf177f73f04b97ce618a3ed3"
+        );
+        // A tag of exactly 255 bytes is used as is.
+        let limit = &long[..MAX_DST_BYTES];
+        let hashed_limit = Sha256::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(limit)
+            .finalize();
+        assert_ne!(
+            expand_message_xmd(limit, b"abc", 0x20),
+            expand_message_xmd(&hashed_limit, b"abc", 0x20)
+        );
+    }
+
+    #[test]
+    fn the_longest_expansion_is_allowed() {
+        assert_eq!(expand_message_xmd(DST, b"abc", 255 * 32).len(), 255 * 32);
+    }
+
+    #[test]
+    #[should_panic(expected = "expand_message_xmd output too long")]
+    fn expanding_past_255_blocks_panics() {
+        expand_message_xmd(DST, b"abc", 255 * 32 + 1);
+    }
+}
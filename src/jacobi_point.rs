@@ -0,0 +1,1579 @@
+//Reference for JacobiPoint https://github.com/hanabi1224/Programming-Language-Benchmarks/blob/c70b897767867d7247a94fc3ac7b1e7fa75b0f50/bench/algorithm/secp256k1/1.rs
+
+use crate::curve::{self, rem_n, Curve, Secp256k1, SCALAR_BYTES};
+use crate::error::ProofError;
+use crate::field::{FieldElement, Scalar};
+use ibig::{ibig, IBig, UBig};
+use std::{borrow::Borrow, marker::PhantomData, sync::LazyLock};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+/// `a1` and `-b1` of the GLV lattice basis; the other two vectors are
+/// `a2 = a1 - b1` and `b2 = a1`.
+const GLV_A1: u128 = 0x3086_d221_a7d4_6bcd_e86c_90e4_9284_eb15;
+const GLV_MINUS_B1: u128 = 0xe443_7ed6_010e_8828_6f54_7fa9_0abf_e4c3;
+
+static POW_2_128: LazyLock<IBig> = LazyLock::new(|| ibig!(1) << 128);
+static A1: LazyLock<IBig> = LazyLock::new(|| IBig::from(GLV_A1));
+static B1: LazyLock<IBig> = LazyLock::new(|| -IBig::from(GLV_MINUS_B1));
+static A2: LazyLock<IBig> = LazyLock::new(|| IBig::from(GLV_A1) + IBig::from(GLV_MINUS_B1));
+static GENERATOR_TABLE: LazyLock<FixedBaseTable> =
+    LazyLock::new(|| FixedBaseTable::new(&PointJacobi::generator()));
+
+/// Length in bytes of a compressed point encoding.
+pub const POINT_BYTES: usize = 33;
+/// Length in bytes of an uncompressed point encoding.
+pub const UNCOMPRESSED_POINT_BYTES: usize = 65;
+/// Length in bytes of an x-only point encoding, as used by BIP-340.
+pub const X_ONLY_POINT_BYTES: usize = 32;
+
+#[derive(Debug, Clone)]
+pub struct PointJacobi<C: Curve = Secp256k1> {
+    pub x: IBig,
+    pub y: IBig,
+    pub z: IBig,
+    curve: PhantomData<C>,
+}
+
+/// [`PointJacobi`] on secp256k1. The default curve parameter is not used to
+/// infer a path's type, so `PointJacobi::generator()` is ambiguous where no
+/// type is expected; `Secp256k1Point::generator()` is the short spelling.
+pub type Secp256k1Point = PointJacobi<Secp256k1>;
+
+impl<C: Curve> PointJacobi<C> {
+    pub fn new(x: IBig, y: IBig, z: IBig) -> Self {
+        Self {
+            x,
+            y,
+            z,
+            curve: PhantomData,
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::new(ibig!(0), ibig!(1), ibig!(0))
+    }
+
+    pub fn generator() -> Self {
+        Self::from_affine(Point::generator())
+    }
+
+    pub fn from_affine(p: Point<C>) -> Self {
+        if p.is_zero() {
+            return Self::zero();
+        }
+        Self::new(p.x, p.y, ibig!(1))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        C::rem(&self.z) == IBig::from(0)
+    }
+
+    /// `is_on_curve` checks `Y² = X³ + a·X·Z⁴ + b·Z⁶`, the curve equation in
+    /// Jacobian coordinates, without normalizing. The point at infinity is on
+    /// the curve.
+    pub fn is_on_curve(&self) -> bool {
+        if self.is_zero() {
+            return true;
+        }
+        let z2 = C::sqr(&self.z);
+        let mut rhs = self.x.pow(3) + C::b() * C::rem(&(&z2 * &z2 * &z2));
+        if *C::a() != IBig::from(0) {
+            rhs += C::a() * &self.x * C::sqr(&z2);
+        }
+        C::sqr(&self.y) == C::rem(&rhs)
+    }
+
+    pub fn to_affine(&self) -> Point<C> {
+        self.to_affine_with(C::invert)
+    }
+
+    /// `to_affine_secret` is [`PointJacobi::to_affine`] using
+    /// [`Curve::invert_ct`], for points whose `z` coordinate depends on a
+    /// secret. The inversion is constant-time on secp256k1 only.
+    pub fn to_affine_secret(&self) -> Point<C> {
+        self.to_affine_with(C::invert_ct)
+    }
+
+    /// `batch_to_affine` converts every point to affine form with a single
+    /// field inversion shared across all `z` coordinates (Montgomery's
+    /// trick), instead of one per point; see [`Curve::normalize_batch`]. The
+    /// inversion is variable-time, as in [`PointJacobi::to_affine`]. The point
+    /// at infinity maps to [`Point::zero`].
+    pub fn batch_to_affine(points: &[Self]) -> Vec<Point<C>> {
+        let mut points = points.to_vec();
+        C::normalize_batch(&mut points);
+        points.iter().map(Self::to_affine).collect()
+    }
+
+    /// `normalize` rescales the point in place to `z = 1`, with one
+    /// variable-time inversion, so that later [`PointJacobi::to_affine`]
+    /// calls, and with them encoding and hashing, read `x` and `y` directly.
+    /// The point at infinity is left as it is.
+    pub fn normalize(&mut self) {
+        if !self.is_zero() && !self.is_normalized() {
+            *self = Self::from_affine(self.to_affine());
+        }
+    }
+
+    /// `is_normalized` reports whether `z` is one, so that `x` and `y` are the
+    /// affine coordinates. Points built from affine ones always are.
+    pub fn is_normalized(&self) -> bool {
+        self.z == IBig::from(1)
+    }
+
+    fn to_affine_with(&self, invert: fn(&IBig) -> IBig) -> Point<C> {
+        if self.is_normalized() {
+            return Point::new(C::rem(&self.x), C::rem(&self.y));
+        }
+        let inv_z = invert(&self.z);
+        let inv_z_pow = C::sqr(&inv_z);
+        let x = C::rem((&self.x * &inv_z_pow).borrow());
+        let y = C::rem((&self.y * &inv_z * &inv_z_pow).borrow());
+        Point::new(x, y)
+    }
+
+    pub fn negate(self) -> Self {
+        Self::new(self.x, C::rem(&-self.y), self.z)
+    }
+
+    pub fn double(&self) -> Self {
+        C::double(self)
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        C::add(self, other)
+    }
+
+    /// `double_assign` replaces the point with its double.
+    ///
+    /// On [`Secp256k1`], `double`, `add` and their `_assign` forms each
+    /// convert their operands to fixed-limb coordinates and back, which is
+    /// where they allocate; the formulas themselves do not. Chains of
+    /// operations are cheapest through the scalar multiplication methods,
+    /// which convert once.
+    pub fn double_assign(&mut self) {
+        *self = self.double();
+    }
+
+    /// `add_assign` replaces the point with its sum with `other`.
+    pub fn add_assign(&mut self, other: &Self) {
+        *self = self.add(other);
+    }
+
+    /// `mul_unsafe` computes `scalar·P` for a public scalar, in time that
+    /// depends on the scalar. On [`Secp256k1`] the scalar is split with the
+    /// endomorphism into two halves of about 128 bits, processed together.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "dlogproof.mul", level = "trace", skip_all)
+    )]
+    pub fn mul_unsafe(&self, scalar: &IBig) -> Self {
+        C::mul_unsafe(self, scalar)
+    }
+
+    /// `mul_double_base` computes `a·P + b·Q` for public scalars.
+    ///
+    /// On [`Secp256k1`] it uses Straus' interleaving ("Shamir's trick"). Both
+    /// scalars are split with the endomorphism as in
+    /// [`PointJacobi::mul_unsafe`], leaving four scalars of about 128 bits for
+    /// the bases `P`, `λ·P`, `Q` and `λ·Q`. After precomputing the 15
+    /// non-empty sums of those bases, normalized together so that every
+    /// addition is a mixed one (see [`PointJacobi::add_affine`]), one pass
+    /// over the bits costs a doubling and at most one addition per bit, about
+    /// half as much as two separate multiplications and an addition.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "dlogproof.mul", level = "trace", skip_all)
+    )]
+    pub fn mul_double_base(&self, a: &IBig, other: &Self, b: &IBig) -> Self {
+        C::mul_double_base(self, a, other, b)
+    }
+
+    /// `mul_secret` computes `scalar·P` for a secret scalar with a Montgomery
+    /// ladder, so the sequence of point operations does not depend on the
+    /// scalar's bits or length.
+    ///
+    /// The scalar is reduced modulo n and offset by a multiple of n into
+    /// `[2^m, 2^(m+1))`, for the bit length `m` of n, so the ladder always
+    /// runs `m` steps of one addition and one doubling, and its two
+    /// accumulators never meet the special cases of `add`. The result is
+    /// returned with `z = 1`, normalized by a Fermat inversion whose sequence
+    /// of operations is fixed.
+    ///
+    /// On [`Secp256k1`] the ladder runs on fixed-limb [`FieldElement`]s: the
+    /// scalar is converted once into a [`Scalar`], offset on fixed limbs by
+    /// `Scalar::ladder_limbs`, and each step swaps the accumulators with
+    /// `conditional_swap` instead of indexing or branching on the bit. Only
+    /// the conversion of the `IBig` argument depends on its length. On other
+    /// curves the reduction, offset and bits still use `IBig`, which removes
+    /// the large, operation-count timing signal of
+    /// [`PointJacobi::mul_unsafe`] but is not a constant-time guarantee.
+    /// Either way it is about twice as slow as `mul_unsafe`, which is why
+    /// verification, where every scalar is public, keeps `mul_unsafe`.
+    ///
+    /// With the `co-z` feature the secp256k1 ladder uses co-Z formulas
+    /// instead; see `Jacobian::co_z_ladder`.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "dlogproof.mul", level = "trace", skip_all)
+    )]
+    pub fn mul_secret(&self, scalar: &IBig) -> Self {
+        C::mul_secret(self, scalar)
+    }
+
+    /// `mul_generator` computes `scalar·G` for a secret scalar. On
+    /// [`Secp256k1`] it uses a table of multiples of `G` built on first use;
+    /// see [`FixedBaseTable::mul_secret`]. It gives the same result as
+    /// `PointJacobi::generator().mul_secret(scalar)` several times faster.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "dlogproof.mul", level = "trace", skip_all)
+    )]
+    pub fn mul_generator(scalar: &IBig) -> Self {
+        C::mul_generator(scalar)
+    }
+
+    /// Compressed SEC1 encoding of the affine form of the point.
+    pub fn to_bytes(&self) -> [u8; POINT_BYTES] {
+        self.to_affine().to_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        Point::from_bytes(bytes).map(Self::from_affine)
+    }
+
+    /// SEC1 encoding of the affine form of the point; see [`Point::to_sec1_bytes`].
+    pub fn to_sec1_bytes(&self, compressed: bool) -> Vec<u8> {
+        self.to_affine().to_sec1_bytes(compressed)
+    }
+
+    pub fn from_sec1_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        Point::from_sec1_bytes(bytes).map(Self::from_affine)
+    }
+
+    /// x-only encoding of the affine form of the point; see
+    /// [`Point::to_x_only_bytes`].
+    pub fn to_x_only_bytes(&self) -> Option<[u8; X_ONLY_POINT_BYTES]> {
+        self.to_affine().to_x_only_bytes()
+    }
+
+    pub fn from_x_only_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        Point::from_x_only_bytes(bytes).map(Self::from_affine)
+    }
+}
+
+impl Point {
+    /// `hash_to_curve` hashes `msg` to a point of unknown discrete log under
+    /// the domain separation tag `domain`, as RFC 9380 specifies for
+    /// secp256k1; see [`crate::hash_to_curve`].
+    pub fn hash_to_curve(domain: &[u8], msg: &[u8]) -> Self {
+        PointJacobi::hash_to_curve(domain, msg).to_affine()
+    }
+}
+
+impl PointJacobi {
+    /// `hash_to_curve` is [`Point::hash_to_curve`] in Jacobian coordinates,
+    /// e.g. for a base point derived from a session id.
+    pub fn hash_to_curve(domain: &[u8], msg: &[u8]) -> Self {
+        crate::hash_to_curve::hash_to_curve(domain, msg)
+    }
+
+    /// `add_affine` is [`PointJacobi::add`] for an affine second operand,
+    /// whose implicit `z = 1` saves four field multiplications and a
+    /// squaring.
+    pub fn add_affine(&self, other: &Point) -> Self {
+        if other.is_zero() {
+            return self.clone();
+        }
+        let other = Jacobian {
+            x: FieldElement::from_ibig(&other.x),
+            y: FieldElement::from_ibig(&other.y),
+            z: FieldElement::ONE,
+        };
+        Jacobian::from(self).add_mixed(&other).into()
+    }
+}
+
+/// Width in bits of a window in a [`FixedBaseTable`].
+const WINDOW_BITS: usize = 4;
+const WINDOW_SIZE: usize = 1 << WINDOW_BITS;
+const WINDOWS: usize = 256 / WINDOW_BITS;
+
+/// Precomputed multiples `j·16^i·P` of a fixed point `P`, for every 4-bit
+/// window `i` of a 256-bit scalar and every digit `j`.
+///
+/// Multiplying by a scalar reduced modulo `n` is then one table lookup and
+/// one addition per window, with no doublings. Entries are stored with
+/// `z = 1`, normalized together with one shared inversion, so every addition
+/// is a mixed one (see [`PointJacobi::add_affine`]). Building a table costs
+/// about as much as ten ordinary multiplications, so it only pays off when
+/// the same point is multiplied many times.
+#[derive(Debug, Clone)]
+pub struct FixedBaseTable {
+    windows: Vec<[Jacobian; WINDOW_SIZE]>,
+    /// `16^64·P`, the digit after the last window.
+    top: Jacobian,
+    /// `-(Σ 16^i)·P`, which cancels the offset digits of `mul_secret`.
+    offset: Jacobian,
+}
+
+impl FixedBaseTable {
+    pub fn new(point: &PointJacobi) -> Self {
+        let mut entries = Vec::with_capacity(WINDOWS * WINDOW_SIZE + 2);
+        let mut base = Jacobian::from(point);
+        for _ in 0..WINDOWS {
+            entries.push(Jacobian::ZERO);
+            for _ in 1..WINDOW_SIZE {
+                entries.push(entries[entries.len() - 1].add(&base));
+            }
+            base = entries[entries.len() - 1].add(&base);
+        }
+        let offset = entries
+            .iter()
+            .skip(1)
+            .step_by(WINDOW_SIZE)
+            .fold(Jacobian::ZERO, |acc, entry| acc.add(entry))
+            .negate();
+        entries.extend([base, offset]);
+        Jacobian::normalize_batch(&mut entries);
+        let offset = entries.pop().expect("pushed above");
+        let top = entries.pop().expect("pushed above");
+        let windows = entries
+            .chunks_exact(WINDOW_SIZE)
+            .map(|row| row.try_into().expect("rows are WINDOW_SIZE long"))
+            .collect();
+        Self {
+            windows,
+            top,
+            offset,
+        }
+    }
+
+    /// `mul` returns `scalar·P`, reducing `scalar` modulo `n` first.
+    pub fn mul(&self, scalar: &IBig) -> PointJacobi {
+        self.windows
+            .iter()
+            .zip(Self::digits(scalar))
+            .fold(Jacobian::ZERO, |acc, (row, j)| acc.add_mixed(&row[j]))
+            .into()
+    }
+
+    /// `mul_secret` is [`FixedBaseTable::mul`] for a secret scalar.
+    ///
+    /// Each window adds the entry for its digit plus one, `(j + 1)·16^i·P`,
+    /// and the sum of the extra `16^i·P` is subtracted once at the end. No
+    /// entry is the point at infinity, so every scalar costs the same 64
+    /// additions, and the result is normalized as in
+    /// [`PointJacobi::mul_secret`]. Each entry is read with a
+    /// `conditional_assign` over all 16 candidates of its window, so the
+    /// memory accessed does not depend on the digits.
+    pub fn mul_secret(&self, scalar: &IBig) -> PointJacobi {
+        Self::digits(scalar)
+            .enumerate()
+            .map(|(i, j)| {
+                // (j + 1)·16^i·P is in this row unless j is 15, when it is
+                // 16^(i + 1)·P, the first entry of the next row.
+                let j = j as u8;
+                let next = self.windows.get(i + 1).map_or(&self.top, |row| &row[1]);
+                let mut entry = *next;
+                for (d, candidate) in (0u8..).zip(&self.windows[i][1..]) {
+                    entry.conditional_assign(candidate, d.ct_eq(&j));
+                }
+                entry
+            })
+            .fold(self.offset, |acc, entry| acc.add_mixed(&entry))
+            .normalize()
+            .into()
+    }
+
+    /// The 64 base-16 digits of `scalar mod n`, least significant first.
+    fn digits(scalar: &IBig) -> impl Iterator<Item = usize> {
+        Scalar::from_ibig(scalar)
+            .to_bytes()
+            .into_iter()
+            .rev()
+            .flat_map(|b| [(b & 0x0f) as usize, (b >> 4) as usize])
+    }
+}
+
+/// Points are equal when they represent the same affine point, whatever their
+/// `z` coordinates: `x1·z2² == x2·z1²` and `y1·z2³ == y2·z1³`. The encoded
+/// cross products are compared in constant time; see [`Curve::ct_eq`].
+impl<C: Curve> ConstantTimeEq for PointJacobi<C> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        C::ct_eq(self, other)
+    }
+}
+
+impl<C: Curve> PartialEq for PointJacobi<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl<C: Curve> Eq for PointJacobi<C> {}
+
+#[derive(Debug, Eq, Clone)]
+pub struct Point<C: Curve = Secp256k1> {
+    pub x: IBig,
+    pub y: IBig,
+    curve: PhantomData<C>,
+}
+
+/// [`Point`] on secp256k1; see [`Secp256k1Point`].
+pub type Secp256k1AffinePoint = Point<Secp256k1>;
+
+impl<C: Curve> ConstantTimeEq for Point<C> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        field_to_bytes::<C>(&self.x).ct_eq(&field_to_bytes::<C>(&other.x))
+            & field_to_bytes::<C>(&self.y).ct_eq(&field_to_bytes::<C>(&other.y))
+    }
+}
+
+impl<C: Curve> PartialEq for Point<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl<C: Curve> Point<C> {
+    pub fn new(x: IBig, y: IBig) -> Self {
+        Self {
+            x,
+            y,
+            curve: PhantomData,
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::new(ibig!(0), ibig!(0))
+    }
+
+    pub fn generator() -> Self {
+        let (x, y) = C::generator();
+        Self::new(x.clone(), y.clone())
+    }
+
+    pub fn mul(&self, scalar: &IBig) -> Self {
+        let pj = PointJacobi::from_affine(self.clone());
+        pj.mul_unsafe(scalar).to_affine()
+    }
+
+    /// `y² = x³ + a·x + b`, reduced modulo `p`.
+    fn rhs(x: &IBig) -> IBig {
+        C::rem(&(x.pow(3) + C::a() * x + C::b()))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.x == IBig::from(0) && self.y == IBig::from(0)
+    }
+
+    /// `is_on_curve` checks that `x` and `y` are field elements satisfying
+    /// `y² = x³ + a·x + b`. The point at infinity is on the curve.
+    pub fn is_on_curve(&self) -> bool {
+        if self.is_zero() {
+            return true;
+        }
+        let in_field = |v: &IBig| *v >= IBig::from(0) && *v < *C::p();
+        in_field(&self.x) && in_field(&self.y) && C::sqr(&self.y) == Self::rhs(&self.x)
+    }
+
+    /// Every decoder returns its point through here, so no encoding can
+    /// produce a point off the curve.
+    fn checked(self) -> Result<Self, ProofError> {
+        if self.is_on_curve() {
+            Ok(self)
+        } else {
+            Err(ProofError::DeserializationError(
+                "point is not on the curve".to_string(),
+            ))
+        }
+    }
+
+    /// Compressed SEC1 encoding: a `0x02`/`0x03` parity tag followed by the
+    /// 32-byte big-endian `x` coordinate. The point at infinity encodes as
+    /// all zeroes.
+    pub fn to_bytes(&self) -> [u8; POINT_BYTES] {
+        let mut out = [0u8; POINT_BYTES];
+        if self.is_zero() {
+            return out;
+        }
+        out[0] = if (&self.y & 1_u8) == 0 { 0x02 } else { 0x03 };
+        out[1..].copy_from_slice(&field_to_bytes::<C>(&self.x));
+        out
+    }
+
+    /// Parses a compressed SEC1 encoding produced by [`Point::to_bytes`],
+    /// recovering `y` from the curve equation `y² = x³ + a·x + b`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != POINT_BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} point bytes, got {}",
+                POINT_BYTES,
+                bytes.len()
+            )));
+        }
+        if bytes.iter().all(|b| *b == 0) {
+            return Ok(Self::zero());
+        }
+        let odd = match bytes[0] {
+            0x02 => false,
+            0x03 => true,
+            tag => {
+                return Err(ProofError::DeserializationError(format!(
+                    "invalid point tag {:#04x}",
+                    tag
+                )))
+            }
+        };
+        let x = curve::field_from_bytes(bytes[1..].try_into().unwrap());
+        if x >= *C::p() {
+            return Err(ProofError::DeserializationError(
+                "x coordinate is not a field element".to_string(),
+            ));
+        }
+        let y = C::sqrt(&Self::rhs(&x)).ok_or_else(|| {
+            ProofError::DeserializationError("x coordinate is not on the curve".to_string())
+        })?;
+        let y = if ((&y & 1_u8) != 0) == odd {
+            y
+        } else {
+            C::rem(&-y)
+        };
+        Self::new(x, y).checked()
+    }
+
+    /// Uncompressed SEC1 encoding: `0x04` followed by the 32-byte big-endian
+    /// `x` and `y` coordinates. The point at infinity has no such encoding.
+    pub fn to_uncompressed_bytes(&self) -> Option<[u8; UNCOMPRESSED_POINT_BYTES]> {
+        if self.is_zero() {
+            return None;
+        }
+        let mut out = [0u8; UNCOMPRESSED_POINT_BYTES];
+        out[0] = 0x04;
+        out[1..33].copy_from_slice(&field_to_bytes::<C>(&self.x));
+        out[33..].copy_from_slice(&field_to_bytes::<C>(&self.y));
+        Some(out)
+    }
+
+    /// Parses an uncompressed SEC1 encoding, checking `y² = x³ + a·x + b`.
+    pub fn from_uncompressed_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != UNCOMPRESSED_POINT_BYTES || bytes[0] != 0x04 {
+            return Err(ProofError::DeserializationError(
+                "expected a 65-byte uncompressed point".to_string(),
+            ));
+        }
+        let x = curve::field_from_bytes(bytes[1..33].try_into().unwrap());
+        let y = curve::field_from_bytes(bytes[33..].try_into().unwrap());
+        Self::new(x, y).checked()
+    }
+
+    /// `to_x_only_bytes` returns the 32-byte big-endian `x` coordinate, the
+    /// BIP-340 encoding of a public key, which drops the parity of `y`. The
+    /// point at infinity has no such encoding.
+    pub fn to_x_only_bytes(&self) -> Option<[u8; X_ONLY_POINT_BYTES]> {
+        if self.is_zero() {
+            return None;
+        }
+        Some(field_to_bytes::<C>(&self.x))
+    }
+
+    /// `from_x_only_bytes` parses a 32-byte x-only encoding into the point
+    /// with that `x` and an even `y`, as BIP-340's `lift_x` does.
+    ///
+    /// Returns:
+    ///
+    /// The point, or a `DeserializationError` if the length is wrong or no
+    /// point on the curve has that `x`.
+    pub fn from_x_only_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != X_ONLY_POINT_BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} x-only point bytes, got {}",
+                X_ONLY_POINT_BYTES,
+                bytes.len()
+            )));
+        }
+        let mut compressed = [0x02; POINT_BYTES];
+        compressed[1..].copy_from_slice(bytes);
+        Self::from_bytes(&compressed)
+    }
+
+    /// `to_sec1_bytes` returns the SEC1 encoding, compressed (33 bytes) or
+    /// uncompressed (65 bytes). The point at infinity is the single byte `0x00`.
+    pub fn to_sec1_bytes(&self, compressed: bool) -> Vec<u8> {
+        if self.is_zero() {
+            return vec![0x00];
+        }
+        if compressed {
+            self.to_bytes().to_vec()
+        } else {
+            self.to_uncompressed_bytes().unwrap().to_vec()
+        }
+    }
+
+    /// `from_sec1_bytes` parses any SEC1 encoding, telling the compressed and
+    /// uncompressed forms apart by length.
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: a 33-byte compressed point, a 65-byte uncompressed point or
+    ///   `0x00` for the point at infinity
+    ///
+    /// Returns:
+    ///
+    /// The point, or a `DeserializationError` if the length or tag is wrong or
+    /// the point is not on the curve.
+    pub fn from_sec1_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        match bytes.len() {
+            1 if bytes[0] == 0x00 => Ok(Self::zero()),
+            POINT_BYTES => Self::from_bytes(bytes),
+            UNCOMPRESSED_POINT_BYTES => Self::from_uncompressed_bytes(bytes),
+            len => Err(ProofError::DeserializationError(format!(
+                "expected a 33- or 65-byte SEC1 point, got {} bytes",
+                len
+            ))),
+        }
+    }
+}
+
+/// A point in Jacobian coordinates over fixed-limb [`FieldElement`]s, the
+/// representation [`PointJacobi`]'s arithmetic runs on. The point at
+/// infinity has `z = 0`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Jacobian {
+    x: FieldElement,
+    y: FieldElement,
+    z: FieldElement,
+}
+
+impl Jacobian {
+    pub(crate) const ZERO: Self = Self {
+        x: FieldElement::ZERO,
+        y: FieldElement::ONE,
+        z: FieldElement::ZERO,
+    };
+
+    pub(crate) fn is_zero(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    pub(crate) fn negate(&self) -> Self {
+        Self {
+            y: self.y.neg(),
+            ..*self
+        }
+    }
+
+    /// `λ·P = (β·x, y)`, the curve endomorphism.
+    fn endomorphism(&self) -> Self {
+        Self {
+            x: self.x.mul(&curve::BETA_FIELD),
+            ..*self
+        }
+    }
+
+    pub(crate) fn double(&self) -> Self {
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = b.square();
+        let d = self.x.add(&b).square().sub(&a).sub(&c).double();
+        let e = a.mul_small(3);
+        let x3 = e.square().sub(&d.double());
+        let y3 = e.mul(&d.sub(&x3)).sub(&c.mul_small(8));
+        let z3 = self.y.mul(&self.z).double();
+        Self {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    pub(crate) fn add(&self, other: &Self) -> Self {
+        if other.is_zero() {
+            return *self;
+        }
+        if self.is_zero() {
+            return *other;
+        }
+        let z1z1 = self.z.square();
+        let z2z2 = other.z.square();
+        let u1 = self.x.mul(&z2z2);
+        let u2 = other.x.mul(&z1z1);
+        let s1 = self.y.mul(&other.z).mul(&z2z2);
+        let s2 = other.y.mul(&self.z).mul(&z1z1);
+        let h = u2.sub(&u1);
+        let r = s2.sub(&s1);
+        if h.is_zero() {
+            return if r.is_zero() {
+                self.double()
+            } else {
+                Self::ZERO
+            };
+        }
+        let hh = h.square();
+        let hhh = h.mul(&hh);
+        let v = u1.mul(&hh);
+        let x3 = r.square().sub(&hhh).sub(&v.double());
+        let y3 = r.mul(&v.sub(&x3)).sub(&s1.mul(&hhh));
+        let z3 = self.z.mul(&other.z).mul(&h);
+        Self {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    /// `ladder` computes `k·P` for `k` in `[2^256, 2^257)` with one addition
+    /// and one doubling per bit.
+    fn ladder(p: Self, k: &Bits) -> Self {
+        let (mut r0, mut r1) = (p, p.double());
+        for i in (0..256).rev() {
+            // With the bit set the roles swap: r1 is doubled and r0 gets the
+            // sum.
+            let bit = k.choice(i);
+            Self::conditional_swap(&mut r0, &mut r1, bit);
+            r1 = r0.add(&r1);
+            r0 = r0.double();
+            Self::conditional_swap(&mut r0, &mut r1, bit);
+        }
+        r0
+    }
+
+    /// `co_z_ladder` is `ladder` with the co-Z formulas of Goundar, Joye and
+    /// Miyaji ("Co-Z addition formulæ and binary ladders on elliptic curves",
+    /// CHES 2010). Both accumulators share one `z` coordinate, so each bit
+    /// costs a conjugate addition and an addition with update, about 11
+    /// multiplications and 5 squarings, against 14 and 9 for `ladder`.
+    ///
+    /// The formulas have no special cases: an accumulator at infinity, or
+    /// two with the same `x`, collapse `z` to zero for the rest of the
+    /// ladder. That happens only for scalars whose prefixes are `0` or `±1`
+    /// modulo `n`, such as `0` and `1`, and those are recomputed with
+    /// `ladder`.
+    #[cfg(feature = "co-z")]
+    fn co_z_ladder(p: Self, k: &Bits) -> Self {
+        let (double, p) = p.co_z_double();
+        let (mut r0, mut r1) = (p, double);
+        for i in (0..256).rev() {
+            // Swapped so that r0 is the accumulator the bit doubles.
+            let bit = k.choice(i);
+            Self::conditional_swap(&mut r0, &mut r1, bit);
+            let (sum, difference) = r0.co_z_add_conjugate(&r1);
+            (r0, r1) = sum.co_z_add_update(&difference);
+            Self::conditional_swap(&mut r0, &mut r1, bit);
+        }
+        if r0.is_zero() {
+            Self::ladder(p, k)
+        } else {
+            r0
+        }
+    }
+
+    /// `co_z_double` returns `(2·P, P)` with a shared `z`, the doubling's.
+    #[cfg(feature = "co-z")]
+    fn co_z_double(&self) -> (Self, Self) {
+        // As in `double`; P rescaled by λ = 2y is (4·x·y², 8·y⁴, 2·y·z).
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = b.square();
+        let d = self.x.add(&b).square().sub(&a).sub(&c).double();
+        let e = a.mul_small(3);
+        let x3 = e.square().sub(&d.double());
+        let y3 = e.mul(&d.sub(&x3)).sub(&c.mul_small(8));
+        let z3 = self.y.mul(&self.z).double();
+        let doubled = Self {
+            x: x3,
+            y: y3,
+            z: z3,
+        };
+        let rescaled = Self {
+            x: d,
+            y: c.mul_small(8),
+            z: z3,
+        };
+        (doubled, rescaled)
+    }
+
+    /// `co_z_add_update` returns `(P + Q, P)` for co-Z `P = self` and `Q`,
+    /// with a new shared `z` (ZADDU).
+    #[cfg(feature = "co-z")]
+    fn co_z_add_update(&self, other: &Self) -> (Self, Self) {
+        let dx = other.x.sub(&self.x);
+        let dy = other.y.sub(&self.y);
+        let a = dx.square();
+        let b = self.x.mul(&a);
+        let c = other.x.mul(&a);
+        let e = self.y.mul(&c.sub(&b));
+        let z3 = self.z.mul(&dx);
+        let x3 = dy.square().sub(&b).sub(&c);
+        let y3 = dy.mul(&b.sub(&x3)).sub(&e);
+        let sum = Self {
+            x: x3,
+            y: y3,
+            z: z3,
+        };
+        (sum, Self { x: b, y: e, z: z3 })
+    }
+
+    /// `co_z_add_conjugate` returns `(P + Q, P - Q)` for co-Z `P = self` and
+    /// `Q`, sharing a new `z` (ZADDC).
+    #[cfg(feature = "co-z")]
+    fn co_z_add_conjugate(&self, other: &Self) -> (Self, Self) {
+        let dx = other.x.sub(&self.x);
+        let dy = other.y.sub(&self.y);
+        let sy = other.y.add(&self.y);
+        let a = dx.square();
+        let b = self.x.mul(&a);
+        let c = other.x.mul(&a);
+        let e = self.y.mul(&c.sub(&b));
+        let z3 = self.z.mul(&dx);
+        let x3 = dy.square().sub(&b).sub(&c);
+        let y3 = dy.mul(&b.sub(&x3)).sub(&e);
+        let x4 = sy.square().sub(&b).sub(&c);
+        let y4 = sy.mul(&x4.sub(&b)).sub(&e);
+        (
+            Self {
+                x: x3,
+                y: y3,
+                z: z3,
+            },
+            Self {
+                x: x4,
+                y: y4,
+                z: z3,
+            },
+        )
+    }
+
+    /// `add_mixed` is `add` for an `other` with `z = 1` or at infinity, as
+    /// stored in normalized tables.
+    fn add_mixed(&self, other: &Self) -> Self {
+        if other.is_zero() {
+            return *self;
+        }
+        if self.is_zero() {
+            return *other;
+        }
+        let z1z1 = self.z.square();
+        let u2 = other.x.mul(&z1z1);
+        let s2 = other.y.mul(&self.z).mul(&z1z1);
+        let h = u2.sub(&self.x);
+        let r = s2.sub(&self.y);
+        if h.is_zero() {
+            return if r.is_zero() {
+                self.double()
+            } else {
+                Self::ZERO
+            };
+        }
+        let hh = h.square();
+        let hhh = h.mul(&hh);
+        let v = self.x.mul(&hh);
+        let x3 = r.square().sub(&hhh).sub(&v.double());
+        let y3 = r.mul(&v.sub(&x3)).sub(&self.y.mul(&hhh));
+        let z3 = self.z.mul(&h);
+        Self {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    /// `ct_eq` compares `x1·z2² = x2·z1²` and `y1·z2³ = y2·z1³` without
+    /// normalizing either point.
+    pub(crate) fn ct_eq(&self, other: &Self) -> Choice {
+        let zero1 = self.z.ct_eq(&FieldElement::ZERO);
+        let zero2 = other.z.ct_eq(&FieldElement::ZERO);
+        let z1z1 = self.z.square();
+        let z2z2 = other.z.square();
+        let x_eq = self.x.mul(&z2z2).ct_eq(&other.x.mul(&z1z1));
+        let y_eq = self
+            .y
+            .mul(&z2z2.mul(&other.z))
+            .ct_eq(&other.y.mul(&z1z1.mul(&self.z)));
+        (zero1 & zero2) | (!zero1 & !zero2 & x_eq & y_eq)
+    }
+
+    /// The same point with `z = 1`.
+    fn normalize(&self) -> Self {
+        if self.is_zero() {
+            return Self::ZERO;
+        }
+        let inv_z = self.z.invert();
+        let inv_z2 = inv_z.square();
+        Self {
+            x: self.x.mul(&inv_z2),
+            y: self.y.mul(&inv_z2.mul(&inv_z)),
+            z: FieldElement::ONE,
+        }
+    }
+
+    /// `normalize_batch` normalizes every point with one variable-time
+    /// inversion shared across all `z` coordinates.
+    fn normalize_batch(points: &mut [Self]) {
+        let zs: Vec<FieldElement> = points.iter().map(|p| p.z).collect();
+        for (p, inv_z) in points.iter_mut().zip(FieldElement::invert_batch(&zs)) {
+            if p.is_zero() {
+                *p = Self::ZERO;
+                continue;
+            }
+            let inv_z2 = inv_z.square();
+            *p = Self {
+                x: p.x.mul(&inv_z2),
+                y: p.y.mul(&inv_z2.mul(&inv_z)),
+                z: FieldElement::ONE,
+            };
+        }
+    }
+}
+
+impl ConditionallySelectable for Jacobian {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self {
+            x: FieldElement::conditional_select(&a.x, &b.x, choice),
+            y: FieldElement::conditional_select(&a.y, &b.y, choice),
+            z: FieldElement::conditional_select(&a.z, &b.z, choice),
+        }
+    }
+}
+
+impl From<&PointJacobi> for Jacobian {
+    fn from(p: &PointJacobi) -> Self {
+        if p.is_zero() {
+            return Self::ZERO;
+        }
+        Self {
+            x: FieldElement::from_ibig(&p.x),
+            y: FieldElement::from_ibig(&p.y),
+            z: FieldElement::from_ibig(&p.z),
+        }
+    }
+}
+
+impl From<Jacobian> for PointJacobi {
+    fn from(p: Jacobian) -> Self {
+        if p.is_zero() {
+            return Self::zero();
+        }
+        Self::new(p.x.to_ibig(), p.y.to_ibig(), p.z.to_ibig())
+    }
+}
+
+/// The secp256k1 backend: point arithmetic on fixed-limb `Jacobian`
+/// coordinates, and the GLV endomorphism for public scalars. With the
+/// `libsecp256k1` feature, multiplications go to the C library first.
+impl Curve for Secp256k1 {
+    fn p() -> &'static IBig {
+        &curve::P
+    }
+
+    fn n() -> &'static IBig {
+        &curve::N
+    }
+
+    fn a() -> &'static IBig {
+        &curve::A
+    }
+
+    fn b() -> &'static IBig {
+        &curve::B
+    }
+
+    fn generator() -> (&'static IBig, &'static IBig) {
+        let (x, y) = &*curve::G;
+        (x, y)
+    }
+
+    fn rem(a: &IBig) -> IBig {
+        curve::rem(a)
+    }
+
+    fn rem_n(a: &IBig) -> IBig {
+        curve::rem_n(a)
+    }
+
+    fn sqr(a: &IBig) -> IBig {
+        curve::sqr(a)
+    }
+
+    fn invert(a: &IBig) -> IBig {
+        curve::invert(a)
+    }
+
+    fn invert_ct(a: &IBig) -> IBig {
+        curve::invert_ct(a)
+    }
+
+    fn sqrt(a: &IBig) -> Option<IBig> {
+        curve::sqrt(a)
+    }
+
+    fn double(p: &PointJacobi) -> PointJacobi {
+        Jacobian::from(p).double().into()
+    }
+
+    fn add(p: &PointJacobi, q: &PointJacobi) -> PointJacobi {
+        Jacobian::from(p).add(&Jacobian::from(q)).into()
+    }
+
+    fn mul_unsafe(p: &PointJacobi, k: &IBig) -> PointJacobi {
+        #[cfg(feature = "libsecp256k1")]
+        if let Some(product) = crate::libsecp::mul_unsafe(p, k) {
+            return product;
+        }
+        let (k1neg, k1, k2neg, k2) = split_scalar_endo(k);
+        let (k1, k2) = (Bits::new(&k1), Bits::new(&k2));
+        let mut k1p = Jacobian::ZERO;
+        let mut k2p = Jacobian::ZERO;
+        let mut d = Jacobian::from(p);
+        for i in 0..k1.len().max(k2.len()) {
+            if k1.bit(i) == 1 {
+                k1p = k1p.add(&d);
+            }
+            if k2.bit(i) == 1 {
+                k2p = k2p.add(&d);
+            }
+            d = d.double();
+        }
+        if k1neg {
+            k1p = k1p.negate();
+        }
+        if k2neg {
+            k2p = k2p.negate();
+        }
+        k1p.add(&k2p.endomorphism()).into()
+    }
+
+    fn mul_double_base(p: &PointJacobi, a: &IBig, q: &PointJacobi, b: &IBig) -> PointJacobi {
+        #[cfg(feature = "libsecp256k1")]
+        if let Some(product) = crate::libsecp::mul_double_base(p, a, q, b) {
+            return product;
+        }
+        let (a1neg, a1, a2neg, a2) = split_scalar_endo(&rem_n(a));
+        let (b1neg, b1, b2neg, b2) = split_scalar_endo(&rem_n(b));
+        let signed = |p: Jacobian, negative: bool| if negative { p.negate() } else { p };
+        let p = Jacobian::from(p);
+        let q = Jacobian::from(q);
+        let bases = [
+            signed(p, a1neg),
+            signed(p.endomorphism(), a2neg),
+            signed(q, b1neg),
+            signed(q.endomorphism(), b2neg),
+        ];
+        let scalars = [a1, a2, b1, b2].map(|k| Bits::new(&k));
+        let mut sums = [Jacobian::ZERO; 1 << 4];
+        for mask in 1..sums.len() {
+            let low = mask & mask.wrapping_neg();
+            sums[mask] = sums[mask ^ low].add(&bases[low.trailing_zeros() as usize]);
+        }
+        Jacobian::normalize_batch(&mut sums);
+        let bits = scalars.iter().map(Bits::len).max().unwrap_or(0);
+        (0..bits)
+            .rev()
+            .fold(Jacobian::ZERO, |acc, i| {
+                let acc = acc.double();
+                let mask = scalars
+                    .iter()
+                    .enumerate()
+                    .fold(0, |mask, (j, k)| mask | (k.bit(i) << j));
+                if mask == 0 {
+                    acc
+                } else {
+                    acc.add_mixed(&sums[mask])
+                }
+            })
+            .into()
+    }
+
+    fn mul_secret(p: &PointJacobi, k: &IBig) -> PointJacobi {
+        #[cfg(feature = "libsecp256k1")]
+        if let Some(product) = crate::libsecp::mul_secret(p, k) {
+            return product;
+        }
+        let k = Bits(Scalar::from_ibig(k).ladder_limbs());
+        let p = Jacobian::from(p);
+        #[cfg(feature = "co-z")]
+        let result = Jacobian::co_z_ladder(p, &k);
+        #[cfg(not(feature = "co-z"))]
+        let result = Jacobian::ladder(p, &k);
+        // The ladder's z coordinate depends on every bit of the scalar;
+        // normalizing here keeps the variable-time inversion in `to_affine`
+        // away from it.
+        result.normalize().into()
+    }
+
+    fn mul_generator(k: &IBig) -> PointJacobi {
+        #[cfg(feature = "libsecp256k1")]
+        if let Some(product) = crate::libsecp::mul_generator(k) {
+            return product;
+        }
+        GENERATOR_TABLE.mul_secret(k)
+    }
+
+    fn normalize_batch(points: &mut [PointJacobi]) {
+        let mut jacobian: Vec<Jacobian> = points.iter().map(Jacobian::from).collect();
+        Jacobian::normalize_batch(&mut jacobian);
+        for (point, normalized) in points.iter_mut().zip(jacobian) {
+            *point = normalized.into();
+        }
+    }
+
+    fn ct_eq(p: &PointJacobi, q: &PointJacobi) -> Choice {
+        Jacobian::from(p).ct_eq(&Jacobian::from(q))
+    }
+}
+
+/// The scalar of [`PointJacobi::mul_secret`]: `k mod n` offset by `n` or
+/// `2·n` into `[2^bits, 2^(bits+1))`, for an `n` of `bits` bits. This is the
+/// `IBig` version for the generic ladder; secp256k1 uses
+/// [`Scalar::ladder_limbs`].
+fn ladder_scalar<C: Curve>(k: &IBig, bits: usize) -> IBig {
+    let n = C::n();
+    let k = C::rem_n(k) + n;
+    [&k + n, k.clone()][usize::from((&k >> bits) != IBig::from(0))].clone()
+}
+
+/// Doubling on `IBig` coordinates for any `a` (`dbl-2007-bl`), behind the
+/// provided [`Curve::double`].
+pub(crate) fn double_ibig<C: Curve>(p: &PointJacobi<C>) -> PointJacobi<C> {
+    if p.is_zero() {
+        return PointJacobi::zero();
+    }
+    let xx = C::sqr(&p.x);
+    let yy = C::sqr(&p.y);
+    let yyyy = C::sqr(&yy);
+    let zz = C::sqr(&p.z);
+    let s = C::rem(&(ibig!(4) * &p.x * &yy));
+    let m = C::rem(&(ibig!(3) * xx + C::a() * C::sqr(&zz)));
+    let x3 = C::rem(&(C::sqr(&m) - ibig!(2) * &s));
+    let y3 = C::rem(&(&m * (s - &x3) - ibig!(8) * yyyy));
+    let z3 = C::rem(&(ibig!(2) * &p.y * &p.z));
+    PointJacobi::new(x3, y3, z3)
+}
+
+/// Addition on `IBig` coordinates (`add-2007-bl`), behind the provided
+/// [`Curve::add`].
+pub(crate) fn add_ibig<C: Curve>(p: &PointJacobi<C>, q: &PointJacobi<C>) -> PointJacobi<C> {
+    if q.is_zero() {
+        return p.clone();
+    }
+    if p.is_zero() {
+        return q.clone();
+    }
+    let z1z1 = C::sqr(&p.z);
+    let z2z2 = C::sqr(&q.z);
+    let u1 = C::rem(&(&p.x * &z2z2));
+    let u2 = C::rem(&(&q.x * &z1z1));
+    let s1 = C::rem(&(&p.y * &q.z * z2z2));
+    let s2 = C::rem(&(&q.y * &p.z * z1z1));
+    let h = C::rem(&(u2 - &u1));
+    let r = C::rem(&(s2 - &s1));
+    if h == IBig::from(0) {
+        return if r == IBig::from(0) {
+            C::double(p)
+        } else {
+            PointJacobi::zero()
+        };
+    }
+    let hh = C::sqr(&h);
+    let hhh = C::rem(&(&h * &hh));
+    let v = C::rem(&(u1 * hh));
+    let x3 = C::rem(&(C::sqr(&r) - &hhh - ibig!(2) * &v));
+    let y3 = C::rem(&(r * (v - &x3) - s1 * hhh));
+    let z3 = C::rem(&(&p.z * &q.z * h));
+    PointJacobi::new(x3, y3, z3)
+}
+
+/// Left-to-right double-and-add, behind the provided [`Curve::mul_unsafe`].
+pub(crate) fn mul_ibig<C: Curve>(p: &PointJacobi<C>, k: &IBig) -> PointJacobi<C> {
+    let k = UBig::try_from(C::rem_n(k)).expect("reduced scalar is non-negative");
+    (0..k.bit_len()).rev().fold(PointJacobi::zero(), |acc, i| {
+        let acc = C::double(&acc);
+        if k.bit(i) {
+            C::add(&acc, p)
+        } else {
+            acc
+        }
+    })
+}
+
+/// `k·P` with an endomorphism `φ(x, y) = (β·x, y)` that acts as `λ`: `k`
+/// is split as in [`PointJacobi::mul_unsafe`] with the lattice basis `basis`
+/// and both halves run through one double-and-add.
+pub(crate) fn mul_endo_ibig<C: Curve>(
+    p: &PointJacobi<C>,
+    k: &IBig,
+    beta: &IBig,
+    basis: [&IBig; 4],
+) -> PointJacobi<C> {
+    let n = C::n();
+    let (k1neg, k1, k2neg, k2) = split_scalar(&C::rem_n(k), n, basis, &(n >> 1));
+    let signed = |p: PointJacobi<C>, negative: bool| if negative { p.negate() } else { p };
+    let p1 = signed(p.clone(), k1neg);
+    let p2 = signed(
+        PointJacobi::new(C::rem(&(beta * &p.x)), p.y.clone(), p.z.clone()),
+        k2neg,
+    );
+    let (k1, k2) = (UBig::try_from(k1).unwrap(), UBig::try_from(k2).unwrap());
+    (0..k1.bit_len().max(k2.bit_len()))
+        .rev()
+        .fold(PointJacobi::zero(), |acc, i| {
+            let mut acc = C::double(&acc);
+            if k1.bit(i) {
+                acc = C::add(&acc, &p1);
+            }
+            if k2.bit(i) {
+                acc = C::add(&acc, &p2);
+            }
+            acc
+        })
+}
+
+/// The Montgomery ladder of [`PointJacobi::mul_secret`] on `IBig`
+/// coordinates, behind the provided [`Curve::mul_secret`].
+pub(crate) fn ladder_ibig<C: Curve>(p: &PointJacobi<C>, k: &IBig) -> PointJacobi<C> {
+    let bits = UBig::try_from(C::n()).expect("n is positive").bit_len();
+    let k = UBig::try_from(ladder_scalar::<C>(k, bits)).expect("offset scalar is positive");
+    let mut r = [p.clone(), C::double(p)];
+    for i in (0..bits).rev() {
+        let bit = usize::from(k.bit(i));
+        let sum = C::add(&r[0], &r[1]);
+        r[bit] = C::double(&r[bit]);
+        r[1 - bit] = sum;
+    }
+    PointJacobi::from_affine(r[0].to_affine_secret())
+}
+
+/// The comparison of [`PointJacobi`]'s `ConstantTimeEq` on `IBig`
+/// coordinates, behind the provided [`Curve::ct_eq`].
+pub(crate) fn ct_eq_ibig<C: Curve>(p: &PointJacobi<C>, q: &PointJacobi<C>) -> Choice {
+    let zero1 = Choice::from(p.is_zero() as u8);
+    let zero2 = Choice::from(q.is_zero() as u8);
+    let z1z1 = C::sqr(&p.z);
+    let z2z2 = C::sqr(&q.z);
+    let x_eq = field_to_bytes::<C>(&(&p.x * &z2z2)).ct_eq(&field_to_bytes::<C>(&(&q.x * &z1z1)));
+    let y_eq = field_to_bytes::<C>(&(&p.y * z2z2 * &q.z))
+        .ct_eq(&field_to_bytes::<C>(&(&q.y * z1z1 * &p.z)));
+    (zero1 & zero2) | (!zero1 & !zero2 & x_eq & y_eq)
+}
+
+/// Encodes a coordinate as 32 big-endian bytes after reducing it modulo `p`.
+fn field_to_bytes<C: Curve>(a: &IBig) -> [u8; SCALAR_BYTES] {
+    curve::to_be_bytes(&C::rem(a))
+}
+
+/// The little-endian 64-bit limbs of a non-negative scalar below `2^320`,
+/// so that the multiplication loops can read its bits without shifting an
+/// `IBig`, which allocates, once per bit.
+struct Bits([u64; 5]);
+
+impl Bits {
+    fn new(k: &IBig) -> Self {
+        let bytes = UBig::try_from(k)
+            .expect("scalar is non-negative")
+            .to_le_bytes();
+        let mut limbs = [0u64; 5];
+        for (i, byte) in bytes.iter().enumerate() {
+            limbs[i / 8] |= u64::from(*byte) << (8 * (i % 8));
+        }
+        Self(limbs)
+    }
+
+    fn bit(&self, i: usize) -> usize {
+        ((self.0[i / 64] >> (i % 64)) & 1) as usize
+    }
+
+    fn choice(&self, i: usize) -> Choice {
+        Choice::from(((self.0[i / 64] >> (i % 64)) & 1) as u8)
+    }
+
+    /// The number of bits up to the highest set one.
+    fn len(&self) -> usize {
+        self.0
+            .iter()
+            .rposition(|&limb| limb != 0)
+            .map_or(0, |i| 64 * (i + 1) - self.0[i].leading_zeros() as usize)
+    }
+}
+
+fn div_nearest(a: &IBig, b: &IBig) -> IBig {
+    (a + b / ibig!(2)) / b
+}
+
+fn split_scalar_endo(k: &IBig) -> (bool, IBig, bool, IBig) {
+    split_scalar(k, &curve::N, [&A1, &B1, &A2, &A1], &POW_2_128)
+}
+
+/// Splits `k` into `(k1, k2)` with `k ≡ k1 + k2·λ (mod n)`, given the
+/// reduced basis `(a1, b1)`, `(a2, b2)` of the lattice of `(x, y)` with
+/// `x + y·λ ≡ 0`. Halves above `bound` are returned negated, with their
+/// sign flag set.
+pub(crate) fn split_scalar(
+    k: &IBig,
+    n: &IBig,
+    [a1, b1, a2, b2]: [&IBig; 4],
+    bound: &IBig,
+) -> (bool, IBig, bool, IBig) {
+    let c1 = div_nearest(&(b2 * k), n);
+    let c2 = div_nearest(&(-b1 * k), n);
+    let mut k1 = curve::rem_mod(&(k - &c1 * a1 - &c2 * a2), n);
+    let mut k2 = curve::rem_mod(&(-&c1 * b1 - &c2 * b2), n);
+    let k1neg = &k1 > bound;
+    let k2neg = &k2 > bound;
+    if k1neg {
+        k1 = n - &k1;
+    }
+    if k2neg {
+        k2 = n - &k2;
+    }
+    (k1neg, k1, k2neg, k2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::p256::P256;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Doubling as it was before lazy reduction (`dbl-2009-l`, `a = 0`), with
+    /// a reduction after nearly every operation.
+    fn double_reference(p: &PointJacobi) -> PointJacobi {
+        let rem = curve::rem;
+        let a = rem(&p.x.pow(2));
+        let b = rem(&p.y.pow(2));
+        let c = rem(&b.pow(2));
+        let d = rem(&(ibig!(2) * rem(&(rem(&(&p.x + &b).pow(2)) - &a - &c))));
+        let e = rem(&(ibig!(3) * &a));
+        let f = rem(&e.pow(2));
+        let x3 = rem(&(f - &d * ibig!(2)));
+        let y3 = rem(&(&e * (&d - &x3) - &c * ibig!(8)));
+        let z3 = rem(&(&p.y * &p.z * ibig!(2)));
+        PointJacobi::new(x3, y3, z3)
+    }
+
+    /// Addition as it was before lazy reduction, which does not depend on
+    /// `a`, with `double` for the `P + P` case.
+    fn add_reference<C: Curve>(
+        p: &PointJacobi<C>,
+        q: &PointJacobi<C>,
+        double: fn(&PointJacobi<C>) -> PointJacobi<C>,
+    ) -> PointJacobi<C> {
+        let rem = C::rem;
+        if q.is_zero() {
+            return p.clone();
+        }
+        if p.is_zero() {
+            return q.clone();
+        }
+        let z1z1 = p.z.pow(2);
+        let z2z2 = q.z.pow(2);
+        let u1 = rem(&(&p.x * &z2z2));
+        let u2 = rem(&(&q.x * &z1z1));
+        let s1 = rem(&(&p.y * &q.z * &z2z2));
+        let s2 = rem(&(rem(&(&q.y * &p.z)) * &z1z1));
+        let h = rem(&(&u2 - &u1));
+        let r = rem(&(&s2 - &s1));
+        if h == IBig::from(0) {
+            return if r == IBig::from(0) {
+                double(p)
+            } else {
+                PointJacobi::zero()
+            };
+        }
+        let hh = rem(&h.pow(2));
+        let hhh = rem(&(&h * &hh));
+        let v = rem(&(&u1 * &hh));
+        let x3 = rem(&(&r.pow(2) - &hhh - &v * ibig!(2)));
+        let y3 = rem(&(&r * (&v - &x3) - &s1 * &hhh));
+        let z3 = rem(&(&p.z * &q.z * &h));
+        PointJacobi::new(x3, y3, z3)
+    }
+
+    /// `dbl-2007-bl` for any `a`, reducing after every operation.
+    fn double_reference_a<C: Curve>(p: &PointJacobi<C>) -> PointJacobi<C> {
+        if p.is_zero() {
+            return PointJacobi::zero();
+        }
+        let xx = C::rem(&(&p.x * &p.x));
+        let yy = C::rem(&(&p.y * &p.y));
+        let yyyy = C::rem(&(&yy * &yy));
+        let zz = C::rem(&(&p.z * &p.z));
+        let s = C::rem(&(ibig!(4) * C::rem(&(&p.x * &yy))));
+        let m = C::rem(&(C::rem(&(ibig!(3) * xx)) + C::rem(&(C::a() * C::rem(&(&zz * &zz))))));
+        let x3 = C::rem(&(C::rem(&(&m * &m)) - C::rem(&(ibig!(2) * &s))));
+        let y3 = C::rem(&(C::rem(&(&m * C::rem(&(s - &x3)))) - C::rem(&(ibig!(8) * yyyy))));
+        let z3 = C::rem(&(ibig!(2) * C::rem(&(&p.y * &p.z))));
+        PointJacobi::new(x3, y3, z3)
+    }
+
+    /// Picks a random Jacobian representative `(λ²x, λ³y, λz)` of `p`.
+    fn rescale<C: Curve>(p: &PointJacobi<C>, rng: &mut StdRng) -> PointJacobi<C> {
+        let l = curve::random_below(rng, C::p());
+        let l2 = C::rem(&(&l * &l));
+        PointJacobi::new(
+            C::rem(&(&p.x * &l2)),
+            C::rem(&(&p.y * &l2 * &l)),
+            C::rem(&(&p.z * &l)),
+        )
+    }
+
+    fn random_point<C: Curve>(rng: &mut StdRng) -> PointJacobi<C> {
+        let k = curve::random_below(rng, C::n());
+        rescale(&mul_ibig(&PointJacobi::generator(), &k), rng)
+    }
+
+    fn assert_same<C: Curve>(lazy: &PointJacobi<C>, eager: &PointJacobi<C>) {
+        for coordinate in [&lazy.x, &lazy.y, &lazy.z] {
+            assert!(*coordinate >= IBig::from(0) && coordinate < C::p());
+        }
+        assert!(lazy.is_on_curve());
+        assert_eq!(lazy.is_zero(), eager.is_zero());
+        if !lazy.is_zero() {
+            assert_eq!(lazy.to_affine(), eager.to_affine());
+        }
+    }
+
+    /// The points the formulas special-case, plus random ones: the identity,
+    /// `P + P` from distinct representatives and `P + (-P)`.
+    fn cases<C: Curve>(rng: &mut StdRng) -> Vec<(PointJacobi<C>, PointJacobi<C>)> {
+        let mut cases = Vec::new();
+        for _ in 0..64 {
+            let p = random_point::<C>(rng);
+            let q = random_point::<C>(rng);
+            cases.push((p.clone(), q));
+            cases.push((p.clone(), rescale(&p, rng)));
+            cases.push((p.clone(), rescale(&p, rng).negate()));
+            cases.push((p.clone(), PointJacobi::zero()));
+            cases.push((PointJacobi::zero(), p));
+        }
+        cases.push((PointJacobi::zero(), PointJacobi::zero()));
+        cases.push((PointJacobi::generator(), PointJacobi::generator()));
+        cases
+    }
+
+    #[test]
+    fn lazy_formulas_match_the_eager_ones_on_secp256k1() {
+        let mut rng = StdRng::seed_from_u64(842);
+        for (p, q) in cases::<Secp256k1>(&mut rng) {
+            let double = double_ibig(&p);
+            assert_same(&double, &double_reference(&p));
+            assert_same(&Secp256k1::double(&p), &double);
+            let sum = add_ibig(&p, &q);
+            assert_same(&sum, &add_reference(&p, &q, double_reference));
+            assert_same(&Secp256k1::add(&p, &q), &sum);
+        }
+    }
+
+    #[test]
+    fn lazy_formulas_match_the_eager_ones_for_nonzero_a() {
+        let mut rng = StdRng::seed_from_u64(842);
+        for (p, q) in cases::<P256>(&mut rng) {
+            assert_same(&double_ibig(&p), &double_reference_a(&p));
+            assert_same(
+                &add_ibig(&p, &q),
+                &add_reference(&p, &q, double_reference_a),
+            );
+        }
+    }
+
+    /// Zero, one, `n - 1`, `n`, the largest 256-bit value, negatives and
+    /// random scalars, the edges of the reduction and the ladder offset.
+    fn secret_scalars(rng: &mut StdRng) -> Vec<IBig> {
+        let n = Secp256k1::n();
+        let mut scalars = vec![
+            ibig!(0),
+            ibig!(1),
+            ibig!(2),
+            n - 1,
+            n.clone(),
+            n + 1,
+            (IBig::from(1) << 256) - 1,
+            -ibig!(1),
+            -n.clone(),
+        ];
+        scalars.extend((0..16).map(|_| curve::random_scalar(rng)));
+        scalars
+    }
+
+    #[test]
+    fn fixed_limb_ladder_scalar_matches_the_ibig_one() {
+        let mut rng = StdRng::seed_from_u64(1014);
+        for k in secret_scalars(&mut rng) {
+            let limbs = Bits(Scalar::from_ibig(&k).ladder_limbs());
+            assert_eq!(limbs.0, Bits::new(&ladder_scalar::<Secp256k1>(&k, 256)).0);
+            assert_eq!(limbs.len(), 257);
+        }
+    }
+
+    #[test]
+    fn mul_secret_matches_mul_unsafe() {
+        let mut rng = StdRng::seed_from_u64(1014);
+        let p = random_point::<Secp256k1>(&mut rng);
+        for k in secret_scalars(&mut rng) {
+            assert_eq!(p.mul_secret(&k), p.mul_unsafe(&k), "k = {k}");
+            assert_eq!(
+                Secp256k1Point::mul_generator(&k),
+                Secp256k1Point::generator().mul_unsafe(&k),
+                "k = {k}"
+            );
+        }
+    }
+
+    /// Counts the allocations made on each thread, so that tests running in
+    /// parallel do not see each other's.
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+            ALLOCATIONS.with(|count| count.set(count.get() + 1));
+            std::alloc::System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+            std::alloc::System.dealloc(ptr, layout)
+        }
+
+        unsafe fn realloc(
+            &self,
+            ptr: *mut u8,
+            layout: std::alloc::Layout,
+            new_size: usize,
+        ) -> *mut u8 {
+            ALLOCATIONS.with(|count| count.set(count.get() + 1));
+            std::alloc::System.realloc(ptr, layout, new_size)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    fn allocations<T>(operation: impl Fn() -> T) -> usize {
+        // The first call builds the lazily initialized tables.
+        operation();
+        let before = ALLOCATIONS.with(|count| count.get());
+        std::hint::black_box(operation());
+        ALLOCATIONS.with(|count| count.get()) - before
+    }
+
+    #[test]
+    fn multiplication_allocations_do_not_grow_with_the_scalar() {
+        let mut rng = StdRng::seed_from_u64(1057);
+        let p = random_point::<Secp256k1>(&mut rng);
+        let k = curve::random_scalar(&mut rng);
+        // A loop that cloned a point or shifted an IBig per bit would make
+        // hundreds of allocations for a 256-bit scalar.
+        for scalar in [ibig!(3), k] {
+            let counts = [
+                allocations(|| p.mul_unsafe(&scalar)),
+                allocations(|| p.mul_secret(&scalar)),
+                allocations(|| Secp256k1Point::mul_generator(&scalar)),
+            ];
+            assert!(counts.iter().all(|count| *count < 64), "{counts:?}");
+        }
+    }
+}
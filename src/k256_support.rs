@@ -0,0 +1,110 @@
+//! Conversions to and from `k256` types behind the `k256` feature.
+//!
+//! Points cross as affine coordinates, scalars and secret keys as their
+//! 32-byte big-endian encodings, so both sides check their own invariants.
+//! Conversions out of k256 cannot fail. Conversions into it fail with
+//! `ProofError::PointNotOnCurve` for a [`Point`] off the curve, and with
+//! `ProofError::InvalidPoint` for a [`PublicKey`] at infinity, which k256
+//! does not represent.
+
+use crate::error::ProofError;
+use crate::field;
+use crate::jacobi_point::{Point, PointJacobi};
+use crate::keys::{PublicKey, SecretKey};
+use crate::secret::SecretScalar;
+use k256::elliptic_curve::ops::Reduce;
+use k256::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+use k256::{AffinePoint, EncodedPoint, FieldBytes, U256};
+
+impl From<AffinePoint> for Point {
+    fn from(p: AffinePoint) -> Self {
+        Point::from_sec1_bytes(p.to_encoded_point(false).as_bytes())
+            .expect("k256 points are on the curve")
+    }
+}
+
+impl TryFrom<&Point> for AffinePoint {
+    type Error = ProofError;
+
+    fn try_from(p: &Point) -> Result<Self, Self::Error> {
+        if !p.is_on_curve() {
+            return Err(ProofError::PointNotOnCurve);
+        }
+        let encoded = match p.to_uncompressed_bytes() {
+            Some(bytes) => EncodedPoint::from_bytes(bytes),
+            None => return Ok(AffinePoint::IDENTITY),
+        };
+        let point = encoded
+            .ok()
+            .and_then(|e| AffinePoint::from_encoded_point(&e).into());
+        point.ok_or(ProofError::PointNotOnCurve)
+    }
+}
+
+impl From<AffinePoint> for PointJacobi {
+    fn from(p: AffinePoint) -> Self {
+        PointJacobi::from_affine(p.into())
+    }
+}
+
+impl TryFrom<&PointJacobi> for AffinePoint {
+    type Error = ProofError;
+
+    fn try_from(p: &PointJacobi) -> Result<Self, Self::Error> {
+        AffinePoint::try_from(&p.to_affine())
+    }
+}
+
+impl From<k256::Scalar> for field::Scalar {
+    fn from(k: k256::Scalar) -> Self {
+        field::Scalar::from_bytes(&k.to_bytes().into()).expect("k256 scalars are below n")
+    }
+}
+
+impl From<field::Scalar> for k256::Scalar {
+    fn from(k: field::Scalar) -> Self {
+        <k256::Scalar as Reduce<U256>>::reduce_bytes(&FieldBytes::from(k.to_bytes()))
+    }
+}
+
+impl From<k256::Scalar> for SecretScalar {
+    fn from(k: k256::Scalar) -> Self {
+        SecretScalar::from_bytes(k.to_bytes().into())
+    }
+}
+
+/// The encoding is reduced modulo `n`, as [`SecretScalar::from_bytes`] does
+/// not check its range.
+impl From<&SecretScalar> for k256::Scalar {
+    fn from(k: &SecretScalar) -> Self {
+        <k256::Scalar as Reduce<U256>>::reduce_bytes(&FieldBytes::from(*k.as_bytes()))
+    }
+}
+
+impl From<&k256::SecretKey> for SecretKey {
+    fn from(key: &k256::SecretKey) -> Self {
+        SecretKey::from_bytes(&key.to_bytes()).expect("k256 secret keys are in [1, n)")
+    }
+}
+
+impl From<&SecretKey> for k256::SecretKey {
+    fn from(key: &SecretKey) -> Self {
+        k256::SecretKey::from_bytes(&FieldBytes::from(key.to_bytes()))
+            .expect("secret keys are in [1, n)")
+    }
+}
+
+impl From<k256::PublicKey> for PublicKey {
+    fn from(key: k256::PublicKey) -> Self {
+        PublicKey::new((*key.as_affine()).into())
+    }
+}
+
+impl TryFrom<&PublicKey> for k256::PublicKey {
+    type Error = ProofError;
+
+    fn try_from(key: &PublicKey) -> Result<Self, Self::Error> {
+        let point = AffinePoint::try_from(key.point())?;
+        k256::PublicKey::from_affine(point).map_err(|_| ProofError::InvalidPoint)
+    }
+}
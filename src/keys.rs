@@ -0,0 +1,565 @@
+//! Secret and public keys, the passphrase-protected secret key file format
+//! and the DER/PEM `SubjectPublicKeyInfo` encoding of public keys.
+//!
+//! An encrypted key is laid out as
+//!
+//! ```text
+//! magic "DLSK" | version: u8 = 1 | kdf: u8 = 1 (scrypt)
+//!   | log_n: u8 | r: u32 BE | p: u32 BE | salt: 16 | nonce: 12
+//!   | ChaCha20-Poly1305(secret: 32) + tag: 16
+//! ```
+//!
+//! The key is derived from the passphrase with scrypt and everything before
+//! the ciphertext is authenticated as associated data. KDF parameters read
+//! from a file are capped so that a crafted file cannot make loading it
+//! allocate unbounded memory or spin for minutes.
+
+use crate::curve::{self, SCALAR_BYTES};
+use crate::error::ProofError;
+use crate::hex;
+use crate::jacobi_point::{Point, PointJacobi, POINT_BYTES};
+use crate::secret::SecretScalar;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use ibig::IBig;
+use rand::{CryptoRng, RngCore};
+use std::fmt;
+use zeroize::Zeroizing;
+
+const MAGIC: &[u8; 4] = b"DLSK";
+const VERSION: u8 = 1;
+const KDF_SCRYPT: u8 = 1;
+const SALT_BYTES: usize = 16;
+const NONCE_BYTES: usize = 12;
+const TAG_BYTES: usize = 16;
+const HEADER_BYTES: usize = MAGIC.len() + 3 + 4 + 4 + SALT_BYTES + NONCE_BYTES;
+const ENCRYPTED_BYTES: usize = HEADER_BYTES + SCALAR_BYTES + TAG_BYTES;
+
+const DEFAULT_LOG_N: u8 = 17;
+const DEFAULT_R: u32 = 8;
+const DEFAULT_P: u32 = 1;
+/// Upper bounds on parameters accepted from a file: at most 256 MiB of
+/// scrypt memory (`128·r·2^log_n` bytes) and 4 lanes.
+const MAX_LOG_N: u8 = 20;
+const MAX_P: u32 = 4;
+const MAX_KDF_MEMORY: u64 = 256 * 1024 * 1024;
+
+/// A secret scalar in `[1, n)`, zeroized on drop.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretKey {
+    scalar: SecretScalar,
+}
+
+impl SecretKey {
+    pub fn random<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        Self {
+            scalar: SecretScalar::new(&curve::random_scalar(rng)),
+        }
+    }
+
+    /// `from_scalar` wraps a scalar, rejecting zero and values not below n.
+    pub fn from_scalar(scalar: IBig) -> Result<Self, ProofError> {
+        if scalar == IBig::from(0) || scalar >= *curve::N {
+            return Err(ProofError::DeserializationError(
+                "secret key must be in [1, n)".to_string(),
+            ));
+        }
+        Ok(Self {
+            scalar: SecretScalar::new(&scalar),
+        })
+    }
+
+    /// `scalar` returns a copy of the secret; see [`SecretScalar::expose`].
+    pub fn scalar(&self) -> IBig {
+        self.scalar.expose()
+    }
+
+    pub fn secret_scalar(&self) -> &SecretScalar {
+        &self.scalar
+    }
+
+    /// `public_key` returns `x·G`.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::new(PointJacobi::mul_generator(&self.scalar()))
+    }
+
+    pub fn to_bytes(&self) -> [u8; SCALAR_BYTES] {
+        *self.scalar.as_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        let bytes: &[u8; SCALAR_BYTES] = bytes.try_into().map_err(|_| {
+            ProofError::DeserializationError(format!(
+                "expected {} secret key bytes, got {}",
+                SCALAR_BYTES,
+                bytes.len()
+            ))
+        })?;
+        Self::from_scalar(curve::scalar_from_bytes(bytes))
+    }
+
+    /// `to_encrypted_bytes` encrypts the key under `passphrase` in the format
+    /// described in the module documentation, with a fresh salt and nonce.
+    pub fn to_encrypted_bytes(&self, passphrase: &str) -> Vec<u8> {
+        self.encrypt(passphrase, DEFAULT_LOG_N, DEFAULT_R, DEFAULT_P)
+    }
+
+    fn encrypt(&self, passphrase: &str, log_n: u8, r: u32, p: u32) -> Vec<u8> {
+        let mut rng = rand::rngs::OsRng;
+        let mut salt = [0u8; SALT_BYTES];
+        let mut nonce = [0u8; NONCE_BYTES];
+        rng.fill_bytes(&mut salt);
+        rng.fill_bytes(&mut nonce);
+
+        let mut out = Vec::with_capacity(ENCRYPTED_BYTES);
+        out.extend(MAGIC);
+        out.extend([VERSION, KDF_SCRYPT, log_n]);
+        out.extend(r.to_be_bytes());
+        out.extend(p.to_be_bytes());
+        out.extend(salt);
+        out.extend(nonce);
+
+        let cipher =
+            derive_cipher(passphrase, &salt, log_n, r, p).expect("scrypt parameters are valid");
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: self.scalar.as_bytes(),
+                    aad: &out,
+                },
+            )
+            .expect("encrypting 32 bytes cannot fail");
+        out.extend(ciphertext);
+        out
+    }
+
+    /// `from_encrypted_bytes` decrypts a key written by [`SecretKey::to_encrypted_bytes`].
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: the encrypted key file contents
+    /// * `passphrase`: the passphrase it was encrypted under
+    ///
+    /// Returns:
+    ///
+    /// The key, `ProofError::DecryptionFailed` for a wrong passphrase or any
+    /// modified byte, or a `DeserializationError` for a malformed header or
+    /// KDF parameters above the accepted limits.
+    pub fn from_encrypted_bytes(bytes: &[u8], passphrase: &str) -> Result<Self, ProofError> {
+        let malformed = |msg: &str| ProofError::DeserializationError(msg.to_string());
+        if bytes.len() != ENCRYPTED_BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} encrypted key bytes, got {}",
+                ENCRYPTED_BYTES,
+                bytes.len()
+            )));
+        }
+        let (header, ciphertext) = bytes.split_at(HEADER_BYTES);
+        if &header[..4] != MAGIC {
+            return Err(malformed("not an encrypted secret key"));
+        }
+        if header[4] != VERSION {
+            return Err(malformed("unsupported encrypted key version"));
+        }
+        if header[5] != KDF_SCRYPT {
+            return Err(malformed("unsupported key derivation function"));
+        }
+        let log_n = header[6];
+        let r = u32::from_be_bytes(header[7..11].try_into().unwrap());
+        let p = u32::from_be_bytes(header[11..15].try_into().unwrap());
+        if log_n > MAX_LOG_N || p > MAX_P || (128 * r as u64) << log_n > MAX_KDF_MEMORY {
+            return Err(malformed("key derivation parameters exceed limits"));
+        }
+        let salt = &header[15..15 + SALT_BYTES];
+        let nonce = &header[15 + SALT_BYTES..];
+
+        let cipher = derive_cipher(passphrase, salt, log_n, r, p)
+            .ok_or_else(|| malformed("invalid key derivation parameters"))?;
+        let plaintext = Zeroizing::new(
+            cipher
+                .decrypt(
+                    Nonce::from_slice(nonce),
+                    Payload {
+                        msg: ciphertext,
+                        aad: header,
+                    },
+                )
+                .map_err(|_| ProofError::DecryptionFailed)?,
+        );
+        Self::from_bytes(&plaintext)
+    }
+}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretKey(..)")
+    }
+}
+
+/// A public key `Y = x·G`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    point: PointJacobi,
+}
+
+impl PublicKey {
+    pub fn new(point: PointJacobi) -> Self {
+        Self { point }
+    }
+
+    /// `from_secret` returns the public key `x·G` of `key`; see
+    /// [`SecretKey::public_key`].
+    pub fn from_secret(key: &SecretKey) -> Self {
+        key.public_key()
+    }
+
+    pub fn point(&self) -> &PointJacobi {
+        &self.point
+    }
+
+    /// `to_bytes` returns the 33-byte compressed SEC1 encoding.
+    pub fn to_bytes(&self) -> [u8; POINT_BYTES] {
+        self.point.to_bytes()
+    }
+
+    /// `from_bytes` parses a compressed or uncompressed SEC1 encoding.
+    ///
+    /// Returns:
+    ///
+    /// The key, or a `DeserializationError` if `bytes` is not a point on the
+    /// curve or is the point at infinity.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        let point = Point::from_sec1_bytes(bytes)?;
+        if point.is_zero() {
+            return Err(ProofError::DeserializationError(
+                "public key is the point at infinity".to_string(),
+            ));
+        }
+        Ok(Self::new(PointJacobi::from_affine(point)))
+    }
+
+    /// `to_spki_der` encodes the key as a DER `SubjectPublicKeyInfo` with the
+    /// `id-ecPublicKey` algorithm, the `secp256k1` named curve and the
+    /// uncompressed SEC1 point, as OpenSSL and Java keystores expect.
+    ///
+    /// Panics if the key is the point at infinity, which has no SEC1 encoding.
+    pub fn to_spki_der(&self) -> Vec<u8> {
+        let point = self
+            .point
+            .to_affine()
+            .to_uncompressed_bytes()
+            .expect("the point at infinity is not a public key");
+        let mut bit_string = vec![0u8];
+        bit_string.extend(point);
+        let mut body = der_tlv(DER_SEQUENCE, &SPKI_ALGORITHM);
+        body.extend(der_tlv(DER_BIT_STRING, &bit_string));
+        der_tlv(DER_SEQUENCE, &body)
+    }
+
+    /// `from_spki_der` parses a DER `SubjectPublicKeyInfo` holding a
+    /// compressed or uncompressed secp256k1 point.
+    ///
+    /// Arguments:
+    ///
+    /// * `der`: the DER bytes, with nothing after the outer structure
+    ///
+    /// Returns:
+    ///
+    /// The key, or a `DeserializationError` naming the first problem: bad
+    /// DER, an algorithm other than `id-ecPublicKey`, a curve other than
+    /// `secp256k1`, an invalid point or trailing bytes.
+    pub fn from_spki_der(der: &[u8]) -> Result<Self, ProofError> {
+        let (spki, rest) = der_read(der, DER_SEQUENCE)?;
+        if !rest.is_empty() {
+            return Err(der_error("trailing bytes after SubjectPublicKeyInfo"));
+        }
+        let (algorithm, rest) = der_read(spki, DER_SEQUENCE)?;
+        let (bit_string, rest) = der_read(rest, DER_BIT_STRING)?;
+        if !rest.is_empty() {
+            return Err(der_error("trailing bytes inside SubjectPublicKeyInfo"));
+        }
+        let (algorithm_oid, parameters) = der_read(algorithm, DER_OID)?;
+        if algorithm_oid != OID_EC_PUBLIC_KEY {
+            return Err(der_error("algorithm is not id-ecPublicKey"));
+        }
+        let (curve_oid, rest) = der_read(parameters, DER_OID)?;
+        if curve_oid != OID_SECP256K1 {
+            return Err(der_error("curve is not secp256k1"));
+        }
+        if !rest.is_empty() {
+            return Err(der_error("trailing bytes after the curve identifier"));
+        }
+        let point = match bit_string.split_first() {
+            Some((0, point)) => point,
+            _ => return Err(der_error("public key BIT STRING has unused bits")),
+        };
+        let point = Point::from_sec1_bytes(point)?;
+        if point.is_zero() {
+            return Err(der_error("public key is the point at infinity"));
+        }
+        Ok(Self::new(PointJacobi::from_affine(point)))
+    }
+
+    /// `to_pem` wraps [`PublicKey::to_spki_der`] in a `PUBLIC KEY` PEM block.
+    pub fn to_pem(&self) -> String {
+        let encoded = BASE64.encode(self.to_spki_der());
+        let mut pem = format!("{}\n", PEM_BEGIN);
+        for line in encoded.as_bytes().chunks(64) {
+            pem.push_str(std::str::from_utf8(line).unwrap());
+            pem.push('\n');
+        }
+        pem.push_str(PEM_END);
+        pem.push('\n');
+        pem
+    }
+
+    /// `from_pem` parses a `PUBLIC KEY` PEM block with [`PublicKey::from_spki_der`].
+    pub fn from_pem(pem: &str) -> Result<Self, ProofError> {
+        let body = pem
+            .trim()
+            .strip_prefix(PEM_BEGIN)
+            .and_then(|rest| rest.strip_suffix(PEM_END))
+            .ok_or_else(|| der_error("not a PUBLIC KEY PEM block"))?;
+        let encoded: String = body.split_whitespace().collect();
+        let der = BASE64
+            .decode(encoded)
+            .map_err(|_| der_error("invalid base64 in PEM body"))?;
+        Self::from_spki_der(&der)
+    }
+}
+
+/// Formats the key as the lowercase hex of [`PublicKey::to_bytes`].
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(&self.to_bytes()))
+    }
+}
+
+impl From<PointJacobi> for PublicKey {
+    fn from(point: PointJacobi) -> Self {
+        Self::new(point)
+    }
+}
+
+const PEM_BEGIN: &str = "-----BEGIN PUBLIC KEY-----";
+const PEM_END: &str = "-----END PUBLIC KEY-----";
+const DER_SEQUENCE: u8 = 0x30;
+const DER_BIT_STRING: u8 = 0x03;
+const DER_OID: u8 = 0x06;
+/// 1.2.840.10045.2.1
+const OID_EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+/// 1.3.132.0.10
+const OID_SECP256K1: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x0a];
+/// The `AlgorithmIdentifier` contents: both OIDs, DER-encoded.
+const SPKI_ALGORITHM: [u8; 16] = [
+    0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x0a,
+];
+
+fn der_error(msg: &str) -> ProofError {
+    ProofError::DeserializationError(msg.to_string())
+}
+
+/// Encodes a DER tag-length-value; contents here never exceed 255 bytes.
+fn der_tlv(tag: u8, contents: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    if contents.len() < 0x80 {
+        out.push(contents.len() as u8);
+    } else {
+        out.extend([0x81, contents.len() as u8]);
+    }
+    out.extend(contents);
+    out
+}
+
+/// Reads one DER element with the expected tag, returning its contents and
+/// the bytes after it. Only the definite short and one-byte long length
+/// forms are accepted, each in its minimal encoding.
+fn der_read(input: &[u8], tag: u8) -> Result<(&[u8], &[u8]), ProofError> {
+    let (&actual, rest) = input
+        .split_first()
+        .ok_or_else(|| der_error("unexpected end of DER input"))?;
+    if actual != tag {
+        return Err(ProofError::DeserializationError(format!(
+            "expected DER tag {:#04x}, found {:#04x}",
+            tag, actual
+        )));
+    }
+    let (len, rest) = match rest.split_first() {
+        Some((&len, rest)) if len < 0x80 => (len as usize, rest),
+        Some((0x81, [len, rest @ ..])) if *len >= 0x80 => (*len as usize, rest),
+        _ => return Err(der_error("unsupported DER length encoding")),
+    };
+    if rest.len() < len {
+        return Err(der_error("DER element is truncated"));
+    }
+    Ok(rest.split_at(len))
+}
+
+fn derive_cipher(
+    passphrase: &str,
+    salt: &[u8],
+    log_n: u8,
+    r: u32,
+    p: u32,
+) -> Option<ChaCha20Poly1305> {
+    let params = scrypt::Params::new(log_n, r, p, 32).ok()?;
+    let mut key = [0u8; 32];
+    scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key).ok()?;
+    Some(ChaCha20Poly1305::new(Key::from_slice(&key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prover::Prover;
+    use crate::verifier::Verifier;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Cheap scrypt parameters, so that the tests do not spend seconds on the
+    /// KDF; the file records them and decryption reads them back.
+    fn encrypt_fast(key: &SecretKey, passphrase: &str) -> Vec<u8> {
+        key.encrypt(passphrase, 4, 1, 1)
+    }
+
+    #[test]
+    fn encrypted_round_trip() {
+        let key = SecretKey::random(&mut rand::rngs::OsRng);
+        let bytes = key.to_encrypted_bytes("correct horse");
+        assert_eq!(bytes.len(), ENCRYPTED_BYTES);
+        assert_eq!(&bytes[..7], b"DLSK\x01\x01\x11");
+        assert!(SecretKey::from_encrypted_bytes(&bytes, "correct horse") == Ok(key.clone()));
+        let fast = encrypt_fast(&key, "");
+        assert!(SecretKey::from_encrypted_bytes(&fast, "") == Ok(key));
+    }
+
+    #[test]
+    fn wrong_passphrase_fails_to_decrypt() {
+        let key = SecretKey::random(&mut rand::rngs::OsRng);
+        let bytes = encrypt_fast(&key, "correct horse");
+        for passphrase in ["correct horsf", "", "correct horse "] {
+            assert!(
+                SecretKey::from_encrypted_bytes(&bytes, passphrase)
+                    == Err(ProofError::DecryptionFailed)
+            );
+        }
+    }
+
+    #[test]
+    fn tampered_bytes_fail_to_decrypt() {
+        let key = SecretKey::random(&mut rand::rngs::OsRng);
+        let bytes = encrypt_fast(&key, "correct horse");
+        // Every byte after the KDF parameters: salt, nonce, ciphertext, tag.
+        for i in 15..bytes.len() {
+            let mut tampered = bytes.clone();
+            tampered[i] ^= 0x01;
+            assert!(
+                SecretKey::from_encrypted_bytes(&tampered, "correct horse")
+                    == Err(ProofError::DecryptionFailed),
+                "byte {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn malformed_headers_are_rejected() {
+        let key = SecretKey::random(&mut rand::rngs::OsRng);
+        let bytes = encrypt_fast(&key, "pw");
+        let with = |i: usize, v: u8| {
+            let mut b = bytes.clone();
+            b[i] = v;
+            SecretKey::from_encrypted_bytes(&b, "pw")
+        };
+        let malformed = |r: Result<SecretKey, ProofError>| {
+            matches!(r, Err(ProofError::DeserializationError(_)))
+        };
+        assert!(malformed(with(0, b'X')));
+        assert!(malformed(with(4, 2)));
+        assert!(malformed(with(5, 2)));
+        assert!(malformed(with(6, MAX_LOG_N + 1)));
+        assert!(malformed(with(14, MAX_P as u8 + 1)));
+        assert!(malformed(SecretKey::from_encrypted_bytes(
+            &bytes[..bytes.len() - 1],
+            "pw"
+        )));
+    }
+
+    /// A secp256k1 public key exported by `openssl ec -pubout`, in both
+    /// point forms and as PEM, with keys on other curves for the rejections.
+    const OPENSSL_DER: &[u8] = include_bytes!("../testdata/spki/public-key.der");
+    const OPENSSL_COMPRESSED_DER: &[u8] =
+        include_bytes!("../testdata/spki/public-key-compressed.der");
+    const OPENSSL_PEM: &str = include_str!("../testdata/spki/public-key.pem");
+    const PRIME256V1_DER: &[u8] = include_bytes!("../testdata/spki/prime256v1-public-key.der");
+    const ED25519_DER: &[u8] = include_bytes!("../testdata/spki/ed25519-public-key.der");
+
+    fn der_message(result: Result<PublicKey, ProofError>) -> String {
+        match result {
+            Err(ProofError::DeserializationError(msg)) => msg,
+            other => panic!("expected a DER error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn openssl_fixtures_parse_and_re_encode_identically() {
+        let key = PublicKey::from_spki_der(OPENSSL_DER).unwrap();
+        assert_eq!(key.to_spki_der(), OPENSSL_DER);
+        assert_eq!(key.to_pem(), OPENSSL_PEM);
+        assert_eq!(PublicKey::from_pem(OPENSSL_PEM).unwrap(), key);
+        assert_eq!(
+            PublicKey::from_spki_der(OPENSSL_COMPRESSED_DER).unwrap(),
+            key
+        );
+        // The DER ends with the uncompressed point; the compressed one ends
+        // with the same x coordinate.
+        assert_eq!(
+            key.point().to_affine().to_uncompressed_bytes().unwrap()[..],
+            OPENSSL_DER[OPENSSL_DER.len() - 65..]
+        );
+        assert_eq!(
+            key.to_bytes()[1..],
+            OPENSSL_COMPRESSED_DER[OPENSSL_COMPRESSED_DER.len() - 32..]
+        );
+    }
+
+    #[test]
+    fn exported_keys_verify_proofs_after_import() {
+        let key = SecretKey::random(&mut StdRng::seed_from_u64(848));
+        let public_key = PublicKey::from_pem(&key.public_key().to_pem()).unwrap();
+        assert_eq!(public_key, key.public_key());
+        let g = PointJacobi::generator();
+        let proof = Prover::prove("spki", 1, &key.scalar(), public_key.point(), &g).unwrap();
+        assert_eq!(
+            Verifier::verify(&proof, "spki", 1, public_key.point(), &g),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn foreign_and_malformed_spki_are_rejected() {
+        assert_eq!(
+            der_message(PublicKey::from_spki_der(PRIME256V1_DER)),
+            "curve is not secp256k1"
+        );
+        assert_eq!(
+            der_message(PublicKey::from_spki_der(ED25519_DER)),
+            "algorithm is not id-ecPublicKey"
+        );
+        let mut trailing = OPENSSL_DER.to_vec();
+        trailing.push(0);
+        assert_eq!(
+            der_message(PublicKey::from_spki_der(&trailing)),
+            "trailing bytes after SubjectPublicKeyInfo"
+        );
+        der_message(PublicKey::from_spki_der(
+            &OPENSSL_DER[..OPENSSL_DER.len() - 1],
+        ));
+        der_message(PublicKey::from_pem(
+            &OPENSSL_PEM.replace("PUBLIC KEY", "EC PUBLIC KEY"),
+        ));
+    }
+}
@@ -0,0 +1,91 @@
+//! Non-interactive Schnorr ZK DLOG Proof scheme with a Fiat-Shamir transformation
+
+pub mod adaptor;
+pub mod and_proof;
+pub mod bip340;
+pub mod bn254;
+#[cfg(feature = "borsh")]
+mod borsh_support;
+pub mod builder;
+pub mod bundle;
+#[cfg(feature = "cbor")]
+pub mod cbor;
+pub mod commitment;
+pub mod context;
+pub mod curve;
+pub mod custom;
+pub mod dkg;
+pub mod dleq;
+pub mod ecdh;
+pub mod encryption;
+pub mod error;
+#[cfg(feature = "evm")]
+pub mod evm;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod field;
+#[cfg(feature = "group")]
+mod group_support;
+pub mod hash;
+pub mod hash_to_curve;
+mod hex;
+pub mod jacobi_point;
+#[cfg(feature = "k256")]
+mod k256_support;
+pub mod keys;
+#[cfg(feature = "libsecp256k1")]
+mod libsecp;
+pub mod linear;
+#[cfg(feature = "merlin")]
+pub mod merlin_transcript;
+#[cfg(feature = "uniffi")]
+pub mod mobile;
+pub mod musig;
+pub mod nonce;
+pub mod or_proof;
+pub mod p256;
+pub mod proof;
+#[cfg(feature = "proto")]
+pub mod proto;
+pub mod prover;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod range_proof;
+pub mod ring;
+#[cfg(feature = "ristretto")]
+pub mod ristretto;
+pub mod scalar;
+pub mod schnorr;
+pub mod secret;
+#[cfg(feature = "serde")]
+mod serde_support;
+pub mod threshold;
+pub mod transcript;
+pub mod verifier;
+pub mod vrf;
+pub mod vss;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use bn254::Bn254;
+pub use builder::ProofBuilder;
+pub use bundle::ProofBundle;
+pub use context::ProofContext;
+pub use curve::{Curve, Secp256k1};
+pub use custom::{CurveParams, CustomCurve};
+pub use error::ProofError;
+#[cfg(feature = "group")]
+pub use group_support::{PointBytes, ProjectivePoint};
+pub use jacobi_point::{FixedBaseTable, Point, PointJacobi, Secp256k1AffinePoint, Secp256k1Point};
+pub use keys::{PublicKey, SecretKey};
+pub use p256::P256;
+pub use proof::{DLogProof, ShortDLogProof, TimedDLogProof};
+pub use prover::Prover;
+pub use scalar::Scalar;
+pub use schnorr::SchnorrSignature;
+pub use secret::SecretScalar;
+pub use transcript::Transcript;
+pub use verifier::{Verifier, VerifierContext};
+
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
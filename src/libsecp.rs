@@ -0,0 +1,103 @@
+//! Point multiplication on secp256k1 through libsecp256k1, behind the
+//! `libsecp256k1` feature.
+//!
+//! [`Secp256k1`](crate::Secp256k1)'s multiplications try these first and
+//! fall back to the pure-Rust arithmetic when they return `None`, which they
+//! do for inputs libsecp256k1 cannot represent: the point at infinity and a
+//! zero scalar. Points cross the boundary in uncompressed SEC1 form, so
+//! every call pays for one inversion per input point.
+//!
+//! Public scalars use `secp256k1_ec_pubkey_tweak_mul`, which is
+//! variable-time. Secret ones use `secp256k1_ecdh` with a hash callback that
+//! returns the shared point, which runs the constant-time multiplication,
+//! and `secp256k1_ec_pubkey_create` for the generator.
+
+use crate::curve::{self, SCALAR_BYTES};
+use crate::jacobi_point::{Point, PointJacobi};
+use ibig::IBig;
+use secp256k1::{ecdh, All, PublicKey, Scalar, SecretKey};
+use std::sync::LazyLock;
+use zeroize::Zeroizing;
+
+static CONTEXT: LazyLock<secp256k1::Secp256k1<All>> = LazyLock::new(secp256k1::Secp256k1::new);
+static GENERATOR: LazyLock<PublicKey> = LazyLock::new(|| {
+    to_public_key(&PointJacobi::generator()).expect("the generator is a valid public key")
+});
+
+fn to_public_key(p: &PointJacobi) -> Option<PublicKey> {
+    let bytes = p.to_affine().to_uncompressed_bytes()?;
+    PublicKey::from_byte_array_uncompressed(bytes).ok()
+}
+
+fn from_xy(xy: &[u8]) -> PointJacobi {
+    let (x, y) = xy.split_at(SCALAR_BYTES);
+    PointJacobi::from_affine(Point::new(
+        curve::field_from_bytes(x.try_into().unwrap()),
+        curve::field_from_bytes(y.try_into().unwrap()),
+    ))
+}
+
+fn from_public_key(p: &PublicKey) -> PointJacobi {
+    from_xy(&p.serialize_uncompressed()[1..])
+}
+
+/// `k` reduced modulo `n`, or `None` for zero.
+fn to_scalar(k: &IBig) -> Option<Scalar> {
+    let k = curve::scalar_to_bytes(k);
+    if k == [0; SCALAR_BYTES] {
+        return None;
+    }
+    Scalar::from_be_bytes(k).ok()
+}
+
+/// `k` reduced modulo `n` as a secret key, or `None` for zero.
+fn to_secret_key(k: &IBig) -> Option<SecretKey> {
+    let k = Zeroizing::new(curve::scalar_to_bytes(k));
+    SecretKey::from_byte_array(*k).ok()
+}
+
+/// `k·P` for a public `k`.
+pub(crate) fn mul_unsafe(p: &PointJacobi, k: &IBig) -> Option<PointJacobi> {
+    let product = to_public_key(p)?.mul_tweak(&CONTEXT, &to_scalar(k)?).ok()?;
+    Some(from_public_key(&product))
+}
+
+/// `a·P + b·Q` for public `a` and `b`. When either base is the generator its
+/// term is added with `secp256k1_ec_pubkey_tweak_add`, which uses the
+/// precomputed generator tables, instead of a second `tweak_mul`.
+pub(crate) fn mul_double_base(
+    p: &PointJacobi,
+    a: &IBig,
+    q: &PointJacobi,
+    b: &IBig,
+) -> Option<PointJacobi> {
+    let (p, q) = (to_public_key(p)?, to_public_key(q)?);
+    let (a, b) = (to_scalar(a)?, to_scalar(b)?);
+    let sum = if p == *GENERATOR {
+        q.mul_tweak(&CONTEXT, &b).ok()?.add_exp_tweak(&CONTEXT, &a)
+    } else if q == *GENERATOR {
+        p.mul_tweak(&CONTEXT, &a).ok()?.add_exp_tweak(&CONTEXT, &b)
+    } else {
+        let ap = p.mul_tweak(&CONTEXT, &a).ok()?;
+        ap.combine(&q.mul_tweak(&CONTEXT, &b).ok()?)
+    };
+    // Both terms are non-zero, so an error means they cancelled.
+    Some(sum.map_or_else(|_| PointJacobi::zero(), |sum| from_public_key(&sum)))
+}
+
+/// `k·P` in constant time in `k`.
+pub(crate) fn mul_secret(p: &PointJacobi, k: &IBig) -> Option<PointJacobi> {
+    let p = to_public_key(p)?;
+    let mut k = to_secret_key(k)?;
+    let xy = Zeroizing::new(ecdh::shared_secret_point(&p, &k));
+    k.non_secure_erase();
+    Some(from_xy(&xy[..]))
+}
+
+/// `k·G` in constant time in `k`.
+pub(crate) fn mul_generator(k: &IBig) -> Option<PointJacobi> {
+    let mut k = to_secret_key(k)?;
+    let product = PublicKey::from_secret_key(&CONTEXT, &k);
+    k.non_secure_erase();
+    Some(from_public_key(&product))
+}
@@ -0,0 +1,414 @@
+//! Proof of knowledge of `(x_1, ..., x_k)` with `Y = Σ x_i·G_i`.
+//!
+//! The prover commits `T = Σ r_i·G_i`, derives one challenge
+//! `c = H(sid, pid, G_1, ..., G_k, Y, T)` and responds with `s_i = r_i + c·x_i`;
+//! the verifier checks `Σ s_i·G_i == T + c·Y`. The challenge is tagged
+//! [`DLOG_TAG`]: with a single base the transcript is the one used by
+//! [`DLogProof`], and with two bases `(G, H)`
+//! it proves knowledge of an opening of the Pedersen commitment `Y = x·G + r·H`;
+//! [`RepresentationProof`] is that two-base case with a fixed-size encoding.
+
+use crate::curve::{self, SCALAR_BYTES};
+use crate::error::ProofError;
+use crate::hash::{hash_tagged, DLOG_TAG};
+use crate::jacobi_point::{PointJacobi, POINT_BYTES};
+use crate::proof::DLogProof;
+use crate::prover::Prover;
+use crate::scalar::Scalar;
+use ibig::IBig;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinearProof {
+    pub t: PointJacobi,
+    pub s: Vec<IBig>,
+}
+
+impl LinearProof {
+    /// `prove` proves knowledge of `witnesses` such that `y = Σ witnesses[i]·bases[i]`.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `witnesses`: the secrets `x_i`
+    /// * `bases`: the bases `G_i`, one per witness
+    /// * `y`: the combined point `Σ x_i·G_i`
+    ///
+    /// Returns:
+    ///
+    /// The proof, or `ProofError::InvalidStatement` if `bases` is empty or its
+    /// length differs from that of `witnesses`.
+    pub fn prove(
+        sid: &str,
+        pid: i32,
+        witnesses: &[IBig],
+        bases: &[PointJacobi],
+        y: &PointJacobi,
+    ) -> Result<Self, ProofError> {
+        check_shape(witnesses.len(), bases)?;
+        let nonces: Vec<IBig> = bases
+            .iter()
+            .map(|_| Prover::generate_random_nonce())
+            .collect();
+        let t = nonces
+            .iter()
+            .zip(bases)
+            .fold(PointJacobi::zero(), |acc, (r, g)| acc.add(&g.mul_secret(r)));
+        let c = challenge(sid, pid, bases, y, &t)?;
+        let s = nonces
+            .into_iter()
+            .zip(witnesses)
+            .map(|(r, x)| curve::rem_n(&(r + &c * x)))
+            .collect();
+        Ok(Self { t, s })
+    }
+
+    /// `verify` checks `Σ s_i·G_i == T + c·Y`.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `bases`: the bases `G_i`
+    /// * `y`: the combined point
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid, `ProofError::InvalidStatement` if the
+    /// number of responses does not match the bases, and
+    /// `ProofError::VerificationFailed` otherwise.
+    pub fn verify(
+        &self,
+        sid: &str,
+        pid: i32,
+        bases: &[PointJacobi],
+        y: &PointJacobi,
+    ) -> Result<(), ProofError> {
+        check_shape(self.s.len(), bases)?;
+        let c = challenge(sid, pid, bases, y, &self.t)?;
+        let lhs = linear_combination(&self.s, bases);
+        let rhs = self.t.add(&y.mul_unsafe(&c));
+        if lhs == rhs {
+            Ok(())
+        } else {
+            Err(ProofError::VerificationFailed)
+        }
+    }
+
+    /// `to_bytes` encodes the compressed `t` followed by each 32-byte `s_i`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.t.to_bytes().to_vec();
+        for s in &self.s {
+            out.extend(curve::scalar_to_bytes(s));
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() < POINT_BYTES + SCALAR_BYTES
+            || !(bytes.len() - POINT_BYTES).is_multiple_of(SCALAR_BYTES)
+        {
+            return Err(ProofError::DeserializationError(format!(
+                "invalid linear proof length {}",
+                bytes.len()
+            )));
+        }
+        let t = PointJacobi::from_bytes(&bytes[..POINT_BYTES])?;
+        let s = bytes[POINT_BYTES..]
+            .chunks(SCALAR_BYTES)
+            .map(curve::scalar_from_canonical_bytes)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { t, s })
+    }
+}
+
+/// Okamoto's proof of knowledge of a representation `Y = x_1·G_1 + x_2·G_2`:
+/// a [`LinearProof`] over two bases, with a fixed-size encoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepresentationProof {
+    pub t: PointJacobi,
+    pub s1: IBig,
+    pub s2: IBig,
+}
+
+impl RepresentationProof {
+    /// Length in bytes of an encoded proof: compressed `t`, then `s1` and
+    /// `s2`, 32 bytes each.
+    pub const BYTES: usize = POINT_BYTES + 2 * SCALAR_BYTES;
+
+    /// `prove` proves knowledge of `(x_1, x_2)` with `y = x_1·G_1 + x_2·G_2`.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `witnesses`: the secrets `x_1` and `x_2`
+    /// * `bases`: the bases `G_1` and `G_2`
+    /// * `y`: the represented point
+    ///
+    /// Returns:
+    ///
+    /// The proof, or the errors of [`LinearProof::prove`].
+    pub fn prove(
+        sid: &str,
+        pid: i32,
+        witnesses: &[IBig; 2],
+        bases: &[PointJacobi; 2],
+        y: &PointJacobi,
+    ) -> Result<Self, ProofError> {
+        let proof = LinearProof::prove(sid, pid, witnesses, bases, y)?;
+        let [s1, s2]: [IBig; 2] = proof.s.try_into().expect("two responses");
+        Ok(Self { t: proof.t, s1, s2 })
+    }
+
+    /// `verify` checks `s_1·G_1 + s_2·G_2 == T + c·Y`.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid, `ProofError::VerificationFailed` otherwise.
+    pub fn verify(
+        &self,
+        sid: &str,
+        pid: i32,
+        bases: &[PointJacobi; 2],
+        y: &PointJacobi,
+    ) -> Result<(), ProofError> {
+        let proof = LinearProof {
+            t: self.t.clone(),
+            s: vec![self.s1.clone(), self.s2.clone()],
+        };
+        proof.verify(sid, pid, bases, y)
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut out = [0u8; Self::BYTES];
+        out[..POINT_BYTES].copy_from_slice(&self.t.to_bytes());
+        out[POINT_BYTES..POINT_BYTES + SCALAR_BYTES]
+            .copy_from_slice(&curve::scalar_to_bytes(&self.s1));
+        out[POINT_BYTES + SCALAR_BYTES..].copy_from_slice(&curve::scalar_to_bytes(&self.s2));
+        out
+    }
+
+    /// `from_bytes` parses the encoding produced by [`RepresentationProof::to_bytes`],
+    /// rejecting responses that are not reduced modulo n.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != Self::BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} representation proof bytes, got {}",
+                Self::BYTES,
+                bytes.len()
+            )));
+        }
+        Ok(Self {
+            t: PointJacobi::from_bytes(&bytes[..POINT_BYTES])?,
+            s1: curve::scalar_from_canonical_bytes(
+                &bytes[POINT_BYTES..POINT_BYTES + SCALAR_BYTES],
+            )?,
+            s2: curve::scalar_from_canonical_bytes(&bytes[POINT_BYTES + SCALAR_BYTES..])?,
+        })
+    }
+}
+
+/// A single-base linear proof is an ordinary [`DLogProof`].
+impl TryFrom<LinearProof> for DLogProof {
+    type Error = ProofError;
+
+    fn try_from(proof: LinearProof) -> Result<Self, Self::Error> {
+        match <[IBig; 1]>::try_from(proof.s) {
+            Ok([s]) => Ok(DLogProof::new(proof.t, Scalar::new(&s))),
+            Err(_) => Err(ProofError::InvalidStatement(
+                "only a single-base proof is a DLogProof".to_string(),
+            )),
+        }
+    }
+}
+
+fn check_shape(scalars: usize, bases: &[PointJacobi]) -> Result<(), ProofError> {
+    if bases.is_empty() {
+        return Err(ProofError::InvalidStatement("empty basis".to_string()));
+    }
+    if scalars != bases.len() {
+        return Err(ProofError::InvalidStatement(format!(
+            "{} scalars for {} bases",
+            scalars,
+            bases.len()
+        )));
+    }
+    Ok(())
+}
+
+fn linear_combination(scalars: &[IBig], bases: &[PointJacobi]) -> PointJacobi {
+    scalars
+        .iter()
+        .zip(bases)
+        .fold(PointJacobi::zero(), |acc, (k, g)| acc.add(&g.mul_unsafe(k)))
+}
+
+fn challenge(
+    sid: &str,
+    pid: i32,
+    bases: &[PointJacobi],
+    y: &PointJacobi,
+    t: &PointJacobi,
+) -> Result<IBig, ProofError> {
+    let mut points: Vec<&PointJacobi> = bases.iter().collect();
+    points.extend([y, t]);
+    hash_tagged(DLOG_TAG, sid, pid, &points, &[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commitment;
+    use crate::verifier::Verifier;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Random witnesses for `bases` and the point they represent.
+    fn statement(seed: u64, bases: &[PointJacobi]) -> (Vec<IBig>, PointJacobi) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let witnesses: Vec<IBig> = bases
+            .iter()
+            .map(|_| curve::random_scalar(&mut rng))
+            .collect();
+        let y = linear_combination(&witnesses, bases);
+        (witnesses, y)
+    }
+
+    fn pedersen_bases() -> [PointJacobi; 2] {
+        [PointJacobi::generator(), commitment::h()]
+    }
+
+    #[test]
+    fn a_single_base_proof_is_a_dlog_proof() {
+        let g = PointJacobi::generator();
+        let (witnesses, y) = statement(845, std::slice::from_ref(&g));
+        let proof =
+            LinearProof::prove("linear", 1, &witnesses, std::slice::from_ref(&g), &y).unwrap();
+        assert_eq!(
+            proof.verify("linear", 1, std::slice::from_ref(&g), &y),
+            Ok(())
+        );
+        let dlog = DLogProof::try_from(proof).unwrap();
+        assert_eq!(Verifier::verify(&dlog, "linear", 1, &y, &g), Ok(()));
+        assert!(Verifier::verify(&dlog, "linear", 2, &y, &g).is_err());
+
+        let bases = pedersen_bases();
+        let (witnesses, y) = statement(845, &bases);
+        let two = LinearProof::prove("linear", 1, &witnesses, &bases, &y).unwrap();
+        assert!(matches!(
+            DLogProof::try_from(two),
+            Err(ProofError::InvalidStatement(_))
+        ));
+    }
+
+    #[test]
+    fn a_pedersen_opening_verifies_only_under_its_bases() {
+        let bases = pedersen_bases();
+        let (witnesses, y) = statement(845, &bases);
+        let proof = LinearProof::prove("linear", 1, &witnesses, &bases, &y).unwrap();
+        assert_eq!(proof.verify("linear", 1, &bases, &y), Ok(()));
+        let c = commitment::PedersenCommitment::commit(&witnesses[0], &witnesses[1]);
+        assert_eq!(c.c, y);
+
+        let swapped = [bases[1].clone(), bases[0].clone()];
+        assert_eq!(
+            proof.verify("linear", 1, &swapped, &y),
+            Err(ProofError::VerificationFailed)
+        );
+        assert_eq!(
+            proof.verify("linear", 2, &bases, &y),
+            Err(ProofError::VerificationFailed)
+        );
+        let other = y.add(&PointJacobi::generator());
+        assert_eq!(
+            proof.verify("linear", 1, &bases, &other),
+            Err(ProofError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn an_empty_basis_or_mismatched_lengths_are_invalid() {
+        let bases = pedersen_bases();
+        let (witnesses, y) = statement(845, &bases);
+        assert!(matches!(
+            LinearProof::prove("linear", 1, &[], &[], &y),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        assert!(matches!(
+            LinearProof::prove("linear", 1, &witnesses[..1], &bases, &y),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        let proof = LinearProof::prove("linear", 1, &witnesses, &bases, &y).unwrap();
+        assert!(matches!(
+            proof.verify("linear", 1, &bases[..1], &y),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        assert!(matches!(
+            proof.verify("linear", 1, &[], &y),
+            Err(ProofError::InvalidStatement(_))
+        ));
+    }
+
+    #[test]
+    fn linear_proofs_round_trip_through_bytes() {
+        let bases = [
+            PointJacobi::generator(),
+            commitment::h(),
+            commitment::nums_point(b"dlogproof/test/linear"),
+        ];
+        let (witnesses, y) = statement(845, &bases);
+        let proof = LinearProof::prove("linear", 1, &witnesses, &bases, &y).unwrap();
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes.len(), POINT_BYTES + 3 * SCALAR_BYTES);
+        let decoded = LinearProof::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, proof);
+        assert_eq!(decoded.verify("linear", 1, &bases, &y), Ok(()));
+        for len in [POINT_BYTES, bytes.len() - 1] {
+            assert!(matches!(
+                LinearProof::from_bytes(&bytes[..len]),
+                Err(ProofError::DeserializationError(_))
+            ));
+        }
+        let mut unreduced = bytes.clone();
+        unreduced[POINT_BYTES..POINT_BYTES + SCALAR_BYTES].fill(0xff);
+        assert!(LinearProof::from_bytes(&unreduced).is_err());
+    }
+
+    #[test]
+    fn representation_proofs_match_two_base_linear_proofs() {
+        let bases = pedersen_bases();
+        let (witnesses, y) = statement(1025, &bases);
+        let witnesses: [IBig; 2] = witnesses.try_into().unwrap();
+        let proof = RepresentationProof::prove("repr", 1, &witnesses, &bases, &y).unwrap();
+        assert_eq!(proof.verify("repr", 1, &bases, &y), Ok(()));
+        let linear = LinearProof {
+            t: proof.t.clone(),
+            s: vec![proof.s1.clone(), proof.s2.clone()],
+        };
+        assert_eq!(linear.verify("repr", 1, &bases, &y), Ok(()));
+
+        let swapped = [bases[1].clone(), bases[0].clone()];
+        assert_eq!(
+            proof.verify("repr", 1, &swapped, &y),
+            Err(ProofError::VerificationFailed)
+        );
+        assert_eq!(
+            proof.verify("repr", 2, &bases, &y),
+            Err(ProofError::VerificationFailed)
+        );
+        let mut tampered = proof.clone();
+        tampered.s2 = curve::rem_n(&(&tampered.s2 + 1));
+        assert_eq!(
+            tampered.verify("repr", 1, &bases, &y),
+            Err(ProofError::VerificationFailed)
+        );
+
+        let bytes = proof.to_bytes();
+        assert_eq!(RepresentationProof::from_bytes(&bytes), Ok(proof));
+        assert!(matches!(
+            RepresentationProof::from_bytes(&bytes[1..]),
+            Err(ProofError::DeserializationError(_))
+        ));
+    }
+}
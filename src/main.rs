@@ -1,141 +1,484 @@
-mod JacobiPoint;
-use crate::JacobiPoint::{Point, PointJacobi};
-use ibig::IBig;
-use rand::Rng;
-use sha256::digest;
-
-fn generate_random_number() -> i32 {
-    let mut rng = rand::thread_rng();
-    rng.gen_range(1..50)
-}
-
-struct DLogProof {
-    t: PointJacobi,
-    s: IBig,
-}
-
-impl DLogProof {
-    /*
-       Non-interactive Schnorr ZK DLOG Proof scheme with a Fiat-Shamir transformation
-       ";
-
-    */
-
-    /// `hash_points` takes a string, an integer, and a vector of points, and returns a big integer
-    ///
-    /// Arguments:
-    ///
-    /// * `sid`: the id of the signature
-    /// * `pid`: the id of the point
-    /// * `points`: The points that are being hashed.
-    ///
-    /// Returns:
-    ///
-    /// A hash of the points.
-    fn hash_points(sid: &str, pid: i32, points: Vec<PointJacobi>) -> IBig {
-        let mut point_feild = vec![];
-        point_feild.extend(sid.as_bytes());
-        point_feild.extend(IBig::from(pid).to_string().as_bytes());
-        for point in points {
-            point_feild.extend(point.as_bytes());
+//! `dlogproof`: generate keys, and make and check proofs of knowledge of a
+//! secret key, over secp256k1 and the generator `G`.
+//!
+//! ```text
+//! dlogproof keygen --secret-file key.sec --passphrase-file pass.txt --public-key-file key.pub
+//! dlogproof prove --secret-file key.sec --passphrase-file pass.txt --sid SID --pid 1 --proof-file proof.bin
+//! dlogproof verify --proof-file proof.bin --sid SID --pid 1 --public-key 02…
+//! ```
+//!
+//! Secret key files are created readable by their owner only and hold the
+//! key encrypted under the passphrase of `--passphrase-file`, its first line,
+//! in the format of `SecretKey::to_encrypted_bytes`. Keys stored as plain hex
+//! need `--insecure-plaintext` instead, to write them and to read them back.
+//! Public keys are 33-byte compressed points as hex.
+//! Proofs are read and written in the canonical binary format of
+//! `DLogProof::to_versioned_bytes` or, with `--format json`, as
+//! `DLogProof::to_json`. The exit code is 0 on success, 1 if a proof does not
+//! verify and 2 for bad arguments, unreadable files or malformed input.
+
+use clap::{Parser, Subcommand, ValueEnum};
+use dlogproof::{DLogProof, ProofError, Prover, PublicKey, SecretKey, Verifier};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use zeroize::Zeroizing;
+
+#[derive(Parser)]
+#[command(
+    version,
+    about = "Non-interactive Schnorr proofs of knowledge of a discrete logarithm"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate a secret key and print its public key.
+    Keygen {
+        /// Where to write the secret key; an existing file is never overwritten.
+        #[arg(long)]
+        secret_file: PathBuf,
+        /// Encrypt the secret key under the passphrase in this file.
+        #[arg(long, required_unless_present = "insecure_plaintext")]
+        passphrase_file: Option<PathBuf>,
+        /// Write the secret key unencrypted, as hex.
+        #[arg(long, conflicts_with = "passphrase_file")]
+        insecure_plaintext: bool,
+        /// Also write the public key to this file.
+        #[arg(long)]
+        public_key_file: Option<PathBuf>,
+    },
+    /// Prove knowledge of a secret key in a session.
+    Prove {
+        #[arg(long)]
+        secret_file: PathBuf,
+        /// Decrypt the secret key with the passphrase in this file.
+        #[arg(long, required_unless_present = "insecure_plaintext")]
+        passphrase_file: Option<PathBuf>,
+        /// Read an unencrypted hex secret key.
+        #[arg(long, conflicts_with = "passphrase_file")]
+        insecure_plaintext: bool,
+        /// Session id the proof is bound to.
+        #[arg(long)]
+        sid: String,
+        /// Participant id the proof is bound to.
+        #[arg(long, allow_negative_numbers = true)]
+        pid: i32,
+        /// Where to write the proof; standard output if omitted.
+        #[arg(long)]
+        proof_file: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = Format::Binary)]
+        format: Format,
+    },
+    /// Check a proof against a public key.
+    Verify {
+        /// The proof to check; standard input if omitted.
+        #[arg(long)]
+        proof_file: Option<PathBuf>,
+        #[arg(long)]
+        sid: String,
+        #[arg(long, allow_negative_numbers = true)]
+        pid: i32,
+        /// Compressed public key as hex.
+        #[arg(long)]
+        public_key: String,
+        #[arg(long, value_enum, default_value_t = Format::Binary)]
+        format: Format,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// Version byte, compressed `t` and `s`: 66 bytes.
+    Binary,
+    /// `{"t": "0x…", "s": "0x…", "version": 1}`.
+    Json,
+}
+
+/// Why a command failed, and so which exit code it ends with.
+enum Failure {
+    Invalid,
+    Error(String),
+}
+
+impl From<ProofError> for Failure {
+    fn from(err: ProofError) -> Self {
+        match err {
+            ProofError::VerificationFailed => Failure::Invalid,
+            err => Failure::Error(err.to_string()),
         }
-        let digest = digest(&point_feild[..]);
-        IBig::from_str_radix(&digest, 32).unwrap()
     }
+}
 
-    /// > The prover generates a random number `r`, computes `t = r*G` and `c = H(sid, pid, G, y, t)`,
-    /// and then computes `s = r + c*x` and returns the proof `(t, s)`
-    ///
-    /// Arguments:
-    ///
-    /// * `sid`: the signature id
-    /// * `pid`: the participant id
-    /// * `x`: the secret number
-    /// * `y`: the point that we want to prove that we know the discrete logarithm of
-    /// * `base_point`: The base point of the group.
-    ///
-    /// Returns:
-    ///
-    /// A DLogProof struct containing the t and s values.
-    fn prove(sid: &str, pid: i32, x: i32, y: PointJacobi, base_point: PointJacobi) -> DLogProof {
-        "y = x*G";
-        let r = generate_random_number();
-        let t = base_point.mul_unsafe(&IBig::from(r));
-        let c = DLogProof::hash_points(sid, pid, vec![base_point, y, t.clone()]);
-        let curve_order: IBig = IBig::from_str_with_radix_prefix(
-            "115792089237316195423570985008687907852837564279074904382605163141518161494337",
-        )
-        .unwrap();
-        let s = (r + (c * x)) % curve_order;
-        return DLogProof { t, s };
-    }
+fn main() -> ExitCode {
+    ExitCode::from(exit_code(run(Cli::parse().command)))
+}
 
-    /// > The function verifies that the point `t` is the sum of the base point multiplied by `s` and
-    /// the point `y` multiplied by the hash of the inputs
-    ///
-    /// Arguments:
-    ///
-    /// * `sid`: the session id
-    /// * `pid`: the id of the prover
-    /// * `y`: the public key
-    /// * `base_point`: the base point of the group
-    ///
-    /// Returns:
-    ///
-    /// a boolean value.
-    fn verify(&self, sid: &str, pid: i32, y: PointJacobi, base_point: PointJacobi) -> bool {
-        let c = DLogProof::hash_points(
+/// Runs `command`; `passphrase_file` is `None` only with
+/// `--insecure-plaintext`, which clap enforces.
+fn run(command: Command) -> Result<(), Failure> {
+    match command {
+        Command::Keygen {
+            secret_file,
+            passphrase_file,
+            public_key_file,
+            ..
+        } => keygen(
+            &secret_file,
+            passphrase_file.as_deref(),
+            public_key_file.as_deref(),
+        ),
+        Command::Prove {
+            secret_file,
+            passphrase_file,
             sid,
             pid,
-            vec![base_point.clone(), y.clone(), self.t.clone()],
-        );
-        let lhs = base_point.mul_unsafe(&self.s);
-        let rhs = self.t.add(&y.mul_unsafe(&c));
-        lhs == rhs
+            proof_file,
+            format,
+            ..
+        } => prove(
+            &secret_file,
+            passphrase_file.as_deref(),
+            &sid,
+            pid,
+            proof_file.as_deref(),
+            format,
+        ),
+        Command::Verify {
+            proof_file,
+            sid,
+            pid,
+            public_key,
+            format,
+        } => verify(proof_file.as_deref(), &sid, pid, &public_key, format),
+    }
+}
+
+/// Reports a failure on standard error and returns the exit code for `result`.
+fn exit_code(result: Result<(), Failure>) -> u8 {
+    match result {
+        Ok(()) => 0,
+        Err(Failure::Invalid) => {
+            eprintln!("proof is not valid");
+            1
+        }
+        Err(Failure::Error(msg)) => {
+            eprintln!("error: {}", msg);
+            2
+        }
+    }
+}
+
+fn keygen(
+    secret_file: &Path,
+    passphrase_file: Option<&Path>,
+    public_key_file: Option<&Path>,
+) -> Result<(), Failure> {
+    let key = SecretKey::random(&mut rand::rngs::OsRng);
+    let public_key = key.public_key();
+    let contents = match passphrase_file {
+        Some(path) => key.to_encrypted_bytes(&read_passphrase(path)?),
+        None => format!("{}\n", encode_hex(&key.to_bytes())).into_bytes(),
+    };
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(secret_file)
+        .map_err(|e| io_error(secret_file, e))?;
+    file.write_all(&Zeroizing::new(contents))
+        .map_err(|e| io_error(secret_file, e))?;
+    if let Some(path) = public_key_file {
+        fs::write(path, format!("{}\n", public_key)).map_err(|e| io_error(path, e))?;
+    }
+    println!("{}", public_key);
+    Ok(())
+}
+
+fn prove(
+    secret_file: &Path,
+    passphrase_file: Option<&Path>,
+    sid: &str,
+    pid: i32,
+    proof_file: Option<&Path>,
+    format: Format,
+) -> Result<(), Failure> {
+    let contents = Zeroizing::new(fs::read(secret_file).map_err(|e| io_error(secret_file, e))?);
+    let key = match passphrase_file {
+        Some(path) => SecretKey::from_encrypted_bytes(&contents, &read_passphrase(path)?)?,
+        None => {
+            let bytes = std::str::from_utf8(&contents)
+                .ok()
+                .and_then(|hex| decode_hex(hex.trim()))
+                .map(Zeroizing::new)
+                .ok_or_else(|| {
+                    Failure::Error(format!("{}: not a hex secret key", secret_file.display()))
+                })?;
+            SecretKey::from_bytes(&bytes)?
+        }
+    };
+    let proof = Prover::prove_with_key(sid, pid, &key, &key.public_key())?;
+    let encoded = match format {
+        Format::Binary => proof.to_versioned_bytes().to_vec(),
+        Format::Json => format!("{}\n", proof.to_json()).into_bytes(),
+    };
+    match proof_file {
+        Some(path) => fs::write(path, encoded).map_err(|e| io_error(path, e)),
+        None => io::stdout()
+            .write_all(&encoded)
+            .map_err(|e| Failure::Error(format!("standard output: {}", e))),
+    }
+}
+
+fn verify(
+    proof_file: Option<&Path>,
+    sid: &str,
+    pid: i32,
+    public_key: &str,
+    format: Format,
+) -> Result<(), Failure> {
+    let public_key = decode_hex(public_key.trim())
+        .ok_or_else(|| Failure::Error("public key is not hex".to_string()))?;
+    let public_key = PublicKey::from_bytes(&public_key)?;
+    let encoded = match proof_file {
+        Some(path) => fs::read(path).map_err(|e| io_error(path, e))?,
+        None => {
+            let mut encoded = Vec::new();
+            io::stdin()
+                .read_to_end(&mut encoded)
+                .map_err(|e| Failure::Error(format!("standard input: {}", e)))?;
+            encoded
+        }
+    };
+    let proof = match format {
+        Format::Binary => DLogProof::from_versioned_bytes(&encoded)?,
+        Format::Json => {
+            let json = std::str::from_utf8(&encoded)
+                .map_err(|_| Failure::Error("proof JSON is not UTF-8".to_string()))?;
+            DLogProof::from_json(json.trim())?
+        }
+    };
+    Verifier::verify_with_key(&proof, sid, pid, &public_key)?;
+    println!("proof is valid");
+    Ok(())
+}
+
+/// The first line of `path`, without its line ending.
+fn read_passphrase(path: &Path) -> Result<Zeroizing<String>, Failure> {
+    let contents = Zeroizing::new(fs::read_to_string(path).map_err(|e| io_error(path, e))?);
+    let line = contents.lines().next().unwrap_or("");
+    Ok(Zeroizing::new(line.to_string()))
+}
+
+fn io_error(path: &Path, err: io::Error) -> Failure {
+    Failure::Error(format!("{}: {}", path.display(), err))
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
     }
+    s.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let hi = char::from(pair[0]).to_digit(16)?;
+            let lo = char::from(pair[1]).to_digit(16)?;
+            Some((hi * 16 + lo) as u8)
+        })
+        .collect()
 }
 
-fn main() {
-    let sid = "sid";
-    let pid = 1;
-    let x = generate_random_number();
-    let private_key = IBig::from_str_radix(
-        "20775598474904240222758871485654738649026525153462921990999819694398496339603",
-        10,
-    )
-    .unwrap();
-
-    let base_point = Point::generator();
-    let mut point = base_point.clone();
-    for _i in 1..25 {
-        point = point.mul(&private_key);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::error::ErrorKind;
+
+    /// A fresh directory under the system temporary directory.
+    fn scratch(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("dlogproof-cli-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn parse(args: &[&str]) -> Result<Command, clap::Error> {
+        Cli::try_parse_from(std::iter::once("dlogproof").chain(args.iter().copied()))
+            .map(|cli| cli.command)
+    }
+
+    /// The exit code of the command line `args`.
+    fn status(args: &[&str]) -> u8 {
+        exit_code(run(parse(args).expect("valid arguments")))
     }
-    let start_proof = std::time::Instant::now();
-    let dlog_proof = DLogProof::prove(
-        sid,
-        pid,
-        x as i32,
-        PointJacobi::from_affine(point.clone()),
-        PointJacobi::from_affine(base_point.clone()),
-    );
-    println!(
-        "Proof computation time {:?} nanos_secs",
-        start_proof.elapsed().as_nanos()
-    );
-    println!("Proof {:?} ", dlog_proof.s);
-    let result = dlog_proof.verify(
-        sid,
-        pid,
-        PointJacobi::from_affine(point),
-        PointJacobi::from_affine(base_point),
-    );
-    println!(
-        "Verify computation time: {:?} nano_secs",
-        start_proof.elapsed().as_nanos()
-    );
-    if result {
-        println!("{:?} ", "DLOG proof is correct");
-    } else {
-        println!("{:?} ", "DLOG proof is not correct");
+
+    #[test]
+    fn decode_hex_accepts_only_whole_hex_bytes() {
+        assert_eq!(decode_hex(""), Some(vec![]));
+        assert_eq!(decode_hex("00ffA5"), Some(vec![0x00, 0xff, 0xa5]));
+        assert_eq!(decode_hex(&encode_hex(&[1, 2, 254])), Some(vec![1, 2, 254]));
+        assert_eq!(decode_hex("abc"), None);
+        assert_eq!(decode_hex("zz"), None);
+        assert_eq!(decode_hex("+1"), None);
+        assert_eq!(decode_hex("é0"), None);
+    }
+
+    #[test]
+    fn only_an_invalid_proof_maps_to_exit_code_1() {
+        assert!(matches!(
+            Failure::from(ProofError::VerificationFailed),
+            Failure::Invalid
+        ));
+        for err in [
+            ProofError::InvalidPoint,
+            ProofError::DecryptionFailed,
+            ProofError::ScalarOutOfRange,
+            ProofError::DeserializationError("bad".to_string()),
+        ] {
+            let message = err.to_string();
+            assert!(matches!(Failure::from(err), Failure::Error(m) if m == message));
+        }
+        assert_eq!(exit_code(Ok(())), 0);
+        assert_eq!(exit_code(Err(Failure::Invalid)), 1);
+        assert_eq!(exit_code(Err(Failure::Error("bad".to_string()))), 2);
+    }
+
+    #[test]
+    fn plaintext_keys_need_an_explicit_flag() {
+        let err = parse(&["keygen", "--secret-file", "k"]).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+        let err = parse(&[
+            "keygen",
+            "--secret-file",
+            "k",
+            "--passphrase-file",
+            "p",
+            "--insecure-plaintext",
+        ])
+        .err()
+        .unwrap();
+        assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+        let err = parse(&["prove", "--secret-file", "k", "--sid", "s", "--pid", "1"])
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn exit_codes_of_keygen_prove_and_verify() {
+        let dir = scratch("exit-codes");
+        let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+        let (secret, public, proof) = (path("key.sec"), path("key.pub"), path("proof.bin"));
+        let (passphrase, wrong) = (path("pass.txt"), path("wrong.txt"));
+        fs::write(&passphrase, "correct horse\n").unwrap();
+        fs::write(&wrong, "correct horsf\n").unwrap();
+
+        let keygen = [
+            "keygen",
+            "--secret-file",
+            &secret,
+            "--passphrase-file",
+            &passphrase,
+            "--public-key-file",
+            &public,
+        ];
+        assert_eq!(status(&keygen), 0);
+        // An existing key file is never overwritten.
+        assert_eq!(status(&keygen), 2);
+        let public_key = fs::read_to_string(&public).unwrap();
+        let public_key = public_key.trim();
+
+        let prove = |passphrase: &str| {
+            status(&[
+                "prove",
+                "--secret-file",
+                &secret,
+                "--passphrase-file",
+                passphrase,
+                "--sid",
+                "session",
+                "--pid",
+                "7",
+                "--proof-file",
+                &proof,
+            ])
+        };
+        assert_eq!(prove(&wrong), 2);
+        assert_eq!(prove(&passphrase), 0);
+
+        let verify = |pid: &str, public_key: &str| {
+            status(&[
+                "verify",
+                "--proof-file",
+                &proof,
+                "--sid",
+                "session",
+                "--pid",
+                pid,
+                "--public-key",
+                public_key,
+            ])
+        };
+        assert_eq!(verify("7", public_key), 0);
+        assert_eq!(verify("8", public_key), 1);
+        assert_eq!(verify("7", "02zz"), 2);
+        fs::write(&proof, [0u8; 3]).unwrap();
+        assert_eq!(verify("7", public_key), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn insecure_plaintext_keys_round_trip() {
+        let dir = scratch("plaintext");
+        let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+        let (secret, proof) = (path("key.sec"), path("proof.json"));
+        assert_eq!(
+            status(&["keygen", "--secret-file", &secret, "--insecure-plaintext"]),
+            0
+        );
+        let key = SecretKey::from_bytes(
+            &decode_hex(fs::read_to_string(&secret).unwrap().trim()).unwrap(),
+        )
+        .unwrap();
+        let prove = [
+            "prove",
+            "--secret-file",
+            &secret,
+            "--insecure-plaintext",
+            "--sid",
+            "s",
+            "--pid",
+            "-1",
+            "--proof-file",
+            &proof,
+            "--format",
+            "json",
+        ];
+        assert_eq!(status(&prove), 0);
+        let public_key = key.public_key().to_string();
+        let verify = [
+            "verify",
+            "--proof-file",
+            &proof,
+            "--sid",
+            "s",
+            "--pid",
+            "-1",
+            "--public-key",
+            &public_key,
+            "--format",
+            "json",
+        ];
+        assert_eq!(status(&verify), 0);
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
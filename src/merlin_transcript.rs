@@ -0,0 +1,78 @@
+//! Fiat-Shamir challenges drawn from a [`merlin::Transcript`], behind the
+//! `merlin` feature.
+//!
+//! Instead of hashing the framed transcript of [`crate::hash`], the prover and
+//! verifier append the statement to a caller-supplied merlin transcript with
+//! labeled, length-prefixed messages and squeeze the challenge from it. A
+//! proof can then be composed with other merlin-based protocols: whatever was
+//! appended before, such as an outer protocol's messages, is bound into the
+//! challenge, and the transcript carries the proof's messages forward. The
+//! messages are
+//!
+//! ```text
+//! dom-sep: "dlogproof-v1" | sid: sid (UTF-8 bytes) | pid: i32 BE
+//!   | G | Y | T (33-byte compressed points) | c: 64 challenge bytes
+//! ```
+//!
+//! and the 64 challenge bytes, read as a big-endian integer, are reduced
+//! modulo n. Prove with [`Prover::prove_with_transcript`] and check with
+//! [`Verifier::verify_with_transcript`] from transcripts in the same state.
+//!
+//! [`Prover::prove_with_transcript`]: crate::Prover::prove_with_transcript
+//! [`Verifier::verify_with_transcript`]: crate::Verifier::verify_with_transcript
+
+use crate::curve::Curve;
+use crate::jacobi_point::PointJacobi;
+use ibig::{IBig, UBig};
+use merlin::Transcript;
+
+/// Domain separator appended before every statement.
+pub const DOMAIN_SEPARATOR: &[u8] = b"dlogproof-v1";
+
+/// Labeled appends of this crate's statements to a merlin transcript.
+pub trait TranscriptProtocol {
+    /// `dlog_proof_domain_sep` starts a statement for session `sid` and
+    /// participant `pid`.
+    fn dlog_proof_domain_sep(&mut self, sid: &str, pid: i32);
+
+    /// `append_point` appends the compressed encoding of `point`.
+    fn append_point<C: Curve>(&mut self, label: &'static [u8], point: &PointJacobi<C>);
+
+    /// `challenge_scalar` squeezes 64 bytes and reduces them modulo n.
+    fn challenge_scalar<C: Curve>(&mut self, label: &'static [u8]) -> IBig;
+}
+
+impl TranscriptProtocol for Transcript {
+    fn dlog_proof_domain_sep(&mut self, sid: &str, pid: i32) {
+        self.append_message(b"dom-sep", DOMAIN_SEPARATOR);
+        self.append_message(b"sid", sid.as_bytes());
+        self.append_message(b"pid", &pid.to_be_bytes());
+    }
+
+    fn append_point<C: Curve>(&mut self, label: &'static [u8], point: &PointJacobi<C>) {
+        self.append_message(label, &point.to_bytes());
+    }
+
+    fn challenge_scalar<C: Curve>(&mut self, label: &'static [u8]) -> IBig {
+        let mut bytes = [0u8; 64];
+        self.challenge_bytes(label, &mut bytes);
+        C::rem_n(&UBig::from_be_bytes(&bytes).into())
+    }
+}
+
+/// `challenge` appends the statement `(sid, pid, G, y, t)` to `transcript`
+/// and returns the challenge for it.
+pub fn challenge<C: Curve>(
+    transcript: &mut Transcript,
+    sid: &str,
+    pid: i32,
+    base_point: &PointJacobi<C>,
+    y: &PointJacobi<C>,
+    t: &PointJacobi<C>,
+) -> IBig {
+    transcript.dlog_proof_domain_sep(sid, pid);
+    transcript.append_point(b"G", base_point);
+    transcript.append_point(b"Y", y);
+    transcript.append_point(b"T", t);
+    transcript.challenge_scalar::<C>(b"c")
+}
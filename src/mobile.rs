@@ -0,0 +1,292 @@
+//! UniFFI interface for the Kotlin and Swift bindings.
+//!
+//! Every value crosses the boundary in its canonical byte encoding: secrets
+//! as 32-byte big-endian scalars, public keys as 33-byte compressed points
+//! and proofs as [`DLogProof::to_bytes`]. Proofs are over the generator `G`.
+//! Panics are caught here and surfaced as [`DlogError::Internal`].
+//!
+//! The interface is declared with UniFFI's proc macros, so there is no UDL
+//! file. Build the shared library and generate the bindings from it, with
+//! the package and module names of `uniffi.toml`:
+//!
+//! ```text
+//! cargo rustc --release --lib --features uniffi --crate-type cdylib
+//! cargo run --features uniffi-bindgen --bin uniffi-bindgen -- generate \
+//!     --library target/release/libdlogproof.so --language kotlin --out-dir out
+//! cargo run --features uniffi-bindgen --bin uniffi-bindgen -- generate \
+//!     --library target/release/libdlogproof.so --language swift --out-dir out
+//! ```
+//!
+//! For Android, build the library per ABI with the NDK (e.g. through
+//! cargo-ndk) and package it as `jniLibs`; for iOS, build a static library
+//! per target with `--crate-type staticlib` and wrap it in an XCFramework
+//! together with the generated `dlogproofFFI.h` and modulemap.
+
+use crate::curve::{self, SCALAR_BYTES};
+use crate::error::ProofError;
+use crate::jacobi_point::PointJacobi;
+use crate::proof::DLogProof;
+use crate::prover::Prover;
+use crate::verifier::Verifier;
+use ibig::IBig;
+use std::fmt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Mirror of [`ProofError`] for foreign callers, plus the failures that can
+/// only happen at the boundary.
+#[derive(Debug, uniffi::Error)]
+#[uniffi(flat_error)]
+pub enum DlogError {
+    DeserializationError(String),
+    VerificationFailed,
+    Expired,
+    NotYetValid,
+    InvalidShare(u32),
+    DuplicateShareIndex(u32),
+    InsufficientShares,
+    InvalidStatement(String),
+    DecryptionFailed,
+    ScalarOutOfRange,
+    IdentityCommitment,
+    PointNotOnCurve,
+    InvalidSecret,
+    InvalidPoint,
+    InvalidScalar,
+    /// An argument is out of range, e.g. a zero secret or a pid above `i32::MAX`.
+    InvalidInput(String),
+    /// The library panicked; the message is the panic payload if it was a string.
+    Internal(String),
+}
+
+impl From<ProofError> for DlogError {
+    fn from(err: ProofError) -> Self {
+        match err {
+            ProofError::DeserializationError(msg) => DlogError::DeserializationError(msg),
+            ProofError::VerificationFailed => DlogError::VerificationFailed,
+            ProofError::Expired => DlogError::Expired,
+            ProofError::NotYetValid => DlogError::NotYetValid,
+            ProofError::InvalidShare(index) => DlogError::InvalidShare(index),
+            ProofError::DuplicateShareIndex(index) => DlogError::DuplicateShareIndex(index),
+            ProofError::InsufficientShares => DlogError::InsufficientShares,
+            ProofError::InvalidStatement(msg) => DlogError::InvalidStatement(msg),
+            ProofError::DecryptionFailed => DlogError::DecryptionFailed,
+            ProofError::ScalarOutOfRange => DlogError::ScalarOutOfRange,
+            ProofError::IdentityCommitment => DlogError::IdentityCommitment,
+            ProofError::PointNotOnCurve => DlogError::PointNotOnCurve,
+            ProofError::InvalidSecret => DlogError::InvalidSecret,
+            ProofError::InvalidPoint => DlogError::InvalidPoint,
+            ProofError::InvalidScalar => DlogError::InvalidScalar,
+        }
+    }
+}
+
+impl fmt::Display for DlogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DlogError::InvalidInput(msg) => write!(f, "invalid input: {}", msg),
+            DlogError::Internal(msg) => write!(f, "internal error: {}", msg),
+            DlogError::DeserializationError(msg) => {
+                ProofError::DeserializationError(msg.clone()).fmt(f)
+            }
+            DlogError::VerificationFailed => ProofError::VerificationFailed.fmt(f),
+            DlogError::Expired => ProofError::Expired.fmt(f),
+            DlogError::NotYetValid => ProofError::NotYetValid.fmt(f),
+            DlogError::InvalidShare(index) => ProofError::InvalidShare(*index).fmt(f),
+            DlogError::DuplicateShareIndex(index) => ProofError::DuplicateShareIndex(*index).fmt(f),
+            DlogError::InsufficientShares => ProofError::InsufficientShares.fmt(f),
+            DlogError::InvalidStatement(msg) => ProofError::InvalidStatement(msg.clone()).fmt(f),
+            DlogError::DecryptionFailed => ProofError::DecryptionFailed.fmt(f),
+            DlogError::ScalarOutOfRange => ProofError::ScalarOutOfRange.fmt(f),
+            DlogError::IdentityCommitment => ProofError::IdentityCommitment.fmt(f),
+            DlogError::PointNotOnCurve => ProofError::PointNotOnCurve.fmt(f),
+            DlogError::InvalidSecret => ProofError::InvalidSecret.fmt(f),
+            DlogError::InvalidPoint => ProofError::InvalidPoint.fmt(f),
+            DlogError::InvalidScalar => ProofError::InvalidScalar.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for DlogError {}
+
+#[derive(Debug, uniffi::Record)]
+pub struct Keypair {
+    pub secret: Vec<u8>,
+    pub public_key: Vec<u8>,
+}
+
+/// Generates a random secret and its public key `secret·G`.
+#[uniffi::export]
+pub fn generate_keypair() -> Result<Keypair, DlogError> {
+    guard(|| {
+        let secret = curve::random_scalar(&mut rand::rngs::OsRng);
+        let public_key: PointJacobi = PointJacobi::mul_generator(&secret);
+        Ok(Keypair {
+            secret: curve::scalar_to_bytes(&secret).to_vec(),
+            public_key: public_key.to_bytes().to_vec(),
+        })
+    })
+}
+
+/// Proves knowledge of `secret` for `public_key` in session `sid` as participant `pid`.
+#[uniffi::export]
+pub fn prove(
+    sid: String,
+    pid: u32,
+    secret: Vec<u8>,
+    public_key: Vec<u8>,
+) -> Result<Vec<u8>, DlogError> {
+    guard(|| {
+        let pid = parse_pid(pid)?;
+        let secret = parse_secret(&secret)?;
+        let y: PointJacobi = PointJacobi::from_bytes(&public_key)?;
+        let proof = Prover::prove(&sid, pid, &secret, &y, &PointJacobi::generator())?;
+        Ok(proof.to_bytes().to_vec())
+    })
+}
+
+/// Returns whether `proof` is valid for `public_key`; malformed inputs are errors.
+#[uniffi::export]
+pub fn verify(
+    sid: String,
+    pid: u32,
+    public_key: Vec<u8>,
+    proof: Vec<u8>,
+) -> Result<bool, DlogError> {
+    guard(|| {
+        let pid = parse_pid(pid)?;
+        let y: PointJacobi = PointJacobi::from_bytes(&public_key)?;
+        let proof = DLogProof::from_bytes(&proof)?;
+        match Verifier::verify(&proof, &sid, pid, &y, &PointJacobi::generator()) {
+            Ok(()) => Ok(true),
+            Err(ProofError::VerificationFailed) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    })
+}
+
+fn guard<T>(f: impl FnOnce() -> Result<T, DlogError>) -> Result<T, DlogError> {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let msg = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panic".to_string());
+        Err(DlogError::Internal(msg))
+    })
+}
+
+fn parse_pid(pid: u32) -> Result<i32, DlogError> {
+    i32::try_from(pid).map_err(|_| DlogError::InvalidInput(format!("pid {} out of range", pid)))
+}
+
+fn parse_secret(bytes: &[u8]) -> Result<IBig, DlogError> {
+    let bytes: &[u8; SCALAR_BYTES] = bytes
+        .try_into()
+        .map_err(|_| DlogError::InvalidInput(format!("expected {} secret bytes", SCALAR_BYTES)))?;
+    let secret = curve::scalar_from_bytes(bytes);
+    if secret == IBig::from(0) || secret >= *curve::N {
+        return Err(DlogError::InvalidInput(
+            "secret must be in [1, n)".to_string(),
+        ));
+    }
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_keys_prove_and_verify() {
+        let keypair = generate_keypair().unwrap();
+        assert_eq!(keypair.secret.len(), SCALAR_BYTES);
+        assert_eq!(keypair.public_key.len(), 33);
+        let proof = prove(
+            "mobile".into(),
+            1,
+            keypair.secret,
+            keypair.public_key.clone(),
+        )
+        .unwrap();
+        assert_eq!(proof.len(), DLogProof::BYTES);
+        assert!(verify(
+            "mobile".into(),
+            1,
+            keypair.public_key.clone(),
+            proof.clone()
+        )
+        .unwrap());
+        assert!(!verify(
+            "mobile".into(),
+            2,
+            keypair.public_key.clone(),
+            proof.clone()
+        )
+        .unwrap());
+        assert!(!verify("other".into(), 1, keypair.public_key, proof).unwrap());
+    }
+
+    #[test]
+    fn malformed_inputs_are_errors() {
+        let keypair = generate_keypair().unwrap();
+        let prove_with = |pid: u32, secret: Vec<u8>, public_key: Vec<u8>| {
+            prove("mobile".into(), pid, secret, public_key)
+        };
+        assert!(matches!(
+            prove_with(1 << 31, keypair.secret.clone(), keypair.public_key.clone()),
+            Err(DlogError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            prove_with(1, vec![0; SCALAR_BYTES], keypair.public_key.clone()),
+            Err(DlogError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            prove_with(1, vec![0xff; SCALAR_BYTES], keypair.public_key.clone()),
+            Err(DlogError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            prove_with(1, keypair.secret[1..].to_vec(), keypair.public_key.clone()),
+            Err(DlogError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            prove_with(1, keypair.secret.clone(), keypair.public_key[1..].to_vec()),
+            Err(DlogError::DeserializationError(_))
+        ));
+        // A proof for someone else's public key is made but does not verify.
+        let other = generate_keypair().unwrap().public_key;
+        let proof = prove_with(1, keypair.secret.clone(), other.clone()).unwrap();
+        assert!(!verify("mobile".into(), 1, other, proof).unwrap());
+
+        let proof = prove_with(1, keypair.secret, keypair.public_key.clone()).unwrap();
+        assert!(matches!(
+            verify(
+                "mobile".into(),
+                1,
+                keypair.public_key.clone(),
+                proof[1..].to_vec()
+            ),
+            Err(DlogError::DeserializationError(_))
+        ));
+        assert!(matches!(
+            verify("mobile".into(), 1 << 31, keypair.public_key, proof),
+            Err(DlogError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn panics_become_internal_errors() {
+        let result: Result<(), DlogError> = guard(|| panic!("boom"));
+        assert!(matches!(result, Err(DlogError::Internal(msg)) if msg == "boom"));
+    }
+
+    #[test]
+    fn errors_display_as_the_proof_error() {
+        for err in [
+            ProofError::VerificationFailed,
+            ProofError::InvalidShare(3),
+            ProofError::InvalidStatement("statement".into()),
+        ] {
+            assert_eq!(DlogError::from(err.clone()).to_string(), err.to_string());
+        }
+    }
+}
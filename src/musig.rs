@@ -0,0 +1,414 @@
+//! Multi-party proofs in the style of MuSig2 (Nick, Ruffing and Seurin,
+//! CRYPTO '21): `n` provers, each knowing `x_i` for `Y_i = x_i·G`, jointly
+//! produce one ordinary [`DLogProof`] for an aggregate key.
+//!
+//! Keys are aggregated with per-key coefficients,
+//!
+//! ```text
+//! a_i = H("", 0, Y_1, ..., Y_n, Y_i),  Ỹ = Σ a_i·Y_i
+//! ```
+//!
+//! with the hash tagged `"dlogproof/musig/coefficient"`, so that a
+//! participant who picks its key after seeing the others cannot cancel them
+//! out (a rogue-key attack). The proof itself takes two rounds:
+//!
+//! 1. Each prover samples two nonces `r_i1`, `r_i2` and broadcasts the
+//!    [`PublicNonce`] `(R_i1, R_i2) = (r_i1·G, r_i2·G)`. This round does not
+//!    depend on the session and may be run ahead of time.
+//! 2. With `R_1 = Σ R_i1` and `R_2 = Σ R_i2`, each prover derives
+//!    `b = H(sid, pid, Ỹ, R_1, R_2)` tagged `"dlogproof/musig/nonce"`,
+//!    `R = R_1 + b·R_2` and the proof challenge `c = H(sid, pid, G, Ỹ, R)`
+//!    tagged [`DLOG_TAG`], and sends the [`PartialProof`]
+//!    `s_i = r_i1 + b·r_i2 + c·a_i·x_i`.
+//!
+//! [`combine`] checks every partial proof and outputs `(R, Σ s_i)`, which
+//! [`crate::Verifier::verify`] accepts for `Ỹ`. The second nonce is what lets
+//! the nonce round be run without a commitment round before it.
+
+use crate::curve;
+use crate::error::ProofError;
+use crate::hash::{hash_tagged, DLOG_TAG};
+use crate::jacobi_point::PointJacobi;
+use crate::proof::DLogProof;
+use crate::prover::Prover;
+use crate::scalar::Scalar;
+use crate::secret::SecretScalar;
+use ibig::IBig;
+
+const COEFFICIENT_TAG: &str = "dlogproof/musig/coefficient";
+const NONCE_TAG: &str = "dlogproof/musig/nonce";
+
+/// The participants' keys, their aggregation coefficients and the aggregate key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyAggregation {
+    pub keys: Vec<PointJacobi>,
+    pub coefficients: Vec<IBig>,
+    pub aggregate: PointJacobi,
+}
+
+impl KeyAggregation {
+    /// `new` aggregates `keys`; the order matters and must be the same for
+    /// every participant.
+    ///
+    /// Returns:
+    ///
+    /// The aggregation, `ProofError::InvalidStatement` if `keys` is empty, or
+    /// `ProofError::InvalidPoint` if a key or the aggregate is the point at
+    /// infinity.
+    pub fn new(keys: &[PointJacobi]) -> Result<Self, ProofError> {
+        if keys.is_empty() {
+            return Err(ProofError::InvalidStatement("no public keys".to_string()));
+        }
+        if keys.iter().any(|key| key.is_zero()) {
+            return Err(ProofError::InvalidPoint);
+        }
+        let coefficients: Vec<IBig> = keys
+            .iter()
+            .map(|key| {
+                let mut points: Vec<&PointJacobi> = keys.iter().collect();
+                points.push(key);
+                hash_tagged(COEFFICIENT_TAG, "", 0, &points, &[])
+            })
+            .collect::<Result<_, _>>()?;
+        let aggregate = keys
+            .iter()
+            .zip(&coefficients)
+            .fold(PointJacobi::zero(), |acc, (key, a)| {
+                acc.add(&key.mul_unsafe(a))
+            });
+        if aggregate.is_zero() {
+            return Err(ProofError::InvalidPoint);
+        }
+        Ok(Self {
+            keys: keys.to_vec(),
+            coefficients,
+            aggregate,
+        })
+    }
+}
+
+/// Round-one message: a prover's two public nonces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicNonce {
+    pub r1: PointJacobi,
+    pub r2: PointJacobi,
+}
+
+/// Round-two message: the response of the prover at `index` in the key list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialProof {
+    pub index: usize,
+    pub s: IBig,
+}
+
+/// One participant's side of the protocol.
+pub struct MusigProver {
+    index: usize,
+    secret: SecretScalar,
+    aggregation: KeyAggregation,
+    nonce: Option<([SecretScalar; 2], PublicNonce)>,
+}
+
+impl MusigProver {
+    /// `new` creates the prover for `x`, whose public key must be in
+    /// `aggregation`.
+    ///
+    /// Returns:
+    ///
+    /// The prover, `ProofError::InvalidSecret` if `x` is not in `[1, n)`, or
+    /// `ProofError::InvalidStatement` if `x·G` is not one of the keys.
+    pub fn new(x: &IBig, aggregation: &KeyAggregation) -> Result<Self, ProofError> {
+        if *x <= IBig::from(0) || *x >= *curve::N {
+            return Err(ProofError::InvalidSecret);
+        }
+        let y = PointJacobi::mul_generator(x);
+        let index = aggregation
+            .keys
+            .iter()
+            .position(|key| *key == y)
+            .ok_or_else(|| {
+                ProofError::InvalidStatement("public key is not in the aggregation".to_string())
+            })?;
+        Ok(Self {
+            index,
+            secret: SecretScalar::new(x),
+            aggregation: aggregation.clone(),
+            nonce: None,
+        })
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// `commit` samples a fresh nonce pair, replacing any unused one, and
+    /// returns it for broadcast.
+    pub fn commit(&mut self) -> PublicNonce {
+        let g = PointJacobi::generator();
+        let r1 = Prover::generate_random_nonce();
+        let r2 = Prover::generate_random_nonce();
+        let public = PublicNonce {
+            r1: g.mul_secret(&r1),
+            r2: g.mul_secret(&r2),
+        };
+        self.nonce = Some((
+            [SecretScalar::new(&r1), SecretScalar::new(&r2)],
+            public.clone(),
+        ));
+        public
+    }
+
+    /// `respond` answers the joint challenge, consuming the nonce pair.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id the joint proof is made for
+    /// * `nonces`: every prover's public nonce, in key order
+    ///
+    /// Returns:
+    ///
+    /// The partial proof, or `ProofError::InvalidStatement` if there is not
+    /// one nonce per key or this prover's current nonce is not among them.
+    pub fn respond(
+        &mut self,
+        sid: &str,
+        pid: i32,
+        nonces: &[PublicNonce],
+    ) -> Result<PartialProof, ProofError> {
+        check_nonces(&self.aggregation, nonces)?;
+        if !self
+            .nonce
+            .as_ref()
+            .is_some_and(|(_, own)| *own == nonces[self.index])
+        {
+            return Err(ProofError::InvalidStatement(format!(
+                "no current nonce from signer {}",
+                self.index
+            )));
+        }
+        let (b, c, _) = challenges(sid, pid, &self.aggregation, nonces)?;
+        let ([r1, r2], _) = self.nonce.take().expect("checked above");
+        let a = &self.aggregation.coefficients[self.index];
+        let s = curve::rem_n(&(r1.expose() + &b * r2.expose() + c * a * self.secret.expose()));
+        Ok(PartialProof {
+            index: self.index,
+            s,
+        })
+    }
+}
+
+/// `combine` checks every partial proof and assembles the proof for the
+/// aggregate key.
+///
+/// Arguments:
+///
+/// * `sid`: the session id
+/// * `pid`: the participant id the joint proof is made for
+/// * `aggregation`: the aggregated keys
+/// * `nonces`: every prover's public nonce, in key order
+/// * `partials`: the partial proofs, one per key in any order
+///
+/// Returns:
+///
+/// A proof for `aggregation.aggregate` under the generator, or
+/// `ProofError::InvalidStatement` if a nonce or partial proof is missing,
+/// repeated or does not verify.
+pub fn combine(
+    sid: &str,
+    pid: i32,
+    aggregation: &KeyAggregation,
+    nonces: &[PublicNonce],
+    partials: &[PartialProof],
+) -> Result<DLogProof, ProofError> {
+    check_nonces(aggregation, nonces)?;
+    if partials.len() != aggregation.keys.len() {
+        return Err(ProofError::InvalidStatement(format!(
+            "{} partial proofs for {} keys",
+            partials.len(),
+            aggregation.keys.len()
+        )));
+    }
+    let g = PointJacobi::generator();
+    let (b, c, r) = challenges(sid, pid, aggregation, nonces)?;
+    let mut s = IBig::from(0);
+    for (index, nonce) in nonces.iter().enumerate() {
+        let partial = partials.iter().find(|p| p.index == index).ok_or_else(|| {
+            ProofError::InvalidStatement(format!("no partial proof from signer {}", index))
+        })?;
+        let a = &aggregation.coefficients[index];
+        let expected = nonce
+            .r1
+            .add(&nonce.r2.mul_unsafe(&b))
+            .add(&aggregation.keys[index].mul_unsafe(&(&c * a)));
+        if partial.s < IBig::from(0)
+            || partial.s >= *curve::N
+            || g.mul_unsafe(&partial.s) != expected
+        {
+            return Err(ProofError::InvalidStatement(format!(
+                "invalid partial proof from signer {}",
+                index
+            )));
+        }
+        s = curve::rem_n(&(s + &partial.s));
+    }
+    Ok(DLogProof::new(r, Scalar::from_reduced(s)))
+}
+
+fn check_nonces(aggregation: &KeyAggregation, nonces: &[PublicNonce]) -> Result<(), ProofError> {
+    if nonces.len() != aggregation.keys.len() {
+        return Err(ProofError::InvalidStatement(format!(
+            "{} nonces for {} keys",
+            nonces.len(),
+            aggregation.keys.len()
+        )));
+    }
+    Ok(())
+}
+
+/// The nonce coefficient `b`, the proof challenge `c` and the joint nonce `R`.
+fn challenges(
+    sid: &str,
+    pid: i32,
+    aggregation: &KeyAggregation,
+    nonces: &[PublicNonce],
+) -> Result<(IBig, IBig, PointJacobi), ProofError> {
+    let (r1, r2) = nonces.iter().fold(
+        (PointJacobi::zero(), PointJacobi::zero()),
+        |(r1, r2), nonce| (r1.add(&nonce.r1), r2.add(&nonce.r2)),
+    );
+    let b = hash_tagged(
+        NONCE_TAG,
+        sid,
+        pid,
+        &[&aggregation.aggregate, &r1, &r2],
+        &[],
+    )?;
+    let r = r1.add(&r2.mul_unsafe(&b));
+    let c = hash_tagged(
+        DLOG_TAG,
+        sid,
+        pid,
+        &[&PointJacobi::generator(), &aggregation.aggregate, &r],
+        &[],
+    )?;
+    Ok((b, c, r))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::Verifier;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn provers(count: usize) -> (KeyAggregation, Vec<MusigProver>) {
+        let mut rng = StdRng::seed_from_u64(1034);
+        let secrets: Vec<IBig> = (0..count).map(|_| curve::random_scalar(&mut rng)).collect();
+        let keys: Vec<PointJacobi> = secrets.iter().map(PointJacobi::mul_generator).collect();
+        let aggregation = KeyAggregation::new(&keys).unwrap();
+        let provers = secrets
+            .iter()
+            .map(|x| MusigProver::new(x, &aggregation).unwrap())
+            .collect();
+        (aggregation, provers)
+    }
+
+    fn run(provers: &mut [MusigProver]) -> (Vec<PublicNonce>, Vec<PartialProof>) {
+        let nonces: Vec<PublicNonce> = provers.iter_mut().map(|p| p.commit()).collect();
+        let partials = provers
+            .iter_mut()
+            .map(|p| p.respond("musig", 3, &nonces).unwrap())
+            .collect();
+        (nonces, partials)
+    }
+
+    #[test]
+    fn n_provers_produce_a_proof_for_the_aggregate_key() {
+        for count in [1, 2, 4] {
+            let (aggregation, mut provers) = provers(count);
+            let (nonces, mut partials) = run(&mut provers);
+            partials.reverse();
+            let proof = combine("musig", 3, &aggregation, &nonces, &partials).unwrap();
+            let g = PointJacobi::generator();
+            assert_eq!(
+                Verifier::verify(&proof, "musig", 3, &aggregation.aggregate, &g),
+                Ok(())
+            );
+            assert!(Verifier::verify(&proof, "musig", 4, &aggregation.aggregate, &g).is_err());
+        }
+    }
+
+    #[test]
+    fn a_rogue_key_does_not_control_the_aggregate() {
+        let mut rng = StdRng::seed_from_u64(1034);
+        let x_1 = curve::random_scalar(&mut rng);
+        let y_1 = PointJacobi::mul_generator(&x_1);
+        // The attacker knows x' and announces Y_2 = Y' - Y_1, whose discrete
+        // log it does not know, so that Y_1 + Y_2 = Y'.
+        let x_prime = curve::random_scalar(&mut rng);
+        let y_prime = PointJacobi::mul_generator(&x_prime);
+        let y_2 = y_prime.add(&y_1.clone().negate());
+        assert_eq!(y_1.add(&y_2), y_prime);
+
+        let aggregation = KeyAggregation::new(&[y_1, y_2]).unwrap();
+        assert_ne!(aggregation.aggregate, y_prime);
+        let g = PointJacobi::generator();
+        let proof = Prover::prove("musig", 3, &x_prime, &y_prime, &g).unwrap();
+        assert!(Verifier::verify(&proof, "musig", 3, &aggregation.aggregate, &g).is_err());
+        assert!(matches!(
+            MusigProver::new(&x_prime, &aggregation),
+            Err(ProofError::InvalidStatement(_))
+        ));
+    }
+
+    #[test]
+    fn a_wrong_partial_proof_is_rejected() {
+        let (aggregation, mut provers) = provers(3);
+        let (nonces, mut partials) = run(&mut provers);
+        partials[2].s = curve::rem_n(&(&partials[2].s + 1));
+        assert!(matches!(
+            combine("musig", 3, &aggregation, &nonces, &partials),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        partials.pop();
+        assert!(matches!(
+            combine("musig", 3, &aggregation, &nonces, &partials),
+            Err(ProofError::InvalidStatement(_))
+        ));
+    }
+
+    #[test]
+    fn a_nonce_pair_answers_one_challenge() {
+        let (_, mut provers) = provers(2);
+        let nonces: Vec<PublicNonce> = provers.iter_mut().map(|p| p.commit()).collect();
+        provers[0].respond("musig", 3, &nonces).unwrap();
+        assert!(matches!(
+            provers[0].respond("musig", 4, &nonces),
+            Err(ProofError::InvalidStatement(_))
+        ));
+    }
+
+    #[test]
+    fn only_the_current_nonce_pair_is_answered() {
+        let (_, mut provers) = provers(2);
+        let stale: Vec<PublicNonce> = provers.iter_mut().map(|p| p.commit()).collect();
+        let fresh = provers[0].commit();
+        assert!(matches!(
+            provers[0].respond("musig", 3, &stale),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        let mut swapped = stale.clone();
+        swapped.swap(0, 1);
+        assert!(matches!(
+            provers[1].respond("musig", 3, &swapped),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        assert!(matches!(
+            provers[0].respond("musig", 3, &stale[..1]),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        let current = [fresh, stale[1].clone()];
+        assert!(provers[0].respond("musig", 3, &current).is_ok());
+    }
+}
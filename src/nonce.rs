@@ -0,0 +1,127 @@
+//! Deterministic nonce derivation following RFC 6979, section 3.2, with
+//! HMAC-SHA256 as the HMAC-DRBG.
+//!
+//! The nonce depends only on the secret and the message digest, so a broken
+//! or missing RNG cannot leak the secret through a repeated or biased nonce.
+
+use crate::curve::{self, SCALAR_BYTES};
+use hmac::{Hmac, Mac};
+use ibig::IBig;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn hmac(key: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for part in parts {
+        mac.update(part);
+    }
+    mac.finalize().into_bytes().into()
+}
+
+/// `rfc6979_nonce` derives the nonce for `secret` and a 32-byte message digest.
+///
+/// Arguments:
+///
+/// * `secret`: the secret scalar in `[1, n)`
+/// * `digest`: the SHA-256 digest `h1` of the message
+///
+/// Returns:
+///
+/// A nonce in `[1, n)`, the same for the same inputs.
+pub fn rfc6979_nonce(secret: &IBig, digest: &[u8; 32]) -> IBig {
+    let x = Zeroizing::new(curve::scalar_to_bytes(secret));
+    // bits2octets(h1): with qlen = hlen = 256 this is h1 reduced modulo n.
+    let h1 = curve::scalar_to_bytes(&curve::scalar_from_bytes(digest));
+    // The DRBG state determines the nonce, so it is wiped with the secret.
+    let mut v = Zeroizing::new([0x01u8; SCALAR_BYTES]);
+    let mut k = Zeroizing::new([0x00u8; SCALAR_BYTES]);
+    *k = hmac(&*k, &[&*v, &[0x00], &*x, &h1]);
+    *v = hmac(&*k, &[&*v]);
+    *k = hmac(&*k, &[&*v, &[0x01], &*x, &h1]);
+    *v = hmac(&*k, &[&*v]);
+    loop {
+        *v = hmac(&*k, &[&*v]);
+        let nonce = curve::scalar_from_bytes(&v);
+        if nonce != IBig::from(0) && nonce < *curve::N {
+            return nonce;
+        }
+        *k = hmac(&*k, &[&*v, &[0x00]]);
+        *v = hmac(&*k, &[&*v]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+    use crate::jacobi_point::PointJacobi;
+    use crate::prover::Prover;
+    use crate::verifier::Verifier;
+    use sha2::Digest;
+
+    fn scalar(digits: &str) -> IBig {
+        curve::scalar_from_bytes(&hex::decode(digits).unwrap().try_into().unwrap())
+    }
+
+    #[test]
+    fn nonces_match_published_secp256k1_sha256_vectors() {
+        // The secp256k1/SHA-256 vectors used by bitcoin-core and trezor-crypto:
+        // secret key, message, k.
+        let n_minus_one = hex::encode(&curve::scalar_to_bytes(&(&*curve::N - 1)));
+        let vectors = [
+            (
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "Satoshi Nakamoto",
+                "8f8a276c19f4149656b280621e358cce24f5f52542772691ee69063b74f15d15",
+            ),
+            (
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "All those moments will be lost in time, like tears in rain. Time to die...",
+                "38aa22d72376b4dbc472e06c3ba403ee0a394da63fc58d88686c611aba98d6b3",
+            ),
+            (
+                n_minus_one.as_str(),
+                "Satoshi Nakamoto",
+                "33a19b60e25fb6f4435af53a3d42d493644827367e6453928554f43e49aa6f90",
+            ),
+            (
+                "f8b8af8ce3c7cca5e300d33939540c10d45ce001b8f252bfbc57ba0342904181",
+                "Alan Turing",
+                "525a82b70e67874398067543fd84c83d30c175fdc45fdeee082fe13b1d7cfdf1",
+            ),
+        ];
+        for (secret, message, k) in vectors {
+            let digest: [u8; 32] = Sha256::digest(message.as_bytes()).into();
+            assert_eq!(
+                rfc6979_nonce(&scalar(secret), &digest),
+                scalar(k),
+                "{}",
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn deterministic_proofs_are_stable_and_bound_to_the_session() {
+        let g = PointJacobi::generator();
+        let x = scalar("f8b8af8ce3c7cca5e300d33939540c10d45ce001b8f252bfbc57ba0342904181");
+        let y = g.mul_unsafe(&x);
+        let proof = Prover::prove_deterministic("nonce", 1, &x, &y, &g).unwrap();
+        assert_eq!(
+            Prover::prove_deterministic("nonce", 1, &x, &y, &g).unwrap(),
+            proof
+        );
+        assert_eq!(Verifier::verify(&proof, "nonce", 1, &y, &g), Ok(()));
+        assert!(Verifier::verify(&proof, "nonce", 2, &y, &g).is_err());
+
+        let other_sid = Prover::prove_deterministic("other", 1, &x, &y, &g).unwrap();
+        let other_pid = Prover::prove_deterministic("nonce", 2, &x, &y, &g).unwrap();
+        assert_ne!(other_sid.t, proof.t);
+        assert_ne!(other_pid.t, proof.t);
+        assert_ne!(other_sid.t, other_pid.t);
+        assert_eq!(Verifier::verify(&other_sid, "other", 1, &y, &g), Ok(()));
+        assert_eq!(Verifier::verify(&other_pid, "nonce", 2, &y, &g), Ok(()));
+    }
+}
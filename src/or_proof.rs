@@ -0,0 +1,242 @@
+//! Proof of knowledge of the discrete log of `Y_1` or of `Y_2`, without
+//! revealing which (Cramer, Damgård and Schoenmakers, CRYPTO '94).
+//!
+//! For the branch it knows, the prover commits `T_i = r·G` as usual. For the
+//! other branch it picks the challenge `c_j` and response `s_j` first and
+//! solves for `T_j = s_j·G - c_j·Y_j`, a simulated transcript. The overall
+//! challenge `c = H(sid, pid, G, Y_1, Y_2, T_1, T_2)`, tagged [`TAG`], fixes
+//! `c_i = c - c_j`, so only one branch can be simulated and the other must be answered with
+//! the secret. The verifier checks `c_1 + c_2 == c` and
+//! `s_k·G == T_k + c_k·Y_k` for both branches.
+//!
+//! Only `c_1` is sent; `c_2` is recomputed as `c - c_1`.
+
+use crate::curve::{self, SCALAR_BYTES};
+use crate::error::ProofError;
+use crate::hash::hash_tagged;
+use crate::jacobi_point::{PointJacobi, POINT_BYTES};
+use crate::prover::Prover;
+use crate::verifier::Verifier;
+use ibig::IBig;
+
+/// Tag of the challenge transcript; see [`crate::hash`].
+pub const TAG: &str = "dlogproof/or";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrProof {
+    pub t1: PointJacobi,
+    pub t2: PointJacobi,
+    pub c1: IBig,
+    pub s1: IBig,
+    pub s2: IBig,
+}
+
+impl OrProof {
+    /// Length in bytes of an encoded proof: compressed `t1` and `t2`, then
+    /// `c1`, `s1` and `s2`, 32 bytes each.
+    pub const BYTES: usize = 2 * POINT_BYTES + 3 * SCALAR_BYTES;
+
+    /// `prove` proves knowledge of the discrete log of one of `ys`.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `x`: the secret for `ys[known]`
+    /// * `known`: `0` or `1`, the index of the public key `x` belongs to
+    /// * `ys`: the public keys `Y_1` and `Y_2`
+    /// * `base_point`: the base point of the group
+    ///
+    /// Returns:
+    ///
+    /// The proof, `ProofError::InvalidSecret` if `x` is not in `[1, n)`, or
+    /// `ProofError::InvalidStatement` if `known` is not `0` or `1` or
+    /// `ys[known]` is not `x·base_point`.
+    pub fn prove(
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        known: usize,
+        ys: &[PointJacobi; 2],
+        base_point: &PointJacobi,
+    ) -> Result<Self, ProofError> {
+        if known > 1 {
+            return Err(ProofError::InvalidStatement(format!(
+                "branch {} of an OR proof",
+                known
+            )));
+        }
+        if *x <= IBig::from(0) || *x >= *curve::N {
+            return Err(ProofError::InvalidSecret);
+        }
+        if base_point.mul_secret(x) != ys[known] {
+            return Err(ProofError::InvalidStatement(
+                "public key does not match the secret".to_string(),
+            ));
+        }
+        let other = 1 - known;
+        let r = Prover::generate_random_nonce();
+        let mut cs = [IBig::from(0), IBig::from(0)];
+        let mut ss = [IBig::from(0), IBig::from(0)];
+        cs[other] = Prover::generate_random_nonce();
+        ss[other] = Prover::generate_random_nonce();
+        let mut ts = [PointJacobi::zero(), PointJacobi::zero()];
+        ts[known] = base_point.mul_secret(&r);
+        ts[other] = base_point
+            .mul_unsafe(&ss[other])
+            .add(&ys[other].mul_unsafe(&cs[other]).negate());
+        let c = challenge(sid, pid, ys, &ts, base_point)?;
+        cs[known] = curve::rem_n(&(c - &cs[other]));
+        ss[known] = curve::rem_n(&(r + &cs[known] * x));
+        let [t1, t2] = ts;
+        let [c1, _] = cs;
+        let [s1, s2] = ss;
+        Ok(Self { t1, t2, c1, s1, s2 })
+    }
+
+    /// `verify` checks the proof against both public keys.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `ys`: the public keys `Y_1` and `Y_2`
+    /// * `base_point`: the base point of the group
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid, or:
+    ///
+    /// * `ProofError::PointNotOnCurve` if a public key, `base_point`, `t1` or
+    ///   `t2` is off the curve
+    /// * `ProofError::InvalidPoint` if a public key or `base_point` is the
+    ///   point at infinity, or a public key equals `base_point`
+    /// * `ProofError::ScalarOutOfRange` if a scalar is not reduced modulo n
+    /// * `ProofError::VerificationFailed` otherwise
+    pub fn verify(
+        &self,
+        sid: &str,
+        pid: i32,
+        ys: &[PointJacobi; 2],
+        base_point: &PointJacobi,
+    ) -> Result<(), ProofError> {
+        for y in ys {
+            Verifier::validate_points(y, base_point)?;
+        }
+        if !self.t1.is_on_curve() || !self.t2.is_on_curve() {
+            return Err(ProofError::PointNotOnCurve);
+        }
+        let reduced = |v: &IBig| *v >= IBig::from(0) && *v < *curve::N;
+        if !reduced(&self.c1) || !reduced(&self.s1) || !reduced(&self.s2) {
+            return Err(ProofError::ScalarOutOfRange);
+        }
+        let ts = [self.t1.clone(), self.t2.clone()];
+        let c = challenge(sid, pid, ys, &ts, base_point)?;
+        let c2 = curve::rem_n(&(c - &self.c1));
+        let holds = |t: &PointJacobi, c: &IBig, s: &IBig, y: &PointJacobi| {
+            base_point.mul_unsafe(s) == t.add(&y.mul_unsafe(c))
+        };
+        if holds(&self.t1, &self.c1, &self.s1, &ys[0]) && holds(&self.t2, &c2, &self.s2, &ys[1]) {
+            Ok(())
+        } else {
+            Err(ProofError::VerificationFailed)
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut out = [0u8; Self::BYTES];
+        out[..POINT_BYTES].copy_from_slice(&self.t1.to_bytes());
+        out[POINT_BYTES..2 * POINT_BYTES].copy_from_slice(&self.t2.to_bytes());
+        for (i, scalar) in [&self.c1, &self.s1, &self.s2].into_iter().enumerate() {
+            let start = 2 * POINT_BYTES + i * SCALAR_BYTES;
+            out[start..start + SCALAR_BYTES].copy_from_slice(&curve::scalar_to_bytes(scalar));
+        }
+        out
+    }
+
+    /// `from_bytes` parses the encoding produced by [`OrProof::to_bytes`],
+    /// rejecting scalars that are not reduced modulo n.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != Self::BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} OR proof bytes, got {}",
+                Self::BYTES,
+                bytes.len()
+            )));
+        }
+        let scalar = |i: usize| {
+            let start = 2 * POINT_BYTES + i * SCALAR_BYTES;
+            curve::scalar_from_canonical_bytes(&bytes[start..start + SCALAR_BYTES])
+        };
+        Ok(Self {
+            t1: PointJacobi::from_bytes(&bytes[..POINT_BYTES])?,
+            t2: PointJacobi::from_bytes(&bytes[POINT_BYTES..2 * POINT_BYTES])?,
+            c1: scalar(0)?,
+            s1: scalar(1)?,
+            s2: scalar(2)?,
+        })
+    }
+}
+
+fn challenge(
+    sid: &str,
+    pid: i32,
+    ys: &[PointJacobi; 2],
+    ts: &[PointJacobi; 2],
+    base_point: &PointJacobi,
+) -> Result<IBig, ProofError> {
+    let points = [base_point, &ys[0], &ys[1], &ts[0], &ts[1]];
+    hash_tagged(TAG, sid, pid, &points, &[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn statement() -> (IBig, [PointJacobi; 2], PointJacobi) {
+        let mut rng = StdRng::seed_from_u64(1023);
+        let g = PointJacobi::generator();
+        let x = curve::random_scalar(&mut rng);
+        let other = curve::random_scalar(&mut rng);
+        let ys = [g.mul_unsafe(&x), g.mul_unsafe(&other)];
+        (x, ys, g)
+    }
+
+    #[test]
+    fn either_branch_verifies_and_round_trips() {
+        let (x, [y, other], g) = statement();
+        for (known, ys) in [(0, [y.clone(), other.clone()]), (1, [other, y])] {
+            let proof = OrProof::prove("or", 1, &x, known, &ys, &g).unwrap();
+            assert_eq!(proof.verify("or", 1, &ys, &g), Ok(()));
+            assert_eq!(
+                proof.verify("or", 2, &ys, &g),
+                Err(ProofError::VerificationFailed)
+            );
+            assert_eq!(OrProof::from_bytes(&proof.to_bytes()), Ok(proof));
+        }
+    }
+
+    #[test]
+    fn invalid_statement_keys_are_rejected() {
+        let (x, ys, g) = statement();
+        let proof = OrProof::prove("or", 1, &x, 0, &ys, &g).unwrap();
+        let off_curve = PointJacobi::new(ys[1].x.clone(), &ys[1].y + 1, ys[1].z.clone());
+        let cases = [
+            (
+                [ys[0].clone(), PointJacobi::zero()],
+                ProofError::InvalidPoint,
+            ),
+            ([ys[0].clone(), g.clone()], ProofError::InvalidPoint),
+            ([ys[0].clone(), off_curve], ProofError::PointNotOnCurve),
+        ];
+        for (keys, err) in cases {
+            assert_eq!(proof.verify("or", 1, &keys, &g), Err(err));
+        }
+        assert_eq!(
+            proof.verify("or", 1, &ys, &PointJacobi::zero()),
+            Err(ProofError::InvalidPoint)
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! NIST P-256 (secp256r1), `y² = x³ - 3·x + b`, for deployments that must
+//! stay on FIPS 186 curves.
+//!
+//! Only the domain parameters are given here: the point arithmetic is the
+//! provided `IBig` implementation of [`Curve`], with general `a`. P-256 has
+//! no efficiently computable endomorphism, so public scalars are multiplied
+//! by plain double-and-add, without the GLV split used for secp256k1.
+//!
+//! Proofs use the same transcript and encodings as on secp256k1:
+//!
+//! ```text
+//! let g = PointJacobi::<P256>::generator();
+//! let y = g.mul_secret(&x);
+//! let proof = Prover::prove(sid, pid, &x, &y, &g)?;
+//! Verifier::verify(&proof, sid, pid, &y, &g)?;
+//! ```
+
+use crate::curve::Curve;
+use crate::field::limbs_to_ibig;
+use ibig::{ibig, IBig};
+use std::sync::LazyLock;
+
+/// The NIST P-256 curve.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct P256;
+
+/// `p = 2^256 - 2^224 + 2^192 + 2^96 - 1`, little-endian limbs.
+const P_LIMBS: [u64; 4] = [
+    0xFFFF_FFFF_FFFF_FFFF,
+    0x0000_0000_FFFF_FFFF,
+    0x0000_0000_0000_0000,
+    0xFFFF_FFFF_0000_0001,
+];
+const N_LIMBS: [u64; 4] = [
+    0xF3B9_CAC2_FC63_2551,
+    0xBCE6_FAAD_A717_9E84,
+    0xFFFF_FFFF_FFFF_FFFF,
+    0xFFFF_FFFF_0000_0000,
+];
+const B_LIMBS: [u64; 4] = [
+    0x3BCE_3C3E_27D2_604B,
+    0x651D_06B0_CC53_B0F6,
+    0xB3EB_BD55_7698_86BC,
+    0x5AC6_35D8_AA3A_93E7,
+];
+const GX_LIMBS: [u64; 4] = [
+    0xF4A1_3945_D898_C296,
+    0x7703_7D81_2DEB_33A0,
+    0xF8BC_E6E5_63A4_40F2,
+    0x6B17_D1F2_E12C_4247,
+];
+const GY_LIMBS: [u64; 4] = [
+    0xCBB6_4068_37BF_51F5,
+    0x2BCE_3357_6B31_5ECE,
+    0x8EE7_EB4A_7C0F_9E16,
+    0x4FE3_42E2_FE1A_7F9B,
+];
+
+static P: LazyLock<IBig> = LazyLock::new(|| limbs_to_ibig(&P_LIMBS));
+static N: LazyLock<IBig> = LazyLock::new(|| limbs_to_ibig(&N_LIMBS));
+static A: LazyLock<IBig> = LazyLock::new(|| ibig!(-3));
+static B: LazyLock<IBig> = LazyLock::new(|| limbs_to_ibig(&B_LIMBS));
+static G: LazyLock<(IBig, IBig)> =
+    LazyLock::new(|| (limbs_to_ibig(&GX_LIMBS), limbs_to_ibig(&GY_LIMBS)));
+
+impl Curve for P256 {
+    fn p() -> &'static IBig {
+        &P
+    }
+
+    fn n() -> &'static IBig {
+        &N
+    }
+
+    fn a() -> &'static IBig {
+        &A
+    }
+
+    fn b() -> &'static IBig {
+        &B
+    }
+
+    fn generator() -> (&'static IBig, &'static IBig) {
+        let (x, y) = &*G;
+        (x, y)
+    }
+}
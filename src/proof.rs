@@ -0,0 +1,584 @@
+use crate::builder::ProofBuilder;
+use crate::curve::{self, Curve, Secp256k1, SCALAR_BYTES};
+use crate::error::ProofError;
+#[cfg(feature = "evm")]
+use crate::evm;
+use crate::hash::{hash_tagged, DLOG_TAG};
+use crate::hex;
+use crate::jacobi_point::{PointJacobi, POINT_BYTES};
+use crate::scalar::Scalar;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ibig::IBig;
+
+/// Version byte leading [`DLogProof::to_versioned_bytes`].
+pub const PROOF_FORMAT_VERSION: u8 = 1;
+
+/// Non-interactive Schnorr ZK DLOG proof: the commitment `t = r·G` and the
+/// response `s = r + c·x`, a [`Scalar`] and so always reduced modulo `n`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DLogProof<C: Curve = Secp256k1> {
+    pub t: PointJacobi<C>,
+    pub s: Scalar<C>,
+}
+
+impl<C: Curve> DLogProof<C> {
+    pub fn new(t: PointJacobi<C>, s: Scalar<C>) -> Self {
+        Self { t, s }
+    }
+
+    /// `builder` starts a [`ProofBuilder`], which names each input of
+    /// [`crate::Prover::prove`] instead of taking them by position.
+    pub fn builder<'a>() -> ProofBuilder<'a, C> {
+        ProofBuilder::new()
+    }
+
+    /// `challenge` recomputes the Fiat-Shamir challenge `c = H(sid, pid, G, y, t)`
+    /// this proof answers, for protocols that build on `c`.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `public_key`: the point `y` the proof is about
+    /// * `base_point`: the base point `G`
+    ///
+    /// Returns:
+    ///
+    /// The challenge as computed by [`hash_tagged`] with [`DLOG_TAG`], reduced
+    /// modulo `n`, or its `ProofError::InvalidStatement` for an overlong `sid`.
+    pub fn challenge(
+        &self,
+        sid: &str,
+        pid: i32,
+        public_key: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<IBig, ProofError> {
+        hash_tagged(DLOG_TAG, sid, pid, &[base_point, public_key, &self.t], &[])
+    }
+
+    /// `to_bytes` encodes the proof as the compressed point `t` followed by the
+    /// 32-byte big-endian scalar `s`.
+    pub fn to_bytes(&self) -> [u8; POINT_BYTES + SCALAR_BYTES] {
+        let mut out = [0u8; POINT_BYTES + SCALAR_BYTES];
+        out[..POINT_BYTES].copy_from_slice(&self.t.to_bytes());
+        out[POINT_BYTES..].copy_from_slice(&self.s.to_bytes());
+        out
+    }
+
+    /// `from_bytes` parses the encoding produced by [`DLogProof::to_bytes`].
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: exactly [`DLogProof::BYTES`] bytes
+    ///
+    /// Returns:
+    ///
+    /// The decoded proof, or a `DeserializationError` if the length is wrong,
+    /// `t` is not a point on the curve or `s` is not reduced modulo n.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        let len = POINT_BYTES + SCALAR_BYTES;
+        if bytes.len() != len {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} proof bytes, got {}",
+                len,
+                bytes.len()
+            )));
+        }
+        let t = PointJacobi::from_bytes(&bytes[..POINT_BYTES])?;
+        let s = Scalar::from_bytes(&bytes[POINT_BYTES..])?;
+        Ok(Self { t, s })
+    }
+
+    /// `to_hex` is [`DLogProof::to_bytes`] as lowercase hex.
+    pub fn to_hex(&self) -> String {
+        hex::encode(&self.to_bytes())
+    }
+
+    /// `from_hex` parses hex of either case with [`DLogProof::from_bytes`].
+    pub fn from_hex(s: &str) -> Result<Self, ProofError> {
+        let bytes = hex::decode(s)
+            .ok_or_else(|| ProofError::DeserializationError("invalid hex string".to_string()))?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// `to_base64` is [`DLogProof::to_bytes`] as padded standard base64.
+    pub fn to_base64(&self) -> String {
+        BASE64.encode(self.to_bytes())
+    }
+
+    /// `from_base64` parses padded standard base64 with [`DLogProof::from_bytes`].
+    pub fn from_base64(s: &str) -> Result<Self, ProofError> {
+        let bytes = BASE64
+            .decode(s)
+            .map_err(|_| ProofError::DeserializationError("invalid base64 string".to_string()))?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+impl DLogProof {
+    /// Length in bytes of an encoded proof: compressed `t` followed by `s`.
+    pub const BYTES: usize = POINT_BYTES + SCALAR_BYTES;
+    /// Length in bytes of a versioned proof: the version byte, then the proof.
+    pub const VERSIONED_BYTES: usize = 1 + Self::BYTES;
+
+    /// `to_versioned_bytes` is the canonical wire format for storing and
+    /// exchanging proofs:
+    ///
+    /// ```text
+    /// version: u8 = 1 | t: 33 (compressed SEC1) | s: 32 (big-endian, < n)
+    /// ```
+    ///
+    /// Every proof has exactly one encoding, so the bytes can be hashed or
+    /// compared directly.
+    pub fn to_versioned_bytes(&self) -> [u8; Self::VERSIONED_BYTES] {
+        let mut out = [0u8; Self::VERSIONED_BYTES];
+        out[0] = PROOF_FORMAT_VERSION;
+        out[1..].copy_from_slice(&self.to_bytes());
+        out
+    }
+
+    /// `from_versioned_bytes` parses the encoding produced by
+    /// [`DLogProof::to_versioned_bytes`].
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: exactly [`DLogProof::VERSIONED_BYTES`] bytes
+    ///
+    /// Returns:
+    ///
+    /// The decoded proof, or a `DeserializationError` for an unknown version
+    /// or any input that [`DLogProof::from_bytes`] rejects. Only canonical
+    /// encodings are accepted, so re-encoding the result yields `bytes`.
+    pub fn from_versioned_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        match bytes.split_first() {
+            Some((&PROOF_FORMAT_VERSION, proof)) if bytes.len() == Self::VERSIONED_BYTES => {
+                Self::from_bytes(proof)
+            }
+            Some((&PROOF_FORMAT_VERSION, _)) => Err(ProofError::DeserializationError(format!(
+                "expected {} proof bytes, got {}",
+                Self::VERSIONED_BYTES,
+                bytes.len()
+            ))),
+            Some((version, _)) => Err(ProofError::DeserializationError(format!(
+                "unsupported proof format version {}",
+                version
+            ))),
+            None => Err(ProofError::DeserializationError(
+                "empty proof encoding".to_string(),
+            )),
+        }
+    }
+
+    /// `to_json` encodes the proof as a JSON object with `0x`-prefixed hex
+    /// fields, `t` compressed and `s` as 32 bytes:
+    ///
+    /// ```text
+    /// {"t": "0x02…", "s": "0x…", "version": 1}
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        serde_json::json!({
+            "t": format!("0x{}", hex::encode(&self.t.to_bytes())),
+            "s": format!("0x{}", hex::encode(&self.s.to_bytes())),
+            "version": PROOF_FORMAT_VERSION,
+        })
+        .to_string()
+    }
+
+    /// `from_json` parses the object produced by [`DLogProof::to_json`].
+    ///
+    /// Arguments:
+    ///
+    /// * `json`: a JSON object with exactly the fields `t`, `s` and `version`
+    ///
+    /// Returns:
+    ///
+    /// The decoded proof, or a `DeserializationError` if the JSON is malformed,
+    /// a field is missing, unknown or not `0x`-prefixed hex, the version is not
+    /// supported, or the decoded `t` and `s` fail the [`DLogProof::from_bytes`]
+    /// checks.
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self, ProofError> {
+        let invalid = |msg: &str| ProofError::DeserializationError(msg.to_string());
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| invalid(&format!("invalid JSON: {}", e)))?;
+        let object = value
+            .as_object()
+            .ok_or_else(|| invalid("proof JSON must be an object"))?;
+        if let Some(key) = object
+            .keys()
+            .find(|key| !["t", "s", "version"].contains(&key.as_str()))
+        {
+            return Err(invalid(&format!("unknown field {:?}", key)));
+        }
+        match object.get("version").and_then(|v| v.as_u64()) {
+            Some(version) if version == PROOF_FORMAT_VERSION as u64 => {}
+            Some(version) => {
+                return Err(invalid(&format!(
+                    "unsupported proof format version {}",
+                    version
+                )))
+            }
+            None => return Err(invalid("missing or non-integer field \"version\"")),
+        }
+        let field = |name: &str, len: usize| {
+            let bytes = object
+                .get(name)
+                .and_then(|v| v.as_str())
+                .and_then(|s| s.strip_prefix("0x"))
+                .and_then(hex::decode)
+                .ok_or_else(|| {
+                    invalid(&format!(
+                        "field {:?} must be a 0x-prefixed hex string",
+                        name
+                    ))
+                })?;
+            if bytes.len() != len {
+                return Err(invalid(&format!(
+                    "field {:?} must hold {} bytes, got {}",
+                    name,
+                    len,
+                    bytes.len()
+                )));
+            }
+            Ok(bytes)
+        };
+        let mut bytes = field("t", POINT_BYTES)?;
+        bytes.extend(field("s", SCALAR_BYTES)?);
+        Self::from_bytes(&bytes)
+    }
+
+    /// `to_calldata` encodes the proof as `abi.encode(uint256 tx, uint256
+    /// ty, uint256 s)`, the layout the verifier in [`crate::evm`] decodes.
+    ///
+    /// Returns:
+    ///
+    /// The 96 bytes, or `ProofError::IdentityCommitment` if `t` is the point
+    /// at infinity.
+    #[cfg(feature = "evm")]
+    pub fn to_calldata(&self) -> Result<[u8; evm::CALLDATA_BYTES], ProofError> {
+        let t = evm::point_to_calldata(&self.t).map_err(|_| ProofError::IdentityCommitment)?;
+        let mut out = [0u8; evm::CALLDATA_BYTES];
+        out[..2 * SCALAR_BYTES].copy_from_slice(&t);
+        out[2 * SCALAR_BYTES..].copy_from_slice(&self.s.to_bytes());
+        Ok(out)
+    }
+
+    /// `from_calldata` parses the encoding produced by
+    /// [`DLogProof::to_calldata`].
+    ///
+    /// Returns:
+    ///
+    /// The decoded proof, or a `DeserializationError` if the length is wrong,
+    /// `t` is not on the curve or `s` is not reduced modulo n.
+    #[cfg(feature = "evm")]
+    pub fn from_calldata(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != evm::CALLDATA_BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} calldata bytes, got {}",
+                evm::CALLDATA_BYTES,
+                bytes.len()
+            )));
+        }
+        let mut point = [0x04; 1 + 2 * SCALAR_BYTES];
+        point[1..].copy_from_slice(&bytes[..2 * SCALAR_BYTES]);
+        let t = PointJacobi::from_sec1_bytes(&point)?;
+        let s = Scalar::from_bytes(&bytes[2 * SCALAR_BYTES..])?;
+        Ok(Self::new(t, s))
+    }
+}
+
+impl<C: Curve> TryFrom<&[u8]> for DLogProof<C> {
+    type Error = ProofError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, ProofError> {
+        Self::from_bytes(bytes)
+    }
+}
+
+/// A [`DLogProof`] that is only acceptable within
+/// `[issued_at, issued_at + ttl_secs]`. Both fields are bound into the
+/// challenge, so altering them invalidates the proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimedDLogProof {
+    pub proof: DLogProof,
+    pub issued_at: u64,
+    pub ttl_secs: u32,
+}
+
+impl TimedDLogProof {
+    /// Length in bytes of an encoded proof: the proof, then `issued_at` and
+    /// `ttl_secs` big-endian.
+    pub const BYTES: usize = DLogProof::BYTES + 8 + 4;
+
+    /// The `aux` field of the challenge transcript.
+    pub(crate) fn validity_aux(issued_at: u64, ttl_secs: u32) -> [u8; 12] {
+        let mut aux = [0u8; 12];
+        aux[..8].copy_from_slice(&issued_at.to_be_bytes());
+        aux[8..].copy_from_slice(&ttl_secs.to_be_bytes());
+        aux
+    }
+
+    pub fn expires_at(&self) -> u64 {
+        self.issued_at.saturating_add(self.ttl_secs as u64)
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut out = [0u8; Self::BYTES];
+        out[..DLogProof::BYTES].copy_from_slice(&self.proof.to_bytes());
+        out[DLogProof::BYTES..].copy_from_slice(&Self::validity_aux(self.issued_at, self.ttl_secs));
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != Self::BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} proof bytes, got {}",
+                Self::BYTES,
+                bytes.len()
+            )));
+        }
+        let (proof, validity) = bytes.split_at(DLogProof::BYTES);
+        Ok(Self {
+            proof: DLogProof::from_bytes(proof)?,
+            issued_at: u64::from_be_bytes(validity[..8].try_into().unwrap()),
+            ttl_secs: u32::from_be_bytes(validity[8..].try_into().unwrap()),
+        })
+    }
+}
+
+/// Schnorr proof in compressed form: the challenge `c` replaces the
+/// commitment `t`, which the verifier recomputes as `t' = s·G - c·y` before
+/// checking `H(sid, pid, G, y, t') == c`. The challenge is kept reduced
+/// modulo n; only `c mod n` enters the verification equation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortDLogProof {
+    pub c: IBig,
+    pub s: IBig,
+}
+
+impl ShortDLogProof {
+    /// Length in bytes of an encoded proof: `c` then `s`, 32 bytes each.
+    pub const BYTES: usize = 2 * SCALAR_BYTES;
+
+    /// `from_proof` compresses a proof for the statement it was made for.
+    ///
+    /// Arguments:
+    ///
+    /// * `proof`: the full proof
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `public_key`: the point `y` the proof is about
+    /// * `base_point`: the base point `G`
+    ///
+    /// Returns:
+    ///
+    /// The short proof `(c, s)`, or the errors of [`DLogProof::challenge`].
+    pub fn from_proof(
+        proof: &DLogProof,
+        sid: &str,
+        pid: i32,
+        public_key: &PointJacobi,
+        base_point: &PointJacobi,
+    ) -> Result<Self, ProofError> {
+        Ok(Self {
+            c: proof.challenge(sid, pid, public_key, base_point)?,
+            s: proof.s.value().clone(),
+        })
+    }
+
+    /// `expand` recovers the full proof `(t', s)` with `t' = s·G - c·y`.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `public_key`: the point `y` the proof is about
+    /// * `base_point`: the base point `G`
+    ///
+    /// Returns:
+    ///
+    /// The full proof, `ProofError::ScalarOutOfRange` if `s` is not reduced
+    /// modulo n, or `ProofError::VerificationFailed` if `c` is not the
+    /// challenge for `t'`, in which case no valid full proof corresponds to it.
+    pub fn expand(
+        &self,
+        sid: &str,
+        pid: i32,
+        public_key: &PointJacobi,
+        base_point: &PointJacobi,
+    ) -> Result<DLogProof, ProofError> {
+        let t = base_point
+            .mul_unsafe(&self.s)
+            .add(&public_key.mul_unsafe(&self.c).negate());
+        let proof = DLogProof::new(t, Scalar::from_canonical(self.s.clone())?);
+        let c = proof.challenge(sid, pid, public_key, base_point)?;
+        if bool::from(curve::scalar_ct_eq(&c, &self.c)) {
+            Ok(proof)
+        } else {
+            Err(ProofError::VerificationFailed)
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut out = [0u8; Self::BYTES];
+        out[..SCALAR_BYTES].copy_from_slice(&curve::scalar_to_bytes(&self.c));
+        out[SCALAR_BYTES..].copy_from_slice(&curve::scalar_to_bytes(&self.s));
+        out
+    }
+
+    /// `from_bytes` parses the encoding produced by [`ShortDLogProof::to_bytes`],
+    /// rejecting a `c` or `s` that is not reduced modulo n.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != Self::BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} proof bytes, got {}",
+                Self::BYTES,
+                bytes.len()
+            )));
+        }
+        let c = curve::scalar_from_canonical_bytes(&bytes[..SCALAR_BYTES])?;
+        let s = curve::scalar_from_canonical_bytes(&bytes[SCALAR_BYTES..])?;
+        Ok(Self { c, s })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prover::Prover;
+    use crate::verifier::Verifier;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// A secret, its public key and the generator.
+    fn statement(seed: u64) -> (IBig, PointJacobi, PointJacobi) {
+        let g = PointJacobi::generator();
+        let x = curve::random_scalar(&mut StdRng::seed_from_u64(seed));
+        let y = g.mul_unsafe(&x);
+        (x, y, g)
+    }
+
+    const ISSUED_AT: u64 = 1_700_000_000;
+    const TTL: u32 = 300;
+
+    #[test]
+    fn timed_proofs_verify_inside_the_window_only() {
+        let (x, y, g) = statement(839);
+        let proof = Prover::prove_with_validity("timed", 1, &x, &y, &g, ISSUED_AT, TTL).unwrap();
+        let at = |now: u64| Verifier::verify_with_validity(&proof, "timed", 1, &y, &g, now);
+        assert_eq!(at(ISSUED_AT), Ok(()));
+        assert_eq!(at(ISSUED_AT + 100), Ok(()));
+        assert_eq!(at(ISSUED_AT + TTL as u64), Ok(()));
+        assert_eq!(at(ISSUED_AT - 1), Err(ProofError::NotYetValid));
+        assert_eq!(at(ISSUED_AT + TTL as u64 + 1), Err(ProofError::Expired));
+        assert_eq!(at(0), Err(ProofError::NotYetValid));
+        assert_eq!(at(u64::MAX), Err(ProofError::Expired));
+    }
+
+    #[test]
+    fn tampered_timestamps_break_the_challenge() {
+        let (x, y, g) = statement(839);
+        let proof = Prover::prove_with_validity("timed", 1, &x, &y, &g, ISSUED_AT, TTL).unwrap();
+        let now = ISSUED_AT + 10;
+        // Each tampered window still contains `now`, so only the challenge
+        // can reject it.
+        for (issued_at, ttl_secs) in [(ISSUED_AT - 1, TTL), (ISSUED_AT, TTL + 1), (now, 0)] {
+            let tampered = TimedDLogProof {
+                issued_at,
+                ttl_secs,
+                ..proof.clone()
+            };
+            assert_eq!(
+                Verifier::verify_with_validity(&tampered, "timed", 1, &y, &g, now),
+                Err(ProofError::VerificationFailed)
+            );
+        }
+        // Without the validity fields the proof is not a plain proof either.
+        assert_eq!(
+            Verifier::verify(&proof.proof, "timed", 1, &y, &g),
+            Err(ProofError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn timed_proofs_round_trip() {
+        let (x, y, g) = statement(839);
+        let proof = Prover::prove_with_validity("timed", 1, &x, &y, &g, ISSUED_AT, TTL).unwrap();
+        let bytes = proof.to_bytes();
+        assert_eq!(TimedDLogProof::from_bytes(&bytes), Ok(proof.clone()));
+        assert!(matches!(
+            TimedDLogProof::from_bytes(&bytes[1..]),
+            Err(ProofError::DeserializationError(_))
+        ));
+        let forever = TimedDLogProof {
+            issued_at: u64::MAX - 1,
+            ..proof
+        };
+        assert_eq!(forever.expires_at(), u64::MAX);
+    }
+
+    #[test]
+    fn short_and_full_proofs_convert_both_ways() {
+        let (x, y, g) = statement(846);
+        let full = Prover::prove("short", 1, &x, &y, &g).unwrap();
+        let short = ShortDLogProof::from_proof(&full, "short", 1, &y, &g).unwrap();
+        assert_eq!(Verifier::verify_short(&short, "short", 1, &y, &g), Ok(()));
+        assert_eq!(short.expand("short", 1, &y, &g), Ok(full.clone()));
+
+        let short = Prover::prove_short("short", 1, &x, &y, &g).unwrap();
+        assert_eq!(Verifier::verify_short(&short, "short", 1, &y, &g), Ok(()));
+        let full = short.expand("short", 1, &y, &g).unwrap();
+        assert_eq!(Verifier::verify(&full, "short", 1, &y, &g), Ok(()));
+        assert_eq!(
+            ShortDLogProof::from_proof(&full, "short", 1, &y, &g),
+            Ok(short.clone())
+        );
+
+        let bytes = short.to_bytes();
+        assert_eq!(bytes.len(), 64);
+        assert_eq!(ShortDLogProof::from_bytes(&bytes), Ok(short));
+    }
+
+    #[test]
+    fn tampered_short_proofs_are_rejected() {
+        let (x, y, g) = statement(846);
+        let short = Prover::prove_short("short", 1, &x, &y, &g).unwrap();
+        let verify = |proof: &ShortDLogProof| Verifier::verify_short(proof, "short", 1, &y, &g);
+        let tampered_c = ShortDLogProof {
+            c: curve::rem_n(&(&short.c + 1)),
+            ..short.clone()
+        };
+        let tampered_s = ShortDLogProof {
+            s: curve::rem_n(&(&short.s + 1)),
+            ..short.clone()
+        };
+        assert_eq!(verify(&tampered_c), Err(ProofError::VerificationFailed));
+        assert_eq!(verify(&tampered_s), Err(ProofError::VerificationFailed));
+        assert_eq!(
+            Verifier::verify_short(&short, "short", 2, &y, &g),
+            Err(ProofError::VerificationFailed)
+        );
+        let unreduced_c = ShortDLogProof {
+            c: &short.c + &*curve::N,
+            ..short.clone()
+        };
+        let unreduced_s = ShortDLogProof {
+            s: &short.s + &*curve::N,
+            ..short.clone()
+        };
+        assert_eq!(verify(&unreduced_c), Err(ProofError::ScalarOutOfRange));
+        assert_eq!(verify(&unreduced_s), Err(ProofError::ScalarOutOfRange));
+        let zero_c = ShortDLogProof {
+            c: IBig::from(0),
+            ..short.clone()
+        };
+        assert_eq!(verify(&zero_c), Err(ProofError::InvalidScalar));
+
+        let mut bytes = short.to_bytes();
+        bytes[..SCALAR_BYTES].fill(0xff);
+        assert!(ShortDLogProof::from_bytes(&bytes).is_err());
+        assert!(ShortDLogProof::from_bytes(&bytes[1..]).is_err());
+    }
+}
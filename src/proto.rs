@@ -0,0 +1,201 @@
+//! Protocol Buffers messages for proofs, mirroring `proto/dlogproof.proto`.
+//!
+//! The message structs are what `prost-build` emits for that schema, checked
+//! in so that building the `proto` feature does not need `protoc`. Keep the
+//! field tags in sync with the `.proto` file.
+//!
+//! Decoding tolerates unknown fields, as proto3 requires, but a byte field of
+//! the wrong length is a `DeserializationError`.
+//!
+//! For reference, the proof `(T = G, s = 1)` encodes as
+//!
+//! ```text
+//! 0a21 0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798
+//! 1220 0000000000000000000000000000000000000000000000000000000000000001
+//! ```
+
+use crate::curve::SCALAR_BYTES;
+use crate::error::ProofError;
+use crate::jacobi_point::POINT_BYTES;
+use crate::proof::DLogProof;
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProofV1 {
+    /// Commitment T as a 33-byte compressed SEC1 point.
+    #[prost(bytes = "vec", tag = "1")]
+    pub t: Vec<u8>,
+    /// Response s as a 32-byte big-endian scalar, reduced modulo n.
+    #[prost(bytes = "vec", tag = "2")]
+    pub s: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KeyPair {
+    /// 32-byte big-endian secret scalar.
+    #[prost(bytes = "vec", tag = "1")]
+    pub secret_key: Vec<u8>,
+    /// 33-byte compressed SEC1 public key.
+    #[prost(bytes = "vec", tag = "2")]
+    pub public_key: Vec<u8>,
+}
+
+/// Asks a participant to prove knowledge of the secret behind `public_key`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProofRequest {
+    #[prost(string, tag = "1")]
+    pub sid: String,
+    #[prost(int32, tag = "2")]
+    pub pid: i32,
+    #[prost(bytes = "vec", tag = "3")]
+    pub public_key: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProofResponse {
+    #[prost(message, optional, tag = "1")]
+    pub proof: Option<ProofV1>,
+}
+
+impl From<&DLogProof> for ProofV1 {
+    fn from(proof: &DLogProof) -> Self {
+        let bytes = proof.to_bytes();
+        Self {
+            t: bytes[..POINT_BYTES].to_vec(),
+            s: bytes[POINT_BYTES..].to_vec(),
+        }
+    }
+}
+
+impl TryFrom<ProofV1> for DLogProof {
+    type Error = ProofError;
+
+    /// Checks the field lengths, then decodes through [`DLogProof::from_bytes`],
+    /// which rejects points off the curve and unreduced scalars.
+    fn try_from(message: ProofV1) -> Result<Self, Self::Error> {
+        if message.t.len() != POINT_BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "field t: expected {} bytes, got {}",
+                POINT_BYTES,
+                message.t.len()
+            )));
+        }
+        if message.s.len() != SCALAR_BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "field s: expected {} bytes, got {}",
+                SCALAR_BYTES,
+                message.s.len()
+            )));
+        }
+        DLogProof::from_bytes(&[message.t, message.s].concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve;
+    use crate::hex;
+    use crate::jacobi_point::PointJacobi;
+    use crate::prover::Prover;
+    use crate::scalar::Scalar;
+    use ibig::IBig;
+    use prost::Message;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// The encoding of `(T = G, s = 1)` given in the module documentation.
+    const PINNED: &str = "0a210279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+                          12200000000000000000000000000000000000000000000000000000000000000001";
+
+    fn pinned_proof() -> DLogProof {
+        DLogProof::new(
+            PointJacobi::generator(),
+            Scalar::from_canonical(IBig::from(1)).unwrap(),
+        )
+    }
+
+    #[test]
+    fn pinned_message_locks_the_wire_format() {
+        let encoded = ProofV1::from(&pinned_proof()).encode_to_vec();
+        assert_eq!(hex::encode(&encoded), PINNED);
+        let decoded = ProofV1::decode(hex::decode(PINNED).unwrap().as_slice()).unwrap();
+        assert_eq!(DLogProof::try_from(decoded), Ok(pinned_proof()));
+    }
+
+    #[test]
+    fn messages_round_trip() {
+        let mut rng = StdRng::seed_from_u64(844);
+        let g = PointJacobi::generator();
+        let x = curve::random_scalar(&mut rng);
+        let y = g.mul_unsafe(&x);
+        let proof = Prover::prove("proto", 1, &x, &y, &g).unwrap();
+        let response = ProofResponse {
+            proof: Some(ProofV1::from(&proof)),
+        };
+        let decoded = ProofResponse::decode(response.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, response);
+        assert_eq!(DLogProof::try_from(decoded.proof.unwrap()), Ok(proof));
+
+        let request = ProofRequest {
+            sid: "proto".to_string(),
+            pid: -3,
+            public_key: y.to_bytes().to_vec(),
+        };
+        assert_eq!(
+            ProofRequest::decode(request.encode_to_vec().as_slice()).unwrap(),
+            request
+        );
+        let keypair = KeyPair {
+            secret_key: curve::scalar_to_bytes(&x).to_vec(),
+            public_key: y.to_bytes().to_vec(),
+        };
+        assert_eq!(
+            KeyPair::decode(keypair.encode_to_vec().as_slice()).unwrap(),
+            keypair
+        );
+    }
+
+    #[test]
+    fn unknown_fields_are_skipped() {
+        let mut encoded = hex::decode(PINNED).unwrap();
+        // Field 15 as a varint, then field 16 as length-delimited bytes.
+        encoded.extend([0x78, 0x2a, 0x82, 0x01, 0x02, 0xab, 0xcd]);
+        let decoded = ProofV1::decode(encoded.as_slice()).unwrap();
+        assert_eq!(DLogProof::try_from(decoded), Ok(pinned_proof()));
+    }
+
+    #[test]
+    fn wrong_lengths_and_invalid_values_are_rejected() {
+        let valid = ProofV1::from(&pinned_proof());
+        let cases = [
+            ProofV1 {
+                t: valid.t[1..].to_vec(),
+                ..valid.clone()
+            },
+            ProofV1 {
+                s: [valid.s.clone(), vec![0]].concat(),
+                ..valid.clone()
+            },
+            ProofV1::default(),
+            ProofV1 {
+                t: [vec![0x02], vec![0xff; POINT_BYTES - 1]].concat(),
+                ..valid.clone()
+            },
+            ProofV1 {
+                s: vec![0xff; SCALAR_BYTES],
+                ..valid.clone()
+            },
+        ];
+        for message in cases {
+            assert!(DLogProof::try_from(message).is_err());
+        }
+        let short_t = ProofV1 {
+            t: valid.t[1..].to_vec(),
+            ..valid
+        };
+        assert!(matches!(
+            DLogProof::try_from(short_t),
+            Err(ProofError::DeserializationError(msg)) if msg.starts_with("field t")
+        ));
+    }
+}
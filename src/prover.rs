@@ -0,0 +1,675 @@
+use crate::and_proof::AndProof;
+use crate::context::ProofContext;
+use crate::curve::{self, Curve, Secp256k1};
+use crate::error::ProofError;
+#[cfg(feature = "evm")]
+use crate::evm;
+use crate::hash::{self, tagged_transcript, ChallengeHash, DLOG_MESSAGE_TAG, DLOG_TAG};
+use crate::jacobi_point::PointJacobi;
+use crate::keys::{PublicKey, SecretKey};
+#[cfg(feature = "merlin")]
+use crate::merlin_transcript;
+use crate::nonce::rfc6979_nonce;
+use crate::proof::{DLogProof, ShortDLogProof, TimedDLogProof};
+use crate::scalar::Scalar;
+use crate::transcript::Transcript;
+use ibig::IBig;
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+use std::marker::PhantomData;
+
+/// Tag of the nonce transcript of [`Prover::prove_seeded`].
+#[cfg(feature = "test-vectors")]
+const SEEDED_NONCE_TAG: &str = "dlogproof/test/nonce";
+
+/// Proves knowledge of discrete logarithms on the curve `C`. Time-bound,
+/// short, deterministic and vector proofs are only made on [`Secp256k1`].
+pub struct Prover<C: Curve = Secp256k1>(PhantomData<C>);
+
+impl<C: Curve> Prover<C> {
+    /// > The prover generates a random number `r`, computes `t = r*G` and `c = H(sid, pid, G, y, t)`,
+    /// > and then computes `s = r + c*x` and returns the proof `(t, s)`
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `x`: the secret number
+    /// * `y`: the point that we want to prove that we know the discrete logarithm of
+    /// * `base_point`: The base point of the group.
+    ///
+    /// Returns:
+    ///
+    /// A DLogProof struct containing the t and s values, or
+    /// `ProofError::InvalidSecret` if `x` is not in `[1, n)`,
+    /// `ProofError::InvalidPoint` if `y` or `base_point` is the point at
+    /// infinity or `y` equals `base_point`, and `ProofError::InvalidScalar` in
+    /// the negligible case of a zero challenge.
+    ///
+    /// `x`, the nonce `r` and the intermediate values of `r + c·x` are `IBig`s
+    /// and are not wiped when dropped; see [`crate::secret`] for what is.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "dlogproof.prove",
+            skip_all,
+            fields(sid_len = sid.len(), pid = pid),
+            err(Debug)
+        )
+    )]
+    pub fn prove(
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<DLogProof<C>, ProofError> {
+        Self::prove_with_aux::<hash::Sha256>(sid, pid, x, y, base_point, &[])
+    }
+
+    /// `prove_with_rng` is [`Prover::prove`] with the nonce drawn from `rng`
+    /// instead of the operating system's CSPRNG, e.g. a DRBG seeded from an
+    /// HSM or a seeded generator in tests. A predictable or repeating `rng`
+    /// reveals the secret, as a reused nonce does.
+    ///
+    /// Returns:
+    ///
+    /// The proof, or the errors of [`Prover::prove`].
+    pub fn prove_with_rng<R: RngCore + CryptoRng>(
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+        rng: &mut R,
+    ) -> Result<DLogProof<C>, ProofError> {
+        Self::validate(x, y, base_point)?;
+        let r = curve::random_below(rng, C::n());
+        Self::prove_with_nonce::<hash::Sha256>(sid, pid, x, y, base_point, &[], r)
+    }
+
+    /// `prove_seeded` is [`Prover::prove`] with the nonce derived from `seed`
+    /// instead of drawn from an RNG, for tests and cross-language vectors that
+    /// need byte-identical proofs. The nonce is
+    ///
+    /// ```text
+    /// r = SHA-512(tagged_transcript("dlogproof/test/nonce", sid, pid, [G, y], seed)) mod n
+    /// ```
+    ///
+    /// with the version 4 transcript of [`crate::hash`]. It does not depend on
+    /// `x`, so anyone who knows the seed recovers `x` from the proof: only
+    /// prove test secrets this way.
+    ///
+    /// For `x = 2`, `y = 2·G` on secp256k1, `sid = "sid"`, `pid = 1` and 32
+    /// zero bytes of seed the proof is
+    ///
+    /// ```text
+    /// t = 02edcd7248e0aa990be209287e26b0e8758c877adac2060f96dbbaf86027eda9d7
+    /// s = e8c76daeca7b766f206ba68cc9901f0ba770cd4a5fa48213cced08fc74bc0650
+    /// ```
+    ///
+    /// Returns:
+    ///
+    /// The proof, or the errors of [`Prover::prove`].
+    #[cfg(feature = "test-vectors")]
+    pub fn prove_seeded(
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+        seed: &[u8],
+    ) -> Result<DLogProof<C>, ProofError> {
+        Self::validate(x, y, base_point)?;
+        let transcript = tagged_transcript(SEEDED_NONCE_TAG, sid, pid, &[base_point, y], seed)?;
+        let r = C::rem_n(&hash::Sha512::challenge(&transcript));
+        if r == IBig::from(0) {
+            return Err(ProofError::InvalidScalar);
+        }
+        Self::prove_with_nonce::<hash::Sha256>(sid, pid, x, y, base_point, &[], r)
+    }
+
+    /// `prove_with_hash` is [`Prover::prove`] with the challenge hash `H`
+    /// instead of SHA-256, e.g. `Prover::prove_with_hash::<hash::Sha512>(..)`.
+    /// The proof only verifies with [`Verifier::verify_with_hash`] over the
+    /// same `H`.
+    ///
+    /// [`Verifier::verify_with_hash`]: crate::Verifier::verify_with_hash
+    pub fn prove_with_hash<H: ChallengeHash>(
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<DLogProof<C>, ProofError> {
+        Self::prove_with_aux::<H>(sid, pid, x, y, base_point, &[])
+    }
+
+    /// `prove_with_transcript` is [`Prover::prove`] with the challenge drawn
+    /// from `transcript` after appending `(sid, pid, G, y, t)` to it; see
+    /// [`crate::merlin_transcript`]. The transcript is left in the state the
+    /// verifier's reaches after checking the proof.
+    ///
+    /// Returns:
+    ///
+    /// The proof, or the errors of [`Prover::prove`]. `transcript` is not
+    /// modified when the inputs are rejected.
+    #[cfg(feature = "merlin")]
+    pub fn prove_with_transcript(
+        transcript: &mut merlin::Transcript,
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<DLogProof<C>, ProofError> {
+        Self::validate(x, y, base_point)?;
+        let r = curve::random_below(&mut OsRng, C::n());
+        let t = if *base_point == PointJacobi::generator() {
+            PointJacobi::mul_generator(&r)
+        } else {
+            base_point.mul_secret(&r)
+        };
+        let c = merlin_transcript::challenge(transcript, sid, pid, base_point, y, &t);
+        if c == IBig::from(0) {
+            return Err(ProofError::InvalidScalar);
+        }
+        let s = Scalar::new(&(r + c * x));
+        Ok(DLogProof { t, s })
+    }
+
+    /// `prove_transcript` is [`Prover::prove`] with the challenge drawn from
+    /// `transcript` after appending `G, y, t` to it, so that the proof is
+    /// bound to whatever the caller appended before; see
+    /// [`crate::transcript`]. `transcript` itself is left as it was.
+    ///
+    /// Returns:
+    ///
+    /// The proof, or the errors of [`Prover::prove`].
+    pub fn prove_transcript(
+        transcript: &Transcript<C>,
+        x: &IBig,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<DLogProof<C>, ProofError> {
+        Self::validate(x, y, base_point)?;
+        let r = curve::random_below(&mut OsRng, C::n());
+        Self::prove_over::<hash::Sha256>(transcript.clone(), x, y, base_point, r)
+    }
+
+    /// `prove_in_context` is [`Prover::prove`] bound to `context` instead of
+    /// a loose session id and participant id; see [`crate::context`].
+    ///
+    /// Returns:
+    ///
+    /// The proof, or the errors of [`Prover::prove`].
+    pub fn prove_in_context(
+        context: &ProofContext,
+        x: &IBig,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<DLogProof<C>, ProofError> {
+        Self::prove_transcript(&context.transcript(), x, y, base_point)
+    }
+
+    /// `prove_with_ids` is [`Prover::prove`] for a session and participant
+    /// identified by arbitrary bytes instead of a string and an integer, e.g.
+    /// a 32-byte session hash and a UUID. The challenge is computed over the
+    /// version 5 transcript of [`crate::hash`], so the proof is never one for
+    /// any `(sid, pid)` of [`Prover::prove`].
+    ///
+    /// Returns:
+    ///
+    /// The proof, or the errors of [`Prover::prove`].
+    pub fn prove_with_ids(
+        sid: &[u8],
+        pid: &[u8],
+        x: &IBig,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<DLogProof<C>, ProofError> {
+        Self::prove_transcript(&Transcript::with_ids(DLOG_TAG, sid, pid), x, y, base_point)
+    }
+
+    /// `prove_with_message` is [`Prover::prove`] with `message`, e.g. a
+    /// transaction hash, mixed into the challenge, which makes the proof a
+    /// signature of knowledge of `x` over `message`. The transcript is tagged
+    /// [`DLOG_MESSAGE_TAG`] and carries `message` under the label `message`.
+    ///
+    /// Returns:
+    ///
+    /// The proof, or the errors of [`Prover::prove`].
+    pub fn prove_with_message(
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+        message: &[u8],
+    ) -> Result<DLogProof<C>, ProofError> {
+        let mut transcript = Transcript::new(DLOG_MESSAGE_TAG, sid, pid);
+        transcript.append_message(b"message", message);
+        Self::prove_transcript(&transcript, x, y, base_point)
+    }
+
+    /// `prove_checked` is [`Prover::prove`] that also checks `y == x·G` before
+    /// proving, at the cost of one more scalar multiplication. Use it when `y`
+    /// does not come from the same place as `x`, so that a mismatched key pair
+    /// is reported instead of producing a proof that never verifies.
+    ///
+    /// Returns:
+    ///
+    /// The proof, the errors of [`Prover::prove`], or
+    /// `ProofError::InvalidStatement` if `y` is not `x·G`.
+    pub fn prove_checked(
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<DLogProof<C>, ProofError> {
+        Self::validate(x, y, base_point)?;
+        if base_point.mul_secret(x) != *y {
+            return Err(ProofError::InvalidStatement(
+                "public key does not match the secret".to_string(),
+            ));
+        }
+        Self::prove(sid, pid, x, y, base_point)
+    }
+
+    /// `prove_x_only` is [`Prover::prove`] for a statement given as a 32-byte
+    /// x-only public key, such as a Taproot output key. The key stands for the
+    /// point with that `x` and an even `y`; when `x·G` is its negation, as for
+    /// half of all secrets, the proof is made for `n - x` instead.
+    ///
+    /// Returns:
+    ///
+    /// The proof, a `DeserializationError` if `y` is not the x coordinate of
+    /// a point, the errors of [`Prover::prove`], or
+    /// `ProofError::InvalidStatement` if `x·G` has a different x coordinate.
+    pub fn prove_x_only(
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        y: &[u8],
+        base_point: &PointJacobi<C>,
+    ) -> Result<DLogProof<C>, ProofError> {
+        let y = PointJacobi::from_x_only_bytes(y)?;
+        Self::validate(x, &y, base_point)?;
+        let xg = base_point.mul_secret(x);
+        let x = if xg == y {
+            x.clone()
+        } else if xg.negate() == y {
+            C::n() - x
+        } else {
+            return Err(ProofError::InvalidStatement(
+                "public key does not match the secret".to_string(),
+            ));
+        };
+        Self::prove(sid, pid, &x, &y, base_point)
+    }
+
+    /// `prove_many` proves knowledge of each secret in one session over a
+    /// shared base point.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id shared by every proof
+    /// * `base_point`: the base point shared by every proof
+    /// * `entries`: `(pid, secret, public key)` triples
+    ///
+    /// Returns:
+    ///
+    /// The proofs in the order of `entries`, or the errors of [`Prover::prove`]
+    /// for the first entry that fails them.
+    pub fn prove_many(
+        sid: &str,
+        base_point: &PointJacobi<C>,
+        entries: &[(i32, IBig, PointJacobi<C>)],
+    ) -> Result<Vec<DLogProof<C>>, ProofError> {
+        entries
+            .iter()
+            .map(|(pid, x, y)| Self::prove(sid, *pid, x, y, base_point))
+            .collect()
+    }
+
+    /// `prove_many_parallel` is [`Prover::prove_many`] spread across rayon's
+    /// global thread pool. The result, including which error is returned when
+    /// several entries fail, is the same as that of `prove_many`.
+    #[cfg(feature = "parallel")]
+    pub fn prove_many_parallel(
+        sid: &str,
+        base_point: &PointJacobi<C>,
+        entries: &[(i32, IBig, PointJacobi<C>)],
+    ) -> Result<Vec<DLogProof<C>>, ProofError> {
+        let proofs: Vec<_> = entries
+            .par_iter()
+            .map(|(pid, x, y)| Self::prove(sid, *pid, x, y, base_point))
+            .collect();
+        proofs.into_iter().collect()
+    }
+
+    fn prove_with_aux<H: ChallengeHash>(
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+        aux: &[u8],
+    ) -> Result<DLogProof<C>, ProofError> {
+        Self::validate(x, y, base_point)?;
+        let r = curve::random_below(&mut OsRng, C::n());
+        Self::prove_with_nonce::<H>(sid, pid, x, y, base_point, aux, r)
+    }
+
+    fn validate(
+        x: &IBig,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<(), ProofError> {
+        if *x <= IBig::from(0) || *x >= *C::n() {
+            return Err(ProofError::InvalidSecret);
+        }
+        if y.is_zero() || base_point.is_zero() || y == base_point {
+            return Err(ProofError::InvalidPoint);
+        }
+        Ok(())
+    }
+
+    fn prove_with_nonce<H: ChallengeHash>(
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+        aux: &[u8],
+        r: IBig,
+    ) -> Result<DLogProof<C>, ProofError> {
+        let mut transcript = Transcript::new(DLOG_TAG, sid, pid);
+        transcript.append_aux(aux);
+        Self::prove_over::<H>(transcript, x, y, base_point, r)
+    }
+
+    fn prove_over<H: ChallengeHash>(
+        mut transcript: Transcript<C>,
+        x: &IBig,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+        r: IBig,
+    ) -> Result<DLogProof<C>, ProofError> {
+        let t = if *base_point == PointJacobi::generator() {
+            PointJacobi::mul_generator(&r)
+        } else {
+            base_point.mul_secret(&r)
+        };
+        let c = transcript
+            .append_point(base_point)
+            .append_point(y)
+            .append_point(&t)
+            .challenge_scalar_with::<H>()?;
+        if c == IBig::from(0) {
+            return Err(ProofError::InvalidScalar);
+        }
+        let s = Scalar::new(&(r + c * x));
+        Ok(DLogProof { t, s })
+    }
+}
+
+impl Prover {
+    /// `generate_random_nonce` samples a nonce uniformly from `[1, n)` using
+    /// the operating system's CSPRNG. A nonce that is predictable, or reused
+    /// across two proofs, reveals the secret.
+    pub fn generate_random_nonce() -> IBig {
+        curve::random_scalar(&mut OsRng)
+    }
+
+    /// `prove_with_key` is [`Prover::prove`] over the generator for a typed
+    /// key pair, so that the secret and the public key cannot be passed in
+    /// each other's place.
+    ///
+    /// Returns:
+    ///
+    /// The proof, or the errors of [`Prover::prove`].
+    pub fn prove_with_key(
+        sid: &str,
+        pid: i32,
+        key: &SecretKey,
+        public_key: &PublicKey,
+    ) -> Result<DLogProof, ProofError> {
+        Self::prove(
+            sid,
+            pid,
+            &key.scalar(),
+            public_key.point(),
+            &PointJacobi::generator(),
+        )
+    }
+
+    /// `prove_with_validity` is [`Prover::prove`] for a proof that expires: the
+    /// challenge becomes `c = H(sid, pid, G, y, t, issued_at, ttl_secs)`.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `x`: the secret number
+    /// * `y`: the point that we want to prove that we know the discrete logarithm of
+    /// * `base_point`: The base point of the group.
+    /// * `issued_at`: the creation time, in seconds, on the caller's clock
+    /// * `ttl_secs`: how many seconds after `issued_at` the proof stays valid
+    ///
+    /// Returns:
+    ///
+    /// The proof together with its validity window, or the errors of
+    /// [`Prover::prove`].
+    pub fn prove_with_validity(
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        y: &PointJacobi,
+        base_point: &PointJacobi,
+        issued_at: u64,
+        ttl_secs: u32,
+    ) -> Result<TimedDLogProof, ProofError> {
+        let aux = TimedDLogProof::validity_aux(issued_at, ttl_secs);
+        Ok(TimedDLogProof {
+            proof: Self::prove_with_aux::<hash::Sha256>(sid, pid, x, y, base_point, &aux)?,
+            issued_at,
+            ttl_secs,
+        })
+    }
+
+    /// `prove_short` is [`Prover::prove`] returning the compressed `(c, s)` form.
+    pub fn prove_short(
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        y: &PointJacobi,
+        base_point: &PointJacobi,
+    ) -> Result<ShortDLogProof, ProofError> {
+        let proof = Self::prove(sid, pid, x, y, base_point)?;
+        ShortDLogProof::from_proof(&proof, sid, pid, y, base_point)
+    }
+
+    /// `prove_deterministic` is [`Prover::prove`] with the nonce derived as in
+    /// RFC 6979 from `x` and the digest of the statement transcript
+    /// `(sid, pid, G, y)` instead of drawn from an RNG. Proving the same
+    /// statement twice yields the same proof, which verifies like any other.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `x`: the secret number
+    /// * `y`: the point that we want to prove that we know the discrete logarithm of
+    /// * `base_point`: The base point of the group.
+    ///
+    /// Returns:
+    ///
+    /// A DLogProof struct containing the t and s values, or the errors of
+    /// [`Prover::prove`].
+    pub fn prove_deterministic(
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        y: &PointJacobi,
+        base_point: &PointJacobi,
+    ) -> Result<DLogProof, ProofError> {
+        Self::validate(x, y, base_point)?;
+        let transcript = tagged_transcript(DLOG_TAG, sid, pid, &[base_point, y], &[])?;
+        let digest: [u8; 32] = Sha256::digest(&transcript).into();
+        let r = rfc6979_nonce(x, &digest);
+        Self::prove_with_nonce::<hash::Sha256>(sid, pid, x, y, base_point, &[], r)
+    }
+
+    /// `prove_evm` is [`Prover::prove`] over the generator in the EVM profile
+    /// of [`crate::evm`]: the challenge is the Keccak-256 digest of the same
+    /// transcript, so the proof can be checked by a contract.
+    ///
+    /// Returns:
+    ///
+    /// The proof, or the errors of [`Prover::prove`], including
+    /// `ProofError::InvalidPoint` if the x coordinate of `y` is not below n.
+    #[cfg(feature = "evm")]
+    pub fn prove_evm(
+        sid: &str,
+        pid: i32,
+        x: &IBig,
+        y: &PointJacobi,
+    ) -> Result<DLogProof, ProofError> {
+        Self::validate(x, y, &PointJacobi::generator())?;
+        evm::validate_public_key(y)?;
+        let r = curve::random_scalar(&mut OsRng);
+        let t = PointJacobi::mul_generator(&r);
+        let c = evm::challenge(sid, pid, y, &t)?;
+        if c == IBig::from(0) {
+            return Err(ProofError::InvalidScalar);
+        }
+        let s = Scalar::new(&(r + c * x));
+        Ok(DLogProof { t, s })
+    }
+
+    /// `prove_vector` proves knowledge of every `xs[i]` with
+    /// `ys[i] = xs[i]·G` under a single challenge, returning one commitment
+    /// and one response per key; see [`AndProof`].
+    ///
+    /// Returns:
+    ///
+    /// The proof, the errors of [`Prover::prove`] for the first pair that
+    /// fails them, or `ProofError::InvalidStatement` if `ys` is empty or its
+    /// length differs from that of `xs`.
+    pub fn prove_vector(
+        sid: &str,
+        pid: i32,
+        xs: &[IBig],
+        ys: &[PointJacobi],
+        base_point: &PointJacobi,
+    ) -> Result<AndProof, ProofError> {
+        for (x, y) in xs.iter().zip(ys) {
+            Self::validate(x, y, base_point)?;
+        }
+        AndProof::prove(sid, pid, xs, ys, base_point)
+    }
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use super::*;
+    use crate::verifier::Verifier;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::fmt;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Subscriber};
+    use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+    /// Collects the name of every span and every field value recorded on a
+    /// span or an event.
+    #[derive(Clone, Default)]
+    struct Recorder {
+        spans: Arc<Mutex<Vec<String>>>,
+        values: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Visit for Recorder {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            let value = format!("{}={:?}", field.name(), value);
+            self.values.lock().unwrap().push(value);
+        }
+    }
+
+    impl<S: Subscriber> Layer<S> for Recorder {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _: &Id, _: Context<'_, S>) {
+            let name = attrs.metadata().name().to_string();
+            self.spans.lock().unwrap().push(name);
+            attrs.record(&mut self.clone());
+        }
+
+        fn on_record(&self, _: &Id, values: &Record<'_>, _: Context<'_, S>) {
+            values.record(&mut self.clone());
+        }
+
+        fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+            event.record(&mut self.clone());
+        }
+    }
+
+    #[test]
+    fn prove_and_verify_spans_fire_without_the_secret() {
+        let recorder = Recorder::default();
+        let subscriber = tracing_subscriber::registry().with(recorder.clone());
+        let x = curve::random_scalar(&mut StdRng::seed_from_u64(850));
+        let g = PointJacobi::<Secp256k1>::generator();
+        let y = g.mul_unsafe(&x);
+        tracing::subscriber::with_default(subscriber, || {
+            let proof = Prover::prove("tracing", 7, &x, &y, &g).unwrap();
+            assert_eq!(Verifier::verify(&proof, "tracing", 7, &y, &g), Ok(()));
+            assert_eq!(
+                Verifier::verify(&proof, "tracing", 8, &y, &g),
+                Err(ProofError::VerificationFailed)
+            );
+            assert_eq!(
+                Prover::prove("tracing", 7, &IBig::from(0), &y, &g),
+                Err(ProofError::InvalidSecret)
+            );
+        });
+
+        let spans = recorder.spans.lock().unwrap();
+        for name in [
+            "dlogproof.prove",
+            "dlogproof.verify",
+            "dlogproof.hash_points",
+            "dlogproof.mul",
+        ] {
+            assert!(spans.iter().any(|span| span == name), "no {name} span");
+        }
+        let values = recorder.values.lock().unwrap();
+        for expected in [
+            "sid_len=7",
+            "pid=7",
+            "holds=false",
+            "error=VerificationFailed",
+            "error=InvalidSecret",
+        ] {
+            assert!(
+                values.iter().any(|value| value == expected),
+                "no {expected}"
+            );
+        }
+        let digits = [
+            x.to_string(),
+            format!("{:x}", x),
+            crate::hex::encode(&curve::scalar_to_bytes(&x)),
+        ];
+        for value in values.iter() {
+            for secret in &digits {
+                assert!(!value.contains(secret.as_str()), "{value} holds the secret");
+            }
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! PyO3 module `dlogproof` for Python, behind the `python` feature.
+//!
+//! The classes mirror the Rust API over secp256k1 and the generator `G`:
+//!
+//! ```text
+//! from dlogproof import Keypair, Prover, Verifier, DLogProof
+//!
+//! keypair = Keypair.generate()
+//! proof = Prover.prove("sid", 1, keypair)
+//! assert Verifier.verify(DLogProof.from_bytes(bytes(proof)), "sid", 1, keypair.public_key)
+//! ```
+//!
+//! Keys and proofs convert to and from `bytes` in the encodings of
+//! [`crate::mobile`], so a proof made in Python verifies with
+//! [`Verifier::verify_with_key`] and the other way around. Errors are raised
+//! as `dlogproof.ProofError`, a subclass of `ValueError`, carrying the
+//! [`ProofError`] message.
+
+use crate::error::ProofError;
+use crate::hex;
+use crate::keys::{PublicKey, SecretKey};
+use crate::proof::DLogProof;
+use crate::prover::Prover;
+use crate::verifier::Verifier;
+use pyo3::prelude::*;
+
+mod exceptions {
+    pyo3::create_exception!(
+        dlogproof,
+        ProofError,
+        pyo3::exceptions::PyValueError,
+        "A key or proof is malformed, or a proof could not be made."
+    );
+}
+
+impl From<ProofError> for PyErr {
+    fn from(err: ProofError) -> Self {
+        exceptions::ProofError::new_err(err.to_string())
+    }
+}
+
+/// A secret key and its public key `secret·G`.
+#[pyclass(name = "Keypair", module = "dlogproof", frozen)]
+pub struct PyKeypair {
+    key: SecretKey,
+    public_key: PublicKey,
+}
+
+#[pymethods]
+impl PyKeypair {
+    /// Generates a random keypair.
+    #[staticmethod]
+    fn generate() -> Self {
+        Self::from(SecretKey::random(&mut rand::rngs::OsRng))
+    }
+
+    /// Restores a keypair from the 32-byte big-endian secret.
+    #[staticmethod]
+    fn from_secret(secret: &[u8]) -> PyResult<Self> {
+        Ok(Self::from(SecretKey::from_bytes(secret)?))
+    }
+
+    #[getter]
+    fn secret(&self) -> Vec<u8> {
+        self.key.to_bytes().to_vec()
+    }
+
+    /// The 33-byte compressed public key.
+    #[getter]
+    fn public_key(&self) -> Vec<u8> {
+        self.public_key.to_bytes().to_vec()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Keypair(public_key={})", self.public_key)
+    }
+}
+
+impl From<SecretKey> for PyKeypair {
+    fn from(key: SecretKey) -> Self {
+        let public_key = key.public_key();
+        Self { key, public_key }
+    }
+}
+
+/// A proof of knowledge of a discrete logarithm.
+#[pyclass(name = "DLogProof", module = "dlogproof", frozen, eq)]
+#[derive(PartialEq)]
+pub struct PyDLogProof {
+    proof: DLogProof,
+}
+
+#[pymethods]
+impl PyDLogProof {
+    /// Parses the 65-byte encoding `T || s`.
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        Ok(Self {
+            proof: DLogProof::from_bytes(bytes)?,
+        })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.proof.to_bytes().to_vec()
+    }
+
+    fn __bytes__(&self) -> Vec<u8> {
+        self.to_bytes()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("DLogProof({})", hex::encode(&self.proof.to_bytes()))
+    }
+}
+
+/// Makes proofs; see [`Prover`].
+#[pyclass(name = "Prover", module = "dlogproof", frozen)]
+pub struct PyProver;
+
+#[pymethods]
+impl PyProver {
+    /// Proves knowledge of `keypair`'s secret in session `sid` as participant `pid`.
+    #[staticmethod]
+    fn prove(sid: &str, pid: i32, keypair: &PyKeypair) -> PyResult<PyDLogProof> {
+        let proof = Prover::prove_with_key(sid, pid, &keypair.key, &keypair.public_key)?;
+        Ok(PyDLogProof { proof })
+    }
+}
+
+/// Checks proofs; see [`Verifier`].
+#[pyclass(name = "Verifier", module = "dlogproof", frozen)]
+pub struct PyVerifier;
+
+#[pymethods]
+impl PyVerifier {
+    /// Returns whether `proof` is valid for `public_key`; a malformed public
+    /// key raises.
+    #[staticmethod]
+    fn verify(proof: &PyDLogProof, sid: &str, pid: i32, public_key: &[u8]) -> PyResult<bool> {
+        let public_key = PublicKey::from_bytes(public_key)?;
+        match Verifier::verify_with_key(&proof.proof, sid, pid, &public_key) {
+            Ok(()) => Ok(true),
+            Err(ProofError::VerificationFailed) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+#[pymodule]
+fn dlogproof(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyKeypair>()?;
+    m.add_class::<PyDLogProof>()?;
+    m.add_class::<PyProver>()?;
+    m.add_class::<PyVerifier>()?;
+    m.add("ProofError", m.py().get_type::<exceptions::ProofError>())?;
+    Ok(())
+}
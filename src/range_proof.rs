@@ -0,0 +1,609 @@
+//! Bulletproofs range proofs (Bünz et al., S&P 2018): a proof that a
+//! [`PedersenCommitment`] `V = v·G + γ·H` hides a value `v` in `[0, 2^bits)`,
+//! of size logarithmic in `bits`.
+//!
+//! This follows section 4.2 of the paper, with the inner-product argument of
+//! section 3 run recursively and without the multi-exponentiation
+//! optimizations, so proving and verifying cost a few hundred scalar
+//! multiplications each. The vector generators `G_i`, `H_i` and the
+//! inner-product generator `U` are hashed to points the way `H` is in
+//! [`crate::commitment`], from the domains
+//! `"dlogproof/bulletproofs/G/<i>"`, `"dlogproof/bulletproofs/H/<i>"` and
+//! `"dlogproof/bulletproofs/U"`.
+//!
+//! Challenges are chained through [`hash_tagged`] with the tag [`TAG`]: each
+//! is the hash of the session, the points sent since the previous challenge
+//! and, as auxiliary data, a label, the previous challenge and any scalars
+//! sent.
+
+use crate::commitment::{self, nums_point, PedersenCommitment};
+use crate::curve::{self, invert_n, rem_n, SCALAR_BYTES};
+use crate::error::ProofError;
+use crate::hash::hash_tagged;
+use crate::jacobi_point::{PointJacobi, POINT_BYTES};
+use crate::prover::Prover;
+use ibig::IBig;
+use std::sync::LazyLock;
+
+/// Tag of every challenge transcript; see [`crate::hash`].
+pub const TAG: &str = "dlogproof/bulletproofs";
+
+/// The largest supported range is `[0, 2^64)`.
+pub const MAX_BITS: usize = 64;
+
+static GENERATORS: LazyLock<(Vec<PointJacobi>, Vec<PointJacobi>)> = LazyLock::new(|| {
+    (
+        (0..MAX_BITS)
+            .map(|i| nums_point(format!("dlogproof/bulletproofs/G/{}", i).as_bytes()))
+            .collect(),
+        (0..MAX_BITS)
+            .map(|i| nums_point(format!("dlogproof/bulletproofs/H/{}", i).as_bytes()))
+            .collect(),
+    )
+});
+static U: LazyLock<PointJacobi> = LazyLock::new(|| nums_point(b"dlogproof/bulletproofs/U"));
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeProof {
+    pub a: PointJacobi,
+    pub s: PointJacobi,
+    pub t1: PointJacobi,
+    pub t2: PointJacobi,
+    pub tau_x: IBig,
+    pub mu: IBig,
+    pub t_hat: IBig,
+    /// The `L` and `R` commitments of each round of the inner-product argument.
+    pub ipa_rounds: Vec<(PointJacobi, PointJacobi)>,
+    pub ipa_a: IBig,
+    pub ipa_b: IBig,
+}
+
+impl RangeProof {
+    /// `prove` commits to `value` with `blinding` and proves it lies in
+    /// `[0, 2^bits)`.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `value`: the committed value `v`
+    /// * `blinding`: the commitment's blinding factor `γ`
+    /// * `bits`: the bit length of the range, 8, 16, 32 or 64
+    ///
+    /// Returns:
+    ///
+    /// The proof and the commitment `v·G + γ·H` it is about, or
+    /// `ProofError::InvalidStatement` if `bits` is unsupported or `value` is
+    /// not below `2^bits`.
+    pub fn prove(
+        sid: &str,
+        pid: i32,
+        value: u64,
+        blinding: &IBig,
+        bits: usize,
+    ) -> Result<(Self, PedersenCommitment), ProofError> {
+        check_bits(bits)?;
+        if bits < 64 && value >> bits != 0 {
+            return Err(ProofError::InvalidStatement(format!(
+                "value does not fit in {} bits",
+                bits
+            )));
+        }
+        let g = PointJacobi::generator();
+        let h = commitment::h();
+        let (gs, hs) = (&GENERATORS.0[..bits], &GENERATORS.1[..bits]);
+        let commitment = PedersenCommitment::commit(&IBig::from(value), blinding);
+        let mut transcript = Transcript::new(sid, pid, bits);
+
+        let a_l: Vec<IBig> = (0..bits).map(|i| IBig::from((value >> i) & 1)).collect();
+        let a_r: Vec<IBig> = a_l.iter().map(|a| rem_n(&(a - 1))).collect();
+        let alpha = Prover::generate_random_nonce();
+        let a = h
+            .mul_secret(&alpha)
+            .add(&multi_mul_secret(&a_l, gs))
+            .add(&multi_mul_secret(&a_r, hs));
+        let s_l = random_vector(bits);
+        let s_r = random_vector(bits);
+        let rho = Prover::generate_random_nonce();
+        let s = h
+            .mul_secret(&rho)
+            .add(&multi_mul_secret(&s_l, gs))
+            .add(&multi_mul_secret(&s_r, hs));
+        let y = transcript.challenge(b"y", &[commitment.c.clone(), a.clone(), s.clone()], &[])?;
+        let z = transcript.challenge(b"z", &[], &[])?;
+
+        let y_n = powers(&y, bits);
+        let two_n = powers(&IBig::from(2), bits);
+        let z2 = rem_n(&(&z * &z));
+        let l0: Vec<IBig> = a_l.iter().map(|a| rem_n(&(a - &z))).collect();
+        let r0: Vec<IBig> = (0..bits)
+            .map(|i| rem_n(&(&y_n[i] * (&a_r[i] + &z) + &z2 * &two_n[i])))
+            .collect();
+        let r1: Vec<IBig> = (0..bits).map(|i| rem_n(&(&y_n[i] * &s_r[i]))).collect();
+        let t1 = rem_n(&(inner_product(&l0, &r1) + inner_product(&s_l, &r0)));
+        let t2 = inner_product(&s_l, &r1);
+        let tau1 = Prover::generate_random_nonce();
+        let tau2 = Prover::generate_random_nonce();
+        let t1_point = g.mul_secret(&t1).add(&h.mul_secret(&tau1));
+        let t2_point = g.mul_secret(&t2).add(&h.mul_secret(&tau2));
+        let x = transcript.challenge(b"x", &[t1_point.clone(), t2_point.clone()], &[])?;
+
+        let l: Vec<IBig> = (0..bits).map(|i| rem_n(&(&l0[i] + &s_l[i] * &x))).collect();
+        let r: Vec<IBig> = (0..bits).map(|i| rem_n(&(&r0[i] + &r1[i] * &x))).collect();
+        let t_hat = inner_product(&l, &r);
+        let tau_x = rem_n(&(tau2 * &x * &x + tau1 * &x + &z2 * blinding));
+        let mu = rem_n(&(alpha + rho * &x));
+        let w = transcript.challenge(b"w", &[], &[&tau_x, &mu, &t_hat])?;
+
+        let u = U.mul_unsafe(&w);
+        let hs_prime = scaled_generators(hs, &y);
+        let (ipa_rounds, ipa_a, ipa_b) =
+            inner_product_prove(&mut transcript, gs.to_vec(), hs_prime, &u, l, r)?;
+        let proof = Self {
+            a,
+            s,
+            t1: t1_point,
+            t2: t2_point,
+            tau_x,
+            mu,
+            t_hat,
+            ipa_rounds,
+            ipa_a,
+            ipa_b,
+        };
+        Ok((proof, commitment))
+    }
+
+    /// `verify` checks that `commitment` hides a value in `[0, 2^bits)`.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `commitment`: the commitment `V`
+    /// * `bits`: the bit length of the range the proof was made for
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid, `ProofError::InvalidStatement` if
+    /// `bits` is unsupported or does not match the proof,
+    /// `ProofError::ScalarOutOfRange` if a scalar is not reduced modulo n,
+    /// and `ProofError::VerificationFailed` otherwise.
+    pub fn verify(
+        &self,
+        sid: &str,
+        pid: i32,
+        commitment: &PedersenCommitment,
+        bits: usize,
+    ) -> Result<(), ProofError> {
+        check_bits(bits)?;
+        if self.ipa_rounds.len() != bits.trailing_zeros() as usize {
+            return Err(ProofError::InvalidStatement(format!(
+                "proof has {} rounds, a {}-bit range needs {}",
+                self.ipa_rounds.len(),
+                bits,
+                bits.trailing_zeros()
+            )));
+        }
+        let reduced = |v: &IBig| *v >= IBig::from(0) && *v < *curve::N;
+        if ![&self.tau_x, &self.mu, &self.t_hat, &self.ipa_a, &self.ipa_b]
+            .into_iter()
+            .all(reduced)
+        {
+            return Err(ProofError::ScalarOutOfRange);
+        }
+        let g = PointJacobi::generator();
+        let h = commitment::h();
+        let (gs, hs) = (&GENERATORS.0[..bits], &GENERATORS.1[..bits]);
+        let mut transcript = Transcript::new(sid, pid, bits);
+        let y = transcript.challenge(
+            b"y",
+            &[commitment.c.clone(), self.a.clone(), self.s.clone()],
+            &[],
+        )?;
+        let z = transcript.challenge(b"z", &[], &[])?;
+        let x = transcript.challenge(b"x", &[self.t1.clone(), self.t2.clone()], &[])?;
+        let w = transcript.challenge(b"w", &[], &[&self.tau_x, &self.mu, &self.t_hat])?;
+
+        // t̂·G + τx·H == z²·V + δ(y, z)·G + x·T1 + x²·T2
+        let y_n = powers(&y, bits);
+        let two_n = powers(&IBig::from(2), bits);
+        let z2 = rem_n(&(&z * &z));
+        let z3 = rem_n(&(&z2 * &z));
+        let sum = |v: &[IBig]| rem_n(&v.iter().fold(IBig::from(0), |acc, e| acc + e));
+        let delta = rem_n(&((&z - &z2) * sum(&y_n) - &z3 * sum(&two_n)));
+        let lhs = g.mul_unsafe(&self.t_hat).add(&h.mul_unsafe(&self.tau_x));
+        let rhs = commitment
+            .c
+            .mul_unsafe(&z2)
+            .add(&g.mul_unsafe(&delta))
+            .add(&self.t1.mul_unsafe(&x))
+            .add(&self.t2.mul_unsafe(&rem_n(&(&x * &x))));
+        if lhs != rhs {
+            return Err(ProofError::VerificationFailed);
+        }
+
+        // P = A + x·S - z·ΣG_i + Σ(z·y^i + z²·2^i)·H'_i, with H'_i = y^-i·H_i,
+        // then P - μ·H + t̂·U' is opened by the inner-product argument.
+        let hs_prime = scaled_generators(hs, &y);
+        let neg_z = rem_n(&-&z);
+        let h_coefficients: Vec<IBig> = (0..bits)
+            .map(|i| rem_n(&(&z * &y_n[i] + &z2 * &two_n[i])))
+            .collect();
+        let u = U.mul_unsafe(&w);
+        let p = self
+            .a
+            .add(&self.s.mul_unsafe(&x))
+            .add(&multi_mul(&vec![neg_z; bits], gs))
+            .add(&multi_mul(&h_coefficients, &hs_prime))
+            .add(&h.mul_unsafe(&rem_n(&-&self.mu)))
+            .add(&u.mul_unsafe(&self.t_hat));
+        if inner_product_verify(
+            &mut transcript,
+            gs.to_vec(),
+            hs_prime,
+            &u,
+            p,
+            &self.ipa_rounds,
+            &self.ipa_a,
+            &self.ipa_b,
+        )? {
+            Ok(())
+        } else {
+            Err(ProofError::VerificationFailed)
+        }
+    }
+
+    /// `to_bytes` encodes `A`, `S`, `T1`, `T2` compressed, then `τx`, `μ`,
+    /// `t̂`, then `L` and `R` of each round, then the final `a` and `b`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(Self::encoded_len(self.ipa_rounds.len()));
+        for point in [&self.a, &self.s, &self.t1, &self.t2] {
+            out.extend(point.to_bytes());
+        }
+        for scalar in [&self.tau_x, &self.mu, &self.t_hat] {
+            out.extend(curve::scalar_to_bytes(scalar));
+        }
+        for (l, r) in &self.ipa_rounds {
+            out.extend(l.to_bytes());
+            out.extend(r.to_bytes());
+        }
+        out.extend(curve::scalar_to_bytes(&self.ipa_a));
+        out.extend(curve::scalar_to_bytes(&self.ipa_b));
+        out
+    }
+
+    /// `from_bytes` parses the encoding produced by [`RangeProof::to_bytes`];
+    /// the number of rounds, and so the range, follows from the length.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        let rounds = (3..=MAX_BITS.trailing_zeros() as usize)
+            .find(|rounds| Self::encoded_len(*rounds) == bytes.len())
+            .ok_or_else(|| {
+                ProofError::DeserializationError(format!(
+                    "invalid range proof length {}",
+                    bytes.len()
+                ))
+            })?;
+        let point_at =
+            |offset: usize| PointJacobi::from_bytes(&bytes[offset..offset + POINT_BYTES]);
+        let scalar_at = |offset: usize| {
+            curve::scalar_from_canonical_bytes(&bytes[offset..offset + SCALAR_BYTES])
+        };
+        let scalars = 4 * POINT_BYTES;
+        let ipa = scalars + 3 * SCALAR_BYTES;
+        let finals = ipa + 2 * rounds * POINT_BYTES;
+        let ipa_rounds = (0..rounds)
+            .map(|i| {
+                let start = ipa + 2 * i * POINT_BYTES;
+                Ok((point_at(start)?, point_at(start + POINT_BYTES)?))
+            })
+            .collect::<Result<Vec<_>, ProofError>>()?;
+        Ok(Self {
+            a: point_at(0)?,
+            s: point_at(POINT_BYTES)?,
+            t1: point_at(2 * POINT_BYTES)?,
+            t2: point_at(3 * POINT_BYTES)?,
+            tau_x: scalar_at(scalars)?,
+            mu: scalar_at(scalars + SCALAR_BYTES)?,
+            t_hat: scalar_at(scalars + 2 * SCALAR_BYTES)?,
+            ipa_rounds,
+            ipa_a: scalar_at(finals)?,
+            ipa_b: scalar_at(finals + SCALAR_BYTES)?,
+        })
+    }
+
+    fn encoded_len(rounds: usize) -> usize {
+        4 * POINT_BYTES + 5 * SCALAR_BYTES + 2 * rounds * POINT_BYTES
+    }
+}
+
+/// Fiat-Shamir transcript: every challenge also covers the one before it.
+struct Transcript<'a> {
+    sid: &'a str,
+    pid: i32,
+    state: Vec<u8>,
+}
+
+impl<'a> Transcript<'a> {
+    fn new(sid: &'a str, pid: i32, bits: usize) -> Self {
+        let mut state = TAG.as_bytes().to_vec();
+        state.extend((bits as u32).to_be_bytes());
+        Self { sid, pid, state }
+    }
+
+    fn challenge(
+        &mut self,
+        label: &[u8],
+        points: &[PointJacobi],
+        scalars: &[&IBig],
+    ) -> Result<IBig, ProofError> {
+        let mut aux = label.to_vec();
+        aux.extend(&self.state);
+        for scalar in scalars {
+            aux.extend(curve::scalar_to_bytes(scalar));
+        }
+        let c = hash_tagged(
+            TAG,
+            self.sid,
+            self.pid,
+            &points.iter().collect::<Vec<_>>(),
+            &aux,
+        )?;
+        self.state = curve::scalar_to_bytes(&c).to_vec();
+        Ok(c)
+    }
+}
+
+fn check_bits(bits: usize) -> Result<(), ProofError> {
+    if matches!(bits, 8 | 16 | 32 | 64) {
+        Ok(())
+    } else {
+        Err(ProofError::InvalidStatement(format!(
+            "unsupported range of {} bits",
+            bits
+        )))
+    }
+}
+
+fn random_vector(len: usize) -> Vec<IBig> {
+    (0..len).map(|_| Prover::generate_random_nonce()).collect()
+}
+
+fn powers(base: &IBig, len: usize) -> Vec<IBig> {
+    let mut out = Vec::with_capacity(len);
+    let mut acc = IBig::from(1);
+    for _ in 0..len {
+        out.push(acc.clone());
+        acc = rem_n(&(acc * base));
+    }
+    out
+}
+
+fn inner_product(a: &[IBig], b: &[IBig]) -> IBig {
+    rem_n(
+        &a.iter()
+            .zip(b)
+            .fold(IBig::from(0), |acc, (x, y)| acc + x * y),
+    )
+}
+
+fn multi_mul(scalars: &[IBig], points: &[PointJacobi]) -> PointJacobi {
+    scalars
+        .iter()
+        .zip(points)
+        .fold(PointJacobi::zero(), |acc, (k, p)| acc.add(&p.mul_unsafe(k)))
+}
+
+fn multi_mul_secret(scalars: &[IBig], points: &[PointJacobi]) -> PointJacobi {
+    scalars
+        .iter()
+        .zip(points)
+        .fold(PointJacobi::zero(), |acc, (k, p)| acc.add(&p.mul_secret(k)))
+}
+
+/// `H'_i = y^-i·H_i`.
+fn scaled_generators(hs: &[PointJacobi], y: &IBig) -> Vec<PointJacobi> {
+    powers(&invert_n(y), hs.len())
+        .iter()
+        .zip(hs)
+        .map(|(k, h)| h.mul_unsafe(k))
+        .collect()
+}
+
+/// Folds `lo·lo_factor + hi·hi_factor` element-wise over the two halves.
+fn fold_scalars(v: &[IBig], lo_factor: &IBig, hi_factor: &IBig) -> Vec<IBig> {
+    let (lo, hi) = v.split_at(v.len() / 2);
+    lo.iter()
+        .zip(hi)
+        .map(|(l, h)| rem_n(&(l * lo_factor + h * hi_factor)))
+        .collect()
+}
+
+fn fold_points(v: &[PointJacobi], lo_factor: &IBig, hi_factor: &IBig) -> Vec<PointJacobi> {
+    let (lo, hi) = v.split_at(v.len() / 2);
+    lo.iter()
+        .zip(hi)
+        .map(|(l, h)| l.mul_unsafe(lo_factor).add(&h.mul_unsafe(hi_factor)))
+        .collect()
+}
+
+/// The `(L, R)` pairs of the inner-product rounds and the final `a` and `b`.
+type InnerProductProof = (Vec<(PointJacobi, PointJacobi)>, IBig, IBig);
+
+/// Proves knowledge of `a`, `b` with `P = <a, G> + <b, H> + <a, b>·U`,
+/// halving the vectors each round.
+fn inner_product_prove(
+    transcript: &mut Transcript,
+    mut gs: Vec<PointJacobi>,
+    mut hs: Vec<PointJacobi>,
+    u: &PointJacobi,
+    mut a: Vec<IBig>,
+    mut b: Vec<IBig>,
+) -> Result<InnerProductProof, ProofError> {
+    let mut rounds = vec![];
+    while a.len() > 1 {
+        let half = a.len() / 2;
+        let c_l = inner_product(&a[..half], &b[half..]);
+        let c_r = inner_product(&a[half..], &b[..half]);
+        let l = multi_mul_secret(&a[..half], &gs[half..])
+            .add(&multi_mul_secret(&b[half..], &hs[..half]))
+            .add(&u.mul_secret(&c_l));
+        let r = multi_mul_secret(&a[half..], &gs[..half])
+            .add(&multi_mul_secret(&b[..half], &hs[half..]))
+            .add(&u.mul_secret(&c_r));
+        let x = transcript.challenge(b"ipa", &[l.clone(), r.clone()], &[])?;
+        let x_inv = invert_n(&x);
+        gs = fold_points(&gs, &x_inv, &x);
+        hs = fold_points(&hs, &x, &x_inv);
+        a = fold_scalars(&a, &x, &x_inv);
+        b = fold_scalars(&b, &x_inv, &x);
+        rounds.push((l, r));
+    }
+    Ok((rounds, a.remove(0), b.remove(0)))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn inner_product_verify(
+    transcript: &mut Transcript,
+    mut gs: Vec<PointJacobi>,
+    mut hs: Vec<PointJacobi>,
+    u: &PointJacobi,
+    mut p: PointJacobi,
+    rounds: &[(PointJacobi, PointJacobi)],
+    a: &IBig,
+    b: &IBig,
+) -> Result<bool, ProofError> {
+    for (l, r) in rounds {
+        let x = transcript.challenge(b"ipa", &[l.clone(), r.clone()], &[])?;
+        let x_inv = invert_n(&x);
+        p = l
+            .mul_unsafe(&rem_n(&(&x * &x)))
+            .add(&p)
+            .add(&r.mul_unsafe(&rem_n(&(&x_inv * &x_inv))));
+        gs = fold_points(&gs, &x_inv, &x);
+        hs = fold_points(&hs, &x, &x_inv);
+    }
+    Ok(p == gs[0]
+        .mul_unsafe(a)
+        .add(&hs[0].mul_unsafe(b))
+        .add(&u.mul_unsafe(&rem_n(&(a * b)))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blinding() -> IBig {
+        curve::rem_n(&IBig::from(1026))
+    }
+
+    #[test]
+    fn values_at_both_ends_of_the_range_verify() {
+        for value in [0, 255] {
+            let (proof, commitment) = RangeProof::prove("range", 1, value, &blinding(), 8).unwrap();
+            assert_eq!(proof.verify("range", 1, &commitment, 8), Ok(()));
+            assert_eq!(commitment.open(&IBig::from(value), &blinding()), Ok(()));
+        }
+        let (proof, commitment) = RangeProof::prove("range", 1, u64::MAX, &blinding(), 64).unwrap();
+        assert_eq!(proof.verify("range", 1, &commitment, 64), Ok(()));
+    }
+
+    #[test]
+    fn values_outside_the_range_are_refused() {
+        assert!(matches!(
+            RangeProof::prove("range", 1, 256, &blinding(), 8),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        assert!(matches!(
+            RangeProof::prove("range", 1, 1 << 32, &blinding(), 32),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        assert!(matches!(
+            RangeProof::prove("range", 1, 1, &blinding(), 12),
+            Err(ProofError::InvalidStatement(_))
+        ));
+    }
+
+    #[test]
+    fn a_proof_is_bound_to_its_commitment_and_session() {
+        let (proof, commitment) = RangeProof::prove("range", 1, 42, &blinding(), 8).unwrap();
+        let other = PedersenCommitment::commit(&IBig::from(42), &curve::rem_n(&(blinding() + 1)));
+        assert_eq!(
+            proof.verify("range", 1, &other, 8),
+            Err(ProofError::VerificationFailed)
+        );
+        assert_eq!(
+            proof.verify("other", 1, &commitment, 8),
+            Err(ProofError::VerificationFailed)
+        );
+        assert_eq!(
+            proof.verify("range", 2, &commitment, 8),
+            Err(ProofError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn tampered_proofs_are_rejected() {
+        let (proof, commitment) = RangeProof::prove("range", 1, 42, &blinding(), 8).unwrap();
+
+        let mut swapped = proof.clone();
+        let (l, r) = swapped.ipa_rounds[1].clone();
+        swapped.ipa_rounds[1] = (r, l);
+        assert_eq!(
+            swapped.verify("range", 1, &commitment, 8),
+            Err(ProofError::VerificationFailed)
+        );
+
+        let mut shifted = proof.clone();
+        shifted.t_hat = curve::rem_n(&(&shifted.t_hat + 1));
+        assert_eq!(
+            shifted.verify("range", 1, &commitment, 8),
+            Err(ProofError::VerificationFailed)
+        );
+
+        let mut final_a = proof.clone();
+        final_a.ipa_a = curve::rem_n(&(&final_a.ipa_a + 1));
+        assert_eq!(
+            final_a.verify("range", 1, &commitment, 8),
+            Err(ProofError::VerificationFailed)
+        );
+
+        let mut unreduced = proof;
+        unreduced.mu = &unreduced.mu + &*curve::N;
+        assert_eq!(
+            unreduced.verify("range", 1, &commitment, 8),
+            Err(ProofError::ScalarOutOfRange)
+        );
+    }
+
+    #[test]
+    fn the_range_must_match_the_proof() {
+        let (proof, commitment) = RangeProof::prove("range", 1, 42, &blinding(), 8).unwrap();
+        assert!(matches!(
+            proof.verify("range", 1, &commitment, 16),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        assert!(matches!(
+            proof.verify("range", 1, &commitment, 12),
+            Err(ProofError::InvalidStatement(_))
+        ));
+    }
+
+    #[test]
+    fn encoding_round_trips_and_truncation_is_rejected() {
+        let (proof, commitment) = RangeProof::prove("range", 1, 42, &blinding(), 8).unwrap();
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes.len(), RangeProof::encoded_len(3));
+        let decoded = RangeProof::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, proof);
+        assert_eq!(decoded.verify("range", 1, &commitment, 8), Ok(()));
+        assert!(matches!(
+            RangeProof::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(ProofError::DeserializationError(_))
+        ));
+        assert!(matches!(
+            RangeProof::from_bytes(&bytes[..RangeProof::encoded_len(2)]),
+            Err(ProofError::DeserializationError(_))
+        ));
+    }
+}
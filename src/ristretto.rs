@@ -0,0 +1,185 @@
+//! Schnorr DLOG proofs over ristretto255 (RFC 9496), the prime-order group
+//! built on Curve25519 that most other zero-knowledge tooling uses, on
+//! curve25519-dalek.
+//!
+//! The protocol is that of [`crate::Prover`] and [`crate::Verifier`]: the
+//! commitment `t = r·B`, the challenge `c = H(sid, pid, B, y, t)` and the
+//! response `s = r + c·x mod ℓ`. The challenge is the SHA-512 digest of the
+//! version 3 transcript of [`crate::hash`], with curve `"ristretto255"` and
+//! 32-byte compressed points, reduced modulo `ℓ`, so it never coincides with
+//! a challenge on secp256k1 or another curve.
+//!
+//! An encoded proof is `t || s`, 64 bytes.
+
+use crate::error::ProofError;
+use crate::hash::curve_transcript;
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::Identity;
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha512};
+use zeroize::Zeroizing;
+
+/// The curve identifier bound into every challenge.
+pub const CURVE_ID: &str = "ristretto255";
+
+/// Length in bytes of a compressed ristretto255 point.
+pub const POINT_BYTES: usize = 32;
+
+/// Length in bytes of a canonically encoded scalar.
+pub const SCALAR_BYTES: usize = 32;
+
+/// Non-interactive Schnorr ZK DLOG proof over ristretto255: the commitment
+/// `t = r·B` and the response `s = r + c·x`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RistrettoDLogProof {
+    pub t: RistrettoPoint,
+    pub s: Scalar,
+}
+
+impl RistrettoDLogProof {
+    /// Length in bytes of an encoded proof: compressed `t` followed by `s`.
+    pub const BYTES: usize = POINT_BYTES + SCALAR_BYTES;
+
+    /// `prove` proves knowledge of `x` with `y = x·base_point`.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id
+    /// * `x`: the secret scalar
+    /// * `y`: the public key
+    /// * `base_point`: the base point, usually
+    ///   `curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT`
+    ///
+    /// Returns:
+    ///
+    /// The proof, `ProofError::InvalidSecret` if `x` is zero,
+    /// `ProofError::InvalidPoint` if `y` or `base_point` is the identity or
+    /// `y` equals `base_point`, and `ProofError::InvalidScalar` in the
+    /// negligible case of a zero challenge.
+    pub fn prove(
+        sid: &str,
+        pid: i32,
+        x: &Scalar,
+        y: &RistrettoPoint,
+        base_point: &RistrettoPoint,
+    ) -> Result<Self, ProofError> {
+        if *x == Scalar::ZERO {
+            return Err(ProofError::InvalidSecret);
+        }
+        validate_points(y, base_point)?;
+        let r = Zeroizing::new(Scalar::random(&mut OsRng));
+        let t = base_point * *r;
+        let c = challenge(sid, pid, y, base_point, &t)?;
+        if c == Scalar::ZERO {
+            return Err(ProofError::InvalidScalar);
+        }
+        Ok(Self { t, s: *r + c * x })
+    }
+
+    /// `verify` checks `s·B == t + c·y`.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid, `ProofError::VerificationFailed`
+    /// otherwise. A `t` at the identity is `ProofError::IdentityCommitment`,
+    /// a weak `y` or `base_point` `ProofError::InvalidPoint` and a zero
+    /// challenge `ProofError::InvalidScalar`.
+    pub fn verify(
+        &self,
+        sid: &str,
+        pid: i32,
+        y: &RistrettoPoint,
+        base_point: &RistrettoPoint,
+    ) -> Result<(), ProofError> {
+        if self.t == RistrettoPoint::identity() {
+            return Err(ProofError::IdentityCommitment);
+        }
+        validate_points(y, base_point)?;
+        let c = self.challenge(sid, pid, y, base_point)?;
+        if c == Scalar::ZERO {
+            return Err(ProofError::InvalidScalar);
+        }
+        if base_point * self.s == self.t + y * c {
+            Ok(())
+        } else {
+            Err(ProofError::VerificationFailed)
+        }
+    }
+
+    /// `challenge` recomputes the challenge `c = H(sid, pid, B, y, t)` this
+    /// proof answers, or fails with `ProofError::InvalidStatement` if `sid` is
+    /// longer than `u32::MAX` bytes.
+    pub fn challenge(
+        &self,
+        sid: &str,
+        pid: i32,
+        y: &RistrettoPoint,
+        base_point: &RistrettoPoint,
+    ) -> Result<Scalar, ProofError> {
+        challenge(sid, pid, y, base_point, &self.t)
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut out = [0u8; Self::BYTES];
+        out[..POINT_BYTES].copy_from_slice(self.t.compress().as_bytes());
+        out[POINT_BYTES..].copy_from_slice(self.s.as_bytes());
+        out
+    }
+
+    /// `from_bytes` parses the encoding produced by
+    /// [`RistrettoDLogProof::to_bytes`].
+    ///
+    /// Returns:
+    ///
+    /// The decoded proof, or a `DeserializationError` if the length is wrong,
+    /// `t` is not a canonical ristretto255 encoding or `s` is not reduced
+    /// modulo `ℓ`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != Self::BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} proof bytes, got {}",
+                Self::BYTES,
+                bytes.len()
+            )));
+        }
+        let (t, s) = bytes.split_at(POINT_BYTES);
+        let t = CompressedRistretto::from_slice(t)
+            .ok()
+            .and_then(|t| t.decompress())
+            .ok_or_else(|| {
+                ProofError::DeserializationError("invalid ristretto255 point".to_string())
+            })?;
+        let s =
+            Option::from(Scalar::from_canonical_bytes(s.try_into().unwrap())).ok_or_else(|| {
+                ProofError::DeserializationError(
+                    "scalar is not reduced modulo the curve order".to_string(),
+                )
+            })?;
+        Ok(Self { t, s })
+    }
+}
+
+fn validate_points(y: &RistrettoPoint, base_point: &RistrettoPoint) -> Result<(), ProofError> {
+    let identity = RistrettoPoint::identity();
+    if *y == identity || *base_point == identity || y == base_point {
+        return Err(ProofError::InvalidPoint);
+    }
+    Ok(())
+}
+
+fn challenge(
+    sid: &str,
+    pid: i32,
+    y: &RistrettoPoint,
+    base_point: &RistrettoPoint,
+    t: &RistrettoPoint,
+) -> Result<Scalar, ProofError> {
+    let points = [base_point, y, t].map(|point| point.compress().to_bytes());
+    let points: Vec<&[u8]> = points.iter().map(|point| point.as_slice()).collect();
+    let transcript = curve_transcript(CURVE_ID, sid, pid, &points, &[])?;
+    Ok(Scalar::from_bytes_mod_order_wide(
+        &Sha512::digest(transcript).into(),
+    ))
+}
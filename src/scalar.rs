@@ -0,0 +1,190 @@
+//! Integers modulo the group order `n` of a [`Curve`].
+//!
+//! A [`Scalar`] is always reduced into `[0, n)`: every constructor either
+//! reduces its input or rejects it, and the arithmetic keeps the result
+//! reduced, so a value held in a `Scalar`, such as the response
+//! [`DLogProof::s`](crate::DLogProof::s), needs no range check where it is
+//! used. Unlike [`crate::field::Scalar`], the fixed-limb secp256k1 residue
+//! behind point arithmetic, it works on any curve and is a thin wrapper over
+//! `IBig`; its arithmetic is not constant-time.
+
+use crate::curve::{self, Curve, Secp256k1, SCALAR_BYTES};
+use crate::error::ProofError;
+use ibig::{modular::ModuloRing, IBig, UBig};
+use rand::rngs::OsRng;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// An integer modulo the order of `C`, always in `[0, n)`.
+pub struct Scalar<C: Curve = Secp256k1> {
+    value: IBig,
+    curve: PhantomData<C>,
+}
+
+impl<C: Curve> Scalar<C> {
+    /// `new` reduces `value` modulo `n`.
+    pub fn new(value: &IBig) -> Self {
+        Self::from_reduced(C::rem_n(value))
+    }
+
+    /// Wraps a value the caller has already reduced modulo `n`.
+    pub(crate) fn from_reduced(value: IBig) -> Self {
+        debug_assert!(value >= IBig::from(0) && value < *C::n());
+        Self {
+            value,
+            curve: PhantomData,
+        }
+    }
+
+    /// `from_canonical` wraps `value` without reducing it.
+    ///
+    /// Returns:
+    ///
+    /// The scalar, or `ProofError::ScalarOutOfRange` if `value` is not in
+    /// `[0, n)`.
+    pub fn from_canonical(value: IBig) -> Result<Self, ProofError> {
+        if value < IBig::from(0) || value >= *C::n() {
+            return Err(ProofError::ScalarOutOfRange);
+        }
+        Ok(Self::from_reduced(value))
+    }
+
+    pub fn zero() -> Self {
+        Self::from_reduced(IBig::from(0))
+    }
+
+    pub fn one() -> Self {
+        Self::from_reduced(IBig::from(1))
+    }
+
+    /// `random` samples a scalar uniformly from `[1, n)` with the operating
+    /// system's CSPRNG.
+    pub fn random() -> Self {
+        Self::from_reduced(curve::random_below(&mut OsRng, C::n()))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value == IBig::from(0)
+    }
+
+    /// `value` returns the scalar as an `IBig` in `[0, n)`.
+    pub fn value(&self) -> &IBig {
+        &self.value
+    }
+
+    /// `add` returns `self + other mod n`.
+    pub fn add(&self, other: &Self) -> Self {
+        Self::new(&(&self.value + &other.value))
+    }
+
+    /// `sub` returns `self - other mod n`.
+    pub fn sub(&self, other: &Self) -> Self {
+        Self::new(&(&self.value - &other.value))
+    }
+
+    /// `mul` returns `self · other mod n`.
+    pub fn mul(&self, other: &Self) -> Self {
+        Self::new(&(&self.value * &other.value))
+    }
+
+    /// `negate` returns `-self mod n`.
+    pub fn negate(&self) -> Self {
+        Self::new(&-&self.value)
+    }
+
+    /// `invert` returns `self⁻¹ mod n`, or `None` for zero.
+    pub fn invert(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        let ring = ModuloRing::new(&UBig::try_from(C::n()).unwrap());
+        let inverse = ring.from(&self.value).inverse()?;
+        Some(Self::from_reduced(IBig::from(inverse.residue())))
+    }
+
+    /// `to_bytes` returns the 32-byte big-endian encoding.
+    pub fn to_bytes(&self) -> [u8; SCALAR_BYTES] {
+        curve::to_be_bytes(&self.value)
+    }
+
+    /// `from_bytes` decodes the canonical 32-byte big-endian encoding.
+    ///
+    /// Returns:
+    ///
+    /// The scalar, or a `DeserializationError` if `bytes` is not 32 bytes
+    /// long or encodes a value not below `n`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        curve::canonical_scalar::<C>(bytes).map(Self::from_reduced)
+    }
+}
+
+impl<C: Curve> Clone for Scalar<C> {
+    fn clone(&self) -> Self {
+        Self::from_reduced(self.value.clone())
+    }
+}
+
+impl<C: Curve> PartialEq for Scalar<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<C: Curve> Eq for Scalar<C> {}
+
+impl<C: Curve> std::hash::Hash for Scalar<C> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<C: Curve> fmt::Debug for Scalar<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Scalar({})", self.value)
+    }
+}
+
+impl<C: Curve> fmt::Display for Scalar<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.value, f)
+    }
+}
+
+impl<C: Curve> From<Scalar<C>> for IBig {
+    fn from(scalar: Scalar<C>) -> IBig {
+        scalar.value
+    }
+}
+
+impl<C: Curve> Add for &Scalar<C> {
+    type Output = Scalar<C>;
+
+    fn add(self, other: Self) -> Scalar<C> {
+        Scalar::add(self, other)
+    }
+}
+
+impl<C: Curve> Sub for &Scalar<C> {
+    type Output = Scalar<C>;
+
+    fn sub(self, other: Self) -> Scalar<C> {
+        Scalar::sub(self, other)
+    }
+}
+
+impl<C: Curve> Mul for &Scalar<C> {
+    type Output = Scalar<C>;
+
+    fn mul(self, other: Self) -> Scalar<C> {
+        Scalar::mul(self, other)
+    }
+}
+
+impl<C: Curve> Neg for &Scalar<C> {
+    type Output = Scalar<C>;
+
+    fn neg(self) -> Scalar<C> {
+        self.negate()
+    }
+}
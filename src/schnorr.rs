@@ -0,0 +1,284 @@
+//! Schnorr signatures: a [`ShortDLogProof`](crate::ShortDLogProof) for the
+//! signer's public key whose challenge also covers the message,
+//!
+//! ```text
+//! c = H("", 0, G, Y, t, message),  s = r + c·x
+//! ```
+//!
+//! with the challenge tagged `"dlogproof/schnorr"`, encoded as `c` then `s`,
+//! 32 bytes each. Prehashed messages use the tag
+//! `"dlogproof/schnorr-prehashed"`, so a signature over a digest is never
+//! valid for the raw message equal to that digest.
+//!
+//! With the `signature-traits` feature, [`SecretKey`] and [`PublicKey`]
+//! implement the RustCrypto `signature` traits.
+
+use crate::curve::{self, SCALAR_BYTES};
+use crate::error::ProofError;
+use crate::hash::hash_tagged;
+use crate::jacobi_point::PointJacobi;
+use crate::keys::{PublicKey, SecretKey};
+use ibig::IBig;
+use rand::{CryptoRng, RngCore};
+
+pub(crate) const MESSAGE_TAG: &str = "dlogproof/schnorr";
+const PREHASHED_TAG: &str = "dlogproof/schnorr-prehashed";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchnorrSignature {
+    pub c: IBig,
+    pub s: IBig,
+}
+
+impl SchnorrSignature {
+    /// Length in bytes of an encoded signature: `c` then `s`, 32 bytes each.
+    pub const BYTES: usize = 2 * SCALAR_BYTES;
+
+    /// `sign` signs `message` with a nonce drawn from `rng`.
+    ///
+    /// Returns:
+    ///
+    /// The signature, or `ProofError::InvalidStatement` if `message` is longer
+    /// than `u32::MAX` bytes.
+    pub fn sign<R: RngCore + CryptoRng>(
+        key: &SecretKey,
+        message: &[u8],
+        rng: &mut R,
+    ) -> Result<Self, ProofError> {
+        Self::sign_with_tag(MESSAGE_TAG, key, message, rng)
+    }
+
+    /// `sign_prehashed` signs the digest of a message, as produced by the caller.
+    pub fn sign_prehashed<R: RngCore + CryptoRng>(
+        key: &SecretKey,
+        digest: &[u8],
+        rng: &mut R,
+    ) -> Result<Self, ProofError> {
+        Self::sign_with_tag(PREHASHED_TAG, key, digest, rng)
+    }
+
+    /// `verify` checks the signature on `message` by recomputing
+    /// `t' = s·G - c·Y` and comparing the challenge for `t'` against `c`.
+    ///
+    /// Arguments:
+    ///
+    /// * `public_key`: the signer's public key `Y`
+    /// * `message`: the signed message
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the signature is valid, `ProofError::VerificationFailed` otherwise.
+    pub fn verify(&self, public_key: &PublicKey, message: &[u8]) -> Result<(), ProofError> {
+        self.verify_with_tag(MESSAGE_TAG, public_key, message)
+    }
+
+    /// `verify_prehashed` checks a signature made by [`SchnorrSignature::sign_prehashed`].
+    pub fn verify_prehashed(
+        &self,
+        public_key: &PublicKey,
+        digest: &[u8],
+    ) -> Result<(), ProofError> {
+        self.verify_with_tag(PREHASHED_TAG, public_key, digest)
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut out = [0u8; Self::BYTES];
+        out[..SCALAR_BYTES].copy_from_slice(&curve::scalar_to_bytes(&self.c));
+        out[SCALAR_BYTES..].copy_from_slice(&curve::scalar_to_bytes(&self.s));
+        out
+    }
+
+    /// `from_bytes` parses the encoding produced by [`SchnorrSignature::to_bytes`],
+    /// rejecting a `c` or `s` that is not reduced modulo n.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != Self::BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} signature bytes, got {}",
+                Self::BYTES,
+                bytes.len()
+            )));
+        }
+        let c = curve::scalar_from_canonical_bytes(&bytes[..SCALAR_BYTES])?;
+        let s = curve::scalar_from_canonical_bytes(&bytes[SCALAR_BYTES..])?;
+        Ok(Self { c, s })
+    }
+
+    fn sign_with_tag<R: RngCore + CryptoRng>(
+        tag: &str,
+        key: &SecretKey,
+        message: &[u8],
+        rng: &mut R,
+    ) -> Result<Self, ProofError> {
+        let g = PointJacobi::generator();
+        let r = curve::random_scalar(rng);
+        let t = g.mul_secret(&r);
+        let c = Self::challenge(tag, key.public_key().point(), &t, message)?;
+        let s = curve::rem_n(&(r + c.clone() * key.scalar()));
+        Ok(Self { c, s })
+    }
+
+    fn verify_with_tag(
+        &self,
+        tag: &str,
+        public_key: &PublicKey,
+        message: &[u8],
+    ) -> Result<(), ProofError> {
+        if self.c >= *curve::N || self.s >= *curve::N {
+            return Err(ProofError::VerificationFailed);
+        }
+        let y = public_key.point();
+        let t = PointJacobi::generator()
+            .mul_unsafe(&self.s)
+            .add(&y.mul_unsafe(&self.c).negate());
+        if bool::from(curve::scalar_ct_eq(
+            &Self::challenge(tag, y, &t, message)?,
+            &self.c,
+        )) {
+            Ok(())
+        } else {
+            Err(ProofError::VerificationFailed)
+        }
+    }
+
+    pub(crate) fn challenge(
+        tag: &str,
+        y: &PointJacobi,
+        t: &PointJacobi,
+        message: &[u8],
+    ) -> Result<IBig, ProofError> {
+        let points = [&PointJacobi::generator(), y, t];
+        hash_tagged(tag, "", 0, &points, message)
+    }
+}
+
+#[cfg(feature = "signature-traits")]
+mod traits {
+    use super::SchnorrSignature;
+    use crate::keys::{PublicKey, SecretKey};
+    use signature::digest::Digest;
+    use signature::rand_core::CryptoRngCore;
+    use signature::{DigestSigner, DigestVerifier, Error, RandomizedSigner, SignatureEncoding};
+
+    impl TryFrom<&[u8]> for SchnorrSignature {
+        type Error = Error;
+
+        fn try_from(bytes: &[u8]) -> Result<Self, Error> {
+            Self::from_bytes(bytes).map_err(|_| Error::new())
+        }
+    }
+
+    impl From<SchnorrSignature> for [u8; SchnorrSignature::BYTES] {
+        fn from(signature: SchnorrSignature) -> Self {
+            signature.to_bytes()
+        }
+    }
+
+    impl SignatureEncoding for SchnorrSignature {
+        type Repr = [u8; SchnorrSignature::BYTES];
+    }
+
+    /// Signs with a nonce from the operating system's RNG.
+    impl signature::Signer<SchnorrSignature> for SecretKey {
+        fn try_sign(&self, msg: &[u8]) -> Result<SchnorrSignature, Error> {
+            SchnorrSignature::sign(self, msg, &mut rand::rngs::OsRng).map_err(|_| Error::new())
+        }
+    }
+
+    impl RandomizedSigner<SchnorrSignature> for SecretKey {
+        fn try_sign_with_rng(
+            &self,
+            rng: &mut impl CryptoRngCore,
+            msg: &[u8],
+        ) -> Result<SchnorrSignature, Error> {
+            SchnorrSignature::sign(self, msg, rng).map_err(|_| Error::new())
+        }
+    }
+
+    impl<D: Digest> DigestSigner<D, SchnorrSignature> for SecretKey {
+        fn try_sign_digest(&self, digest: D) -> Result<SchnorrSignature, Error> {
+            SchnorrSignature::sign_prehashed(self, &digest.finalize(), &mut rand::rngs::OsRng)
+                .map_err(|_| Error::new())
+        }
+    }
+
+    impl signature::Verifier<SchnorrSignature> for PublicKey {
+        fn verify(&self, msg: &[u8], signature: &SchnorrSignature) -> Result<(), Error> {
+            signature.verify(self, msg).map_err(|_| Error::new())
+        }
+    }
+
+    impl<D: Digest> DigestVerifier<D, SchnorrSignature> for PublicKey {
+        fn verify_digest(&self, digest: D, signature: &SchnorrSignature) -> Result<(), Error> {
+            signature
+                .verify_prehashed(self, &digest.finalize())
+                .map_err(|_| Error::new())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        use sha2::Sha256;
+        use signature::{Signer, Verifier};
+
+        /// Generic code that only knows the `signature` traits.
+        fn sign_anything<S: Signer<Sig>, Sig>(signer: &S, msg: &[u8]) -> Sig {
+            signer.sign(msg)
+        }
+
+        fn verify_anything<V: Verifier<Sig> + ?Sized, Sig>(
+            verifier: &V,
+            msg: &[u8],
+            signature: &Sig,
+        ) -> bool {
+            verifier.verify(msg, signature).is_ok()
+        }
+
+        fn keypair() -> (SecretKey, PublicKey) {
+            let key = SecretKey::random(&mut StdRng::seed_from_u64(851));
+            let public_key = key.public_key();
+            (key, public_key)
+        }
+
+        #[test]
+        fn generic_signers_and_verifiers_accept_the_keys() {
+            let (key, public_key) = keypair();
+            let signature: SchnorrSignature = sign_anything(&key, b"message");
+            assert!(verify_anything(&public_key, b"message", &signature));
+
+            let signature = key.sign_with_rng(&mut StdRng::seed_from_u64(1), b"message");
+            assert!(verify_anything(&public_key, b"message", &signature));
+
+            let signature: SchnorrSignature = key.sign_digest(Sha256::new_with_prefix(b"message"));
+            assert!(public_key
+                .verify_digest(Sha256::new_with_prefix(b"message"), &signature)
+                .is_ok());
+        }
+
+        #[test]
+        fn failed_verification_through_a_trait_object() {
+            let (key, public_key) = keypair();
+            let other = SecretKey::random(&mut StdRng::seed_from_u64(852)).public_key();
+            let signature: SchnorrSignature = key.sign(b"message");
+            let verifiers: [&dyn Verifier<SchnorrSignature>; 2] = [&public_key, &other];
+            assert!(verifiers[0].verify(b"message", &signature).is_ok());
+            assert!(verifiers[0].verify(b"other message", &signature).is_err());
+            assert!(verifiers[1].verify(b"message", &signature).is_err());
+            assert!(!verify_anything(verifiers[1], b"message", &signature));
+        }
+
+        #[test]
+        fn signature_encoding_round_trips() {
+            let (key, _) = keypair();
+            let signature: SchnorrSignature = key.sign(b"message");
+            let bytes = signature.to_bytes();
+            assert_eq!(
+                SchnorrSignature::try_from(bytes.as_slice()).unwrap(),
+                signature
+            );
+            assert!(SchnorrSignature::try_from(&bytes[1..]).is_err());
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! Secret scalars that are wiped from memory when dropped.
+//!
+//! The arithmetic in this crate runs on `IBig`, whose heap buffers cannot be
+//! zeroized from outside the `ibig` crate. Secrets are therefore held as their
+//! 32-byte encodings in a [`SecretScalar`] and only expanded into a temporary
+//! `IBig` for the arithmetic that needs them. Those temporaries are released
+//! without being wiped; keeping them short-lived is the best this backend
+//! allows.
+//!
+//! What is wiped: the storage of a [`crate::SecretKey`], of the nonces
+//! and shares of the multi-party provers and of the coefficients of a
+//! [`crate::vss::Polynomial`], the random bytes a scalar is
+//! sampled from, and the secret encoding and DRBG state of the RFC 6979
+//! nonce. What is not: every `IBig`, including a secret passed to
+//! [`crate::Prover::prove`] and the copy returned by [`SecretScalar::expose`],
+//! and, inside the provers, the nonce `r`, the product `c·x` and the
+//! response `r + c·x` before it is reduced into the proof.
+
+use crate::curve::{self, SCALAR_BYTES};
+use ibig::IBig;
+use std::fmt;
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// A scalar modulo `n` stored as 32 big-endian bytes and zeroized on drop.
+#[derive(Clone, Eq)]
+pub struct SecretScalar([u8; SCALAR_BYTES]);
+
+impl SecretScalar {
+    /// `new` reduces `scalar` modulo `n` and stores its encoding.
+    pub fn new(scalar: &IBig) -> Self {
+        Self(curve::scalar_to_bytes(scalar))
+    }
+
+    /// `from_bytes` takes ownership of an encoding without reducing it; the
+    /// caller is responsible for range checks.
+    pub fn from_bytes(bytes: [u8; SCALAR_BYTES]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; SCALAR_BYTES] {
+        &self.0
+    }
+
+    /// `expose` returns the scalar as an `IBig`. The copy is not wiped when it
+    /// is dropped, so keep it in scope only as long as the arithmetic needs.
+    pub fn expose(&self) -> IBig {
+        curve::scalar_from_bytes(&self.0)
+    }
+}
+
+impl ConstantTimeEq for SecretScalar {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl PartialEq for SecretScalar {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Zeroize for SecretScalar {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for SecretScalar {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretScalar {}
+
+impl fmt::Debug for SecretScalar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretScalar(..)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zeroize_clears_the_encoding() {
+        let mut secret = SecretScalar::new(&IBig::from(0x1234_5678_u32));
+        assert_eq!(secret.expose(), IBig::from(0x1234_5678_u32));
+        secret.zeroize();
+        assert_eq!(secret.as_bytes(), &[0u8; SCALAR_BYTES]);
+        assert_eq!(format!("{:?}", secret), "SecretScalar(..)");
+    }
+}
@@ -0,0 +1,109 @@
+//! `serde` support behind the `serde` feature.
+//!
+//! Values serialize as their canonical byte encodings: a lowercase hex string
+//! for human-readable formats such as JSON, raw bytes otherwise. Deserializing
+//! goes through the same `from_bytes` as the wire format, so points are
+//! checked to be on the curve and scalars to be reduced modulo n.
+
+use crate::error::ProofError;
+use crate::hex;
+use crate::jacobi_point::{Point, PointJacobi};
+use crate::proof::DLogProof;
+use serde::de::{self, Deserializer, Visitor};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+fn serialize_encoded<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&hex::encode(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+fn deserialize_encoded<'de, D, T>(
+    deserializer: D,
+    expecting: &'static str,
+    parse: fn(&[u8]) -> Result<T, ProofError>,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let visitor = EncodedVisitor { expecting, parse };
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(visitor)
+    } else {
+        deserializer.deserialize_bytes(visitor)
+    }
+}
+
+struct EncodedVisitor<T> {
+    expecting: &'static str,
+    parse: fn(&[u8]) -> Result<T, ProofError>,
+}
+
+impl<'de, T> Visitor<'de> for EncodedVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.expecting)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+        let bytes = hex::decode(v).ok_or_else(|| E::custom("invalid hex string"))?;
+        self.visit_bytes(&bytes)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<T, E> {
+        (self.parse)(v).map_err(E::custom)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        self.visit_bytes(&bytes)
+    }
+}
+
+impl Serialize for Point {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_encoded(&self.to_bytes(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Point {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_encoded(deserializer, "a compressed SEC1 point", Point::from_bytes)
+    }
+}
+
+impl Serialize for PointJacobi {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_encoded(&self.to_bytes(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PointJacobi {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_encoded(
+            deserializer,
+            "a compressed SEC1 point",
+            PointJacobi::from_bytes,
+        )
+    }
+}
+
+impl Serialize for DLogProof {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_encoded(&self.to_bytes(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DLogProof {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_encoded(deserializer, "an encoded DLOG proof", DLogProof::from_bytes)
+    }
+}
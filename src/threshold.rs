@@ -0,0 +1,381 @@
+//! Threshold proofs: `t + 1` holders of Feldman shares of `x` (see
+//! [`crate::vss`]) jointly produce an ordinary [`DLogProof`] for `Y = x·G`
+//! without reconstructing `x`.
+//!
+//! The protocol takes two rounds among the participating set `S`, following
+//! FROST (Komlo and Goldberg, SAC 2020):
+//!
+//! 1. Each holder `i` samples two nonces `d_i`, `e_i` and broadcasts a
+//!    [`NonceCommitment`] `(D_i, E_i) = (d_i·G, e_i·G)`.
+//! 2. Once every commitment is in, each holder derives every binding factor
+//!    `ρ_j = H(sid, pid, Y, j, {(j, D_j, E_j)})` tagged
+//!    `"dlogproof/threshold/binding"`, the joint nonce
+//!    `T = Σ (D_j + ρ_j·E_j)` and the challenge `c = H(sid, pid, G, Y, T)`,
+//!    and sends the [`PartialProof`] `s_i = d_i + ρ_i·e_i + c·λ_i·x_i`, where
+//!    `x_i` is its share and `λ_i` its Lagrange coefficient for `S`.
+//!
+//! The combiner checks each `s_i·G == D_i + ρ_i·E_i + c·λ_i·X_i` against the
+//! share's public key `X_i` from the dealer's commitments, then outputs the
+//! proof `(T, Σ s_i)`, which [`crate::Verifier::verify`] accepts like any
+//! other.
+//!
+//! The binding factor ties each holder's effective nonce to the whole set of
+//! commitments and the statement, so a holder who sends its commitment last
+//! cannot steer `T` across concurrent sessions, as in the attack of Drijvers
+//! et al. (S&P 2019) on single-nonce schemes. A [`ThresholdProver`] holds its
+//! nonces only until it responds, so they can never answer two challenges.
+
+use crate::curve;
+use crate::error::ProofError;
+use crate::hash::{hash_tagged, DLOG_TAG};
+use crate::jacobi_point::PointJacobi;
+use crate::proof::DLogProof;
+use crate::prover::Prover;
+use crate::scalar::Scalar;
+use crate::secret::SecretScalar;
+use crate::vss::{check_indices, lagrange_at_zero, share_public_key};
+use ibig::IBig;
+
+const BINDING_TAG: &str = "dlogproof/threshold/binding";
+
+/// Round-one message: holder `index`'s nonce commitments `D_i = d_i·G` and
+/// `E_i = e_i·G`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonceCommitment {
+    pub index: u32,
+    pub d: PointJacobi,
+    pub e: PointJacobi,
+}
+
+/// Round-two message: holder `index`'s response
+/// `s_i = d_i + ρ_i·e_i + c·λ_i·x_i`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialProof {
+    pub index: u32,
+    pub s: IBig,
+}
+
+/// One share holder's side of the protocol.
+pub struct ThresholdProver {
+    index: u32,
+    share: SecretScalar,
+    nonce: Option<([SecretScalar; 2], NonceCommitment)>,
+}
+
+impl ThresholdProver {
+    /// `new` creates the prover for the share `f(index)`.
+    pub fn new(index: u32, share: &IBig) -> Self {
+        Self {
+            index,
+            share: SecretScalar::new(share),
+            nonce: None,
+        }
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// `commit` samples a fresh nonce pair, replacing any unused one, and
+    /// returns its commitment for broadcast.
+    pub fn commit(&mut self) -> NonceCommitment {
+        let d = Prover::generate_random_nonce();
+        let e = Prover::generate_random_nonce();
+        let commitment = NonceCommitment {
+            index: self.index,
+            d: PointJacobi::mul_generator(&d),
+            e: PointJacobi::mul_generator(&e),
+        };
+        self.nonce = Some((
+            [SecretScalar::new(&d), SecretScalar::new(&e)],
+            commitment.clone(),
+        ));
+        commitment
+    }
+
+    /// `respond` answers the joint challenge, consuming the nonce pair.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the participant id the joint proof is made for
+    /// * `y`: the shared public key `Y = x·G`
+    /// * `commitments`: the nonce commitments of every participating holder,
+    ///   including this one
+    ///
+    /// Returns:
+    ///
+    /// The partial proof, `ProofError::InvalidStatement` if there is no
+    /// unused nonce pair or this holder's commitment is missing from
+    /// `commitments`, or an error naming an index that is zero or repeated.
+    pub fn respond(
+        &mut self,
+        sid: &str,
+        pid: i32,
+        y: &PointJacobi,
+        commitments: &[NonceCommitment],
+    ) -> Result<PartialProof, ProofError> {
+        let indices = indices(commitments)?;
+        if !commitments.iter().any(|c| self.nonce_matches(c)) {
+            return Err(ProofError::InvalidStatement(format!(
+                "no current nonce commitment from holder {}",
+                self.index
+            )));
+        }
+        let (rhos, c, _) = challenges(sid, pid, y, commitments)?;
+        let rho = &rhos[indices
+            .iter()
+            .position(|i| *i == self.index)
+            .expect("checked by nonce_matches")];
+        let ([d, e], _) = self.nonce.take().expect("checked by nonce_matches");
+        let lambda = lagrange_at_zero(self.index, &indices);
+        let s = curve::rem_n(&(d.expose() + rho * e.expose() + c * lambda * self.share.expose()));
+        Ok(PartialProof {
+            index: self.index,
+            s,
+        })
+    }
+
+    fn nonce_matches(&self, commitment: &NonceCommitment) -> bool {
+        self.nonce
+            .as_ref()
+            .is_some_and(|(_, own)| own == commitment)
+    }
+}
+
+/// `combine` checks every partial proof and assembles the joint [`DLogProof`].
+///
+/// Arguments:
+///
+/// * `sid`: the session id
+/// * `pid`: the participant id the joint proof is made for
+/// * `vss_commitments`: the dealer's commitments `A_0..A_t`; `A_0` is the
+///   shared public key
+/// * `nonce_commitments`: the round-one messages
+/// * `partials`: the round-two messages, one per nonce commitment
+///
+/// Returns:
+///
+/// A proof for `A_0` under the generator, `ProofError::InsufficientShares` if
+/// fewer than `t + 1` holders took part, `ProofError::InvalidStatement` if the
+/// partial proofs do not match the nonce commitments one for one,
+/// `ProofError::InvalidShare` naming a holder whose partial proof is wrong, or
+/// an error naming an index that is zero or repeated.
+pub fn combine(
+    sid: &str,
+    pid: i32,
+    vss_commitments: &[PointJacobi],
+    nonce_commitments: &[NonceCommitment],
+    partials: &[PartialProof],
+) -> Result<DLogProof, ProofError> {
+    let y = vss_commitments
+        .first()
+        .ok_or(ProofError::InsufficientShares)?;
+    let indices = indices(nonce_commitments)?;
+    if indices.len() < vss_commitments.len() {
+        return Err(ProofError::InsufficientShares);
+    }
+    if partials.len() != nonce_commitments.len() {
+        return Err(ProofError::InvalidStatement(format!(
+            "{} partial proofs for {} nonce commitments",
+            partials.len(),
+            nonce_commitments.len()
+        )));
+    }
+    let g = PointJacobi::generator();
+    let (rhos, c, t) = challenges(sid, pid, y, nonce_commitments)?;
+    let mut s = IBig::from(0);
+    for (commitment, rho) in nonce_commitments.iter().zip(&rhos) {
+        let partial = partials
+            .iter()
+            .find(|p| p.index == commitment.index)
+            .ok_or_else(|| {
+                ProofError::InvalidStatement(format!(
+                    "no partial proof from holder {}",
+                    commitment.index
+                ))
+            })?;
+        if partial.s < IBig::from(0) || partial.s >= *curve::N {
+            return Err(ProofError::InvalidShare(partial.index));
+        }
+        let lambda = lagrange_at_zero(partial.index, &indices);
+        let x_i = share_public_key(partial.index, vss_commitments);
+        let expected = commitment
+            .d
+            .add(&commitment.e.mul_unsafe(rho))
+            .add(&x_i.mul_unsafe(&(&c * lambda)));
+        if g.mul_unsafe(&partial.s) != expected {
+            return Err(ProofError::InvalidShare(partial.index));
+        }
+        s = curve::rem_n(&(s + &partial.s));
+    }
+    Ok(DLogProof::new(t, Scalar::from_reduced(s)))
+}
+
+fn indices(commitments: &[NonceCommitment]) -> Result<Vec<u32>, ProofError> {
+    if commitments.is_empty() {
+        return Err(ProofError::InsufficientShares);
+    }
+    let indices: Vec<u32> = commitments.iter().map(|c| c.index).collect();
+    check_indices(&indices)?;
+    Ok(indices)
+}
+
+/// The binding factor `ρ_j` of each commitment in order, the challenge of the
+/// joint proof, exactly as [`Prover::prove`] derives it, and the joint nonce
+/// `T`.
+fn challenges(
+    sid: &str,
+    pid: i32,
+    y: &PointJacobi,
+    commitments: &[NonceCommitment],
+) -> Result<(Vec<IBig>, IBig, PointJacobi), ProofError> {
+    let mut points = vec![y];
+    let mut indices = vec![];
+    for commitment in commitments {
+        points.extend([&commitment.d, &commitment.e]);
+        indices.extend(commitment.index.to_be_bytes());
+    }
+    let mut rhos = Vec::with_capacity(commitments.len());
+    let mut t = PointJacobi::zero();
+    for commitment in commitments {
+        let mut aux = commitment.index.to_be_bytes().to_vec();
+        aux.extend(&indices);
+        let rho = hash_tagged(BINDING_TAG, sid, pid, &points, &aux)?;
+        t = t.add(&commitment.d).add(&commitment.e.mul_unsafe(&rho));
+        rhos.push(rho);
+    }
+    let c = hash_tagged(DLOG_TAG, sid, pid, &[&PointJacobi::generator(), y, &t], &[])?;
+    Ok((rhos, c, t))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::Verifier;
+    use crate::vss::Polynomial;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// A degree-`degree` sharing of a random secret among holders `1..=count`.
+    fn deal(degree: usize, count: u32) -> (Vec<PointJacobi>, Vec<ThresholdProver>) {
+        let mut rng = StdRng::seed_from_u64(1033);
+        let secret = curve::random_scalar(&mut rng);
+        let polynomial = Polynomial::random(degree, &secret, &mut rng);
+        let holders = (1..=count)
+            .map(|i| ThresholdProver::new(i, &polynomial.share(i).unwrap()))
+            .collect();
+        (polynomial.commitments(), holders)
+    }
+
+    fn run(
+        holders: &mut [ThresholdProver],
+        y: &PointJacobi,
+    ) -> (Vec<NonceCommitment>, Vec<PartialProof>) {
+        let commitments: Vec<NonceCommitment> = holders.iter_mut().map(|h| h.commit()).collect();
+        let partials = holders
+            .iter_mut()
+            .map(|h| h.respond("threshold", 7, y, &commitments).unwrap())
+            .collect();
+        (commitments, partials)
+    }
+
+    #[test]
+    fn any_t_plus_one_holders_produce_a_verifying_proof() {
+        let (vss_commitments, mut holders) = deal(2, 5);
+        let y = vss_commitments[0].clone();
+        for subset in [[0, 1, 2], [1, 3, 4], [0, 2, 4]] {
+            let mut chosen: Vec<ThresholdProver> = subset
+                .iter()
+                .map(|k| ThresholdProver::new(holders[*k].index, &holders[*k].share.expose()))
+                .collect();
+            let (commitments, partials) = run(&mut chosen, &y);
+            let proof = combine("threshold", 7, &vss_commitments, &commitments, &partials).unwrap();
+            assert_eq!(
+                Verifier::verify(&proof, "threshold", 7, &y, &PointJacobi::generator()),
+                Ok(())
+            );
+            assert!(
+                Verifier::verify(&proof, "threshold", 8, &y, &PointJacobi::generator()).is_err()
+            );
+        }
+        let (commitments, partials) = run(&mut holders, &y);
+        let proof = combine("threshold", 7, &vss_commitments, &commitments, &partials).unwrap();
+        assert_eq!(
+            Verifier::verify(&proof, "threshold", 7, &y, &PointJacobi::generator()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn a_wrong_partial_proof_names_its_holder() {
+        let (vss_commitments, mut holders) = deal(1, 3);
+        let y = vss_commitments[0].clone();
+        let (commitments, mut partials) = run(&mut holders, &y);
+        partials[1].s = curve::rem_n(&(&partials[1].s + 1));
+        assert_eq!(
+            combine("threshold", 7, &vss_commitments, &commitments, &partials),
+            Err(ProofError::InvalidShare(2))
+        );
+        partials[1].s = &*curve::N + 1;
+        assert_eq!(
+            combine("threshold", 7, &vss_commitments, &commitments, &partials),
+            Err(ProofError::InvalidShare(2))
+        );
+    }
+
+    #[test]
+    fn each_partial_proof_is_bound_to_every_commitment() {
+        // Swapping a holder's nonce pair for another pair with the same sum
+        // leaves Σ (D_j + E_j) unchanged but not the binding factors.
+        let (vss_commitments, mut holders) = deal(1, 2);
+        let y = vss_commitments[0].clone();
+        let (mut commitments, partials) = run(&mut holders, &y);
+        let g = PointJacobi::generator();
+        commitments[0].d = commitments[0].d.add(&g);
+        commitments[0].e = commitments[0].e.add(&g.negate());
+        assert_eq!(
+            combine("threshold", 7, &vss_commitments, &commitments, &partials),
+            Err(ProofError::InvalidShare(1))
+        );
+    }
+
+    #[test]
+    fn too_few_holders_cannot_combine() {
+        let (vss_commitments, mut holders) = deal(2, 5);
+        let y = vss_commitments[0].clone();
+        let (commitments, partials) = run(&mut holders[..2], &y);
+        assert_eq!(
+            combine("threshold", 7, &vss_commitments, &commitments, &partials),
+            Err(ProofError::InsufficientShares)
+        );
+        assert_eq!(
+            combine("threshold", 7, &vss_commitments, &[], &[]),
+            Err(ProofError::InsufficientShares)
+        );
+    }
+
+    #[test]
+    fn a_nonce_pair_answers_one_challenge() {
+        let (vss_commitments, mut holders) = deal(1, 2);
+        let y = vss_commitments[0].clone();
+        let commitments: Vec<NonceCommitment> = holders.iter_mut().map(|h| h.commit()).collect();
+        holders[0]
+            .respond("threshold", 7, &y, &commitments)
+            .unwrap();
+        assert!(matches!(
+            holders[0].respond("threshold", 8, &y, &commitments),
+            Err(ProofError::InvalidStatement(_))
+        ));
+
+        // A fresh pair replaces the old one, which can no longer be answered.
+        let fresh = holders[1].commit();
+        assert_ne!(fresh, commitments[1]);
+        assert!(matches!(
+            holders[1].respond("threshold", 7, &y, &commitments),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        let commitments = [commitments[0].clone(), fresh];
+        assert!(holders[1].respond("threshold", 7, &y, &commitments).is_ok());
+    }
+}
@@ -0,0 +1,167 @@
+//! An incremental builder for the version 4 transcript of [`crate::hash`].
+//!
+//! A [`Transcript`] is started with the protocol tag, session id and
+//! participant id, and points and application data are appended to it one at
+//! a time instead of being gathered into a slice and a hand-concatenated
+//! `aux`. Points fill the transcript's `points` field in the order they are
+//! appended. Messages and scalars go to `aux`, each framed as
+//!
+//! ```text
+//! label_len: u32 BE | label | value_len: u32 BE | value
+//! ```
+//!
+//! with scalars as their 32-byte big-endian encoding, so that no two
+//! sequences of appends produce the same transcript. A transcript with
+//! nothing but points appended hashes to exactly the challenge of
+//! [`hash_tagged`](crate::hash::hash_tagged) over those points.
+//!
+//! [`Prover::prove_transcript`](crate::Prover::prove_transcript) and
+//! [`Verifier::verify_transcript`](crate::Verifier::verify_transcript) prove
+//! and check a discrete-log statement bound to whatever a transcript already
+//! holds. Start such a transcript with a tag of the application's own rather
+//! than [`DLOG_TAG`](crate::hash::DLOG_TAG), so that its proofs are never
+//! plain or time-bound proofs of the same key.
+
+use crate::curve::{self, Curve, Secp256k1};
+use crate::error::ProofError;
+use crate::hash::{
+    length_prefix, ChallengeHash, Sha256, ID_TRANSCRIPT_VERSION, TAGGED_TRANSCRIPT_VERSION,
+};
+use crate::jacobi_point::PointJacobi;
+use ibig::IBig;
+use std::marker::PhantomData;
+
+/// A version 4 transcript under construction, over points of the curve `C`.
+#[derive(Debug, Clone)]
+pub struct Transcript<C: Curve = Secp256k1> {
+    header: Vec<u8>,
+    count: usize,
+    points: Vec<u8>,
+    aux: Vec<u8>,
+    /// Whether every field so far fitted its `u32` length prefix.
+    fits: bool,
+    curve: PhantomData<C>,
+}
+
+impl<C: Curve> Transcript<C> {
+    /// `new` starts a transcript for the protocol named by `tag`, in session
+    /// `sid` for participant `pid`.
+    pub fn new(tag: &str, sid: &str, pid: i32) -> Self {
+        let mut header = Vec::with_capacity(13 + tag.len() + sid.len());
+        header.push(TAGGED_TRANSCRIPT_VERSION);
+        let fits =
+            extend_framed(&mut header, tag.as_bytes()) & extend_framed(&mut header, sid.as_bytes());
+        header.extend(pid.to_be_bytes());
+        Self {
+            header,
+            count: 0,
+            points: Vec::new(),
+            aux: Vec::new(),
+            fits,
+            curve: PhantomData,
+        }
+    }
+
+    /// `with_ids` starts a version 5 transcript, for a session and participant
+    /// identified by arbitrary bytes, e.g. a 32-byte hash and the 16 bytes of
+    /// a UUID. It never equals a transcript started by [`Transcript::new`].
+    pub fn with_ids(tag: &str, sid: &[u8], pid: &[u8]) -> Self {
+        let mut header = Vec::with_capacity(13 + tag.len() + sid.len() + pid.len());
+        header.push(ID_TRANSCRIPT_VERSION);
+        let fits = extend_framed(&mut header, tag.as_bytes())
+            & extend_framed(&mut header, sid)
+            & extend_framed(&mut header, pid);
+        Self {
+            header,
+            count: 0,
+            points: Vec::new(),
+            aux: Vec::new(),
+            fits,
+            curve: PhantomData,
+        }
+    }
+
+    /// `append_point` appends `point` to the transcript's points.
+    pub fn append_point(&mut self, point: &PointJacobi<C>) -> &mut Self {
+        self.count += 1;
+        self.points.extend(point.to_bytes());
+        self
+    }
+
+    /// `append_message` appends `message` under `label` to the transcript's
+    /// `aux`.
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) -> &mut Self {
+        self.fits &= extend_framed(&mut self.aux, label) & extend_framed(&mut self.aux, message);
+        self
+    }
+
+    /// `append_scalar` appends `scalar`, reduced modulo n, under `label` to the
+    /// transcript's `aux`.
+    pub fn append_scalar(&mut self, label: &[u8], scalar: &IBig) -> &mut Self {
+        self.append_message(label, &curve::to_be_bytes(&C::rem_n(scalar)))
+    }
+
+    /// Appends raw bytes to `aux`, for the statement data of time-bound proofs
+    /// that predates framed messages.
+    pub(crate) fn append_aux(&mut self, aux: &[u8]) -> &mut Self {
+        self.aux.extend(aux);
+        self
+    }
+
+    /// `to_bytes` returns the transcript as it stands, the exact bytes
+    /// [`Transcript::challenge_scalar`] hashes.
+    ///
+    /// Returns:
+    ///
+    /// The transcript bytes, or `ProofError::InvalidStatement` if any field
+    /// given so far, or `aux` as a whole, is longer than `u32::MAX` bytes.
+    /// Appends stay chainable by deferring that error to here.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ProofError> {
+        if !self.fits {
+            return Err(ProofError::InvalidStatement(
+                "transcript field longer than u32::MAX bytes".to_string(),
+            ));
+        }
+        let mut transcript =
+            Vec::with_capacity(self.header.len() + self.points.len() + self.aux.len() + 8);
+        transcript.extend(&self.header);
+        transcript.extend(length_prefix(self.count)?);
+        transcript.extend(&self.points);
+        transcript.extend(length_prefix(self.aux.len())?);
+        transcript.extend(&self.aux);
+        Ok(transcript)
+    }
+
+    /// `challenge_scalar` returns the SHA-256 challenge of the transcript,
+    /// reduced modulo n, or the errors of [`Transcript::to_bytes`].
+    pub fn challenge_scalar(&self) -> Result<IBig, ProofError> {
+        self.challenge_scalar_with::<Sha256>()
+    }
+
+    /// `challenge_scalar_with` is [`Transcript::challenge_scalar`] with the
+    /// challenge hash `H` in place of SHA-256.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "dlogproof.hash_points",
+            skip_all,
+            fields(points = self.count, aux_len = self.aux.len())
+        )
+    )]
+    pub fn challenge_scalar_with<H: ChallengeHash>(&self) -> Result<IBig, ProofError> {
+        Ok(C::rem_n(&H::challenge(&self.to_bytes()?)))
+    }
+}
+
+/// Appends `bytes` to `out` behind its length prefix, returning whether the
+/// length fitted the prefix; if not, `out` is left unchanged.
+fn extend_framed(out: &mut Vec<u8>, bytes: &[u8]) -> bool {
+    match length_prefix(bytes.len()) {
+        Ok(prefix) => {
+            out.extend(prefix);
+            out.extend(bytes);
+            true
+        }
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,770 @@
+use crate::and_proof::AndProof;
+use crate::context::ProofContext;
+use crate::curve::{self, Curve, Secp256k1};
+use crate::error::ProofError;
+#[cfg(feature = "evm")]
+use crate::evm;
+use crate::hash::{
+    self, challenge_from_transcript, compute_challenge, hash_tagged, legacy_challenge_transcript,
+    ChallengeHash, TranscriptEncoding, DLOG_MESSAGE_TAG, DLOG_TAG,
+};
+use crate::jacobi_point::{FixedBaseTable, PointJacobi};
+use crate::keys::PublicKey;
+#[cfg(feature = "merlin")]
+use crate::merlin_transcript;
+use crate::proof::{DLogProof, ShortDLogProof, TimedDLogProof};
+use crate::transcript::Transcript;
+use ibig::IBig;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::marker::PhantomData;
+
+/// Checks [`DLogProof`]s over the curve `C`. Time-bound, short, legacy and
+/// vector proofs are only checked on [`Secp256k1`].
+pub struct Verifier<C: Curve = Secp256k1>(PhantomData<C>);
+
+impl<C: Curve> Verifier<C> {
+    /// > The function verifies that the point `t` is the sum of the base point multiplied by `s` and
+    /// > the point `y` multiplied by the hash of the inputs
+    ///
+    /// Arguments:
+    ///
+    /// * `proof`: the proof to check
+    /// * `sid`: the session id
+    /// * `pid`: the id of the prover
+    /// * `y`: the public key
+    /// * `base_point`: the base point of the group
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid, `ProofError::VerificationFailed` otherwise.
+    /// Malformed inputs are rejected first, see [`Verifier::validate`].
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "dlogproof.verify",
+            skip_all,
+            fields(sid_len = sid.len(), pid = pid),
+            err(Debug)
+        )
+    )]
+    pub fn verify(
+        proof: &DLogProof<C>,
+        sid: &str,
+        pid: i32,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<(), ProofError> {
+        Self::verify_with_aux::<hash::Sha256>(proof, sid, pid, y, base_point, &[])
+    }
+
+    /// `verify_with_hash` is [`Verifier::verify`] for a proof made with
+    /// [`Prover::prove_with_hash`] over the challenge hash `H`.
+    ///
+    /// [`Prover::prove_with_hash`]: crate::Prover::prove_with_hash
+    pub fn verify_with_hash<H: ChallengeHash>(
+        proof: &DLogProof<C>,
+        sid: &str,
+        pid: i32,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<(), ProofError> {
+        Self::verify_with_aux::<H>(proof, sid, pid, y, base_point, &[])
+    }
+
+    /// `verify_with_encoding` is [`Verifier::verify`] for a proof whose
+    /// challenge was computed over the transcript `encoding`, such as an
+    /// untagged proof made by an older release; see [`crate::hash`]. Only use
+    /// an encoding other than `TranscriptEncoding::Tagged` for proofs known to
+    /// come from such a release.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid, `ProofError::VerificationFailed` otherwise.
+    /// Malformed inputs are rejected first, see [`Verifier::validate`].
+    pub fn verify_with_encoding(
+        proof: &DLogProof<C>,
+        sid: &str,
+        pid: i32,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+        encoding: TranscriptEncoding,
+    ) -> Result<(), ProofError> {
+        Self::validate(proof, y, base_point)?;
+        let points = [base_point.clone(), y.clone(), proof.t.clone()];
+        let c = match encoding {
+            TranscriptEncoding::Tagged => {
+                hash_tagged(DLOG_TAG, sid, pid, &[base_point, y, &proof.t], &[])?
+            }
+            TranscriptEncoding::Framed => compute_challenge(sid, pid, &points)?,
+            TranscriptEncoding::Legacy => {
+                challenge_from_transcript(&legacy_challenge_transcript(sid, pid, &points, &[]))
+            }
+        };
+        Self::check_equation(proof, &c, y, base_point)
+    }
+
+    /// `verify_with_transcript` checks a proof made by
+    /// [`Prover::prove_with_transcript`] against a transcript in the state the
+    /// prover's was in, appending `(sid, pid, G, y, t)` to it.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid and the errors of [`Verifier::verify`]
+    /// otherwise. `transcript` is not modified when the inputs are rejected.
+    ///
+    /// [`Prover::prove_with_transcript`]: crate::Prover::prove_with_transcript
+    #[cfg(feature = "merlin")]
+    pub fn verify_with_transcript(
+        proof: &DLogProof<C>,
+        transcript: &mut merlin::Transcript,
+        sid: &str,
+        pid: i32,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<(), ProofError> {
+        Self::validate(proof, y, base_point)?;
+        let c = merlin_transcript::challenge(transcript, sid, pid, base_point, y, &proof.t);
+        Self::check_equation(proof, &c, y, base_point)
+    }
+
+    /// `verify_transcript` checks a proof made by
+    /// [`Prover::prove_transcript`] against a transcript holding the same
+    /// appends as the prover's.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid and the errors of [`Verifier::verify`]
+    /// otherwise.
+    ///
+    /// [`Prover::prove_transcript`]: crate::Prover::prove_transcript
+    pub fn verify_transcript(
+        proof: &DLogProof<C>,
+        transcript: &Transcript<C>,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<(), ProofError> {
+        Self::validate(proof, y, base_point)?;
+        Self::verify_over::<hash::Sha256>(proof, transcript.clone(), y, base_point)
+    }
+
+    /// `verify_in_context` checks a proof made by
+    /// [`Prover::prove_in_context`] in an equal context.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid and the errors of [`Verifier::verify`]
+    /// otherwise.
+    ///
+    /// [`Prover::prove_in_context`]: crate::Prover::prove_in_context
+    pub fn verify_in_context(
+        proof: &DLogProof<C>,
+        context: &ProofContext,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<(), ProofError> {
+        Self::verify_transcript(proof, &context.transcript(), y, base_point)
+    }
+
+    /// `verify_with_ids` checks a proof made by [`Prover::prove_with_ids`]
+    /// for the same byte identifiers.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid and the errors of [`Verifier::verify`]
+    /// otherwise.
+    ///
+    /// [`Prover::prove_with_ids`]: crate::Prover::prove_with_ids
+    pub fn verify_with_ids(
+        proof: &DLogProof<C>,
+        sid: &[u8],
+        pid: &[u8],
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<(), ProofError> {
+        Self::verify_transcript(
+            proof,
+            &Transcript::with_ids(DLOG_TAG, sid, pid),
+            y,
+            base_point,
+        )
+    }
+
+    /// `verify_with_message` checks a proof made by
+    /// [`Prover::prove_with_message`] over the same `message`.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid for `message` and the errors of
+    /// [`Verifier::verify`] otherwise.
+    ///
+    /// [`Prover::prove_with_message`]: crate::Prover::prove_with_message
+    pub fn verify_with_message(
+        proof: &DLogProof<C>,
+        sid: &str,
+        pid: i32,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+        message: &[u8],
+    ) -> Result<(), ProofError> {
+        let mut transcript = Transcript::new(DLOG_MESSAGE_TAG, sid, pid);
+        transcript.append_message(b"message", message);
+        Self::verify_transcript(proof, &transcript, y, base_point)
+    }
+
+    /// `verify_x_only` is [`Verifier::verify`] for a statement given as a
+    /// 32-byte x-only public key, which stands for the point with that `x`
+    /// and an even `y`; see [`Prover::prove_x_only`].
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid, a `DeserializationError` if `y` is not
+    /// the x coordinate of a point, and the errors of [`Verifier::verify`]
+    /// otherwise.
+    ///
+    /// [`Prover::prove_x_only`]: crate::Prover::prove_x_only
+    pub fn verify_x_only(
+        proof: &DLogProof<C>,
+        sid: &str,
+        pid: i32,
+        y: &[u8],
+        base_point: &PointJacobi<C>,
+    ) -> Result<(), ProofError> {
+        let y = PointJacobi::from_x_only_bytes(y)?;
+        Self::verify(proof, sid, pid, &y, base_point)
+    }
+
+    fn verify_with_aux<H: ChallengeHash>(
+        proof: &DLogProof<C>,
+        sid: &str,
+        pid: i32,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+        aux: &[u8],
+    ) -> Result<(), ProofError> {
+        Self::validate(proof, y, base_point)?;
+        let mut transcript = Transcript::new(DLOG_TAG, sid, pid);
+        transcript.append_aux(aux);
+        Self::verify_over::<H>(proof, transcript, y, base_point)
+    }
+
+    fn verify_over<H: ChallengeHash>(
+        proof: &DLogProof<C>,
+        mut transcript: Transcript<C>,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<(), ProofError> {
+        let c = transcript
+            .append_point(base_point)
+            .append_point(y)
+            .append_point(&proof.t)
+            .challenge_scalar_with::<H>()?;
+        Self::check_equation(proof, &c, y, base_point)
+    }
+
+    /// `validate` rejects a proof or statement that no honest prover produces,
+    /// before any curve arithmetic is done on it.
+    ///
+    /// Returns:
+    ///
+    /// * `ProofError::IdentityCommitment` if `t` is the point at infinity
+    /// * `ProofError::PointNotOnCurve` if `y`, `base_point` or `t` is off the curve
+    /// * `ProofError::InvalidPoint` if `y` or `base_point` is the point at
+    ///   infinity, or `y` equals `base_point`
+    pub fn validate(
+        proof: &DLogProof<C>,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<(), ProofError> {
+        if proof.t.is_zero() {
+            return Err(ProofError::IdentityCommitment);
+        }
+        Self::validate_points(y, base_point)?;
+        if !proof.t.is_on_curve() {
+            return Err(ProofError::PointNotOnCurve);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn validate_points(
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<(), ProofError> {
+        if !y.is_on_curve() || !base_point.is_on_curve() {
+            return Err(ProofError::PointNotOnCurve);
+        }
+        if y.is_zero() || base_point.is_zero() || y == base_point {
+            return Err(ProofError::InvalidPoint);
+        }
+        Ok(())
+    }
+
+    /// A zero challenge reduces the equation to `s·G == t`, which anyone can
+    /// satisfy without the secret.
+    fn validate_challenge(c: &IBig) -> Result<(), ProofError> {
+        if C::rem_n(c) == IBig::from(0) {
+            Err(ProofError::InvalidScalar)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_equation(
+        proof: &DLogProof<C>,
+        c: &IBig,
+        y: &PointJacobi<C>,
+        base_point: &PointJacobi<C>,
+    ) -> Result<(), ProofError> {
+        Self::validate_challenge(c)?;
+        // s·G == t + c·y, checked as s·G - c·y == t with one double-base
+        // multiplication.
+        let lhs = base_point.mul_double_base(proof.s.value(), y, &C::rem_n(&-c));
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            holds = lhs == proof.t,
+            "verification equation s·G == t + c·y"
+        );
+        if lhs == proof.t {
+            Ok(())
+        } else {
+            Err(ProofError::VerificationFailed)
+        }
+    }
+
+    /// `verify_batch` checks many proofs made in the same session over the same
+    /// base point, stopping at the first one that fails. Public keys and
+    /// commitments are first converted to affine form together, with
+    /// [`PointJacobi::batch_to_affine`], and `base_point` is normalized once,
+    /// so hashing them needs no further inversions.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id shared by every proof
+    /// * `base_point`: the base point shared by every proof
+    /// * `entries`: `(pid, public key, proof)` triples
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if every proof is valid, the first error otherwise.
+    pub fn verify_batch(
+        sid: &str,
+        base_point: &PointJacobi<C>,
+        entries: &[(i32, PointJacobi<C>, DLogProof<C>)],
+    ) -> Result<(), ProofError> {
+        let mut base_point = base_point.clone();
+        base_point.normalize();
+        normalize_entries(entries)
+            .iter()
+            .try_for_each(|(pid, y, proof)| Self::verify(proof, sid, *pid, y, &base_point))
+    }
+
+    /// `verify_batch_parallel` is [`Verifier::verify_batch`] spread across
+    /// rayon's global thread pool. Workers stop picking up new proofs once one
+    /// fails, and the error returned is that of the first failing entry in
+    /// order, as with `verify_batch`.
+    #[cfg(feature = "parallel")]
+    pub fn verify_batch_parallel(
+        sid: &str,
+        base_point: &PointJacobi<C>,
+        entries: &[(i32, PointJacobi<C>, DLogProof<C>)],
+    ) -> Result<(), ProofError> {
+        let mut base_point = base_point.clone();
+        base_point.normalize();
+        normalize_entries(entries)
+            .par_iter()
+            .map(|(pid, y, proof)| Self::verify(proof, sid, *pid, y, &base_point))
+            .find_first(Result::is_err)
+            .unwrap_or(Ok(()))
+    }
+}
+
+impl Verifier {
+    /// `verify_with_key` checks a proof made by
+    /// [`crate::Prover::prove_with_key`] for `public_key`.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid and the errors of [`Verifier::verify`]
+    /// otherwise.
+    pub fn verify_with_key(
+        proof: &DLogProof,
+        sid: &str,
+        pid: i32,
+        public_key: &PublicKey,
+    ) -> Result<(), ProofError> {
+        Self::verify(
+            proof,
+            sid,
+            pid,
+            public_key.point(),
+            &PointJacobi::generator(),
+        )
+    }
+
+    /// `verify_with_validity` checks a proof made by [`crate::Prover::prove_with_validity`]
+    /// at time `now`, on the same clock the prover used.
+    ///
+    /// Arguments:
+    ///
+    /// * `proof`: the time-bound proof to check
+    /// * `sid`: the session id
+    /// * `pid`: the id of the prover
+    /// * `y`: the public key
+    /// * `base_point`: the base point of the group
+    /// * `now`: the current time in seconds
+    ///
+    /// Returns:
+    ///
+    /// `ProofError::NotYetValid` if `now < issued_at`, `ProofError::Expired` if
+    /// `now > issued_at + ttl_secs`, otherwise the result of verifying the proof
+    /// against a challenge that covers both timestamps.
+    pub fn verify_with_validity(
+        proof: &TimedDLogProof,
+        sid: &str,
+        pid: i32,
+        y: &PointJacobi,
+        base_point: &PointJacobi,
+        now: u64,
+    ) -> Result<(), ProofError> {
+        if now < proof.issued_at {
+            return Err(ProofError::NotYetValid);
+        }
+        if now > proof.expires_at() {
+            return Err(ProofError::Expired);
+        }
+        let aux = TimedDLogProof::validity_aux(proof.issued_at, proof.ttl_secs);
+        Self::verify_with_aux::<hash::Sha256>(&proof.proof, sid, pid, y, base_point, &aux)
+    }
+
+    /// `verify_short` checks a compressed proof by recomputing `t' = s·G - c·y`
+    /// and comparing `H(sid, pid, G, y, t')` against `c`.
+    ///
+    /// Arguments:
+    ///
+    /// * `proof`: the short proof to check
+    /// * `sid`: the session id
+    /// * `pid`: the id of the prover
+    /// * `y`: the public key
+    /// * `base_point`: the base point of the group
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid, `ProofError::VerificationFailed` otherwise.
+    /// A `c` or `s` not reduced modulo n is `ProofError::ScalarOutOfRange`, and
+    /// an off-curve `y` or `base_point` is `ProofError::PointNotOnCurve`, a weak
+    /// one `ProofError::InvalidPoint`, and a zero `c` `ProofError::InvalidScalar`.
+    pub fn verify_short(
+        proof: &ShortDLogProof,
+        sid: &str,
+        pid: i32,
+        y: &PointJacobi,
+        base_point: &PointJacobi,
+    ) -> Result<(), ProofError> {
+        if proof.c >= *curve::N || proof.s >= *curve::N {
+            return Err(ProofError::ScalarOutOfRange);
+        }
+        Self::validate_points(y, base_point)?;
+        Self::validate_challenge(&proof.c)?;
+        proof.expand(sid, pid, y, base_point).map(|_| ())
+    }
+
+    /// `verify_legacy` checks a proof made by a release that used the unframed
+    /// version 1 transcript (see [`crate::hash`]). Only use it for proofs known
+    /// to predate the framed transcript.
+    ///
+    /// Arguments:
+    ///
+    /// * `proof`: the proof to check
+    /// * `sid`: the session id
+    /// * `pid`: the id of the prover
+    /// * `y`: the public key
+    /// * `base_point`: the base point of the group
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid, `ProofError::VerificationFailed` otherwise.
+    /// Malformed inputs are rejected first, see [`Verifier::validate`].
+    pub fn verify_legacy(
+        proof: &DLogProof,
+        sid: &str,
+        pid: i32,
+        y: &PointJacobi,
+        base_point: &PointJacobi,
+    ) -> Result<(), ProofError> {
+        Self::verify_with_encoding(proof, sid, pid, y, base_point, TranscriptEncoding::Legacy)
+    }
+
+    /// `verify_evm` checks a proof made by [`Prover::prove_evm`], as the
+    /// contract in [`crate::evm`] does.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid, `ProofError::VerificationFailed`
+    /// otherwise. Malformed inputs are rejected first, see
+    /// [`Verifier::validate`], and a `y` whose x coordinate is not below n is
+    /// `ProofError::InvalidPoint`.
+    ///
+    /// [`Prover::prove_evm`]: crate::Prover::prove_evm
+    #[cfg(feature = "evm")]
+    pub fn verify_evm(
+        proof: &DLogProof,
+        sid: &str,
+        pid: i32,
+        y: &PointJacobi,
+    ) -> Result<(), ProofError> {
+        let g = PointJacobi::generator();
+        Self::validate(proof, y, &g)?;
+        evm::validate_public_key(y)?;
+        let c = evm::challenge(sid, pid, y, &proof.t)?;
+        Self::check_equation(proof, &c, y, &g)
+    }
+
+    /// `verify_vector` checks a proof made by [`Prover::prove_vector`].
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid, `ProofError::InvalidPoint` or
+    /// `ProofError::PointNotOnCurve` for a weak or malformed key, and the
+    /// errors of [`AndProof::verify`] otherwise.
+    ///
+    /// [`Prover::prove_vector`]: crate::Prover::prove_vector
+    pub fn verify_vector(
+        proof: &AndProof,
+        sid: &str,
+        pid: i32,
+        ys: &[PointJacobi],
+        base_point: &PointJacobi,
+    ) -> Result<(), ProofError> {
+        for y in ys {
+            Self::validate_points(y, base_point)?;
+        }
+        proof.verify(sid, pid, ys, base_point)
+    }
+}
+
+/// `normalize_entries` returns `entries` with every public key and
+/// commitment in affine form, converted with one shared inversion.
+fn normalize_entries<C: Curve>(
+    entries: &[(i32, PointJacobi<C>, DLogProof<C>)],
+) -> Vec<(i32, PointJacobi<C>, DLogProof<C>)> {
+    let points: Vec<PointJacobi<C>> = entries
+        .iter()
+        .flat_map(|(_, y, proof)| [y.clone(), proof.t.clone()])
+        .collect();
+    let affine = PointJacobi::batch_to_affine(&points);
+    entries
+        .iter()
+        .zip(affine.chunks_exact(2))
+        .map(|((pid, _, proof), pair)| {
+            let y = PointJacobi::from_affine(pair[0].clone());
+            let t = PointJacobi::from_affine(pair[1].clone());
+            (*pid, y, DLogProof::new(t, proof.s.clone()))
+        })
+        .collect()
+}
+
+/// A verifier bound to one public key and base point, for checking many
+/// proofs against the same statement.
+///
+/// Construction precomputes [`FixedBaseTable`]s for `base_point` and `y`, so
+/// each [`VerifierContext::verify`] replaces both scalar multiplications with
+/// table lookups. The context is `Send + Sync` and can be shared behind an
+/// `Arc`; cloning it copies the tables.
+#[derive(Debug, Clone)]
+pub struct VerifierContext {
+    y: PointJacobi,
+    base_point: PointJacobi,
+    y_table: FixedBaseTable,
+    base_table: FixedBaseTable,
+}
+
+impl VerifierContext {
+    /// `new` builds the tables and normalizes both points, so hashing them
+    /// in each [`VerifierContext::verify`] needs no inversion.
+    pub fn new(y: &PointJacobi, base_point: &PointJacobi) -> Self {
+        let (mut y, mut base_point) = (y.clone(), base_point.clone());
+        y.normalize();
+        base_point.normalize();
+        Self {
+            y_table: FixedBaseTable::new(&y),
+            base_table: FixedBaseTable::new(&base_point),
+            y,
+            base_point,
+        }
+    }
+
+    pub fn public_key(&self) -> &PointJacobi {
+        &self.y
+    }
+
+    pub fn base_point(&self) -> &PointJacobi {
+        &self.base_point
+    }
+
+    /// `verify` is [`Verifier::verify`] against the context's public key and
+    /// base point, and accepts exactly the same proofs.
+    ///
+    /// Arguments:
+    ///
+    /// * `proof`: the proof to check
+    /// * `sid`: the session id
+    /// * `pid`: the id of the prover
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the proof is valid, `ProofError::VerificationFailed` otherwise.
+    /// Malformed inputs are rejected first, see [`Verifier::validate`].
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "dlogproof.verify",
+            skip_all,
+            fields(sid_len = sid.len(), pid = pid),
+            err(Debug)
+        )
+    )]
+    pub fn verify(&self, proof: &DLogProof, sid: &str, pid: i32) -> Result<(), ProofError> {
+        Verifier::validate(proof, &self.y, &self.base_point)?;
+        let c = hash_tagged(
+            DLOG_TAG,
+            sid,
+            pid,
+            &[&self.base_point, &self.y, &proof.t],
+            &[],
+        )?;
+        Verifier::<Secp256k1>::validate_challenge(&c)?;
+        let lhs = self.base_table.mul(proof.s.value());
+        let rhs = proof.t.add(&self.y_table.mul(&c));
+        #[cfg(feature = "tracing")]
+        tracing::debug!(holds = lhs == rhs, "verification equation s·G == t + c·y");
+        if lhs == rhs {
+            Ok(())
+        } else {
+            Err(ProofError::VerificationFailed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prover::Prover;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn verifier_context_matches_the_stateless_verifier() {
+        let mut rng = StdRng::seed_from_u64(849);
+        let g = PointJacobi::generator();
+        let x = curve::random_scalar(&mut rng);
+        let y = g.mul_unsafe(&x);
+        let context = VerifierContext::new(&y, &g);
+        let other_t = g.mul_unsafe(&curve::random_scalar(&mut rng)).to_bytes();
+        for pid in 0..32 {
+            let proof = Prover::prove("context", pid, &x, &y, &g).unwrap();
+            let mut bytes = proof.to_bytes();
+            bytes[DLogProof::BYTES - 1] ^= 1;
+            let tampered_s = DLogProof::from_bytes(&bytes).unwrap();
+            let mut bytes = proof.to_bytes();
+            bytes[..other_t.len()].copy_from_slice(&other_t);
+            let tampered_t = DLogProof::from_bytes(&bytes).unwrap();
+            for (proof, claimed_pid) in [
+                (&proof, pid),
+                (&proof, pid + 1),
+                (&tampered_s, pid),
+                (&tampered_t, pid),
+            ] {
+                let stateless = Verifier::verify(proof, "context", claimed_pid, &y, &g);
+                assert_eq!(context.verify(proof, "context", claimed_pid), stateless);
+            }
+            assert_eq!(context.verify(&proof, "context", pid), Ok(()));
+        }
+    }
+
+    #[test]
+    fn verifier_context_is_shareable() {
+        fn assert_send_sync<T: Send + Sync + Clone>() {}
+        assert_send_sync::<VerifierContext>();
+    }
+
+    fn vector_statements(count: usize) -> (Vec<IBig>, Vec<PointJacobi>) {
+        let mut rng = StdRng::seed_from_u64(1035);
+        let xs: Vec<IBig> = (0..count).map(|_| curve::random_scalar(&mut rng)).collect();
+        let ys = xs.iter().map(PointJacobi::mul_generator).collect();
+        (xs, ys)
+    }
+
+    #[test]
+    fn vector_proofs_round_trip() {
+        let g = PointJacobi::generator();
+        let (xs, ys) = vector_statements(4);
+        let proof = Prover::prove_vector("vector", 1, &xs, &ys, &g).unwrap();
+        assert_eq!(
+            Verifier::verify_vector(&proof, "vector", 1, &ys, &g),
+            Ok(())
+        );
+        let decoded = AndProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(decoded, proof);
+        assert_eq!(
+            Verifier::verify_vector(&decoded, "vector", 1, &ys, &g),
+            Ok(())
+        );
+        assert_eq!(
+            Verifier::verify_vector(&proof, "vector", 2, &ys, &g),
+            Err(ProofError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn vector_proofs_reject_swapped_keys_and_responses() {
+        let g = PointJacobi::generator();
+        let (xs, ys) = vector_statements(3);
+        let proof = Prover::prove_vector("vector", 1, &xs, &ys, &g).unwrap();
+        let mut swapped = ys.clone();
+        swapped.swap(1, 2);
+        assert_eq!(
+            Verifier::verify_vector(&proof, "vector", 1, &swapped, &g),
+            Err(ProofError::VerificationFailed)
+        );
+        let mut tampered = proof.clone();
+        tampered.s.swap(0, 2);
+        assert_eq!(
+            Verifier::verify_vector(&tampered, "vector", 1, &ys, &g),
+            Err(ProofError::VerificationFailed)
+        );
+        let mut weak = ys.clone();
+        weak[0] = PointJacobi::zero();
+        assert_eq!(
+            Verifier::verify_vector(&proof, "vector", 1, &weak, &g),
+            Err(ProofError::InvalidPoint)
+        );
+        // The prover refuses a weak key too.
+        assert_eq!(
+            Prover::prove_vector("vector", 1, &xs, &weak, &g),
+            Err(ProofError::InvalidPoint)
+        );
+    }
+
+    #[test]
+    fn vector_proofs_need_one_key_per_secret() {
+        let g = PointJacobi::generator();
+        let (xs, ys) = vector_statements(3);
+        assert!(matches!(
+            Prover::prove_vector("vector", 1, &[], &[], &g),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        assert!(matches!(
+            Prover::prove_vector("vector", 1, &xs, &ys[..2], &g),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        let proof = Prover::prove_vector("vector", 1, &xs, &ys, &g).unwrap();
+        assert!(matches!(
+            Verifier::verify_vector(&proof, "vector", 1, &ys[..2], &g),
+            Err(ProofError::InvalidStatement(_))
+        ));
+        assert!(matches!(
+            Verifier::verify_vector(&proof, "vector", 1, &[], &g),
+            Err(ProofError::InvalidStatement(_))
+        ));
+    }
+}
@@ -0,0 +1,303 @@
+//! A verifiable random function: the ECVRF construction of RFC 9381 over
+//! secp256k1 with SHA-256 and try-and-increment hashing to the curve
+//! (ECVRF-SECP256K1-SHA256-TAI, suite string `0xFE`).
+//!
+//! The output for input `alpha` is `beta = H(suite || 0x03 || x·H(alpha) || 0x00)`,
+//! pseudorandom to anyone without `x`. The proof is `Gamma = x·H(alpha)` with
+//! a Chaum-Pedersen proof `(c, s)` that `Gamma` and `Y` share the discrete log
+//! `x` to bases `H(alpha)` and `G`:
+//!
+//! ```text
+//! k = RFC 6979 nonce for H(alpha),  U = k·G,  V = k·H(alpha)
+//! c = SHA-256(suite || 0x02 || Y || H(alpha) || Gamma || U || V || 0x00)[..16]
+//! s = k + c·x
+//! ```
+//!
+//! Points are compressed; an encoded proof is `Gamma || c || s`, 81 bytes.
+//! The nonce is deterministic, so proving the same input twice gives the
+//! same proof.
+
+use crate::curve::{self, SCALAR_BYTES};
+use crate::error::ProofError;
+use crate::jacobi_point::{PointJacobi, POINT_BYTES};
+use crate::keys::{PublicKey, SecretKey};
+use crate::nonce::rfc6979_nonce;
+use ibig::{IBig, UBig};
+use sha2::{Digest, Sha256};
+
+/// The ECVRF suite string for secp256k1, SHA-256 and try-and-increment.
+pub const SUITE: u8 = 0xFE;
+
+/// Length in bytes of the challenge `c`.
+pub const CHALLENGE_BYTES: usize = 16;
+
+/// Length in bytes of the VRF output `beta`.
+pub const OUTPUT_BYTES: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VrfProof {
+    pub gamma: PointJacobi,
+    pub c: IBig,
+    pub s: IBig,
+}
+
+impl VrfProof {
+    /// Length in bytes of an encoded proof: compressed `gamma`, the 16-byte
+    /// `c` and the 32-byte `s`.
+    pub const BYTES: usize = POINT_BYTES + CHALLENGE_BYTES + SCALAR_BYTES;
+
+    /// `prove` evaluates the VRF at `alpha` and proves the result.
+    ///
+    /// Returns:
+    ///
+    /// The output `beta` and its proof, or `ProofError::InvalidStatement` in
+    /// the negligible case that `alpha` cannot be hashed to the curve.
+    pub fn prove(key: &SecretKey, alpha: &[u8]) -> Result<([u8; OUTPUT_BYTES], Self), ProofError> {
+        let x = key.scalar();
+        let y = key.public_key();
+        let h = encode_to_curve(&y, alpha)?;
+        let gamma = h.mul_secret(&x);
+        let digest: [u8; 32] = Sha256::digest(h.to_bytes()).into();
+        let k = rfc6979_nonce(&x, &digest);
+        let u = PointJacobi::mul_generator(&k);
+        let v = h.mul_secret(&k);
+        let c = challenge(y.point(), &h, &gamma, &u, &v);
+        let s = curve::rem_n(&(k + &c * x));
+        let proof = Self { gamma, c, s };
+        Ok((proof.output(), proof))
+    }
+
+    /// `verify` checks the proof for `alpha` under `public_key`.
+    ///
+    /// Returns:
+    ///
+    /// The output `beta` if the proof is valid, `ProofError::InvalidPoint` if
+    /// `public_key` or `gamma` is not a point of the group other than the
+    /// identity, `ProofError::ScalarOutOfRange`
+    /// if `c` or `s` is out of range, and `ProofError::VerificationFailed`
+    /// otherwise.
+    pub fn verify(
+        &self,
+        public_key: &PublicKey,
+        alpha: &[u8],
+    ) -> Result<[u8; OUTPUT_BYTES], ProofError> {
+        let y = public_key.point();
+        if y.is_zero() || !y.is_on_curve() {
+            return Err(ProofError::InvalidPoint);
+        }
+        // An identity gamma would make beta the same for every input.
+        if self.gamma.is_zero() || !self.gamma.is_on_curve() {
+            return Err(ProofError::InvalidPoint);
+        }
+        if self.c < IBig::from(0)
+            || self.c >= IBig::from(1) << (8 * CHALLENGE_BYTES)
+            || self.s < IBig::from(0)
+            || self.s >= *curve::N
+        {
+            return Err(ProofError::ScalarOutOfRange);
+        }
+        let h = encode_to_curve(public_key, alpha)?;
+        let u = PointJacobi::generator()
+            .mul_unsafe(&self.s)
+            .add(&y.mul_unsafe(&self.c).negate());
+        let v = h
+            .mul_unsafe(&self.s)
+            .add(&self.gamma.mul_unsafe(&self.c).negate());
+        if challenge(y, &h, &self.gamma, &u, &v) == self.c {
+            Ok(self.output())
+        } else {
+            Err(ProofError::VerificationFailed)
+        }
+    }
+
+    /// `output` returns `beta` for this proof's `gamma`. It is only
+    /// meaningful once the proof has been verified.
+    pub fn output(&self) -> [u8; OUTPUT_BYTES] {
+        // secp256k1 has cofactor 1, so gamma is hashed as is.
+        Sha256::new()
+            .chain_update([SUITE, 0x03])
+            .chain_update(self.gamma.to_bytes())
+            .chain_update([0x00])
+            .finalize()
+            .into()
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut out = [0u8; Self::BYTES];
+        out[..POINT_BYTES].copy_from_slice(&self.gamma.to_bytes());
+        out[POINT_BYTES..POINT_BYTES + CHALLENGE_BYTES]
+            .copy_from_slice(&curve::scalar_to_bytes(&self.c)[SCALAR_BYTES - CHALLENGE_BYTES..]);
+        out[POINT_BYTES + CHALLENGE_BYTES..].copy_from_slice(&curve::scalar_to_bytes(&self.s));
+        out
+    }
+
+    /// `from_bytes` parses the encoding produced by [`VrfProof::to_bytes`],
+    /// rejecting an `s` that is not reduced modulo n.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != Self::BYTES {
+            return Err(ProofError::DeserializationError(format!(
+                "expected {} VRF proof bytes, got {}",
+                Self::BYTES,
+                bytes.len()
+            )));
+        }
+        Ok(Self {
+            gamma: PointJacobi::from_bytes(&bytes[..POINT_BYTES])?,
+            c: IBig::from(UBig::from_be_bytes(
+                &bytes[POINT_BYTES..POINT_BYTES + CHALLENGE_BYTES],
+            )),
+            s: curve::scalar_from_canonical_bytes(&bytes[POINT_BYTES + CHALLENGE_BYTES..])?,
+        })
+    }
+}
+
+/// `encode_to_curve` hashes `alpha` to a point by try-and-increment: the
+/// first `SHA-256(suite || 0x01 || Y || alpha || ctr || 0x00)`, for a one-byte
+/// counter from zero, that is the x coordinate of a point, taking the even `y`.
+///
+/// Returns:
+///
+/// The point, or `ProofError::InvalidStatement` if all 256 counters fail,
+/// which happens with probability about `2^-256`.
+pub fn encode_to_curve(public_key: &PublicKey, alpha: &[u8]) -> Result<PointJacobi, ProofError> {
+    let salt = public_key.point().to_bytes();
+    (0..=u8::MAX)
+        .find_map(|ctr| {
+            let digest = Sha256::new()
+                .chain_update([SUITE, 0x01])
+                .chain_update(salt)
+                .chain_update(alpha)
+                .chain_update([ctr, 0x00])
+                .finalize();
+            let mut compressed = [0x02; POINT_BYTES];
+            compressed[1..].copy_from_slice(&digest);
+            PointJacobi::from_bytes(&compressed).ok()
+        })
+        .ok_or_else(|| ProofError::InvalidStatement("alpha does not hash to the curve".to_string()))
+}
+
+fn challenge(
+    y: &PointJacobi,
+    h: &PointJacobi,
+    gamma: &PointJacobi,
+    u: &PointJacobi,
+    v: &PointJacobi,
+) -> IBig {
+    let mut hasher = Sha256::new().chain_update([SUITE, 0x02]);
+    for point in [y, h, gamma, u, v] {
+        hasher.update(point.to_bytes());
+    }
+    let digest = hasher.chain_update([0x00]).finalize();
+    IBig::from(UBig::from_be_bytes(&digest[..CHALLENGE_BYTES]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn keys() -> (SecretKey, PublicKey) {
+        let key = SecretKey::random(&mut StdRng::seed_from_u64(1038));
+        let public_key = key.public_key();
+        (key, public_key)
+    }
+
+    #[test]
+    fn proofs_verify_and_return_the_output() {
+        let (key, public_key) = keys();
+        let (beta, proof) = VrfProof::prove(&key, b"alpha").unwrap();
+        assert_eq!(beta, proof.output());
+        assert_eq!(proof.verify(&public_key, b"alpha"), Ok(beta));
+        assert_eq!(VrfProof::prove(&key, b"alpha").unwrap(), (beta, proof));
+
+        let (other, _) = VrfProof::prove(&key, b"beta").unwrap();
+        assert_ne!(other, beta);
+    }
+
+    #[test]
+    fn proofs_are_bound_to_the_input_and_key() {
+        let (key, public_key) = keys();
+        let (_, proof) = VrfProof::prove(&key, b"alpha").unwrap();
+        assert_eq!(
+            proof.verify(&public_key, b"alpha2"),
+            Err(ProofError::VerificationFailed)
+        );
+        let other = SecretKey::random(&mut StdRng::seed_from_u64(1039)).public_key();
+        assert_eq!(
+            proof.verify(&other, b"alpha"),
+            Err(ProofError::VerificationFailed)
+        );
+        assert_eq!(
+            proof.verify(&PublicKey::new(PointJacobi::zero()), b"alpha"),
+            Err(ProofError::InvalidPoint)
+        );
+    }
+
+    #[test]
+    fn tampered_proofs_are_rejected() {
+        let (key, public_key) = keys();
+        let (_, proof) = VrfProof::prove(&key, b"alpha").unwrap();
+
+        let mut c = proof.clone();
+        c.c += 1;
+        assert_eq!(
+            c.verify(&public_key, b"alpha"),
+            Err(ProofError::VerificationFailed)
+        );
+        let mut s = proof.clone();
+        s.s = curve::rem_n(&(&s.s + 1));
+        assert_eq!(
+            s.verify(&public_key, b"alpha"),
+            Err(ProofError::VerificationFailed)
+        );
+        let mut wide = proof.clone();
+        wide.c = IBig::from(1) << (8 * CHALLENGE_BYTES);
+        assert_eq!(
+            wide.verify(&public_key, b"alpha"),
+            Err(ProofError::ScalarOutOfRange)
+        );
+        let mut gamma = proof.clone();
+        gamma.gamma = gamma.gamma.add(&PointJacobi::generator());
+        assert_eq!(
+            gamma.verify(&public_key, b"alpha"),
+            Err(ProofError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn an_identity_or_off_curve_gamma_is_rejected() {
+        let (key, public_key) = keys();
+        let (_, proof) = VrfProof::prove(&key, b"alpha").unwrap();
+        let mut zero = proof.clone();
+        zero.gamma = PointJacobi::zero();
+        assert_eq!(
+            zero.verify(&public_key, b"alpha"),
+            Err(ProofError::InvalidPoint)
+        );
+        let mut off_curve = proof.clone();
+        let g = &proof.gamma;
+        off_curve.gamma = PointJacobi::new(g.x.clone(), &g.y + 1, g.z.clone());
+        assert_eq!(
+            off_curve.verify(&public_key, b"alpha"),
+            Err(ProofError::InvalidPoint)
+        );
+    }
+
+    #[test]
+    fn proofs_round_trip_through_bytes() {
+        let (key, public_key) = keys();
+        let (beta, proof) = VrfProof::prove(&key, b"alpha").unwrap();
+        let bytes = proof.to_bytes();
+        let decoded = VrfProof::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, proof);
+        assert_eq!(decoded.verify(&public_key, b"alpha"), Ok(beta));
+        assert!(matches!(
+            VrfProof::from_bytes(&bytes[1..]),
+            Err(ProofError::DeserializationError(_))
+        ));
+        let mut unreduced = bytes;
+        unreduced[POINT_BYTES + CHALLENGE_BYTES..].fill(0xff);
+        assert!(VrfProof::from_bytes(&unreduced).is_err());
+    }
+}
@@ -0,0 +1,272 @@
+//! Feldman verifiable secret sharing over the group generated by `G`.
+//!
+//! A dealer samples `f(z) = a_0 + a_1·z + ... + a_t·z^t` with `a_0` the secret,
+//! publishes the commitments `A_j = a_j·G` and hands participant `i` the share
+//! `f(i)`. Each participant checks `f(i)·G == Σ i^j·A_j`, and any `t + 1`
+//! shares reconstruct `a_0` by Lagrange interpolation modulo `n`.
+
+use crate::curve;
+use crate::error::ProofError;
+use crate::jacobi_point::PointJacobi;
+use crate::proof::DLogProof;
+use crate::prover::Prover;
+use crate::secret::SecretScalar;
+use crate::verifier::Verifier;
+use ibig::IBig;
+use rand::{CryptoRng, RngCore};
+
+/// A dealer's polynomial. The coefficients are held as [`SecretScalar`]s, so
+/// they are wiped when the polynomial is dropped.
+#[derive(Clone)]
+pub struct Polynomial {
+    coefficients: Vec<SecretScalar>,
+}
+
+impl Polynomial {
+    /// `random` samples a polynomial of the given degree whose constant term
+    /// is `secret`; any `degree + 1` shares recover the secret.
+    pub fn random<R: RngCore + CryptoRng>(degree: usize, secret: &IBig, rng: &mut R) -> Self {
+        let mut coefficients = vec![SecretScalar::new(secret)];
+        coefficients.extend((0..degree).map(|_| SecretScalar::new(&curve::random_scalar(rng))));
+        Self { coefficients }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.coefficients.len() - 1
+    }
+
+    /// `commitments` returns `A_j = a_j·G` for every coefficient.
+    pub fn commitments(&self) -> Vec<PointJacobi> {
+        self.coefficients
+            .iter()
+            .map(|a| PointJacobi::mul_generator(&a.expose()))
+            .collect()
+    }
+
+    /// `share` evaluates the polynomial at `index`.
+    ///
+    /// Returns:
+    ///
+    /// The share `f(index)`, or `ProofError::InvalidShare(0)` for index zero,
+    /// since `f(0)` is the secret itself.
+    pub fn share(&self, index: u32) -> Result<IBig, ProofError> {
+        if index == 0 {
+            return Err(ProofError::InvalidShare(0));
+        }
+        let i = IBig::from(index);
+        Ok(self
+            .coefficients
+            .iter()
+            .rev()
+            .fold(IBig::from(0), |acc, a| {
+                curve::rem_n(&(acc * &i + a.expose()))
+            }))
+    }
+
+    /// `prove_secret` proves knowledge of the constant term against `A_0`.
+    ///
+    /// Arguments:
+    ///
+    /// * `sid`: the session id
+    /// * `pid`: the dealer's participant id
+    ///
+    /// Returns:
+    ///
+    /// A proof checked by [`verify_dealer_proof`], or `ProofError::InvalidSecret`
+    /// if the shared secret is zero.
+    pub fn prove_secret(&self, sid: &str, pid: i32) -> Result<DLogProof, ProofError> {
+        let g = PointJacobi::generator();
+        let a_0 = self.coefficients[0].expose();
+        Prover::prove(sid, pid, &a_0, &g.mul_secret(&a_0), &g)
+    }
+}
+
+/// `verify_share` checks a share against the dealer's commitments.
+///
+/// Arguments:
+///
+/// * `index`: the participant's non-zero index
+/// * `share`: the participant's share `f(index)`
+/// * `commitments`: the dealer's commitments `A_0..A_t`
+///
+/// Returns:
+///
+/// `Ok(())` if `share·G == Σ index^j·A_j`, `ProofError::InvalidShare` otherwise.
+pub fn verify_share(
+    index: u32,
+    share: &IBig,
+    commitments: &[PointJacobi],
+) -> Result<(), ProofError> {
+    if index == 0 || commitments.is_empty() {
+        return Err(ProofError::InvalidShare(index));
+    }
+    if PointJacobi::mul_generator(share) == share_public_key(index, commitments) {
+        Ok(())
+    } else {
+        Err(ProofError::InvalidShare(index))
+    }
+}
+
+/// `share_public_key` returns `f(index)·G = Σ index^j·A_j`, the public key of
+/// participant `index`'s share, computed from the dealer's commitments alone.
+pub fn share_public_key(index: u32, commitments: &[PointJacobi]) -> PointJacobi {
+    let i = IBig::from(index);
+    commitments
+        .iter()
+        .rev()
+        .fold(PointJacobi::zero(), |acc, a| acc.mul_unsafe(&i).add(a))
+}
+
+/// `verify_dealer_proof` checks the dealer's proof of knowledge of the secret
+/// committed in `A_0`.
+pub fn verify_dealer_proof(
+    proof: &DLogProof,
+    sid: &str,
+    pid: i32,
+    commitments: &[PointJacobi],
+) -> Result<(), ProofError> {
+    let a_0 = commitments.first().ok_or(ProofError::VerificationFailed)?;
+    Verifier::verify(proof, sid, pid, a_0, &PointJacobi::generator())
+}
+
+/// `reconstruct` recovers the secret from shares by Lagrange interpolation at zero.
+///
+/// Arguments:
+///
+/// * `shares`: `(index, share)` pairs; at least `degree + 1` are needed for
+///   the result to be the secret
+///
+/// Returns:
+///
+/// The interpolated constant term, `ProofError::InsufficientShares` if no
+/// shares are given, or an error naming an index that is zero or repeated.
+pub fn reconstruct(shares: &[(u32, IBig)]) -> Result<IBig, ProofError> {
+    if shares.is_empty() {
+        return Err(ProofError::InsufficientShares);
+    }
+    let indices: Vec<u32> = shares.iter().map(|(index, _)| *index).collect();
+    check_indices(&indices)?;
+    let mut secret = IBig::from(0);
+    for (index, share) in shares {
+        secret = curve::rem_n(&(secret + lagrange_at_zero(*index, &indices) * share));
+    }
+    Ok(secret)
+}
+
+/// `check_indices` rejects a zero or repeated share index.
+pub(crate) fn check_indices(indices: &[u32]) -> Result<(), ProofError> {
+    for (k, index) in indices.iter().enumerate() {
+        if *index == 0 {
+            return Err(ProofError::InvalidShare(0));
+        }
+        if indices[..k].contains(index) {
+            return Err(ProofError::DuplicateShareIndex(*index));
+        }
+    }
+    Ok(())
+}
+
+/// `lagrange_at_zero` returns the Lagrange coefficient of `index` for
+/// interpolating at zero from the shares at `indices`, which must be distinct
+/// and include `index`.
+pub(crate) fn lagrange_at_zero(index: u32, indices: &[u32]) -> IBig {
+    let i = IBig::from(index);
+    let mut numerator = IBig::from(1);
+    let mut denominator = IBig::from(1);
+    for other in indices.iter().filter(|other| **other != index) {
+        let j = IBig::from(*other);
+        numerator = curve::rem_n(&(numerator * &j));
+        denominator = curve::rem_n(&(denominator * (&j - &i)));
+    }
+    curve::rem_n(&(numerator * curve::invert_n(&denominator)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn deal(degree: usize, count: u32) -> (IBig, Polynomial, Vec<(u32, IBig)>) {
+        let mut rng = StdRng::seed_from_u64(841);
+        let secret = curve::random_scalar(&mut rng);
+        let polynomial = Polynomial::random(degree, &secret, &mut rng);
+        let shares = (1..=count)
+            .map(|i| (i, polynomial.share(i).unwrap()))
+            .collect();
+        (secret, polynomial, shares)
+    }
+
+    #[test]
+    fn any_threshold_of_shares_reconstructs_the_secret() {
+        let (secret, polynomial, shares) = deal(2, 5);
+        let commitments = polynomial.commitments();
+        for (index, share) in &shares {
+            assert_eq!(verify_share(*index, share, &commitments), Ok(()));
+        }
+        assert_eq!(reconstruct(&shares[..3]), Ok(secret.clone()));
+        assert_eq!(reconstruct(&shares[2..]), Ok(secret.clone()));
+        assert_eq!(
+            reconstruct(&[shares[4].clone(), shares[0].clone(), shares[2].clone()]),
+            Ok(secret.clone())
+        );
+        assert_ne!(reconstruct(&shares[..2]), Ok(secret));
+    }
+
+    #[test]
+    fn dealer_proof_verifies_against_the_first_commitment() {
+        let (_, polynomial, _) = deal(2, 3);
+        let proof = polynomial.prove_secret("vss", 1).unwrap();
+        assert_eq!(
+            verify_dealer_proof(&proof, "vss", 1, &polynomial.commitments()),
+            Ok(())
+        );
+        assert_eq!(
+            verify_dealer_proof(&proof, "vss", 2, &polynomial.commitments()),
+            Err(ProofError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn share_zero_is_an_error() {
+        let (_, polynomial, _) = deal(2, 3);
+        assert_eq!(polynomial.share(0), Err(ProofError::InvalidShare(0)));
+    }
+
+    #[test]
+    fn bad_shares_are_rejected() {
+        let (_, polynomial, shares) = deal(2, 3);
+        let commitments = polynomial.commitments();
+        let (index, share) = &shares[1];
+        assert_eq!(
+            verify_share(*index, &curve::rem_n(&(share + 1)), &commitments),
+            Err(ProofError::InvalidShare(*index))
+        );
+        assert_eq!(
+            verify_share(*index + 1, share, &commitments),
+            Err(ProofError::InvalidShare(*index + 1))
+        );
+        assert_eq!(
+            verify_share(0, share, &commitments),
+            Err(ProofError::InvalidShare(0))
+        );
+        assert_eq!(
+            verify_share(*index, share, &[]),
+            Err(ProofError::InvalidShare(*index))
+        );
+    }
+
+    #[test]
+    fn malformed_share_sets_are_rejected() {
+        let (_, _, shares) = deal(2, 3);
+        assert_eq!(reconstruct(&[]), Err(ProofError::InsufficientShares));
+        assert_eq!(
+            reconstruct(&[(0, shares[0].1.clone()), shares[1].clone()]),
+            Err(ProofError::InvalidShare(0))
+        );
+        assert_eq!(
+            reconstruct(&[shares[0].clone(), shares[1].clone(), shares[0].clone()]),
+            Err(ProofError::DuplicateShareIndex(1))
+        );
+    }
+}
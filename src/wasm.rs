@@ -0,0 +1,56 @@
+//! wasm-bindgen interface for JavaScript, behind the `wasm` feature.
+//!
+//! As in [`crate::mobile`], every value crosses the boundary in its canonical
+//! byte encoding, as a `Uint8Array`: secrets as 32-byte big-endian scalars,
+//! public keys as 33-byte compressed points and proofs as
+//! [`DLogProof::to_bytes`]. Proofs are over the generator `G`, so a proof
+//! made in a browser verifies with [`Verifier::verify_with_key`] in a Rust backend and
+//! the other way around. Randomness comes from the JS crypto API through
+//! getrandom's `js` backend. Errors are thrown as `Error`s carrying the
+//! [`ProofError`] message.
+
+use crate::error::ProofError;
+use crate::keys::{PublicKey, SecretKey};
+use crate::proof::DLogProof;
+use crate::prover::Prover;
+use crate::verifier::Verifier;
+use wasm_bindgen::prelude::*;
+
+/// A secret and its public key, as returned by [`generate_keypair`].
+#[wasm_bindgen(getter_with_clone)]
+pub struct Keypair {
+    pub secret: Vec<u8>,
+    #[wasm_bindgen(js_name = publicKey)]
+    pub public_key: Vec<u8>,
+}
+
+/// Generates a random secret and its public key `secret·G`.
+#[wasm_bindgen(js_name = generateKeypair)]
+pub fn generate_keypair() -> Keypair {
+    let key = SecretKey::random(&mut rand::rngs::OsRng);
+    Keypair {
+        secret: key.to_bytes().to_vec(),
+        public_key: key.public_key().to_bytes().to_vec(),
+    }
+}
+
+/// Proves knowledge of `secret` for `public_key` in session `sid` as participant `pid`.
+#[wasm_bindgen]
+pub fn prove(sid: &str, pid: i32, secret: &[u8], public_key: &[u8]) -> Result<Vec<u8>, JsError> {
+    let key = SecretKey::from_bytes(secret)?;
+    let public_key = PublicKey::from_bytes(public_key)?;
+    let proof = Prover::prove_with_key(sid, pid, &key, &public_key)?;
+    Ok(proof.to_bytes().to_vec())
+}
+
+/// Returns whether `proof` is valid for `public_key`; malformed inputs throw.
+#[wasm_bindgen]
+pub fn verify(sid: &str, pid: i32, public_key: &[u8], proof: &[u8]) -> Result<bool, JsError> {
+    let public_key = PublicKey::from_bytes(public_key)?;
+    let proof = DLogProof::from_bytes(proof)?;
+    match Verifier::verify_with_key(&proof, sid, pid, &public_key) {
+        Ok(()) => Ok(true),
+        Err(ProofError::VerificationFailed) => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}